@@ -1,9 +1,15 @@
 //! CLI tool for deploying and interacting with DEX smart contracts.
 
+mod faucet;
+mod livenet;
+
 use ectoplasm_contracts::dex::factory::Factory;
+use ectoplasm_contracts::dex::pair::PairFactory;
 use ectoplasm_contracts::dex::router::Router;
+use ectoplasm_contracts::sdk::DEFAULT_DEADLINE_TTL;
 use ectoplasm_contracts::token::LpToken;
-use ectoplasm_contracts::tokens::{EctoToken, UsdcToken, WethToken, WbtcToken};
+use ectoplasm_contracts::tokens::{EctoToken, UsdcToken, WcsprToken, WethToken, WbtcToken};
+use odra::casper_types::{U256, U512};
 use odra::prelude::{Address, Addressable};
 use odra::host::{HostEnv, Deployer};
 use odra::schema::casper_contract_schema::NamedCLType;
@@ -27,10 +33,17 @@ impl DeployScript for FactoryDeployScript {
         use ectoplasm_contracts::dex::factory::FactoryInitArgs;
         
         let caller = env.caller();
+        let pair_factory = PairFactory::load_or_deploy(
+            &env,
+            NoArgs,
+            container,
+            500_000_000_000 // Gas limit for pair factory deployment
+        )?;
         let _factory = Factory::load_or_deploy(
             &env,
             FactoryInitArgs {
                 fee_to_setter: caller,
+                pair_factory: pair_factory.address().clone(),
             },
             container,
             500_000_000_000 // Gas limit for factory deployment
@@ -51,19 +64,15 @@ impl DeployScript for RouterDeployScript {
         container: &mut DeployedContractsContainer
     ) -> Result<(), odra_cli::deploy::Error> {
         use ectoplasm_contracts::dex::router::RouterInitArgs;
-        use ectoplasm_contracts::token::LpTokenInitArgs;
         
         // Get factory address from container
         let factory = container.contract_ref::<Factory>(env)?;
         let factory_address = factory.address().clone();
         
-        // Deploy WCSPR token if not exists
-        let wcspr = LpToken::load_or_deploy(
+        // Deploy WCSPR wrapper if not exists
+        let wcspr = WcsprToken::load_or_deploy(
             &env,
-            LpTokenInitArgs {
-                name: String::from("Wrapped CSPR"),
-                symbol: String::from("WCSPR"),
-            },
+            NoArgs,
             container,
             600_000_000_000 // Increased gas limit for token deployment
         )?;
@@ -195,6 +204,96 @@ impl ScenarioMetadata for CreatePairScenario {
     const DESCRIPTION: &'static str = "Creates a new trading pair for two tokens";
 }
 
+/// Scenario to swap native CSPR for tokens in a single deploy.
+/// The CSPR is attached to the Router call (through Odra's proxy session code),
+/// wrapped into WCSPR and swapped, so no WCSPR balance or approval is needed.
+pub struct SwapCsprScenario;
+
+impl Scenario for SwapCsprScenario {
+    fn args(&self) -> Vec<CommandArg> {
+        vec![
+            CommandArg::new(
+                "amount",
+                "CSPR to swap, in motes",
+                NamedCLType::U512,
+            ),
+            CommandArg::new(
+                "token_out",
+                "Address of the token to receive",
+                NamedCLType::Key,
+            ),
+            CommandArg::new(
+                "amount_out_min",
+                "Minimum amount of token_out to accept",
+                NamedCLType::U256,
+            ),
+        ]
+    }
+
+    fn run(
+        &self,
+        env: &HostEnv,
+        container: &DeployedContractsContainer,
+        args: Args
+    ) -> Result<(), Error> {
+        let router = container.contract_ref::<Router>(env)?;
+        let amount = args.get_single::<U512>("amount")?;
+        let token_out = args.get_single::<Address>("token_out")?;
+        let amount_out_min = args.get_single::<U256>("amount_out_min")?;
+
+        let path = vec![router.wcspr(), token_out];
+        let deadline = router.suggest_deadline(DEFAULT_DEADLINE_TTL);
+        env.set_gas(300_000_000_000);
+        let amounts = router
+            .with_tokens(amount)
+            .try_swap_exact_cspr_for_tokens(amount_out_min, path, env.caller(), deadline)?;
+
+        println!("Swapped {} motes for {} tokens", amount, amounts[amounts.len() - 1]);
+        Ok(())
+    }
+}
+
+impl ScenarioMetadata for SwapCsprScenario {
+    const NAME: &'static str = "swap-cspr";
+    const DESCRIPTION: &'static str = "Swaps native CSPR for tokens in one deploy, without approval";
+}
+
+/// Scenario to print a swap deadline derived from the chain's block time.
+pub struct SuggestDeadlineScenario;
+
+impl Scenario for SuggestDeadlineScenario {
+    fn args(&self) -> Vec<CommandArg> {
+        vec![
+            CommandArg::new(
+                "ttl",
+                "Seconds until the deadline (defaults to 20 minutes)",
+                NamedCLType::U64,
+            ),
+        ]
+    }
+
+    fn run(
+        &self,
+        env: &HostEnv,
+        container: &DeployedContractsContainer,
+        args: Args
+    ) -> Result<(), Error> {
+        let router = container.contract_ref::<Router>(env)?;
+        let ttl = args.get_single::<u64>("ttl").unwrap_or(DEFAULT_DEADLINE_TTL);
+
+        let block_time = router.current_block_time();
+        let deadline = router.suggest_deadline(ttl);
+        println!("Block time: {}", block_time);
+        println!("Deadline:   {} (tolerance {}s)", deadline, router.deadline_tolerance());
+        Ok(())
+    }
+}
+
+impl ScenarioMetadata for SuggestDeadlineScenario {
+    const NAME: &'static str = "suggest-deadline";
+    const DESCRIPTION: &'static str = "Prints a swap deadline based on the current block time";
+}
+
 /// Main function to run the CLI tool.
 pub fn main() {
     OdraCli::new()
@@ -205,7 +304,9 @@ pub fn main() {
         .deploy(DexDeployScript)
         .deploy(TokensDeployScript)
         .deploy(FullDeployScript)
+        .deploy(faucet::TestTokenDeployScript::default())
         // Contract references
+        .contract::<PairFactory>()
         .contract::<Factory>()
         .contract::<Router>()
         .contract::<LpToken>()
@@ -213,8 +314,13 @@ pub fn main() {
         .contract::<UsdcToken>()
         .contract::<WethToken>()
         .contract::<WbtcToken>()
+        .contract::<WcsprToken>()
         // Scenarios
         .scenario(CreatePairScenario)
+        .scenario(SuggestDeadlineScenario)
+        .scenario(SwapCsprScenario)
+        .scenario(faucet::MintTestTokensScenario)
+        .scenario(livenet::LivenetTestsScenario)
         .build()
         .run();
 }
\ No newline at end of file
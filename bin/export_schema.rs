@@ -0,0 +1,55 @@
+//! Exports entrypoint and event schemas of all contracts to one JSON file.
+//!
+//! The frontend and indexer consume this instead of the per-contract files
+//! produced by `cargo odra schema`.
+//!
+//! Usage: cargo run --bin ectoplasm_contracts_export_schema [output.json]
+
+use ectoplasm_contracts::dex::{Factory, LiquidityLocker, Pair, Router, SingleSidedLp};
+use ectoplasm_contracts::governance::{Denylist, Guardian, ParameterRegistry};
+use ectoplasm_contracts::lst::{ScsprToken, StakingManager};
+use ectoplasm_contracts::token::LpToken;
+use odra::schema::{SchemaEntrypoints, SchemaEvents};
+use serde_json::{json, Value};
+
+/// Default output path
+const DEFAULT_OUTPUT: &str = "resources/ectoplasm_schema.json";
+
+/// Schema of a single contract
+fn contract_schema<T: SchemaEntrypoints + SchemaEvents>(name: &str) -> Value {
+    let custom_types: Vec<_> = T::custom_types().into_iter().flatten().collect();
+    json!({
+        "name": name,
+        "entrypoints": T::schema_entrypoints(),
+        "events": T::schema_events(),
+        "types": custom_types,
+    })
+}
+
+fn main() {
+    let output = std::env::args().nth(1).unwrap_or_else(|| DEFAULT_OUTPUT.to_string());
+
+    let schema = json!({
+        "dex": [
+            contract_schema::<Factory>("Factory"),
+            contract_schema::<Pair>("Pair"),
+            contract_schema::<Router>("Router"),
+            contract_schema::<LpToken>("LpToken"),
+            contract_schema::<SingleSidedLp>("SingleSidedLp"),
+            contract_schema::<LiquidityLocker>("LiquidityLocker"),
+        ],
+        "lst": [
+            contract_schema::<StakingManager>("StakingManager"),
+            contract_schema::<ScsprToken>("ScsprToken"),
+        ],
+        "governance": [
+            contract_schema::<ParameterRegistry>("ParameterRegistry"),
+            contract_schema::<Guardian>("Guardian"),
+            contract_schema::<Denylist>("Denylist"),
+        ],
+    });
+
+    let json = serde_json::to_string_pretty(&schema).expect("schema serializes to JSON");
+    std::fs::write(&output, json).expect("failed to write schema file");
+    println!("Schema written to {}", output);
+}
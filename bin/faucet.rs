@@ -0,0 +1,129 @@
+//! Test token faucet for testnets.
+//!
+//! `TestTokenDeployScript` deploys a configurable set of plain CEP-18 test
+//! tokens and seeds the deployer, and `MintTestTokensScenario` mints any of
+//! them (or the built-in ECTO/USDC/WETH/WBTC tokens) to arbitrary addresses,
+//! so QA can set up multi-token pools without hand-written deploys.
+
+use ectoplasm_contracts::token::{LpToken, LpTokenInitArgs};
+use odra::casper_types::U256;
+use odra::host::{Deployer, HostEnv, HostRefLoader};
+use odra::prelude::{Address, Addressable};
+use odra::schema::casper_contract_schema::NamedCLType;
+use odra::{ExecutionError, OdraError};
+use odra_cli::{
+    deploy::DeployScript,
+    scenario::{Args, Error, Scenario, ScenarioMetadata},
+    CommandArg, DeployedContractsContainer,
+};
+
+/// Gas for contract deployments
+const DEPLOY_GAS: u64 = 600_000_000_000;
+/// Gas for regular calls
+const CALL_GAS: u64 = 300_000_000_000;
+/// User error code reported when minting without the testnet flag
+const NOT_TESTNET: u16 = 2;
+
+/// Deploys plain CEP-18 test tokens and mints `initial_mint` of each to the deployer.
+pub struct TestTokenDeployScript {
+    /// Name and symbol of every token to deploy
+    pub tokens: Vec<(&'static str, &'static str)>,
+    /// Amount minted to the deployer per token
+    pub initial_mint: U256,
+}
+
+impl Default for TestTokenDeployScript {
+    fn default() -> Self {
+        TestTokenDeployScript {
+            tokens: vec![
+                ("Test Token A", "TTA"),
+                ("Test Token B", "TTB"),
+                ("Test Token C", "TTC"),
+            ],
+            initial_mint: U256::from(1_000_000u64) * U256::exp10(18),
+        }
+    }
+}
+
+impl DeployScript for TestTokenDeployScript {
+    fn deploy(
+        &self,
+        env: &HostEnv,
+        _container: &mut DeployedContractsContainer
+    ) -> Result<(), odra_cli::deploy::Error> {
+        let deployer = env.caller();
+        for (name, symbol) in &self.tokens {
+            env.set_gas(DEPLOY_GAS);
+            let mut token = LpToken::try_deploy(env, LpTokenInitArgs {
+                name: String::from(*name),
+                symbol: String::from(*symbol),
+            })?;
+            if !self.initial_mint.is_zero() {
+                env.set_gas(CALL_GAS);
+                token.try_mint(deployer, self.initial_mint)?;
+            }
+            println!("{} ({}) deployed at: {:?}", name, symbol, token.address());
+        }
+        Ok(())
+    }
+}
+
+/// Scenario minting a test token to a list of addresses.
+/// Test tokens have an open `mint`, so this refuses to run unless the
+/// `testnet` flag is set, as a guard against pointing it at mainnet.
+pub struct MintTestTokensScenario;
+
+impl Scenario for MintTestTokensScenario {
+    fn args(&self) -> Vec<CommandArg> {
+        vec![
+            CommandArg::new(
+                "token",
+                "Address of the test token",
+                NamedCLType::Key,
+            ),
+            CommandArg::new(
+                "recipients",
+                "Addresses to mint to",
+                NamedCLType::Key,
+            ).list(),
+            CommandArg::new(
+                "amount",
+                "Amount minted to each recipient, in the token's smallest unit",
+                NamedCLType::U256,
+            ),
+            CommandArg::new(
+                "testnet",
+                "Confirms the target network is a testnet",
+                NamedCLType::Bool,
+            ),
+        ]
+    }
+
+    fn run(
+        &self,
+        env: &HostEnv,
+        _container: &DeployedContractsContainer,
+        args: Args
+    ) -> Result<(), Error> {
+        if !args.get_single::<bool>("testnet").unwrap_or(false) {
+            println!("Refusing to mint: pass --testnet true to confirm the target network");
+            return Err(OdraError::ExecutionError(ExecutionError::User(NOT_TESTNET)).into());
+        }
+        let token = args.get_single::<Address>("token")?;
+        let recipients = args.get_many::<Address>("recipients")?;
+        let amount = args.get_single::<U256>("amount")?;
+
+        let mut token = LpToken::load(env, token);
+        for recipient in recipients {
+            env.set_gas(CALL_GAS);
+            token.try_mint(recipient, amount)?;
+            println!("Minted {} to {:?}, balance {}", amount, recipient, token.balance_of(recipient));
+        }
+        Ok(())
+    }
+}
+
+impl ScenarioMetadata for MintTestTokensScenario {
+    const NAME: &'static str = "mint-test-tokens";
+    const DESCRIPTION: &'static str = "Mints a test token to a list of addresses (testnet only)";
+}
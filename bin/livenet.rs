@@ -0,0 +1,243 @@
+//! Livenet acceptance scenario for the DEX.
+//!
+//! Deploys a fresh Factory, Router and two test tokens against the configured
+//! node, then walks a full liquidity and swap cycle, checking balances by
+//! query after every step. Prints a pass/fail report and fails the command
+//! if any check did not hold.
+
+use ectoplasm_contracts::dex::factory::{Factory, FactoryHostRef, FactoryInitArgs};
+use ectoplasm_contracts::dex::pair::PairFactory;
+use ectoplasm_contracts::dex::router::{Router, RouterHostRef, RouterInitArgs};
+use ectoplasm_contracts::token::{LpToken, LpTokenHostRef, LpTokenInitArgs};
+use odra::casper_types::U256;
+use odra::host::{Deployer, HostEnv, HostRef, HostRefLoader, NoArgs};
+use odra::prelude::Addressable;
+use odra::{ExecutionError, OdraError};
+use odra_cli::{
+    scenario::{Args, Error, Scenario, ScenarioMetadata},
+    CommandArg, DeployedContractsContainer,
+};
+
+/// Gas for contract deployments
+const DEPLOY_GAS: u64 = 600_000_000_000;
+/// Gas for regular calls
+const CALL_GAS: u64 = 300_000_000_000;
+/// Deadline used for every router call (1 hour from the current block)
+const DEADLINE_TTL: u64 = 60 * 60;
+/// User error code reported when any check fails
+const LIVENET_FAILURE: u16 = 1;
+
+/// Outcome of one checked step
+struct Check {
+    name: &'static str,
+    result: Result<(), String>,
+}
+
+/// Collected pass/fail results
+#[derive(Default)]
+struct Report {
+    checks: Vec<Check>,
+}
+
+impl Report {
+    fn check(&mut self, name: &'static str, ok: bool, detail: String) {
+        let result = if ok { Ok(()) } else { Err(detail) };
+        self.checks.push(Check { name, result });
+    }
+
+    fn eq(&mut self, name: &'static str, actual: U256, expected: U256) {
+        self.check(name, actual == expected, format!("expected {}, got {}", expected, actual));
+    }
+
+    fn failures(&self) -> usize {
+        self.checks.iter().filter(|check| check.result.is_err()).count()
+    }
+
+    fn print(&self) {
+        println!();
+        println!("Livenet report");
+        println!("==============");
+        for check in &self.checks {
+            match &check.result {
+                Ok(()) => println!("PASS  {}", check.name),
+                Err(detail) => println!("FAIL  {}: {}", check.name, detail),
+            }
+        }
+        println!();
+        println!("{} passed, {} failed", self.checks.len() - self.failures(), self.failures());
+    }
+}
+
+/// Contracts deployed for one run
+struct Deployment {
+    factory: FactoryHostRef,
+    router: RouterHostRef,
+    token_a: LpTokenHostRef,
+    token_b: LpTokenHostRef,
+}
+
+/// Scenario running the DEX deployment acceptance test.
+pub struct LivenetTestsScenario;
+
+impl Scenario for LivenetTestsScenario {
+    fn args(&self) -> Vec<CommandArg> {
+        vec![]
+    }
+
+    fn run(
+        &self,
+        env: &HostEnv,
+        _container: &DeployedContractsContainer,
+        _args: Args
+    ) -> Result<(), Error> {
+        let mut report = Report::default();
+        let mut dex = deploy(env)?;
+        report.check("deploy dex", true, String::new());
+
+        let user = env.caller();
+        let seed = U256::from(1_000_000_000_000u64);
+        let liquidity_a = U256::from(100_000_000_000u64);
+        let liquidity_b = U256::from(400_000_000_000u64);
+        let swap_in = U256::from(1_000_000_000u64);
+        let router_address = dex.router.address().clone();
+
+        // Mint and approve
+        env.set_gas(CALL_GAS);
+        dex.token_a.try_mint(user, seed)?;
+        env.set_gas(CALL_GAS);
+        dex.token_b.try_mint(user, seed)?;
+        report.eq("mint token a", dex.token_a.balance_of(user), seed);
+        report.eq("mint token b", dex.token_b.balance_of(user), seed);
+
+        env.set_gas(CALL_GAS);
+        dex.token_a.try_approve(router_address, seed)?;
+        env.set_gas(CALL_GAS);
+        dex.token_b.try_approve(router_address, seed)?;
+        report.eq("approve token a", dex.token_a.allowance(user, router_address), seed);
+        report.eq("approve token b", dex.token_b.allowance(user, router_address), seed);
+
+        // Add liquidity (creates the pair)
+        let token_a = dex.token_a.address().clone();
+        let token_b = dex.token_b.address().clone();
+        let deadline = dex.router.suggest_deadline(DEADLINE_TTL);
+        env.set_gas(CALL_GAS);
+        let (added_a, added_b, liquidity) = dex.router.try_add_liquidity(
+            token_a, token_b, liquidity_a, liquidity_b, liquidity_a, liquidity_b, user, deadline,
+        )?;
+        report.eq("add liquidity: token a pulled", added_a, liquidity_a);
+        report.eq("add liquidity: token b pulled", added_b, liquidity_b);
+        report.check("add liquidity: lp minted", !liquidity.is_zero(), String::from("no LP minted"));
+
+        let pair = dex.factory.get_pair(token_a, token_b);
+        report.check("add liquidity: pair created", pair.is_some(), String::from("no pair registered"));
+        let Some(pair) = pair else {
+            report.print();
+            return Err(OdraError::ExecutionError(ExecutionError::User(LIVENET_FAILURE)).into());
+        };
+        let mut pair_token = LpToken::load(env, pair);
+        report.eq("add liquidity: lp balance", pair_token.balance_of(user), liquidity);
+        let (reserve_a, reserve_b) = dex.router.get_reserves(token_a, token_b);
+        report.eq("add liquidity: reserve a", reserve_a, liquidity_a);
+        report.eq("add liquidity: reserve b", reserve_b, liquidity_b);
+
+        // Swap exact in
+        let path = vec![token_a, token_b];
+        let quoted = dex.router.get_amounts_out(swap_in, path.clone());
+        let expected_out = quoted[1];
+        let balance_b_before = dex.token_b.balance_of(user);
+        env.set_gas(CALL_GAS);
+        let amounts = dex.router.try_swap_exact_tokens_for_tokens(
+            swap_in, expected_out, path, user, deadline,
+        )?;
+        report.eq("swap: output matches quote", amounts[1], expected_out);
+        report.eq(
+            "swap: token b received",
+            dex.token_b.balance_of(user) - balance_b_before,
+            expected_out,
+        );
+        let (reserve_a, reserve_b) = dex.router.get_reserves(token_a, token_b);
+        report.eq("swap: reserve a", reserve_a, liquidity_a + swap_in);
+        report.eq("swap: reserve b", reserve_b, liquidity_b - expected_out);
+
+        // Remove all liquidity
+        env.set_gas(CALL_GAS);
+        pair_token.try_approve(router_address, liquidity)?;
+        let balance_a_before = dex.token_a.balance_of(user);
+        let balance_b_before = dex.token_b.balance_of(user);
+        env.set_gas(CALL_GAS);
+        let (removed_a, removed_b) = dex.router.try_remove_liquidity(
+            token_a, token_b, liquidity, U256::zero(), U256::zero(), user, deadline,
+        )?;
+        report.eq("remove liquidity: lp burned", pair_token.balance_of(user), U256::zero());
+        report.eq(
+            "remove liquidity: token a returned",
+            dex.token_a.balance_of(user) - balance_a_before,
+            removed_a,
+        );
+        report.eq(
+            "remove liquidity: token b returned",
+            dex.token_b.balance_of(user) - balance_b_before,
+            removed_b,
+        );
+        let (reserve_a, reserve_b) = dex.router.get_reserves(token_a, token_b);
+        report.check(
+            "remove liquidity: only locked minimum remains",
+            reserve_a + removed_a == liquidity_a + swap_in
+                && reserve_b + removed_b == liquidity_b - expected_out,
+            format!("reserves left {} / {}", reserve_a, reserve_b),
+        );
+
+        report.print();
+        if report.failures() > 0 {
+            return Err(OdraError::ExecutionError(ExecutionError::User(LIVENET_FAILURE)).into());
+        }
+        Ok(())
+    }
+}
+
+impl ScenarioMetadata for LivenetTestsScenario {
+    const NAME: &'static str = "livenet-tests";
+    const DESCRIPTION: &'static str =
+        "Deploys a fresh DEX and runs the liquidity and swap acceptance checks";
+}
+
+/// Deploy a fresh PairFactory, Factory, Router, WCSPR and two test tokens
+fn deploy(env: &HostEnv) -> Result<Deployment, Error> {
+    let caller = env.caller();
+
+    env.set_gas(DEPLOY_GAS);
+    let pair_factory = PairFactory::try_deploy(env, NoArgs)?;
+    env.set_gas(DEPLOY_GAS);
+    let factory = Factory::try_deploy(env, FactoryInitArgs {
+        fee_to_setter: caller,
+        pair_factory: pair_factory.address().clone(),
+    })?;
+
+    let mut tokens = Vec::new();
+    for (name, symbol) in [("Livenet A", "LVA"), ("Livenet B", "LVB"), ("Wrapped CSPR", "WCSPR")] {
+        env.set_gas(DEPLOY_GAS);
+        tokens.push(LpToken::try_deploy(env, LpTokenInitArgs {
+            name: String::from(name),
+            symbol: String::from(symbol),
+        })?);
+    }
+    let wcspr = tokens.pop().expect("three tokens deployed");
+    let token_b = tokens.pop().expect("three tokens deployed");
+    let token_a = tokens.pop().expect("three tokens deployed");
+
+    env.set_gas(DEPLOY_GAS);
+    let router = Router::try_deploy(env, RouterInitArgs {
+        factory: factory.address().clone(),
+        wcspr: wcspr.address().clone(),
+    })?;
+
+    println!("Factory deployed at: {:?}", factory.address());
+    println!("Router deployed at: {:?}", router.address());
+
+    Ok(Deployment {
+        factory,
+        router,
+        token_a,
+        token_b,
+    })
+}
@@ -5,10 +5,52 @@
 //! - Managing pair registry
 //! - Setting protocol fees
 use odra::prelude::*;
+use odra::casper_types::{U256, U512};
+use odra::uints::ToU512;
 use odra::ContractRef;
 use crate::errors::DexError;
-use crate::events::PairCreated;
-use super::pair::PairFactoryContractRef;
+use crate::events::{PairCreated, PairCreationFeeUpdated, PairTypeRegistered};
+use crate::governance::parameter_registry::{ParameterRegistryContractRef, KEY_DEX_FEE_TO};
+use crate::governance::ParametersSynced;
+use crate::governance::schedule::{ParameterSchedule, ScheduledParameter};
+use crate::math::{SafeMath, DEFAULT_FEE_BPS, MAX_FEE_BPS};
+use super::pair::{PairContractRef, PairFactoryContractRef};
+use super::pair_key::{pair_key_preimage, sort_tokens, PairKey};
+
+/// Lowercase hex digits used to render pair salts
+const HEX_CHARS: &[u8; 16] = b"0123456789abcdef";
+
+/// Schedule key of the pair creation fee
+pub const PARAM_PAIR_CREATION_FEE: &str = "pair_creation_fee";
+
+/// Schedule key prefix of a pair type's swap fee ("pair_type_fee:<id>")
+pub const PARAM_PAIR_TYPE_FEE_PREFIX: &str = "pair_type_fee:";
+
+/// Pair type id of the default constant product pool (0.3% fee)
+pub const DEFAULT_PAIR_TYPE: u8 = 0;
+
+/// AMM curve implemented by a pair type
+#[odra::odra_type]
+pub enum PairKind {
+    /// x * y = k pool (the `Pair` contract)
+    ConstantProduct,
+    /// Low-slippage curve for pegged assets (not implemented yet; an
+    /// sCSPR/WCSPR pool would peg to `StakingManager::get_rate`)
+    Stable,
+    /// Concentrated liquidity (not implemented yet)
+    Concentrated,
+}
+
+/// Pair type metadata
+#[odra::odra_type]
+pub struct PairTypeInfo {
+    /// Curve implemented by pairs of this type
+    pub kind: PairKind,
+    /// Swap fee in basis points
+    pub fee_bps: u16,
+    /// Whether new pairs of this type can be created
+    pub enabled: bool,
+}
 
 /// Factory contract for creating and managing pairs
 #[odra::module]
@@ -26,6 +68,26 @@ pub struct Factory {
     all_pairs: Mapping<u32, Address>,
     /// Total number of pairs
     all_pairs_length: Var<u32>,
+    /// Mapping from pair salt to pair address
+    pairs_by_salt: Mapping<[u8; 32], Address>,
+    /// Optional parameter registry the fee settings are synced from
+    parameter_registry: Var<Option<Address>>,
+    /// Registry version the fee settings were last synced at
+    parameter_registry_version: Var<u64>,
+    /// Pair type registry: type id -> metadata
+    pair_types: Mapping<u8, PairTypeInfo>,
+    /// Pairs by sorted tokens and pair type
+    typed_pairs: Mapping<(Address, Address, u8), Address>,
+    /// Pair type each pair was created with
+    pair_type_of: Mapping<Address, u8>,
+    /// CSPR fee (in motes) charged for creating a pair
+    pair_creation_fee: Var<U512>,
+    /// Callers that create pairs without paying the fee (e.g. the Router)
+    fee_exempt: Mapping<Address, bool>,
+    /// Delayed fee changes announced ahead of time
+    schedule: SubModule<ParameterSchedule>,
+    /// Sequence number of the last event emitted
+    event_seq: Var<u64>,
 }
 
 #[odra::module]
@@ -36,6 +98,11 @@ impl Factory {
         self.pair_factory.set(pair_factory);
         self.fee_to.set(None);
         self.all_pairs_length.set(0);
+        self.pair_types.set(&DEFAULT_PAIR_TYPE, PairTypeInfo {
+            kind: PairKind::ConstantProduct,
+            fee_bps: DEFAULT_FEE_BPS,
+            enabled: true,
+        });
     }
 
     /// Get the fee recipient address
@@ -50,7 +117,7 @@ impl Factory {
 
     /// Get the pair address for two tokens
     pub fn get_pair(&self, token_a: Address, token_b: Address) -> Option<Address> {
-        let (token0, token1) = self.sort_tokens(token_a, token_b);
+        let (token0, token1) = sort_tokens(token_a, token_b);
         self.pairs.get(&(token0, token1))
     }
 
@@ -64,41 +131,139 @@ impl Factory {
         self.all_pairs_length.get_or_default()
     }
 
-    /// Create a new pair for two tokens
+    /// Get the pair address for two tokens and a pair type
+    pub fn get_pair_with_type(&self, token_a: Address, token_b: Address, pair_type: u8) -> Option<Address> {
+        let (token0, token1) = sort_tokens(token_a, token_b);
+        self.typed_pairs.get(&(token0, token1, pair_type))
+    }
+
+    /// Get the metadata of a pair type
+    pub fn get_pair_type(&self, pair_type: u8) -> Option<PairTypeInfo> {
+        self.pair_types.get(&pair_type)
+    }
+
+    /// Get the CSPR fee (in motes) charged for creating a pair
+    pub fn pair_creation_fee(&self) -> U512 {
+        self.pair_creation_fee.get_or_default()
+    }
+
+    /// Check if an address creates pairs without paying the fee
+    pub fn is_fee_exempt(&self, account: Address) -> bool {
+        self.fee_exempt.get(&account).unwrap_or(false)
+    }
+
+    /// Get the pair type a pair was created with
+    pub fn pair_type_of(&self, pair: Address) -> Option<u8> {
+        self.pair_type_of.get(&pair)
+    }
+
+    /// Get the deterministic salt for a token pair
+    /// The salt is the blake2b hash of the sorted token addresses, so it can be
+    /// computed off-chain before the pair exists
+    pub fn pair_salt(&self, token_a: Address, token_b: Address) -> [u8; 32] {
+        self.pair_salt_with_type(token_a, token_b, DEFAULT_PAIR_TYPE)
+    }
+
+    /// Get the deterministic salt for a token pair of a given type
+    /// The default type keeps the plain token hash; other types append the type id
+    pub fn pair_salt_with_type(&self, token_a: Address, token_b: Address, pair_type: u8) -> [u8; 32] {
+        let bytes = pair_key_preimage(token_a, token_b, pair_type)
+            .unwrap_or_revert_with(&self.env(), DexError::InvalidPair);
+        self.env().hash(bytes)
+    }
+
+    /// Get the canonical token order and key of a token pair
+    /// The key equals `pair_salt`, so off-chain tools can map it to a pair address
+    pub fn pair_key_for(&self, token_a: Address, token_b: Address) -> PairKey {
+        let (token0, token1) = sort_tokens(token_a, token_b);
+        PairKey { token0, token1, key: self.pair_salt(token_a, token_b) }
+    }
+
+    /// Get the package name a pair is (or will be) installed under
+    /// Casper assigns the package hash at install time, so the name derived
+    /// from the salt is the deterministic identifier available up front
+    pub fn pair_package_name(&self, token_a: Address, token_b: Address) -> String {
+        self.package_name_for_salt(self.pair_salt(token_a, token_b))
+    }
+
+    /// Get the pair address registered under a salt
+    pub fn get_pair_by_salt(&self, salt: [u8; 32]) -> Option<Address> {
+        self.pairs_by_salt.get(&salt)
+    }
+
+    /// Create a new default (constant product, 0.3%) pair for two tokens
     /// Returns the address of the created pair
+    #[odra(payable)]
     pub fn create_pair(
         &mut self,
         token_a: Address,
         token_b: Address,
+    ) -> Address {
+        self.create_pair_with_type(token_a, token_b, DEFAULT_PAIR_TYPE)
+    }
+
+    /// Create a new pair of a registered type for two tokens
+    /// Pairs of the default type are also what `get_pair` and the Router resolve
+    /// Non-exempt callers must attach at least `pair_creation_fee` in CSPR
+    #[odra(payable)]
+    pub fn create_pair_with_type(
+        &mut self,
+        token_a: Address,
+        token_b: Address,
+        pair_type: u8,
     ) -> Address {
         // Validate tokens
         if token_a == token_b {
             self.env().revert(DexError::IdenticalAddresses);
         }
 
+        let type_info = self.pair_types.get(&pair_type)
+            .unwrap_or_revert_with(&self.env(), DexError::InvalidConfiguration);
+        if !type_info.enabled {
+            self.env().revert(DexError::InvalidConfiguration);
+        }
+        // Only the constant product curve has an implementation so far
+        if type_info.kind != PairKind::ConstantProduct {
+            self.env().revert(DexError::InvalidConfiguration);
+        }
+
         // Sort tokens
-        let (token0, token1) = self.sort_tokens(token_a, token_b);
+        let (token0, token1) = sort_tokens(token_a, token_b);
 
         // Check if pair already exists
-        if self.pairs.get(&(token0, token1)).is_some() {
+        if self.typed_pairs.get(&(token0, token1, pair_type)).is_some()
+            || (pair_type == DEFAULT_PAIR_TYPE && self.pairs.get(&(token0, token1)).is_some())
+        {
             self.env().revert(DexError::PairExists);
         }
 
+        self.collect_creation_fee();
+
         // Create the new Pair contract using the factory
         let pair_factory_addr = self.pair_factory.get_or_revert_with(DexError::ZeroAddress);
         let mut pair_factory = PairFactoryContractRef::new(self.env(), pair_factory_addr);
         
         // Odra factory deploy returns (contract_package_hash, access_uref).
         // We store the package hash as the Pair identifier.
+        let salt = self.pair_salt_with_type(token0, token1, pair_type);
+        let package_name = self.package_name_for_salt(salt);
         let (pair_address, _pair_access_uref) = pair_factory.new_contract(
-            String::from("Pair"),
+            package_name,
             token0,
             token1,
             self.env().self_address()
         );
+        if type_info.fee_bps != DEFAULT_FEE_BPS {
+            PairContractRef::new(self.env(), pair_address).set_fee_bps(type_info.fee_bps);
+        }
 
         // Store the pair
-        self.pairs.set(&(token0, token1), pair_address);
+        if pair_type == DEFAULT_PAIR_TYPE {
+            self.pairs.set(&(token0, token1), pair_address);
+        }
+        self.typed_pairs.set(&(token0, token1, pair_type), pair_address);
+        self.pair_type_of.set(&pair_address, pair_type);
+        self.pairs_by_salt.set(&salt, pair_address);
         
         // Add to all pairs list
         let pair_index = self.all_pairs_length.get_or_default();
@@ -111,11 +276,288 @@ impl Factory {
             token1,
             pair: pair_address,
             pair_count: pair_index + 1,
+            seq: self.next_event_seq(),
         });
 
         pair_address
     }
 
+    /// Set a pair's circuit breaker: the largest move away from the pair's TWAP
+    /// in basis points, or 0 to disable. Only callable by fee_to_setter
+    pub fn set_pair_circuit_breaker(&mut self, pair: Address, max_move_bps: u16) {
+        let caller = self.env().caller();
+        if caller != self.fee_to_setter() {
+            self.env().revert(DexError::Unauthorized);
+        }
+        if self.pair_type_of.get(&pair).is_none() {
+            self.env().revert(DexError::PairNotFound);
+        }
+        PairContractRef::new(self.env(), pair).set_max_price_move_bps(max_move_bps);
+    }
+
+    /// Point a pair's LP token at a denylist and switch enforcement on or off
+    /// Only callable by fee_to_setter
+    pub fn set_pair_denylist(&mut self, pair: Address, denylist: Address, enforced: bool) {
+        let caller = self.env().caller();
+        if caller != self.fee_to_setter() {
+            self.env().revert(DexError::Unauthorized);
+        }
+        if self.pair_type_of.get(&pair).is_none() {
+            self.env().revert(DexError::PairNotFound);
+        }
+        PairContractRef::new(self.env(), pair).set_denylist(denylist, enforced);
+    }
+
+    /// Set the contracts a pair's LP token notifies on transfers
+    /// Only callable by fee_to_setter
+    pub fn set_pair_transfer_listeners(&mut self, pair: Address, listeners: Vec<Address>) {
+        let caller = self.env().caller();
+        if caller != self.fee_to_setter() {
+            self.env().revert(DexError::Unauthorized);
+        }
+        if self.pair_type_of.get(&pair).is_none() {
+            self.env().revert(DexError::PairNotFound);
+        }
+        PairContractRef::new(self.env(), pair).set_transfer_listeners(listeners);
+    }
+
+    /// Restrict a pair's flash loans to its allowlisted receivers, or make them
+    /// permissionless again. Only callable by fee_to_setter
+    pub fn set_pair_flash_allowlist_enforced(&mut self, pair: Address, enforced: bool) {
+        let caller = self.env().caller();
+        if caller != self.fee_to_setter() {
+            self.env().revert(DexError::Unauthorized);
+        }
+        if self.pair_type_of.get(&pair).is_none() {
+            self.env().revert(DexError::PairNotFound);
+        }
+        PairContractRef::new(self.env(), pair).set_flash_allowlist_enforced(enforced);
+    }
+
+    /// Add or remove a contract from a pair's flash receiver allowlist
+    /// Only callable by fee_to_setter
+    pub fn set_pair_flash_receiver(&mut self, pair: Address, receiver: Address, allowed: bool) {
+        let caller = self.env().caller();
+        if caller != self.fee_to_setter() {
+            self.env().revert(DexError::Unauthorized);
+        }
+        if self.pair_type_of.get(&pair).is_none() {
+            self.env().revert(DexError::PairNotFound);
+        }
+        PairContractRef::new(self.env(), pair).set_flash_receiver(receiver, allowed);
+    }
+
+    /// Start or stop a pair's swap and flash loan metrics
+    /// Only callable by fee_to_setter
+    pub fn set_pair_metrics_enabled(&mut self, pair: Address, enabled: bool) {
+        let caller = self.env().caller();
+        if caller != self.fee_to_setter() {
+            self.env().revert(DexError::Unauthorized);
+        }
+        if self.pair_type_of.get(&pair).is_none() {
+            self.env().revert(DexError::PairNotFound);
+        }
+        PairContractRef::new(self.env(), pair).set_metrics_enabled(enabled);
+    }
+
+    /// Close a pair's metrics epoch and start a new one; returns the new epoch
+    /// Only callable by fee_to_setter
+    pub fn rotate_pair_metrics(&mut self, pair: Address) -> u64 {
+        let caller = self.env().caller();
+        if caller != self.fee_to_setter() {
+            self.env().revert(DexError::Unauthorized);
+        }
+        if self.pair_type_of.get(&pair).is_none() {
+            self.env().revert(DexError::PairNotFound);
+        }
+        PairContractRef::new(self.env(), pair).rotate_metrics()
+    }
+
+    /// Set the share of a pair's swap fees kept for the protocol
+    /// Only callable by fee_to_setter
+    pub fn set_pair_protocol_fee_share(&mut self, pair: Address, share_bps: u16) {
+        let caller = self.env().caller();
+        if caller != self.fee_to_setter() {
+            self.env().revert(DexError::Unauthorized);
+        }
+        if self.pair_type_of.get(&pair).is_none() {
+            self.env().revert(DexError::PairNotFound);
+        }
+        PairContractRef::new(self.env(), pair).set_protocol_fee_share_bps(share_bps);
+    }
+
+    /// Set (or clear) the contract allowed to pay rebates out of a pair's
+    /// protocol fees. Only callable by fee_to_setter
+    pub fn set_pair_rebater(&mut self, pair: Address, rebater: Option<Address>) {
+        let caller = self.env().caller();
+        if caller != self.fee_to_setter() {
+            self.env().revert(DexError::Unauthorized);
+        }
+        if self.pair_type_of.get(&pair).is_none() {
+            self.env().revert(DexError::PairNotFound);
+        }
+        PairContractRef::new(self.env(), pair).set_rebater(rebater);
+    }
+
+    /// Send a pair's accrued protocol fees to `to`; returns the amounts sent
+    /// Only callable by fee_to_setter
+    pub fn collect_pair_protocol_fees(&mut self, pair: Address, to: Address) -> (U256, U256) {
+        let caller = self.env().caller();
+        if caller != self.fee_to_setter() {
+            self.env().revert(DexError::Unauthorized);
+        }
+        if self.pair_type_of.get(&pair).is_none() {
+            self.env().revert(DexError::PairNotFound);
+        }
+        PairContractRef::new(self.env(), pair).collect_protocol_fees(to)
+    }
+
+    /// Register or update a pair type
+    /// Only callable by fee_to_setter; existing pairs keep the fee they were created with
+    pub fn set_pair_type(&mut self, pair_type: u8, kind: PairKind, fee_bps: u16, enabled: bool) {
+        let caller = self.env().caller();
+        if caller != self.fee_to_setter() {
+            self.env().revert(DexError::Unauthorized);
+        }
+        if fee_bps > MAX_FEE_BPS {
+            self.env().revert(DexError::InvalidFee);
+        }
+        self.pair_types.set(&pair_type, PairTypeInfo {
+            kind: kind.clone(),
+            fee_bps,
+            enabled,
+        });
+
+        self.env().emit_event(PairTypeRegistered {
+            pair_type,
+            kind,
+            fee_bps,
+            enabled,
+            seq: self.next_event_seq(),
+        });
+    }
+
+    /// Set the CSPR fee (in motes) for creating a pair; zero disables it
+    /// Only callable by fee_to_setter
+    pub fn set_pair_creation_fee(&mut self, fee: U512) {
+        let caller = self.env().caller();
+        if caller != self.fee_to_setter() {
+            self.env().revert(DexError::Unauthorized);
+        }
+        self.pair_creation_fee.set(fee);
+
+        self.env().emit_event(PairCreationFeeUpdated {
+            fee,
+            updated_by: caller,
+            seq: self.next_event_seq(),
+        });
+    }
+
+    /// Set the delay (in seconds) applied to scheduled fee changes
+    /// Only callable by fee_to_setter; changes already scheduled keep their time.
+    /// A longer delay applies at once, a shorter one is scheduled behind the current one
+    pub fn set_schedule_delay(&mut self, delay: u64) {
+        let caller = self.env().caller();
+        if caller != self.fee_to_setter() {
+            self.env().revert(DexError::Unauthorized);
+        }
+        self.schedule.set_delay(delay);
+    }
+
+    /// Apply a scheduled schedule delay reduction once it is due
+    /// Callable by anyone
+    pub fn apply_schedule_delay(&mut self) {
+        self.schedule.apply_delay();
+    }
+
+    /// Announce a new pair creation fee that can be applied after the schedule delay
+    /// Only callable by fee_to_setter; replaces any pending change
+    pub fn schedule_set_pair_creation_fee(&mut self, fee: U512) {
+        let caller = self.env().caller();
+        if caller != self.fee_to_setter() {
+            self.env().revert(DexError::Unauthorized);
+        }
+        let fee = SafeMath::from_u512(fee).unwrap_or_else(|e| self.env().revert(e));
+        self.schedule.schedule(PARAM_PAIR_CREATION_FEE, fee);
+    }
+
+    /// Announce a new swap fee for a pair type that can be applied after the schedule delay
+    /// Only callable by fee_to_setter; replaces any pending change
+    pub fn schedule_set_pair_type_fee(&mut self, pair_type: u8, fee_bps: u16) {
+        let caller = self.env().caller();
+        if caller != self.fee_to_setter() {
+            self.env().revert(DexError::Unauthorized);
+        }
+        if fee_bps > MAX_FEE_BPS {
+            self.env().revert(DexError::InvalidFee);
+        }
+        if self.pair_types.get(&pair_type).is_none() {
+            self.env().revert(DexError::InvalidConfiguration);
+        }
+        self.schedule.schedule(&pair_type_fee_parameter(pair_type), U256::from(fee_bps));
+    }
+
+    /// Apply the scheduled pair creation fee once it is due
+    /// Callable by anyone
+    pub fn apply_pair_creation_fee(&mut self) {
+        let fee = self.schedule.take_due(PARAM_PAIR_CREATION_FEE).to_u512();
+        self.pair_creation_fee.set(fee);
+
+        self.env().emit_event(PairCreationFeeUpdated {
+            fee,
+            updated_by: self.fee_to_setter(),
+            seq: self.next_event_seq(),
+        });
+    }
+
+    /// Apply the scheduled swap fee of a pair type once it is due
+    /// Callable by anyone; existing pairs keep the fee they were created with
+    pub fn apply_pair_type_fee(&mut self, pair_type: u8) {
+        let fee_bps = self.schedule.take_due(&pair_type_fee_parameter(pair_type)).as_u32() as u16;
+        let mut info = self.pair_types.get(&pair_type)
+            .unwrap_or_else(|| self.env().revert(DexError::InvalidConfiguration));
+        info.fee_bps = fee_bps;
+        self.pair_types.set(&pair_type, info.clone());
+
+        self.env().emit_event(PairTypeRegistered {
+            pair_type,
+            kind: info.kind,
+            fee_bps,
+            enabled: info.enabled,
+            seq: self.next_event_seq(),
+        });
+    }
+
+    /// Drop a scheduled fee change
+    /// Only callable by fee_to_setter
+    pub fn cancel_scheduled(&mut self, parameter: String) {
+        let caller = self.env().caller();
+        if caller != self.fee_to_setter() {
+            self.env().revert(DexError::Unauthorized);
+        }
+        self.schedule.cancel(&parameter);
+    }
+
+    /// Get the delay (in seconds) applied to scheduled fee changes
+    pub fn schedule_delay(&self) -> u64 {
+        self.schedule.delay()
+    }
+
+    /// Get the pending change of a scheduled parameter
+    pub fn get_scheduled(&self, parameter: String) -> Option<ScheduledParameter> {
+        self.schedule.get(parameter)
+    }
+
+    /// Exempt (or stop exempting) an address from the pair creation fee
+    /// Only callable by fee_to_setter
+    pub fn set_fee_exempt(&mut self, account: Address, exempt: bool) {
+        let caller = self.env().caller();
+        if caller != self.fee_to_setter() {
+            self.env().revert(DexError::Unauthorized);
+        }
+        self.fee_exempt.set(&account, exempt);
+    }
+
     /// Set the fee recipient address
     /// Only callable by fee_to_setter
     pub fn set_fee_to(&mut self, fee_to: Address) {
@@ -146,6 +588,45 @@ impl Factory {
         self.fee_to_setter.set(new_fee_to_setter);
     }
 
+    /// Set (or clear) the parameter registry used by `sync_parameters`
+    /// Only callable by fee_to_setter
+    pub fn set_parameter_registry(&mut self, registry: Option<Address>) {
+        let caller = self.env().caller();
+        if caller != self.fee_to_setter() {
+            self.env().revert(DexError::Unauthorized);
+        }
+        self.parameter_registry.set(registry);
+        self.parameter_registry_version.set(0);
+    }
+
+    /// Get the parameter registry address
+    pub fn parameter_registry(&self) -> Option<Address> {
+        self.parameter_registry.get_or_default()
+    }
+
+    /// Pull the fee recipient from the parameter registry
+    /// Callable by anyone; does nothing if the registry has not changed
+    pub fn sync_parameters(&mut self) {
+        let registry_address = self.parameter_registry.get_or_default()
+            .unwrap_or_else(|| self.env().revert(DexError::InvalidConfiguration));
+        let registry = ParameterRegistryContractRef::new(self.env(), registry_address);
+
+        let version = registry.version();
+        if version == self.parameter_registry_version.get_or_default() {
+            return;
+        }
+
+        if let Some(fee_to) = registry.get_address(String::from(KEY_DEX_FEE_TO)) {
+            self.fee_to.set(Some(fee_to));
+        }
+        self.parameter_registry_version.set(version);
+
+        self.env().emit_event(ParametersSynced {
+            registry: registry_address,
+            version,
+        });
+    }
+
     /// Check if a pair exists
     pub fn pair_exists(&self, token_a: Address, token_b: Address) -> bool {
         self.get_pair(token_a, token_b).is_some()
@@ -153,17 +634,50 @@ impl Factory {
 
     // ============ Internal Functions ============
 
-    /// Sort two token addresses (smaller address first)
-    fn sort_tokens(&self, token_a: Address, token_b: Address) -> (Address, Address) {
-        if token_a < token_b {
-            (token_a, token_b)
-        } else {
-            (token_b, token_a)
+    /// Render a salt as a package name ("Pair-" + hex)
+    fn package_name_for_salt(&self, salt: [u8; 32]) -> String {
+        let mut name = String::from("Pair-");
+        for byte in salt.iter() {
+            name.push(HEX_CHARS[(byte >> 4) as usize] as char);
+            name.push(HEX_CHARS[(byte & 0x0f) as usize] as char);
+        }
+        name
+    }
+
+    /// Check the attached CSPR covers the creation fee and forward it
+    /// Fee-exempt callers may attach less (or nothing); whatever is attached
+    /// goes to fee_to, or to fee_to_setter while fees are disabled
+    fn collect_creation_fee(&self) {
+        let attached = self.env().attached_value();
+        let caller = self.env().caller();
+        if !self.is_fee_exempt(caller) && attached < self.pair_creation_fee.get_or_default() {
+            self.env().revert(DexError::InsufficientCreationFee);
+        }
+        if attached.is_zero() {
+            return;
         }
+        let recipient = self.fee_to().unwrap_or_else(|| self.fee_to_setter());
+        self.env().transfer_tokens(&recipient, &attached);
+    }
+
+    /// Sequence number of the last event emitted; indexers page from here
+    pub fn event_seq(&self) -> u64 {
+        self.event_seq.get_or_default()
+    }
+
+    /// Advance the event sequence and return the number for the next event
+    fn next_event_seq(&mut self) -> u64 {
+        let seq = self.event_seq.get_or_default() + 1;
+        self.event_seq.set(seq);
+        seq
     }
 }
 
 
+/// Schedule key of the swap fee of `pair_type`
+pub fn pair_type_fee_parameter(pair_type: u8) -> String {
+    format!("{}{}", PARAM_PAIR_TYPE_FEE_PREFIX, pair_type)
+}
 
 /// External interface for the Factory contract
 #[odra::external_contract]
@@ -173,7 +687,11 @@ pub trait FactoryContract {
     fn get_pair(&self, token_a: Address, token_b: Address) -> Option<Address>;
     fn all_pairs_at(&self, index: u32) -> Option<Address>;
     fn all_pairs_length(&self) -> u32;
+    fn pair_salt(&self, token_a: Address, token_b: Address) -> [u8; 32];
+    fn get_pair_by_salt(&self, salt: [u8; 32]) -> Option<Address>;
     fn create_pair(&mut self, token_a: Address, token_b: Address) -> Address;
+    fn get_pair_with_type(&self, token_a: Address, token_b: Address, pair_type: u8) -> Option<Address>;
+    fn pair_type_of(&self, pair: Address) -> Option<u8>;
     fn set_fee_to(&mut self, fee_to: Address);
     fn set_fee_to_setter(&mut self, new_fee_to_setter: Address);
 }
@@ -182,6 +700,8 @@ pub trait FactoryContract {
 mod tests {
     use super::*;
     use odra::host::{Deployer, HostEnv};
+    use crate::governance::{GovernanceError, ParameterScheduled};
+    use crate::governance::schedule::PARAM_SCHEDULE_DELAY;
 
     fn setup() -> (HostEnv, FactoryHostRef) {
         let env = odra_test::env();
@@ -221,6 +741,174 @@ mod tests {
         assert!(factory.pair_exists(token_b, token_a)); // Should work both ways
     }
 
+    #[test]
+    fn test_pair_salt_is_order_independent() {
+        let (env, factory) = setup();
+        let token_a = env.get_account(1);
+        let token_b = env.get_account(2);
+
+        let salt = factory.pair_salt(token_a, token_b);
+        assert_eq!(salt, factory.pair_salt(token_b, token_a));
+        assert_ne!(salt, factory.pair_salt(token_a, env.get_account(3)));
+
+        let key = factory.pair_key_for(token_b, token_a);
+        assert_eq!(key.key, salt);
+        assert_eq!((key.token0, key.token1), sort_tokens(token_a, token_b));
+
+        let name = factory.pair_package_name(token_a, token_b);
+        assert_eq!(name.len(), "Pair-".len() + 64);
+        assert_eq!(factory.get_pair_by_salt(salt), None);
+    }
+
+    #[test]
+    fn test_pair_type_registry() {
+        let (env, mut factory) = setup();
+        let token_a = env.get_account(1);
+        let token_b = env.get_account(2);
+
+        let default_type = factory.get_pair_type(DEFAULT_PAIR_TYPE).unwrap();
+        assert_eq!(default_type.kind, PairKind::ConstantProduct);
+        assert_eq!(default_type.fee_bps, DEFAULT_FEE_BPS);
+
+        factory.set_pair_type(1, PairKind::ConstantProduct, 5, true);
+        assert_eq!(factory.get_pair_type(1).unwrap().fee_bps, 5);
+        assert_ne!(
+            factory.pair_salt_with_type(token_a, token_b, 1),
+            factory.pair_salt(token_a, token_b)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "InvalidFee")]
+    fn test_pair_type_fee_capped() {
+        let (_env, mut factory) = setup();
+        factory.set_pair_type(1, PairKind::ConstantProduct, MAX_FEE_BPS + 1, true);
+    }
+
+    #[test]
+    fn test_pair_creation_fee_config() {
+        let (env, mut factory) = setup();
+        let router = env.get_account(3);
+
+        assert_eq!(factory.pair_creation_fee(), U512::zero());
+        factory.set_pair_creation_fee(U512::from(50_000_000_000u64));
+        assert_eq!(factory.pair_creation_fee(), U512::from(50_000_000_000u64));
+
+        assert!(!factory.is_fee_exempt(router));
+        factory.set_fee_exempt(router, true);
+        assert!(factory.is_fee_exempt(router));
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized")]
+    fn test_pair_creation_fee_only_fee_to_setter() {
+        let (env, mut factory) = setup();
+        env.set_caller(env.get_account(1));
+        factory.set_pair_creation_fee(U512::one());
+    }
+
+    #[test]
+    #[should_panic(expected = "InsufficientCreationFee")]
+    fn test_create_pair_requires_fee() {
+        let (env, mut factory) = setup();
+        factory.set_pair_creation_fee(U512::from(1_000u64));
+        env.set_caller(env.get_account(1));
+        factory.create_pair(env.get_account(4), env.get_account(5));
+    }
+
+    #[test]
+    fn test_scheduled_fee_changes_wait_for_delay() {
+        let (env, mut factory) = setup();
+        factory.set_schedule_delay(1_000);
+
+        // Announced immediately, applied only after the delay
+        factory.schedule_set_pair_creation_fee(U512::from(500u64));
+        factory.schedule_set_pair_type_fee(DEFAULT_PAIR_TYPE, 10);
+        let scheduled = factory.get_scheduled(String::from(PARAM_PAIR_CREATION_FEE)).unwrap();
+        assert_eq!(scheduled.value, U256::from(500u64));
+        assert!(env.emitted_event(factory.address(), ParameterScheduled {
+            parameter: String::from(PARAM_PAIR_CREATION_FEE),
+            value: U256::from(500u64),
+            effective_at: scheduled.effective_at,
+            scheduled_by: env.get_account(0),
+        }));
+        assert_eq!(
+            factory.try_apply_pair_creation_fee(),
+            Err(GovernanceError::ScheduleNotReady.into())
+        );
+        assert_eq!(factory.pair_creation_fee(), U512::zero());
+
+        env.advance_block_time(1_000);
+        env.set_caller(env.get_account(1));
+        factory.apply_pair_creation_fee();
+        factory.apply_pair_type_fee(DEFAULT_PAIR_TYPE);
+        assert_eq!(factory.pair_creation_fee(), U512::from(500u64));
+        assert_eq!(factory.get_pair_type(DEFAULT_PAIR_TYPE).unwrap().fee_bps, 10);
+        assert_eq!(factory.get_scheduled(String::from(PARAM_PAIR_CREATION_FEE)), None);
+    }
+
+    #[test]
+    fn test_schedule_delay_reduction_waits_for_current_delay() {
+        let (env, mut factory) = setup();
+        factory.set_schedule_delay(1_000);
+
+        // Shortening is announced, not applied, so it cannot skip the notice
+        factory.set_schedule_delay(0);
+        assert_eq!(factory.schedule_delay(), 1_000);
+        assert_eq!(
+            factory.try_apply_schedule_delay(),
+            Err(GovernanceError::ScheduleNotReady.into())
+        );
+
+        env.advance_block_time(1_000);
+        env.set_caller(env.get_account(1));
+        factory.apply_schedule_delay();
+        assert_eq!(factory.schedule_delay(), 0);
+
+        // Lengthening applies at once
+        env.set_caller(env.get_account(0));
+        factory.set_schedule_delay(2_000);
+        assert_eq!(factory.schedule_delay(), 2_000);
+        assert_eq!(factory.get_scheduled(String::from(PARAM_SCHEDULE_DELAY)), None);
+    }
+
+    #[test]
+    fn test_scheduled_creation_fee_beyond_u128() {
+        let (_env, mut factory) = setup();
+        let large = U512::from(u128::MAX) + 1;
+        factory.schedule_set_pair_creation_fee(large);
+        let scheduled = factory.get_scheduled(String::from(PARAM_PAIR_CREATION_FEE)).unwrap();
+        assert_eq!(scheduled.value, U256::from(u128::MAX) + 1);
+
+        // Beyond U256 reverts instead of truncating
+        assert_eq!(
+            factory.try_schedule_set_pair_creation_fee(U256::MAX.to_u512() + 1),
+            Err(DexError::Overflow.into())
+        );
+    }
+
+    #[test]
+    fn test_cancel_scheduled_fee_change() {
+        let (env, mut factory) = setup();
+        factory.schedule_set_pair_creation_fee(U512::from(500u64));
+        assert_eq!(
+            factory.try_schedule_set_pair_type_fee(DEFAULT_PAIR_TYPE, MAX_FEE_BPS + 1),
+            Err(DexError::InvalidFee.into())
+        );
+
+        env.set_caller(env.get_account(1));
+        assert_eq!(
+            factory.try_cancel_scheduled(String::from(PARAM_PAIR_CREATION_FEE)),
+            Err(DexError::Unauthorized.into())
+        );
+        env.set_caller(env.get_account(0));
+        factory.cancel_scheduled(String::from(PARAM_PAIR_CREATION_FEE));
+        assert_eq!(
+            factory.try_apply_pair_creation_fee(),
+            Err(GovernanceError::NotScheduled.into())
+        );
+    }
+
     #[test]
     fn test_set_fee_to() {
         let (env, mut factory) = setup();
@@ -0,0 +1,366 @@
+//! Liquidity Locker for the DEX
+//!
+//! Locks LP tokens until a chosen time and issues a transferable NFT receipt
+//! for each lock. Whoever holds the receipt at unlock time burns it to
+//! receive the LP, so locked positions can be sold or posted as collateral.
+//! The receipt follows the CEP-78 ownership surface (owner_of, balance_of,
+//! transfer, approve) without pulling in a full CEP-78 implementation.
+use odra::prelude::*;
+use odra::casper_types::U256;
+use odra::ContractRef;
+use crate::errors::DexError;
+use crate::token::Cep18TokenContractRef;
+
+/// A liquidity lock represented by a receipt token
+#[odra::odra_type]
+pub struct LiquidityLock {
+    /// Pair (LP token) that is locked
+    pub pair: Address,
+    /// Amount of LP tokens locked
+    pub amount: U256,
+    /// Timestamp from which the lock can be released
+    pub unlock_time: u64,
+    /// Address that created the lock
+    pub locked_by: Address,
+    /// Whether the lock was released and its receipt burned
+    pub released: bool,
+}
+
+/// Liquidity Locker contract
+#[odra::module]
+pub struct LiquidityLocker {
+    /// Lock data by receipt token id
+    locks: Mapping<u64, LiquidityLock>,
+    /// Receipt owner by token id
+    owners: Mapping<u64, Address>,
+    /// Number of receipts held per account
+    balances: Mapping<Address, u64>,
+    /// Approved operator per token id
+    approvals: Mapping<u64, Option<Address>>,
+    /// Next receipt token id
+    next_token_id: Var<u64>,
+    /// Total LP locked per pair
+    total_locked: Mapping<Address, U256>,
+    /// Sequence number of the last event emitted
+    event_seq: Var<u64>,
+}
+
+#[odra::module]
+impl LiquidityLocker {
+    /// Initialize the locker
+    pub fn init(&mut self) {
+        self.next_token_id.set(0);
+    }
+
+    // ============ Lock Functions ============
+
+    /// Lock LP tokens of a pair until `unlock_time`
+    /// Returns the id of the receipt minted to the caller
+    pub fn lock(&mut self, pair: Address, amount: U256, unlock_time: u64) -> u64 {
+        if amount.is_zero() {
+            self.env().revert(DexError::InsufficientAmount);
+        }
+        if unlock_time <= self.env().get_block_time() {
+            self.env().revert(DexError::InvalidConfiguration);
+        }
+
+        let caller = self.env().caller();
+        let mut lp_token = Cep18TokenContractRef::new(self.env(), pair);
+        if !lp_token.transfer_from(caller, self.env().self_address(), amount) {
+            self.env().revert(DexError::TransferFailed);
+        }
+
+        let token_id = self.next_token_id.get_or_default();
+        self.next_token_id.set(token_id + 1);
+        self.locks.set(&token_id, LiquidityLock {
+            pair,
+            amount,
+            unlock_time,
+            locked_by: caller,
+            released: false,
+        });
+        let total = self.total_locked.get(&pair).unwrap_or_default();
+        self.total_locked.set(&pair, total + amount);
+
+        // Mint the receipt
+        self.owners.set(&token_id, caller);
+        let balance = self.balances.get(&caller).unwrap_or_default();
+        self.balances.set(&caller, balance + 1);
+
+        self.env().emit_event(LiquidityLocked {
+            token_id,
+            pair,
+            owner: caller,
+            amount,
+            unlock_time,
+            seq: self.next_event_seq(),
+        });
+
+        token_id
+    }
+
+    /// Burn a receipt after its unlock time and release the LP to the caller
+    pub fn unlock(&mut self, token_id: u64) {
+        let caller = self.env().caller();
+        let owner = self.owner_of(token_id);
+        if caller != owner {
+            self.env().revert(DexError::Unauthorized);
+        }
+
+        let mut lock = self.locks.get(&token_id)
+            .unwrap_or_revert_with(&self.env(), DexError::InvalidConfiguration);
+        if self.env().get_block_time() < lock.unlock_time {
+            self.env().revert(DexError::LockActive);
+        }
+
+        // Burn the receipt
+        lock.released = true;
+        self.locks.set(&token_id, lock.clone());
+        self.burn_receipt(token_id, owner);
+        let total = self.total_locked.get(&lock.pair).unwrap_or_default();
+        self.total_locked.set(&lock.pair, total - lock.amount);
+
+        let mut lp_token = Cep18TokenContractRef::new(self.env(), lock.pair);
+        if !lp_token.transfer(caller, lock.amount) {
+            self.env().revert(DexError::TransferFailed);
+        }
+
+        self.env().emit_event(LiquidityUnlocked {
+            token_id,
+            pair: lock.pair,
+            owner: caller,
+            amount: lock.amount,
+            seq: self.next_event_seq(),
+        });
+    }
+
+    // ============ Receipt (NFT) Functions ============
+
+    /// Get the owner of a live receipt
+    pub fn owner_of(&self, token_id: u64) -> Address {
+        let released = self.locks.get(&token_id).map(|lock| lock.released).unwrap_or(true);
+        if released {
+            self.env().revert(DexError::InvalidConfiguration);
+        }
+        self.owners.get(&token_id)
+            .unwrap_or_revert_with(&self.env(), DexError::InvalidConfiguration)
+    }
+
+    /// Get the number of receipts an account holds
+    pub fn balance_of(&self, owner: Address) -> u64 {
+        self.balances.get(&owner).unwrap_or_default()
+    }
+
+    /// Get the operator approved for a receipt
+    pub fn get_approved(&self, token_id: u64) -> Option<Address> {
+        self.approvals.get(&token_id).flatten()
+    }
+
+    /// Approve an operator to transfer one receipt
+    pub fn approve(&mut self, spender: Address, token_id: u64) {
+        let caller = self.env().caller();
+        let owner = self.owner_of(token_id);
+        if caller != owner {
+            self.env().revert(DexError::Unauthorized);
+        }
+        self.approvals.set(&token_id, Some(spender));
+
+        self.env().emit_event(ReceiptApproval {
+            owner,
+            spender,
+            token_id,
+            seq: self.next_event_seq(),
+        });
+    }
+
+    /// Transfer a receipt (and with it the right to unlock)
+    /// Callable by the owner or the approved operator
+    pub fn transfer(&mut self, to: Address, token_id: u64) {
+        let caller = self.env().caller();
+        let owner = self.owner_of(token_id);
+        if caller != owner && self.get_approved(token_id) != Some(caller) {
+            self.env().revert(DexError::Unauthorized);
+        }
+
+        self.approvals.set(&token_id, None);
+        self.owners.set(&token_id, to);
+        let from_balance = self.balances.get(&owner).unwrap_or_default();
+        self.balances.set(&owner, from_balance - 1);
+        let to_balance = self.balances.get(&to).unwrap_or_default();
+        self.balances.set(&to, to_balance + 1);
+
+        self.env().emit_event(ReceiptTransfer {
+            from: owner,
+            to,
+            token_id,
+            seq: self.next_event_seq(),
+        });
+    }
+
+    // ============ View Functions ============
+
+    /// Get the lock behind a receipt
+    pub fn get_lock(&self, token_id: u64) -> Option<LiquidityLock> {
+        self.locks.get(&token_id)
+    }
+
+    /// Get the total LP locked for a pair
+    pub fn total_locked(&self, pair: Address) -> U256 {
+        self.total_locked.get(&pair).unwrap_or_default()
+    }
+
+    /// Get the number of receipts ever minted
+    pub fn total_receipts(&self) -> u64 {
+        self.next_token_id.get_or_default()
+    }
+
+    // ============ Internal Functions ============
+
+    fn burn_receipt(&mut self, token_id: u64, owner: Address) {
+        self.approvals.set(&token_id, None);
+        let balance = self.balances.get(&owner).unwrap_or_default();
+        self.balances.set(&owner, balance - 1);
+
+        self.env().emit_event(ReceiptTransfer {
+            from: owner,
+            to: self.env().self_address(),
+            token_id,
+            seq: self.next_event_seq(),
+        });
+    }
+
+    /// Sequence number of the last event emitted; indexers page from here
+    pub fn event_seq(&self) -> u64 {
+        self.event_seq.get_or_default()
+    }
+
+    /// Advance the event sequence and return the number for the next event
+    fn next_event_seq(&mut self) -> u64 {
+        let seq = self.event_seq.get_or_default() + 1;
+        self.event_seq.set(seq);
+        seq
+    }
+}
+
+/// Event emitted when LP tokens are locked
+#[odra::event]
+pub struct LiquidityLocked {
+    /// Receipt token id
+    pub token_id: u64,
+    /// Locked pair
+    pub pair: Address,
+    /// Receipt owner
+    pub owner: Address,
+    /// Amount of LP tokens locked
+    pub amount: U256,
+    /// Unlock timestamp
+    pub unlock_time: u64,
+    /// Per-contract event sequence number
+    pub seq: u64,
+}
+
+/// Event emitted when a lock is released
+#[odra::event]
+pub struct LiquidityUnlocked {
+    /// Burned receipt token id
+    pub token_id: u64,
+    /// Unlocked pair
+    pub pair: Address,
+    /// Receipt holder that received the LP
+    pub owner: Address,
+    /// Amount of LP tokens released
+    pub amount: U256,
+    /// Per-contract event sequence number
+    pub seq: u64,
+}
+
+/// Event emitted when a receipt changes hands (burns go to the locker)
+#[odra::event]
+pub struct ReceiptTransfer {
+    /// Previous owner
+    pub from: Address,
+    /// New owner
+    pub to: Address,
+    /// Receipt token id
+    pub token_id: u64,
+    /// Per-contract event sequence number
+    pub seq: u64,
+}
+
+/// Event emitted when an operator is approved for a receipt
+#[odra::event]
+pub struct ReceiptApproval {
+    /// Receipt owner
+    pub owner: Address,
+    /// Approved operator
+    pub spender: Address,
+    /// Receipt token id
+    pub token_id: u64,
+    /// Per-contract event sequence number
+    pub seq: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use odra::host::{Deployer, HostEnv, NoArgs};
+    use crate::token::{LpToken, LpTokenInitArgs, LpTokenHostRef};
+
+    fn setup() -> (HostEnv, LiquidityLockerHostRef, LpTokenHostRef) {
+        let env = odra_test::env();
+        let locker = LiquidityLocker::deploy(&env, NoArgs);
+        let mut lp = LpToken::deploy(&env, LpTokenInitArgs {
+            name: String::from("DEX LP Token"),
+            symbol: String::from("DEX-LP"),
+        });
+        let owner = env.get_account(1);
+        lp.mint(owner, U256::from(1_000));
+        env.set_caller(owner);
+        lp.approve(locker.address().clone(), U256::from(1_000));
+        (env, locker, lp)
+    }
+
+    #[test]
+    fn test_receipt_holder_unlocks() {
+        let (env, mut locker, lp) = setup();
+        let owner = env.get_account(1);
+        let buyer = env.get_account(2);
+        let lp_addr = lp.address().clone();
+        let unlock_time = env.block_time() + 1_000;
+
+        let token_id = locker.lock(lp_addr, U256::from(600), unlock_time);
+        assert_eq!(locker.owner_of(token_id), owner);
+        assert_eq!(locker.total_locked(lp_addr), U256::from(600));
+
+        // Sell the locked position
+        locker.transfer(buyer, token_id);
+        assert_eq!(locker.balance_of(owner), 0);
+        assert_eq!(locker.balance_of(buyer), 1);
+
+        env.advance_block_time(1_000);
+        env.set_caller(buyer);
+        locker.unlock(token_id);
+
+        assert_eq!(lp.balance_of(buyer), U256::from(600));
+        assert_eq!(locker.balance_of(buyer), 0);
+        assert!(locker.get_lock(token_id).unwrap().released);
+        assert_eq!(locker.total_locked(lp_addr), U256::zero());
+    }
+
+    #[test]
+    #[should_panic(expected = "LockActive")]
+    fn test_cannot_unlock_early() {
+        let (env, mut locker, lp) = setup();
+        let token_id = locker.lock(lp.address().clone(), U256::from(600), env.block_time() + 1_000);
+        locker.unlock(token_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized")]
+    fn test_only_owner_or_approved_transfers() {
+        let (env, mut locker, lp) = setup();
+        let token_id = locker.lock(lp.address().clone(), U256::from(600), env.block_time() + 1_000);
+        env.set_caller(env.get_account(3));
+        locker.transfer(env.get_account(3), token_id);
+    }
+}
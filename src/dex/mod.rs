@@ -4,14 +4,22 @@
 //! - Pair: Individual liquidity pools for token pairs
 //! - Factory: Creates and manages pairs
 //! - Router: User-facing contract for swaps and liquidity management
+//! - SingleSidedLp: Periphery for one-token deposits with IL protection
+//! - LiquidityLocker: Time locks for LP tokens with transferable NFT receipts
+//! - pair_key: canonical token order and pair keys shared by the contracts
 
 pub mod pair;
 pub mod factory;
 pub mod router;
+pub mod single_sided;
+pub mod liquidity_locker;
+pub mod pair_key;
 
 #[cfg(test)]
 pub mod tests;
 
 pub use pair::Pair;
 pub use factory::Factory;
-pub use router::Router;
\ No newline at end of file
+pub use router::Router;
+pub use single_sided::SingleSidedLp;
+pub use liquidity_locker::LiquidityLocker;
\ No newline at end of file
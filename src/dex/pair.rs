@@ -5,13 +5,47 @@
 //! - Adding liquidity (minting LP tokens)
 //! - Removing liquidity (burning LP tokens)
 //! - Swapping tokens
+//! - Flash loans of either reserve for a fee
+//!
+//! Donations (tokens transferred to the pair outside of a swap or mint)
+//! only enter the reserves at the current price; the rest stays
+//! skimmable, so a donation cannot move the price a quote was taken at.
+//! The unmatched side of a deposit is sent back to the minter.
+//!
+//! With metrics enabled by the factory, the pair counts swaps and flash loans
+//! per input token, with the LP fee they charged in that token.
 use odra::prelude::*;
 use odra::casper_types::U256;
+use odra::casper_types::bytesrepr::Bytes;
 use odra::ContractRef;
 use crate::errors::DexError;
-use crate::events::{LiquidityAdded, LiquidityRemoved, Swap, Sync};
-use crate::math::MINIMUM_LIQUIDITY;
+use crate::events::{FlashLoan, LiquidityAdded, LiquidityRemoved, Swap, Sync};
+use crate::governance::metrics::{EntrypointMetrics, ProtocolMetrics};
+use odra::casper_types::account::AccountHash;
+use crate::math::{BPS_DENOMINATOR, DEFAULT_FEE_BPS, FLASH_LOAN_FEE_BPS, MAX_FEE_BPS, MINIMUM_LIQUIDITY};
 use crate::token::{LpToken, Cep18TokenContractRef};
+use super::pair_key::sort_tokens;
+
+/// Holder of the permanently locked MINIMUM_LIQUIDITY (the zero account hash,
+/// which has no key and can never move its balance)
+pub fn locked_liquidity_holder() -> Address {
+    Address::Account(AccountHash::new([0u8; 32]))
+}
+
+/// Length of the window the circuit breaker averages the price over
+pub const BREAKER_TWAP_WINDOW: u64 = 10 * 60;
+
+/// Largest share of each swap fee the protocol may take (50%)
+pub const MAX_PROTOCOL_FEE_SHARE_BPS: u16 = 5_000;
+
+/// Metrics entry for swaps paid in token0; fees are in token0
+pub const METRIC_SWAP_TOKEN0_IN: &str = "swap_token0_in";
+/// Metrics entry for swaps paid in token1; fees are in token1
+pub const METRIC_SWAP_TOKEN1_IN: &str = "swap_token1_in";
+/// Metrics entry for flash loans of token0; fees are in token0
+pub const METRIC_FLASH_LOAN_TOKEN0: &str = "flash_loan_token0";
+/// Metrics entry for flash loans of token1; fees are in token1
+pub const METRIC_FLASH_LOAN_TOKEN1: &str = "flash_loan_token1";
 
 /// Liquidity Pair contract
 #[odra::module(factory=on)]
@@ -29,10 +63,8 @@ pub struct Pair {
     /// Block timestamp of last update
     block_timestamp_last: Var<u64>,
     /// Cumulative price of token0 (for oracle)
-    #[allow(dead_code)]
     price0_cumulative_last: Var<U256>,
     /// Cumulative price of token1 (for oracle)
-    #[allow(dead_code)]
     price1_cumulative_last: Var<U256>,
     /// K value from last liquidity event (for fee calculation)
     k_last: Var<U256>,
@@ -40,6 +72,28 @@ pub struct Pair {
     factory: Var<Address>,
     /// Reentrancy lock
     locked: Var<bool>,
+    /// Swap fee in basis points (unset means the default 0.3%)
+    fee_bps: Var<u16>,
+    /// Largest move away from the breaker TWAP in basis points (0 = disabled)
+    max_price_move_bps: Var<u16>,
+    /// Time-weighted price of token0 over the last completed breaker window
+    twap_price0: Var<U256>,
+    /// Cumulative price of token0 when the current breaker window opened
+    twap_cumulative0: Var<U256>,
+    /// Block time the current breaker window opened at
+    twap_time: Var<u64>,
+    /// Whether flash loans are restricted to allowlisted receivers
+    flash_allowlist_enforced: Var<bool>,
+    /// Contracts allowed to receive flash loans while the allowlist is enforced
+    flash_receivers: Mapping<Address, bool>,
+    /// Share of each swap fee kept for the protocol, in basis points
+    protocol_fee_share_bps: Var<u16>,
+    /// Protocol fees accrued per token, held by the pair outside the reserves
+    protocol_fees: Mapping<Address, U256>,
+    /// Contract allowed to pay loyalty rebates out of the protocol fees
+    rebater: Var<Option<Address>>,
+    /// Opt-in swap and flash loan counters
+    metrics: SubModule<ProtocolMetrics>,
 }
 
 #[odra::module(factory=on)]
@@ -52,11 +106,7 @@ impl Pair {
         factory: Address,
     ) {
         // Ensure tokens are ordered
-        let (t0, t1) = if token0 < token1 {
-            (token0, token1)
-        } else {
-            (token1, token0)
-        };
+        let (t0, t1) = sort_tokens(token0, token1);
 
         self.token0.set(t0);
         self.token1.set(t1);
@@ -71,6 +121,175 @@ impl Pair {
         self.lp_token.init(name, symbol);
     }
 
+    /// Get the swap fee in basis points
+    pub fn fee_bps(&self) -> u16 {
+        self.fee_bps.get().unwrap_or(DEFAULT_FEE_BPS)
+    }
+
+    /// Set the swap fee in basis points
+    /// Only callable by the factory, right after creation
+    pub fn set_fee_bps(&mut self, fee_bps: u16) {
+        if self.env().caller() != self.factory.get_or_revert_with(DexError::Unauthorized) {
+            self.env().revert(DexError::Unauthorized);
+        }
+        if fee_bps > MAX_FEE_BPS {
+            self.env().revert(DexError::InvalidFee);
+        }
+        self.fee_bps.set(fee_bps);
+    }
+
+    /// Get the circuit breaker limit in basis points (0 = disabled)
+    pub fn max_price_move_bps(&self) -> u16 {
+        self.max_price_move_bps.get_or_default()
+    }
+
+    /// Set the circuit breaker limit in basis points (0 disables it)
+    /// Swaps may not move the price further than this from its recent TWAP
+    /// Only callable by the factory
+    pub fn set_max_price_move_bps(&mut self, max_move_bps: u16) {
+        if self.env().caller() != self.factory.get_or_revert_with(DexError::Unauthorized) {
+            self.env().revert(DexError::Unauthorized);
+        }
+        self.max_price_move_bps.set(max_move_bps);
+        // Re-arm from the spot price, not a TWAP left from an older window
+        self.twap_price0.set(U256::zero());
+    }
+
+    /// Get the share of each swap fee kept for the protocol, in basis points
+    pub fn protocol_fee_share_bps(&self) -> u16 {
+        self.protocol_fee_share_bps.get_or_default()
+    }
+
+    /// Set the share of each swap fee kept for the protocol (0 = all to LPs)
+    /// Only callable by the factory
+    pub fn set_protocol_fee_share_bps(&mut self, share_bps: u16) {
+        if self.env().caller() != self.factory.get_or_revert_with(DexError::Unauthorized) {
+            self.env().revert(DexError::Unauthorized);
+        }
+        if share_bps > MAX_PROTOCOL_FEE_SHARE_BPS {
+            self.env().revert(DexError::InvalidFee);
+        }
+        self.protocol_fee_share_bps.set(share_bps);
+    }
+
+    /// Get the contract allowed to pay rebates out of the protocol fees
+    pub fn rebater(&self) -> Option<Address> {
+        self.rebater.get_or_default()
+    }
+
+    /// Set (or clear) the contract allowed to pay rebates out of the protocol fees
+    /// Only callable by the factory
+    pub fn set_rebater(&mut self, rebater: Option<Address>) {
+        if self.env().caller() != self.factory.get_or_revert_with(DexError::Unauthorized) {
+            self.env().revert(DexError::Unauthorized);
+        }
+        self.rebater.set(rebater);
+    }
+
+    /// Point the LP token at a denylist and switch enforcement on or off
+    /// Only callable by the factory
+    pub fn set_denylist(&mut self, denylist: Address, enforced: bool) {
+        if self.env().caller() != self.factory.get_or_revert_with(DexError::Unauthorized) {
+            self.env().revert(DexError::Unauthorized);
+        }
+        self.lp_token.configure_denylist(denylist, enforced);
+    }
+
+    /// Set the contracts notified on LP transfers, mints and burns
+    /// Only callable by the factory
+    pub fn set_transfer_listeners(&mut self, listeners: Vec<Address>) {
+        if self.env().caller() != self.factory.get_or_revert_with(DexError::Unauthorized) {
+            self.env().revert(DexError::Unauthorized);
+        }
+        self.lp_token.configure_transfer_listeners(listeners);
+    }
+
+    /// Restrict flash loans to allowlisted receivers, or make them permissionless
+    /// Only callable by the factory
+    pub fn set_flash_allowlist_enforced(&mut self, enforced: bool) {
+        if self.env().caller() != self.factory.get_or_revert_with(DexError::Unauthorized) {
+            self.env().revert(DexError::Unauthorized);
+        }
+        self.flash_allowlist_enforced.set(enforced);
+    }
+
+    /// Add or remove a flash loan receiver from the allowlist
+    /// Only callable by the factory
+    pub fn set_flash_receiver(&mut self, receiver: Address, allowed: bool) {
+        if self.env().caller() != self.factory.get_or_revert_with(DexError::Unauthorized) {
+            self.env().revert(DexError::Unauthorized);
+        }
+        self.flash_receivers.set(&receiver, allowed);
+    }
+
+    /// Start or stop recording swap and flash loan metrics
+    /// Only callable by the factory
+    pub fn set_metrics_enabled(&mut self, enabled: bool) {
+        if self.env().caller() != self.factory.get_or_revert_with(DexError::Unauthorized) {
+            self.env().revert(DexError::Unauthorized);
+        }
+        self.metrics.set_enabled(enabled);
+    }
+
+    /// Close the current metrics epoch and start a new one; returns the new epoch
+    /// Only callable by the factory
+    pub fn rotate_metrics(&mut self) -> u64 {
+        if self.env().caller() != self.factory.get_or_revert_with(DexError::Unauthorized) {
+            self.env().revert(DexError::Unauthorized);
+        }
+        self.metrics.rotate()
+    }
+
+    /// Check if swap and flash loan metrics are being recorded
+    pub fn is_metrics_enabled(&self) -> bool {
+        self.metrics.is_enabled()
+    }
+
+    /// Get the current metrics epoch
+    pub fn get_metrics_epoch(&self) -> u64 {
+        self.metrics.epoch()
+    }
+
+    /// Get the counters of a metrics entry (`METRIC_*`) in an epoch
+    pub fn get_metrics(&self, epoch: u64, entrypoint: String) -> EntrypointMetrics {
+        self.metrics.get(epoch, entrypoint)
+    }
+
+    /// Check if flash loans are restricted to allowlisted receivers
+    pub fn is_flash_allowlist_enforced(&self) -> bool {
+        self.flash_allowlist_enforced.get_or_default()
+    }
+
+    /// Check if `receiver` may receive flash loans
+    pub fn is_flash_receiver_allowed(&self, receiver: Address) -> bool {
+        !self.is_flash_allowlist_enforced() || self.flash_receivers.get(&receiver).unwrap_or_default()
+    }
+
+    /// Get the contracts notified on LP transfers
+    pub fn transfer_listeners(&self) -> Vec<Address> {
+        self.lp_token.transfer_listeners()
+    }
+
+    /// Get the LP token's denylist address, if any
+    pub fn denylist(&self) -> Option<Address> {
+        self.lp_token.denylist()
+    }
+
+    /// Check if the LP token enforces its denylist
+    pub fn is_denylist_enforced(&self) -> bool {
+        self.lp_token.is_denylist_enforced()
+    }
+
+    /// Get the cumulative prices (token0, token1) scaled by 1e18
+    /// TWAP = (cumulative_now - cumulative_then) / (time_now - time_then)
+    pub fn get_price_cumulatives(&self) -> (U256, U256) {
+        self.ensure_unlocked();
+        (
+            self.price0_cumulative_last.get_or_default(),
+            self.price1_cumulative_last.get_or_default(),
+        )
+    }
+
     /// Get token0 address
     pub fn token0(&self) -> Address {
         self.token0.get_or_revert_with(DexError::InvalidPair)
@@ -82,12 +301,18 @@ impl Pair {
     }
 
     /// Get current reserves
+    /// Reverts while the pair is locked, see `is_locked`
     pub fn get_reserves(&self) -> (U256, U256, u64) {
-        (
-            self.reserve0.get_or_default(),
-            self.reserve1.get_or_default(),
-            self.block_timestamp_last.get_or_default(),
-        )
+        self.ensure_unlocked();
+        self.reserves()
+    }
+
+    /// Check if a swap, mint, burn, flash loan, skim or sync is in progress
+    /// Reserve and price views revert while it is, so a callback cannot read
+    /// half-updated state (read-only reentrancy); integrators reading other
+    /// pair state from callbacks should check this first
+    pub fn is_locked(&self) -> bool {
+        self.locked.get_or_default()
     }
 
     /// Get LP token total supply
@@ -100,6 +325,11 @@ impl Pair {
         self.lp_token.balance_of(owner)
     }
 
+    /// Sequence number of the last event emitted by this pair
+    pub fn event_seq(&self) -> u64 {
+        self.lp_token.event_seq()
+    }
+
     /// Transfer LP tokens
     pub fn transfer(&mut self, to: Address, amount: U256) -> bool {
         self.lp_token.transfer(to, amount)
@@ -115,54 +345,80 @@ impl Pair {
         self.lp_token.transfer_from(from, to, amount)
     }
 
+    /// Increase the caller's LP token allowance for a spender
+    pub fn increase_allowance(&mut self, spender: Address, amount: U256) -> bool {
+        self.lp_token.increase_allowance(spender, amount)
+    }
+
+    /// Decrease the caller's LP token allowance for a spender
+    pub fn decrease_allowance(&mut self, spender: Address, amount: U256) -> bool {
+        self.lp_token.decrease_allowance(spender, amount)
+    }
+
     /// Mint LP tokens when liquidity is added
     /// Returns the amount of LP tokens minted
     pub fn mint(&mut self, to: Address) -> U256 {
         self.lock();
 
-        let (reserve0, reserve1, _) = self.get_reserves();
+        let (reserve0, reserve1, _) = self.reserves();
         
         // Get current balances
         let balance0 = self.get_token_balance(self.token0());
         let balance1 = self.get_token_balance(self.token1());
 
         // Calculate amounts deposited
-        let amount0 = self.safe_sub(balance0, reserve0);
-        let amount1 = self.safe_sub(balance1, reserve1);
+        let mut amount0 = self.safe_sub(balance0, reserve0);
+        let mut amount1 = self.safe_sub(balance1, reserve1);
 
         let total_supply = self.total_supply();
         let liquidity: U256;
+        let mut locked_mint = None;
 
         if total_supply.is_zero() {
             // First liquidity provision: sqrt(amount0 * amount1) - MINIMUM_LIQUIDITY
             let product = self.safe_mul(amount0, amount1);
             liquidity = self.safe_sub(self.sqrt(product), U256::from(MINIMUM_LIQUIDITY));
             
-            // Permanently lock MINIMUM_LIQUIDITY tokens
-            // Get self_address before mutable borrow
-            let self_addr = Address::from(self.env().self_address());
-            self.lp_token.mint(
-                self_addr,
+            // Permanently lock MINIMUM_LIQUIDITY tokens with an unspendable holder.
+            // Minting them to the pair would let the next burn sweep them out.
+            let from = self.lp_token.mint_deferred(
+                locked_liquidity_holder(),
                 U256::from(MINIMUM_LIQUIDITY),
             );
+            locked_mint = Some(from);
         } else {
-            // Subsequent liquidity: min(amount0 * totalSupply / reserve0, amount1 * totalSupply / reserve1)
+            // Subsequent liquidity: min(amount * totalSupply / reserve) over both tokens
+            // The locked MINIMUM_LIQUIDITY makes inflating the share price cost
+            // the inflater ~1000x what rounding takes from later depositors
             let liquidity0 = self.safe_div(self.safe_mul(amount0, total_supply), reserve0);
             let liquidity1 = self.safe_div(self.safe_mul(amount1, total_supply), reserve1);
             liquidity = if liquidity0 < liquidity1 { liquidity0 } else { liquidity1 };
+
+            // Keep only what the shares are worth (rounded up, in the pool's
+            // favour) and send the unmatched rest back to the minter, so it
+            // cannot be skimmed or swapped against by anyone else
+            let used0 = self.shares_value_rounded_up(liquidity, reserve0, total_supply);
+            let used1 = self.shares_value_rounded_up(liquidity, reserve1, total_supply);
+            if amount0 > used0 {
+                self.safe_transfer(self.token0(), to, amount0 - used0);
+                amount0 = used0;
+            }
+            if amount1 > used1 {
+                self.safe_transfer(self.token1(), to, amount1 - used1);
+                amount1 = used1;
+            }
         }
 
         if liquidity.is_zero() {
             self.env().revert(DexError::InsufficientLiquidityMinted);
         }
 
-        self.lp_token.mint(to, liquidity);
+        let from = self.lp_token.mint_deferred(to, liquidity);
 
-        // Update reserves
-        self.update_reserves(balance0, balance1);
+        self.update_reserves(self.safe_add(reserve0, amount0), self.safe_add(reserve1, amount1));
 
         // Update k_last for fee calculation
-        let (new_reserve0, new_reserve1, _) = self.get_reserves();
+        let (new_reserve0, new_reserve1, _) = self.reserves();
         self.k_last.set(self.safe_mul(new_reserve0, new_reserve1));
 
         self.env().emit_event(LiquidityAdded {
@@ -171,9 +427,16 @@ impl Pair {
             amount0,
             amount1,
             liquidity,
+            seq: self.lp_token.next_event_seq(),
         });
 
         self.unlock();
+
+        // Listeners run once the pair is unlocked so they can read its reserves
+        if let Some(from) = locked_mint {
+            self.lp_token.notify_listeners(from, locked_liquidity_holder(), U256::from(MINIMUM_LIQUIDITY));
+        }
+        self.lp_token.notify_listeners(from, to, liquidity);
         liquidity
     }
 
@@ -182,13 +445,14 @@ impl Pair {
     pub fn burn(&mut self, to: Address) -> (U256, U256) {
         self.lock();
 
-        let (_reserve0, _reserve1, _) = self.get_reserves();
+        let (reserve0, reserve1, _) = self.reserves();
         let token0 = self.token0();
         let token1 = self.token1();
 
-        // Get current balances
+        // Fold in any donation at the current price before pricing the shares
         let balance0 = self.get_token_balance(token0);
         let balance1 = self.get_token_balance(token1);
+        let (reserve0, reserve1) = self.absorbed_reserves(reserve0, reserve1, balance0, balance1);
 
         // Get LP tokens sent to this contract
         let liquidity = self.lp_token.balance_of(self.env().self_address());
@@ -198,9 +462,9 @@ impl Pair {
             self.env().revert(DexError::InsufficientLiquidity);
         }
 
-        // Calculate amounts to return: amount = liquidity * balance / totalSupply
-        let amount0 = self.safe_div(self.safe_mul(liquidity, balance0), total_supply);
-        let amount1 = self.safe_div(self.safe_mul(liquidity, balance1), total_supply);
+        // Calculate amounts to return: amount = liquidity * reserve / totalSupply
+        let amount0 = self.safe_div(self.safe_mul(liquidity, reserve0), total_supply);
+        let amount1 = self.safe_div(self.safe_mul(liquidity, reserve1), total_supply);
 
         if amount0.is_zero() && amount1.is_zero() {
             self.env().revert(DexError::InsufficientLiquidityBurned);
@@ -209,16 +473,16 @@ impl Pair {
         // Burn LP tokens
         // Get self_address before mutable borrow
         let self_addr = self.env().self_address();
-        self.lp_token.burn(self_addr, liquidity);
+        let burned_to = self.lp_token.burn_deferred(self_addr, liquidity);
 
         // Transfer tokens to user
         self.safe_transfer(token0, to, amount0);
         self.safe_transfer(token1, to, amount1);
 
         // Update reserves
-        let new_balance0 = self.safe_sub(balance0, amount0);
-        let new_balance1 = self.safe_sub(balance1, amount1);
-        self.update_reserves(new_balance0, new_balance1);
+        let new_reserve0 = self.safe_sub(reserve0, amount0);
+        let new_reserve1 = self.safe_sub(reserve1, amount1);
+        self.update_reserves(new_reserve0, new_reserve1);
 
         self.env().emit_event(LiquidityRemoved {
             provider: to,
@@ -226,9 +490,13 @@ impl Pair {
             amount0,
             amount1,
             liquidity,
+            seq: self.lp_token.next_event_seq(),
         });
 
         self.unlock();
+
+        // Listeners run once the pair is unlocked so they can read its reserves
+        self.lp_token.notify_listeners(self_addr, burned_to, liquidity);
         (amount0, amount1)
     }
 
@@ -242,17 +510,82 @@ impl Pair {
         to: Address,
     ) {
         self.lock();
+        self.swap_internal(amount0_out, amount1_out, to);
+        self.unlock();
+    }
+
+    /// Swap everything sent to the pair since the last update, with a floor on the output
+    ///
+    /// The input is measured from balances, the output is priced at the
+    /// current reserves and the pair's fee, and the call reverts if that is
+    /// below `amount_out_min`. Callers that skip the router (aggregators,
+    /// integrations) get slippage protection without pricing the swap themselves.
+    /// Returns the output amount
+    pub fn swap_exact_in(&mut self, amount_out_min: U256, to: Address) -> U256 {
+        self.lock();
+
+        let (reserve0, reserve1, _) = self.reserves();
+        let amount0_in = self.get_token_balance(self.token0()).saturating_sub(reserve0);
+        let amount1_in = self.get_token_balance(self.token1()).saturating_sub(reserve1);
+        if amount0_in.is_zero() == amount1_in.is_zero() {
+            self.env().revert(DexError::InsufficientInputAmount);
+        }
+
+        let zero_for_one = !amount0_in.is_zero();
+        let (amount_in, reserve_in, reserve_out) = if zero_for_one {
+            (amount0_in, reserve0, reserve1)
+        } else {
+            (amount1_in, reserve1, reserve0)
+        };
+        if reserve_in.is_zero() || reserve_out.is_zero() {
+            self.env().revert(DexError::InsufficientLiquidity);
+        }
+        let amount_in_with_fee = self.safe_mul(
+            amount_in,
+            U256::from(BPS_DENOMINATOR - self.fee_bps() as u128),
+        );
+        let amount_out = self.safe_div(
+            self.safe_mul(amount_in_with_fee, reserve_out),
+            self.safe_add(self.safe_mul(reserve_in, U256::from(BPS_DENOMINATOR)), amount_in_with_fee),
+        );
+        if amount_out.is_zero() || amount_out < amount_out_min {
+            self.env().revert(DexError::InsufficientOutputAmount);
+        }
 
+        if zero_for_one {
+            self.swap_internal(U256::zero(), amount_out, to);
+        } else {
+            self.swap_internal(amount_out, U256::zero(), to);
+        }
+
+        self.unlock();
+        amount_out
+    }
+
+    /// Swap body shared by `swap` and `swap_exact_in`; the caller holds the lock
+    fn swap_internal(
+        &mut self,
+        amount0_out: U256,
+        amount1_out: U256,
+        to: Address,
+    ) {
         if amount0_out.is_zero() && amount1_out.is_zero() {
             self.env().revert(DexError::InsufficientOutputAmount);
         }
 
-        let (reserve0, reserve1, _) = self.get_reserves();
+        let (reserve0, reserve1, _) = self.reserves();
 
         if amount0_out >= reserve0 || amount1_out >= reserve1 {
             self.env().revert(DexError::InsufficientLiquidity);
         }
 
+        let max_move_bps = self.max_price_move_bps.get_or_default();
+        let reference = if max_move_bps > 0 {
+            self.refresh_breaker_twap(reserve0, reserve1)
+        } else {
+            U256::zero()
+        };
+
         let token0 = self.token0();
         let token1 = self.token1();
 
@@ -293,28 +626,54 @@ impl Pair {
         }
 
         // Verify K invariant (with fee adjustment)
+        let fee = U256::from(self.fee_bps());
+        let scale = U256::from(BPS_DENOMINATOR);
         let balance0_adjusted = self.safe_sub(
-            self.safe_mul(balance0, U256::from(1000)),
-            self.safe_mul(amount0_in, U256::from(3)),
+            self.safe_mul(balance0, scale),
+            self.safe_mul(amount0_in, fee),
         );
         let balance1_adjusted = self.safe_sub(
-            self.safe_mul(balance1, U256::from(1000)),
-            self.safe_mul(amount1_in, U256::from(3)),
+            self.safe_mul(balance1, scale),
+            self.safe_mul(amount1_in, fee),
         );
 
         let k_new = self.safe_mul(balance0_adjusted, balance1_adjusted);
         let k_old = self.safe_mul(
             self.safe_mul(reserve0, reserve1),
-            U256::from(1000000),
+            self.safe_mul(scale, scale),
         );
 
         if k_new < k_old {
             self.env().revert(DexError::KInvariantViolated);
         }
 
+        // Set the protocol's share of the fee aside; it stays out of the
+        // reserves, which still cover K since the share is part of the fee
+        let balance0 = self.accrue_protocol_fee(token0, amount0_in, balance0);
+        let balance1 = self.accrue_protocol_fee(token1, amount1_in, balance1);
+
+        // Circuit breaker: cap the move away from the TWAP, so neither one
+        // block nor a walk over several blocks can push the price far
+        if max_move_bps > 0 {
+            let price = self.price0_of(balance0, balance1);
+            let diff = if price > reference { price - reference } else { reference - price };
+            if self.safe_mul(diff, U256::from(BPS_DENOMINATOR))
+                > self.safe_mul(reference, U256::from(max_move_bps))
+            {
+                self.env().revert(DexError::PriceMoveTooLarge);
+            }
+        }
+
         // Update reserves
         self.update_reserves(balance0, balance1);
 
+        if !amount0_in.is_zero() {
+            self.metrics.record(METRIC_SWAP_TOKEN0_IN, self.safe_div(self.safe_mul(amount0_in, fee), scale));
+        }
+        if !amount1_in.is_zero() {
+            self.metrics.record(METRIC_SWAP_TOKEN1_IN, self.safe_div(self.safe_mul(amount1_in, fee), scale));
+        }
+
         self.env().emit_event(Swap {
             sender: self.env().caller(),
             pair: self.env().self_address(),
@@ -323,6 +682,78 @@ impl Pair {
             amount0_out,
             amount1_out,
             to,
+            seq: self.lp_token.next_event_seq(),
+        });
+    }
+
+    /// Fee owed on a flash loan of `amount` (rounded up)
+    pub fn flash_fee(&self, amount: U256) -> U256 {
+        let scale = U256::from(BPS_DENOMINATOR);
+        let numerator = self.safe_mul(amount, U256::from(FLASH_LOAN_FEE_BPS));
+        self.safe_div(self.safe_add(numerator, scale - U256::one()), scale)
+    }
+
+    /// Lend `amount` of one of the pair's tokens to `receiver` for one call
+    ///
+    /// The receiver gets the funds, then `on_flash_loan` is called on it and
+    /// must send back `amount` plus the fee before returning. The fee stays
+    /// in the reserves, so it accrues to LPs. The pair is locked for the
+    /// whole call: swaps, mints, burns, skims and syncs revert until it ends.
+    /// While the factory enforces the flash allowlist, only allowlisted
+    /// receivers can borrow.
+    pub fn flash_loan(&mut self, token: Address, amount: U256, receiver: Address, data: Bytes) {
+        self.lock();
+        if !self.is_flash_receiver_allowed(receiver) {
+            self.env().revert(DexError::FlashReceiverNotAllowed);
+        }
+
+        let token0 = self.token0();
+        let token1 = self.token1();
+        if token != token0 && token != token1 {
+            self.env().revert(DexError::InvalidPair);
+        }
+        if receiver == token0 || receiver == token1 {
+            self.env().revert(DexError::InvalidPair);
+        }
+        if amount.is_zero() {
+            self.env().revert(DexError::InsufficientAmount);
+        }
+
+        let (reserve0, reserve1, _) = self.reserves();
+        let reserve = if token == token0 { reserve0 } else { reserve1 };
+        if amount >= reserve {
+            self.env().revert(DexError::InsufficientLiquidity);
+        }
+
+        let fee = self.flash_fee(amount);
+        let initiator = self.env().caller();
+        self.safe_transfer(token, receiver, amount);
+        FlashBorrowerContractRef::new(self.env(), receiver)
+            .on_flash_loan(initiator, token, amount, fee, data);
+
+        let balance0 = self.get_token_balance(token0);
+        let balance1 = self.get_token_balance(token1);
+        let (balance, other_balance, other_reserve) = if token == token0 {
+            (balance0, balance1, reserve1)
+        } else {
+            (balance1, balance0, reserve0)
+        };
+        if balance < self.safe_add(reserve, fee) || other_balance < other_reserve {
+            self.env().revert(DexError::FlashLoanNotRepaid);
+        }
+
+        self.update_reserves(balance0, balance1);
+        let metric = if token == token0 { METRIC_FLASH_LOAN_TOKEN0 } else { METRIC_FLASH_LOAN_TOKEN1 };
+        self.metrics.record(metric, fee);
+
+        self.env().emit_event(FlashLoan {
+            pair: self.env().self_address(),
+            initiator,
+            receiver,
+            token,
+            amount,
+            fee,
+            seq: self.lp_token.next_event_seq(),
         });
 
         self.unlock();
@@ -330,9 +761,10 @@ impl Pair {
 
     /// Force reserves to match balances (for recovery)
     pub fn skim(&mut self, to: Address) {
+        self.lock();
         let token0 = self.token0();
         let token1 = self.token1();
-        let (reserve0, reserve1, _) = self.get_reserves();
+        let (reserve0, reserve1, _) = self.reserves();
 
         let balance0 = self.get_token_balance(token0);
         let balance1 = self.get_token_balance(token1);
@@ -343,22 +775,72 @@ impl Pair {
         if balance1 > reserve1 {
             self.safe_transfer(token1, to, self.safe_sub(balance1, reserve1));
         }
+        self.unlock();
     }
 
-    /// Force balances to match reserves (for recovery)
+    /// Force reserves to match balances (for recovery)
+    /// Donations are only absorbed at the current price; a balance below
+    /// its reserve is always synced down
     pub fn sync(&mut self) {
+        self.lock();
         let token0 = self.token0();
         let token1 = self.token1();
+        let (reserve0, reserve1, _) = self.reserves();
 
         let balance0 = self.get_token_balance(token0);
         let balance1 = self.get_token_balance(token1);
 
-        self.update_reserves(balance0, balance1);
+        let (new_reserve0, new_reserve1) = self.absorbed_reserves(reserve0, reserve1, balance0, balance1);
+        self.update_reserves(new_reserve0, new_reserve1);
+        self.unlock();
+    }
+
+    /// Get the balances held above the reserves (donations not yet absorbed)
+    /// They are credited to the next swap's input or can be skimmed
+    pub fn get_excess_balances(&self) -> (U256, U256) {
+        self.ensure_unlocked();
+        let (reserve0, reserve1, _) = self.reserves();
+        (
+            self.get_token_balance(self.token0()).saturating_sub(reserve0),
+            self.get_token_balance(self.token1()).saturating_sub(reserve1),
+        )
+    }
+
+    /// Get the protocol fees accrued in `token` and not yet paid out
+    pub fn protocol_fees(&self, token: Address) -> U256 {
+        self.protocol_fees.get(&token).unwrap_or_default()
+    }
+
+    /// Send the accrued protocol fees of both tokens to `to`
+    /// Only callable by the factory. Returns the amounts sent
+    pub fn collect_protocol_fees(&mut self, to: Address) -> (U256, U256) {
+        if self.env().caller() != self.factory.get_or_revert_with(DexError::Unauthorized) {
+            self.env().revert(DexError::Unauthorized);
+        }
+        self.lock();
+        let amount0 = self.take_protocol_fees(self.token0(), to, U256::MAX);
+        let amount1 = self.take_protocol_fees(self.token1(), to, U256::MAX);
+        self.unlock();
+        (amount0, amount1)
+    }
+
+    /// Pay up to `amount` of `token` to `to` out of the accrued protocol fees
+    /// Only callable by the rebater; LP reserves are never touched, so the
+    /// payment is capped at what the protocol has accrued. Returns the amount paid
+    pub fn pay_rebate(&mut self, token: Address, to: Address, amount: U256) -> U256 {
+        if Some(self.env().caller()) != self.rebater() {
+            self.env().revert(DexError::Unauthorized);
+        }
+        self.lock();
+        let paid = self.take_protocol_fees(token, to, amount);
+        self.unlock();
+        paid
     }
 
     /// Get the price of token0 in terms of token1
     pub fn get_price0(&self) -> U256 {
-        let (reserve0, reserve1, _) = self.get_reserves();
+        self.ensure_unlocked();
+        let (reserve0, reserve1, _) = self.reserves();
         if reserve0.is_zero() {
             self.env().revert(DexError::InsufficientLiquidity);
         }
@@ -370,7 +852,8 @@ impl Pair {
 
     /// Get the price of token1 in terms of token0
     pub fn get_price1(&self) -> U256 {
-        let (reserve0, reserve1, _) = self.get_reserves();
+        self.ensure_unlocked();
+        let (reserve0, reserve1, _) = self.reserves();
         if reserve1.is_zero() {
             self.env().revert(DexError::InsufficientLiquidity);
         }
@@ -384,6 +867,22 @@ impl Pair {
 
     /// Update reserves and emit Sync event
     fn update_reserves(&mut self, balance0: U256, balance1: U256) {
+        // Accumulate the prices that held since the last update
+        let now = self.env().get_block_time();
+        let elapsed = now.saturating_sub(self.block_timestamp_last.get_or_default());
+        let (reserve0, reserve1, _) = self.reserves();
+        if elapsed > 0 && !reserve0.is_zero() && !reserve1.is_zero() {
+            let elapsed = U256::from(elapsed);
+            let price0 = self.price0_of(reserve0, reserve1);
+            let price1 = self.price0_of(reserve1, reserve0);
+            let cumulative0 = self.price0_cumulative_last.get_or_default()
+                .overflowing_add(price0.overflowing_mul(elapsed).0).0;
+            let cumulative1 = self.price1_cumulative_last.get_or_default()
+                .overflowing_add(price1.overflowing_mul(elapsed).0).0;
+            self.price0_cumulative_last.set(cumulative0);
+            self.price1_cumulative_last.set(cumulative1);
+        }
+
         self.reserve0.set(balance0);
         self.reserve1.set(balance1);
         self.block_timestamp_last.set(self.env().get_block_time());
@@ -392,13 +891,105 @@ impl Pair {
             pair: self.env().self_address(),
             reserve0: balance0,
             reserve1: balance1,
+            seq: self.lp_token.next_event_seq(),
         });
     }
 
-    /// Get token balance of this contract
+    /// Breaker reference price: the TWAP of token0 over the last completed
+    /// window, read from the price cumulatives. A window closes on the
+    /// first swap at least BREAKER_TWAP_WINDOW after it opened; until the
+    /// first one closes, the spot price when the breaker armed is used
+    fn refresh_breaker_twap(&mut self, reserve0: U256, reserve1: U256) -> U256 {
+        let now = self.env().get_block_time();
+        let spot = self.price0_of(reserve0, reserve1);
+        // Cumulative as of now, counting the current reserves up to this block
+        let elapsed = now.saturating_sub(self.block_timestamp_last.get_or_default());
+        let cumulative0 = self.price0_cumulative_last.get_or_default()
+            .overflowing_add(spot.overflowing_mul(U256::from(elapsed)).0).0;
+
+        let twap = self.twap_price0.get_or_default();
+        if twap.is_zero() {
+            self.open_breaker_window(spot, cumulative0, now);
+            return spot;
+        }
+        let window = now.saturating_sub(self.twap_time.get_or_default());
+        if window >= BREAKER_TWAP_WINDOW {
+            let delta = cumulative0.overflowing_sub(self.twap_cumulative0.get_or_default()).0;
+            let twap = self.safe_div(delta, U256::from(window));
+            self.open_breaker_window(twap, cumulative0, now);
+            return twap;
+        }
+        twap
+    }
+
+    /// Store the breaker reference and start a new window
+    fn open_breaker_window(&mut self, twap: U256, cumulative0: U256, now: u64) {
+        self.twap_price0.set(twap);
+        self.twap_cumulative0.set(cumulative0);
+        self.twap_time.set(now);
+    }
+
+    /// Reserves after absorbing the part of the balances above them that
+    /// keeps the reserve ratio. An empty side, or a balance below its
+    /// reserve, takes the balances as they are
+    fn absorbed_reserves(&self, reserve0: U256, reserve1: U256, balance0: U256, balance1: U256) -> (U256, U256) {
+        if reserve0.is_zero() || reserve1.is_zero() || balance0 < reserve0 || balance1 < reserve1 {
+            return (balance0, balance1);
+        }
+        let excess0 = balance0 - reserve0;
+        let excess1 = balance1 - reserve1;
+        let matched0 = excess0.min(self.safe_div(self.safe_mul(excess1, reserve0), reserve1));
+        let matched1 = excess1.min(self.safe_div(self.safe_mul(matched0, reserve1), reserve0));
+        (self.safe_add(reserve0, matched0), self.safe_add(reserve1, matched1))
+    }
+
+    /// Tokens `liquidity` shares are worth out of `reserve`, rounded up
+    fn shares_value_rounded_up(&self, liquidity: U256, reserve: U256, total_supply: U256) -> U256 {
+        let numerator = self.safe_mul(liquidity, reserve);
+        self.safe_div(self.safe_add(numerator, total_supply - U256::one()), total_supply)
+    }
+
+    /// Price of token0 in token1 (scaled by 1e18) for the given reserves
+    fn price0_of(&self, reserve0: U256, reserve1: U256) -> U256 {
+        if reserve0.is_zero() {
+            return U256::zero();
+        }
+        self.safe_div(self.safe_mul(reserve1, U256::from(10u128.pow(18))), reserve0)
+    }
+
+    /// Get token balance of this contract, less the accrued protocol fees
     fn get_token_balance(&self, token: Address) -> U256 {
         let token_ref = Cep18TokenContractRef::new(self.env(), token);
-        token_ref.balance_of(self.env().self_address())
+        token_ref.balance_of(self.env().self_address()).saturating_sub(self.protocol_fees(token))
+    }
+
+    /// Accrue the protocol's share of the fee on `amount_in` of `token`
+    /// Returns what is left of `balance` for the reserves
+    fn accrue_protocol_fee(&mut self, token: Address, amount_in: U256, balance: U256) -> U256 {
+        let share_bps = self.protocol_fee_share_bps();
+        if share_bps == 0 || amount_in.is_zero() {
+            return balance;
+        }
+        let scale = U256::from(BPS_DENOMINATOR);
+        let fee = self.safe_div(self.safe_mul(amount_in, U256::from(self.fee_bps())), scale);
+        let cut = self.safe_div(self.safe_mul(fee, U256::from(share_bps)), scale);
+        if cut.is_zero() {
+            return balance;
+        }
+        self.protocol_fees.set(&token, self.safe_add(self.protocol_fees(token), cut));
+        self.safe_sub(balance, cut)
+    }
+
+    /// Send up to `amount` of the protocol fees accrued in `token` to `to`
+    fn take_protocol_fees(&mut self, token: Address, to: Address, amount: U256) -> U256 {
+        let accrued = self.protocol_fees(token);
+        let paid = amount.min(accrued);
+        if paid.is_zero() {
+            return paid;
+        }
+        self.protocol_fees.set(&token, accrued - paid);
+        self.safe_transfer(token, to, paid);
+        paid
     }
 
     /// Safe transfer tokens
@@ -412,7 +1003,7 @@ impl Pair {
 
     /// Reentrancy lock
     fn lock(&mut self) {
-        if self.locked.get_or_default() {
+        if self.is_locked() {
             self.env().revert(DexError::Locked);
         }
         self.locked.set(true);
@@ -423,6 +1014,22 @@ impl Pair {
         self.locked.set(false);
     }
 
+    /// Revert if the pair is locked; guards views against read-only reentrancy
+    fn ensure_unlocked(&self) {
+        if self.is_locked() {
+            self.env().revert(DexError::Locked);
+        }
+    }
+
+    /// Current reserves without the lock check, for use inside locked calls
+    fn reserves(&self) -> (U256, U256, u64) {
+        (
+            self.reserve0.get_or_default(),
+            self.reserve1.get_or_default(),
+            self.block_timestamp_last.get_or_default(),
+        )
+    }
+
     /// Safe multiplication with overflow check
     fn safe_mul(&self, a: U256, b: U256) -> U256 {
         a.checked_mul(b).unwrap_or_else(|| {
@@ -430,6 +1037,13 @@ impl Pair {
         })
     }
 
+    /// Safe addition with overflow check
+    fn safe_add(&self, a: U256, b: U256) -> U256 {
+        a.checked_add(b).unwrap_or_else(|| {
+            self.env().revert(DexError::Overflow);
+        })
+    }
+
     /// Safe subtraction with underflow check
     fn safe_sub(&self, a: U256, b: U256) -> U256 {
         a.checked_sub(b).unwrap_or_else(|| {
@@ -460,10 +1074,49 @@ impl Pair {
     }
 }
 
+/// Callback interface for flash loan receivers
+#[odra::external_contract]
+pub trait FlashBorrower {
+    /// Called by the pair after sending `amount` of `token`; must send back
+    /// `amount + fee` to the pair before returning
+    fn on_flash_loan(&mut self, initiator: Address, token: Address, amount: U256, fee: U256, data: Bytes);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use odra::host::Deployer;
+    use odra::host::{Deployer, HostEnv, NoArgs};
+    use crate::testing::deploy_token;
+    use crate::token::LpTokenHostRef;
+
+    fn setup_pool() -> (HostEnv, PairHostRef, LpTokenHostRef, LpTokenHostRef) {
+        let env = odra_test::env();
+        let mut token_a = deploy_token(&env, "Token A", "TKA");
+        let mut token_b = deploy_token(&env, "Token B", "TKB");
+        for i in 1..3 {
+            token_a.mint(env.get_account(i), U256::from(10_000_000u64));
+            token_b.mint(env.get_account(i), U256::from(10_000_000u64));
+        }
+        let pair = Pair::deploy(&env, PairInitArgs {
+            token0: token_a.address().clone(),
+            token1: token_b.address().clone(),
+            factory: env.get_account(0),
+        });
+        (env, pair, token_a, token_b)
+    }
+
+    fn deposit(
+        pair: &mut PairHostRef,
+        token_a: &mut LpTokenHostRef,
+        token_b: &mut LpTokenHostRef,
+        amount: u64,
+        to: Address,
+    ) -> U256 {
+        let pair_addr = pair.address().clone();
+        token_a.transfer(pair_addr, U256::from(amount));
+        token_b.transfer(pair_addr, U256::from(amount));
+        pair.mint(to)
+    }
 
     #[test]
     fn test_pair_init() {
@@ -493,4 +1146,547 @@ mod tests {
         assert_eq!(reserve0, U256::zero());
         assert_eq!(reserve1, U256::zero());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_minimum_liquidity_stays_locked() {
+        let (env, mut pair, mut token_a, mut token_b) = setup_pool();
+        let provider = env.get_account(1);
+
+        env.set_caller(provider);
+        let liquidity = deposit(&mut pair, &mut token_a, &mut token_b, 10_000, provider);
+        assert_eq!(liquidity, U256::from(9_000));
+        assert_eq!(pair.balance_of(locked_liquidity_holder()), U256::from(MINIMUM_LIQUIDITY));
+
+        // Burning the whole position leaves the locked shares behind
+        let pair_addr = pair.address().clone();
+        pair.transfer(pair_addr, liquidity);
+        pair.burn(provider);
+        assert_eq!(pair.total_supply(), U256::from(MINIMUM_LIQUIDITY));
+        let (reserve0, reserve1, _) = pair.get_reserves();
+        assert_eq!(reserve0, U256::from(1_000));
+        assert_eq!(reserve1, U256::from(1_000));
+    }
+
+    #[test]
+    fn test_donation_attack_is_unprofitable() {
+        let (env, mut pair, mut token_a, mut token_b) = setup_pool();
+        let attacker = env.get_account(1);
+        let victim = env.get_account(2);
+        let pair_addr = pair.address().clone();
+
+        // Attacker seeds the pool with the smallest possible position
+        env.set_caller(attacker);
+        let attacker_shares = deposit(&mut pair, &mut token_a, &mut token_b, 1_001, attacker);
+        assert_eq!(attacker_shares, U256::one());
+
+        // ...then donates to inflate the share price
+        let donation = 1_000_000u64;
+        token_a.transfer(pair_addr, U256::from(donation));
+        token_b.transfer(pair_addr, U256::from(donation));
+        pair.sync();
+
+        // A deposit worth less than one share reverts instead of minting nothing
+        env.set_caller(victim);
+        token_a.transfer(pair_addr, U256::from(500));
+        token_b.transfer(pair_addr, U256::from(500));
+        assert_eq!(pair.try_mint(victim), Err(DexError::InsufficientLiquidityMinted.into()));
+        pair.skim(victim);
+
+        // Victim deposits after the donation
+        let victim_deposit = 500_000u64;
+        let victim_shares = deposit(&mut pair, &mut token_a, &mut token_b, victim_deposit, victim);
+        assert!(!victim_shares.is_zero());
+
+        // Both exit
+        pair.transfer(pair_addr, victim_shares);
+        let (victim_out, _) = pair.burn(victim);
+        env.set_caller(attacker);
+        pair.transfer(pair_addr, attacker_shares);
+        let (attacker_out, _) = pair.burn(attacker);
+
+        // Victim loses under 0.2% to rounding; the attacker loses over 99% of
+        // what they put in, most of it to the locked shares
+        let victim_loss = U256::from(victim_deposit) - victim_out;
+        let attacker_loss = U256::from(1_001u64 + donation) - attacker_out;
+        assert!(victim_loss * U256::from(500) < U256::from(victim_deposit));
+        assert!(attacker_out * U256::from(100) < U256::from(1_001u64 + donation));
+        assert!(attacker_loss > victim_loss * U256::from(100));
+    }
+
+    #[test]
+    fn test_one_sided_donation_cannot_move_quote() {
+        let (env, mut pair, mut token_a, mut token_b) = setup_pool();
+        let attacker = env.get_account(1);
+        let victim = env.get_account(2);
+        let pair_addr = pair.address().clone();
+        let a_is_token0 = pair.token0() == token_a.address().clone();
+
+        // Thin pool owned by the attacker
+        env.set_caller(attacker);
+        let shares = deposit(&mut pair, &mut token_a, &mut token_b, 10_000, attacker);
+        let reserves_before = pair.get_reserves();
+
+        // Front-run: dump token A into the pair and sync to cheapen it
+        token_a.transfer(pair_addr, U256::from(1_000_000));
+        pair.sync();
+        assert_eq!(pair.get_reserves().0, reserves_before.0);
+        assert_eq!(pair.get_reserves().1, reserves_before.1);
+        let (excess0, excess1) = pair.get_excess_balances();
+        let excess_a = if a_is_token0 { excess0 } else { excess1 };
+        assert_eq!(excess_a, U256::from(1_000_000));
+
+        // A burn does not pay the donation out either
+        pair.transfer(pair_addr, shares);
+        let (amount0, amount1) = pair.burn(attacker);
+        assert_eq!(amount0, U256::from(9_000));
+        assert_eq!(amount1, U256::from(9_000));
+        assert_eq!(pair.get_reserves().0, U256::from(1_000));
+
+        // The victim still gets the quote taken before the donation
+        env.set_caller(victim);
+        pair.skim(attacker);
+        let before = token_b.balance_of(victim);
+        swap_in(&mut pair, &mut token_a, 100, victim);
+        // 100 in at 1_000:1_000 reserves and 0.3% fee
+        assert_eq!(token_b.balance_of(victim) - before, U256::from(90));
+    }
+
+    #[test]
+    fn test_unmatched_deposit_surplus_is_refunded() {
+        let (env, mut pair, mut token_a, mut token_b) = setup_pool();
+        let provider = env.get_account(1);
+        let pair_addr = pair.address().clone();
+
+        env.set_caller(provider);
+        deposit(&mut pair, &mut token_a, &mut token_b, 10_000, provider);
+
+        // 1_000 A with 5_000 B only adds liquidity at the 1:1 price
+        token_a.transfer(pair_addr, U256::from(1_000));
+        token_b.transfer(pair_addr, U256::from(5_000));
+        let b_before = token_b.balance_of(provider);
+        pair.mint(provider);
+        let (reserve0, reserve1, _) = pair.get_reserves();
+        assert_eq!(reserve0, U256::from(11_000));
+        assert_eq!(reserve1, U256::from(11_000));
+
+        // The unmatched 4_000 B goes back to the minter, so nobody can skim it
+        assert_eq!(token_b.balance_of(provider), b_before + U256::from(4_000));
+        assert_eq!(pair.get_excess_balances(), (U256::zero(), U256::zero()));
+        let outsider = env.get_account(3);
+        env.set_caller(outsider);
+        pair.skim(outsider);
+        assert_eq!(token_a.balance_of(outsider), U256::zero());
+        assert_eq!(token_b.balance_of(outsider), U256::zero());
+    }
+
+    fn swap_in(
+        pair: &mut PairHostRef,
+        token_a: &mut LpTokenHostRef,
+        amount_in: u64,
+        to: Address,
+    ) {
+        let (reserve0, reserve1, _) = pair.get_reserves();
+        let a_is_token0 = pair.token0() == token_a.address().clone();
+        let (reserve_in, reserve_out) = if a_is_token0 { (reserve0, reserve1) } else { (reserve1, reserve0) };
+        let amount_in = U256::from(amount_in);
+        let amount_out = amount_in * U256::from(9_970) * reserve_out
+            / (reserve_in * U256::from(10_000) + amount_in * U256::from(9_970));
+
+        token_a.transfer(pair.address().clone(), amount_in);
+        if a_is_token0 {
+            pair.swap(U256::zero(), amount_out, to);
+        } else {
+            pair.swap(amount_out, U256::zero(), to);
+        }
+    }
+
+    #[test]
+    fn test_swap_exact_in_enforces_minimum_output() {
+        let (env, mut pair, mut token_a, mut token_b) = setup_pool();
+        let trader = env.get_account(1);
+        env.set_caller(trader);
+        deposit(&mut pair, &mut token_a, &mut token_b, 1_000_000, trader);
+
+        // Nothing sent yet
+        assert_eq!(
+            pair.try_swap_exact_in(U256::zero(), trader),
+            Err(DexError::InsufficientInputAmount.into())
+        );
+
+        // 10_000 in at 1:1 reserves and 0.3% fee
+        let expected = U256::from(9_871);
+        token_a.transfer(pair.address().clone(), U256::from(10_000));
+        assert_eq!(
+            pair.try_swap_exact_in(expected + 1, trader),
+            Err(DexError::InsufficientOutputAmount.into())
+        );
+        let before = token_b.balance_of(trader);
+        assert_eq!(pair.swap_exact_in(expected, trader), expected);
+        assert_eq!(token_b.balance_of(trader) - before, expected);
+    }
+
+    #[test]
+    fn test_metrics_count_swaps_per_epoch() {
+        let (env, mut pair, mut token_a, mut token_b) = setup_pool();
+        let trader = env.get_account(1);
+        let metric = if pair.token0() == token_a.address().clone() {
+            METRIC_SWAP_TOKEN0_IN
+        } else {
+            METRIC_SWAP_TOKEN1_IN
+        };
+
+        // Nothing is recorded until the factory (account 0) opts in
+        env.set_caller(trader);
+        deposit(&mut pair, &mut token_a, &mut token_b, 1_000_000, trader);
+        token_a.transfer(pair.address().clone(), U256::from(10_000));
+        pair.swap_exact_in(U256::zero(), trader);
+        assert_eq!(pair.get_metrics(0, String::from(metric)).calls, 0);
+        assert_eq!(pair.try_set_metrics_enabled(true), Err(DexError::Unauthorized.into()));
+
+        env.set_caller(env.get_account(0));
+        pair.set_metrics_enabled(true);
+        env.set_caller(trader);
+        for _ in 0..2 {
+            token_a.transfer(pair.address().clone(), U256::from(10_000));
+            pair.swap_exact_in(U256::zero(), trader);
+        }
+        // 0.3% of 10_000 per swap
+        let recorded = pair.get_metrics(0, String::from(metric));
+        assert_eq!(recorded.calls, 2);
+        assert_eq!(recorded.fees, U256::from(60));
+
+        // Rotating starts from zero and keeps the closed epoch
+        env.set_caller(env.get_account(0));
+        assert_eq!(pair.rotate_metrics(), 1);
+        assert_eq!(pair.get_metrics_epoch(), 1);
+        assert_eq!(pair.get_metrics(1, String::from(metric)).calls, 0);
+        assert_eq!(pair.get_metrics(0, String::from(metric)), recorded);
+    }
+
+    #[test]
+    fn test_circuit_breaker_allows_small_moves() {
+        let (env, mut pair, mut token_a, mut token_b) = setup_pool();
+        let trader = env.get_account(1);
+
+        pair.set_max_price_move_bps(500);
+        env.set_caller(trader);
+        deposit(&mut pair, &mut token_a, &mut token_b, 1_000_000, trader);
+
+        // ~2% move is within the 5% limit
+        swap_in(&mut pair, &mut token_a, 10_000, trader);
+        let (reserve0, reserve1, _) = pair.get_reserves();
+        assert_ne!(reserve0, reserve1);
+    }
+
+    #[test]
+    #[should_panic(expected = "PriceMoveTooLarge")]
+    fn test_circuit_breaker_blocks_large_moves() {
+        let (env, mut pair, mut token_a, mut token_b) = setup_pool();
+        let trader = env.get_account(1);
+
+        pair.set_max_price_move_bps(500);
+        env.set_caller(trader);
+        deposit(&mut pair, &mut token_a, &mut token_b, 1_000_000, trader);
+
+        // ~20% move in one block trips the breaker
+        swap_in(&mut pair, &mut token_a, 100_000, trader);
+    }
+
+    #[test]
+    #[should_panic(expected = "PriceMoveTooLarge")]
+    fn test_circuit_breaker_blocks_multi_block_walk() {
+        let (env, mut pair, mut token_a, mut token_b) = setup_pool();
+        let trader = env.get_account(1);
+
+        pair.set_max_price_move_bps(500);
+        env.set_caller(trader);
+        deposit(&mut pair, &mut token_a, &mut token_b, 1_000_000, trader);
+
+        // ~2% per block stays under the limit each time, but the third
+        // step is ~6% away from the TWAP
+        for _ in 0..3 {
+            env.advance_block_time(5);
+            swap_in(&mut pair, &mut token_a, 10_000, trader);
+        }
+    }
+
+    #[test]
+    fn test_circuit_breaker_twap_follows_held_price() {
+        let (env, mut pair, mut token_a, mut token_b) = setup_pool();
+        let trader = env.get_account(1);
+
+        pair.set_max_price_move_bps(500);
+        env.set_caller(trader);
+        deposit(&mut pair, &mut token_a, &mut token_b, 1_000_000, trader);
+
+        // A move that would be ~6% from the opening price passes once the
+        // previous price has held for a full window
+        swap_in(&mut pair, &mut token_a, 10_000, trader);
+        env.advance_block_time(BREAKER_TWAP_WINDOW);
+        swap_in(&mut pair, &mut token_a, 10_000, trader);
+        env.advance_block_time(BREAKER_TWAP_WINDOW);
+        swap_in(&mut pair, &mut token_a, 10_000, trader);
+        let (reserve0, reserve1, _) = pair.get_reserves();
+        assert_ne!(reserve0, reserve1);
+    }
+
+    /// Flash borrower used by the tests; the first data byte picks its behaviour
+    #[odra::module]
+    pub struct TestBorrower {}
+
+    #[odra::module]
+    impl TestBorrower {
+        pub fn on_flash_loan(&mut self, _initiator: Address, token: Address, amount: U256, fee: U256, data: Bytes) {
+            let pair = self.env().caller();
+            match data.first().copied().unwrap_or_default() {
+                // Re-enter the pair while the loan is out
+                1 => PairContractRef::new(self.env(), pair).sync(),
+                // Read the pair's price while the loan is out
+                3 => {
+                    PairContractRef::new(self.env(), pair).get_price0();
+                }
+                // Return the principal only
+                2 => {
+                    Cep18TokenContractRef::new(self.env(), token).transfer(pair, amount);
+                }
+                _ => {
+                    Cep18TokenContractRef::new(self.env(), token).transfer(pair, amount + fee);
+                }
+            }
+        }
+    }
+
+    fn setup_flash() -> (HostEnv, PairHostRef, LpTokenHostRef, TestBorrowerHostRef) {
+        let (env, mut pair, mut token_a, mut token_b) = setup_pool();
+        let provider = env.get_account(1);
+        let borrower = TestBorrower::deploy(&env, NoArgs);
+
+        env.set_caller(provider);
+        deposit(&mut pair, &mut token_a, &mut token_b, 1_000_000, provider);
+        // Borrower holds enough to cover fees
+        env.set_caller(env.get_account(0));
+        token_a.mint(borrower.address().clone(), U256::from(1_000));
+        (env, pair, token_a, borrower)
+    }
+
+    #[test]
+    fn test_flash_loan_fee_accrues_to_reserves() {
+        let (env, mut pair, token_a, borrower) = setup_flash();
+        let amount = U256::from(100_000);
+        let fee = pair.flash_fee(amount);
+        assert_eq!(fee, U256::from(90));
+        // Tiny loans still pay at least one unit
+        assert_eq!(pair.flash_fee(U256::one()), U256::one());
+
+        let a_is_token0 = pair.token0() == token_a.address().clone();
+        let (before0, before1, _) = pair.get_reserves();
+        let k_before = before0 * before1;
+
+        env.set_caller(env.get_account(2));
+        pair.flash_loan(token_a.address().clone(), amount, borrower.address().clone(), Bytes::from(vec![0u8]));
+
+        let (after0, after1, _) = pair.get_reserves();
+        let (before_a, after_a) = if a_is_token0 { (before0, after0) } else { (before1, after1) };
+        assert_eq!(after_a, before_a + fee);
+        assert!(after0 * after1 > k_before);
+        assert_eq!(token_a.balance_of(borrower.address().clone()), U256::from(1_000) - fee);
+    }
+
+    #[test]
+    fn test_flash_loan_must_be_repaid_with_fee() {
+        let (env, mut pair, token_a, borrower) = setup_flash();
+        env.set_caller(env.get_account(2));
+        assert_eq!(
+            pair.try_flash_loan(
+                token_a.address().clone(),
+                U256::from(100_000),
+                borrower.address().clone(),
+                Bytes::from(vec![2u8]),
+            ),
+            Err(DexError::FlashLoanNotRepaid.into())
+        );
+    }
+
+    #[test]
+    fn test_flash_loan_blocks_reentry() {
+        let (env, mut pair, token_a, borrower) = setup_flash();
+        env.set_caller(env.get_account(2));
+        assert_eq!(
+            pair.try_flash_loan(
+                token_a.address().clone(),
+                U256::from(100_000),
+                borrower.address().clone(),
+                Bytes::from(vec![1u8]),
+            ),
+            Err(DexError::Locked.into())
+        );
+
+        // Views cannot be read mid-loan either (read-only reentrancy)
+        assert!(!pair.is_locked());
+        assert_eq!(
+            pair.try_flash_loan(
+                token_a.address().clone(),
+                U256::from(100_000),
+                borrower.address().clone(),
+                Bytes::from(vec![3u8]),
+            ),
+            Err(DexError::Locked.into())
+        );
+
+        // Borrowing the whole reserve is refused
+        let (reserve0, reserve1, _) = pair.get_reserves();
+        let reserve = if pair.token0() == token_a.address().clone() { reserve0 } else { reserve1 };
+        assert_eq!(
+            pair.try_flash_loan(token_a.address().clone(), reserve, borrower.address().clone(), Bytes::new()),
+            Err(DexError::InsufficientLiquidity.into())
+        );
+    }
+
+    #[test]
+    fn test_flash_allowlist_restricts_receivers() {
+        let (env, mut pair, token_a, borrower) = setup_flash();
+        let token = token_a.address().clone();
+        let receiver = borrower.address().clone();
+        assert!(pair.is_flash_receiver_allowed(receiver));
+
+        // Account 0 is the pair's factory
+        pair.set_flash_allowlist_enforced(true);
+        env.set_caller(env.get_account(2));
+        assert_eq!(
+            pair.try_flash_loan(token, U256::from(100_000), receiver, Bytes::new()),
+            Err(DexError::FlashReceiverNotAllowed.into())
+        );
+        assert_eq!(pair.try_set_flash_receiver(receiver, true), Err(DexError::Unauthorized.into()));
+
+        env.set_caller(env.get_account(0));
+        pair.set_flash_receiver(receiver, true);
+        env.set_caller(env.get_account(2));
+        pair.flash_loan(token, U256::from(100_000), receiver, Bytes::new());
+    }
+
+    #[test]
+    fn test_protocol_fee_share_accrues_outside_reserves() {
+        let (env, mut pair, mut token_a, mut token_b) = setup_pool();
+        let trader = env.get_account(1);
+        let rebater = env.get_account(2);
+        let treasury = env.get_account(3);
+        let token = token_a.address().clone();
+
+        // Only the factory (account 0) sets the share, and it is capped
+        env.set_caller(trader);
+        assert_eq!(pair.try_set_protocol_fee_share_bps(2_000), Err(DexError::Unauthorized.into()));
+        env.set_caller(env.get_account(0));
+        assert_eq!(
+            pair.try_set_protocol_fee_share_bps(MAX_PROTOCOL_FEE_SHARE_BPS + 1),
+            Err(DexError::InvalidFee.into())
+        );
+        pair.set_protocol_fee_share_bps(2_000);
+        pair.set_rebater(Some(rebater));
+
+        // 0.3% of 100_000 is a 300 fee, of which 20% is set aside
+        env.set_caller(trader);
+        deposit(&mut pair, &mut token_a, &mut token_b, 1_000_000, trader);
+        swap_in(&mut pair, &mut token_a, 100_000, trader);
+        assert_eq!(pair.protocol_fees(token), U256::from(60));
+        let (reserve0, reserve1, _) = pair.get_reserves();
+        let reserve_a = if pair.token0() == token { reserve0 } else { reserve1 };
+        assert_eq!(reserve_a, U256::from(1_100_000 - 60));
+        assert_eq!(pair.get_excess_balances(), (U256::zero(), U256::zero()));
+
+        // Rebates come out of the accrued share only, and only via the rebater
+        assert_eq!(
+            pair.try_pay_rebate(token, trader, U256::from(10)),
+            Err(DexError::Unauthorized.into())
+        );
+        env.set_caller(rebater);
+        assert_eq!(pair.pay_rebate(token, trader, U256::from(25)), U256::from(25));
+        assert_eq!(pair.pay_rebate(token, trader, U256::from(1_000)), U256::from(35));
+        assert_eq!(pair.protocol_fees(token), U256::zero());
+        assert_eq!(pair.get_reserves().0 + pair.get_reserves().1, reserve0 + reserve1);
+
+        // The factory collects whatever has not been rebated
+        env.set_caller(trader);
+        swap_in(&mut pair, &mut token_a, 100_000, trader);
+        let accrued = pair.protocol_fees(token);
+        assert!(!accrued.is_zero());
+        assert_eq!(pair.try_collect_protocol_fees(treasury), Err(DexError::Unauthorized.into()));
+        env.set_caller(env.get_account(0));
+        pair.collect_protocol_fees(treasury);
+        assert_eq!(token_a.balance_of(treasury), accrued);
+        assert_eq!(pair.protocol_fees(token), U256::zero());
+    }
+
+    /// Transfer listener used by the tests; remembers the last notification
+    #[odra::module]
+    pub struct TestListener {
+        calls: Var<u32>,
+        last: Var<(Address, Address, U256)>,
+        reserves: Var<(U256, U256)>,
+    }
+
+    #[odra::module]
+    impl TestListener {
+        pub fn on_lp_transfer(&mut self, from: Address, to: Address, amount: U256) {
+            self.calls.set(self.calls.get_or_default() + 1);
+            self.last.set((from, to, amount));
+            // Reverts with Locked if the pair notifies before unlocking
+            let (reserve0, reserve1, _) =
+                PairContractRef::new(self.env(), self.env().caller()).get_reserves();
+            self.reserves.set((reserve0, reserve1));
+        }
+
+        pub fn calls(&self) -> u32 {
+            self.calls.get_or_default()
+        }
+
+        pub fn last(&self) -> Option<(Address, Address, U256)> {
+            self.last.get()
+        }
+
+        pub fn reserves(&self) -> Option<(U256, U256)> {
+            self.reserves.get()
+        }
+    }
+
+    #[test]
+    fn test_lp_transfers_notify_listeners() {
+        let (env, mut pair, mut token_a, mut token_b) = setup_pool();
+        let provider = env.get_account(1);
+        let receiver = env.get_account(2);
+        let listener = TestListener::deploy(&env, NoArgs);
+        let pair_addr = pair.address().clone();
+
+        pair.set_transfer_listeners(vec![listener.address().clone()]);
+        assert_eq!(pair.transfer_listeners(), vec![listener.address().clone()]);
+
+        // Minting notifies once for the locked minimum and once for the provider
+        env.set_caller(provider);
+        let liquidity = deposit(&mut pair, &mut token_a, &mut token_b, 10_000, provider);
+        assert_eq!(listener.calls(), 2);
+        assert_eq!(listener.last(), Some((pair_addr, provider, liquidity)));
+        // Notified after the pair unlocked, so the listener saw the new reserves
+        assert_eq!(listener.reserves(), Some((U256::from(10_000), U256::from(10_000))));
+
+        pair.transfer(receiver, U256::from(100));
+        assert_eq!(listener.calls(), 3);
+        assert_eq!(listener.last(), Some((provider, receiver, U256::from(100))));
+
+        // Burning notifies once the reserves are already paid out
+        pair.transfer(pair_addr, U256::from(1_000));
+        pair.burn(provider);
+        assert_eq!(listener.calls(), 5);
+        assert_eq!(listener.last(), Some((pair_addr, pair_addr, U256::from(1_000))));
+        assert_eq!(listener.reserves(), Some((U256::from(9_000), U256::from(9_000))));
+
+        // Only the factory configures listeners, and their number is capped
+        assert_eq!(
+            pair.try_set_transfer_listeners(vec![]),
+            Err(DexError::Unauthorized.into())
+        );
+        env.set_caller(env.get_account(0));
+        assert_eq!(
+            pair.try_set_transfer_listeners(vec![receiver; 5]),
+            Err(crate::errors::TokenError::TooManyListeners.into())
+        );
+    }
+}
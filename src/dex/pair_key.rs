@@ -0,0 +1,70 @@
+//! Canonical token order and pair keys
+//!
+//! Pairs store their tokens sorted by address, and the factory keys its
+//! registry and derives pair salts from that order. Contracts and integrators
+//! use these helpers instead of repeating the comparison, so a pair resolves
+//! to the same `(token0, token1)` and the same key everywhere. The key is the
+//! blake2b hash of `pair_key_preimage`, which is also the pair's install salt;
+//! contracts hash it with `env().hash`, off-chain tools with any blake2b-256.
+use odra::prelude::*;
+use odra::casper_types::bytesrepr::ToBytes;
+use super::factory::DEFAULT_PAIR_TYPE;
+
+/// Canonical identity of a pair
+#[odra::odra_type]
+pub struct PairKey {
+    /// Lower token address
+    pub token0: Address,
+    /// Higher token address
+    pub token1: Address,
+    /// Hash of `pair_key_preimage`; equal to the factory's pair salt
+    pub key: [u8; 32],
+}
+
+/// Order two tokens the way pairs store them
+pub fn sort_tokens(token_a: Address, token_b: Address) -> (Address, Address) {
+    if token_a < token_b {
+        (token_a, token_b)
+    } else {
+        (token_b, token_a)
+    }
+}
+
+/// Bytes hashed into the key of a pair of the given type
+/// The sorted tokens serialized with `bytesrepr`; types other than the
+/// default append the type id. Returns None if serialization fails
+pub fn pair_key_preimage(token_a: Address, token_b: Address, pair_type: u8) -> Option<Vec<u8>> {
+    let (token0, token1) = sort_tokens(token_a, token_b);
+    let mut bytes = token0.to_bytes().ok()?;
+    bytes.extend(token1.to_bytes().ok()?);
+    if pair_type != DEFAULT_PAIR_TYPE {
+        bytes.push(pair_type);
+    }
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use odra::casper_types::account::AccountHash;
+
+    fn account(byte: u8) -> Address {
+        Address::Account(AccountHash::new([byte; 32]))
+    }
+
+    #[test]
+    fn test_sort_tokens_is_order_independent() {
+        assert_eq!(sort_tokens(account(2), account(1)), (account(1), account(2)));
+        assert_eq!(sort_tokens(account(1), account(2)), (account(1), account(2)));
+    }
+
+    #[test]
+    fn test_preimage_is_canonical_per_type() {
+        let preimage = pair_key_preimage(account(1), account(2), DEFAULT_PAIR_TYPE).unwrap();
+        assert_eq!(pair_key_preimage(account(2), account(1), DEFAULT_PAIR_TYPE), Some(preimage.clone()));
+
+        let typed = pair_key_preimage(account(1), account(2), 1).unwrap();
+        assert_eq!(typed[..preimage.len()], preimage[..]);
+        assert_eq!(typed.last(), Some(&1));
+    }
+}
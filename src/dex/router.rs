@@ -8,9 +8,24 @@
 //! - Deadline protection
 use odra::prelude::*;
 use odra::casper_types::U256;
+use odra::casper_types::bytesrepr::{Bytes, ToBytes};
 use odra::ContractRef;
+use odra::uints::ToU512;
 use crate::errors::DexError;
+use crate::events::{
+    DustRefunded, LiquidityMigrated, RouterLiquidityAdded, RouterLiquidityRemoved, RouterSwap,
+    SwapCommitted, SwapDistributed,
+};
+use crate::math::{DecimalMath, SafeMath, BPS_DENOMINATOR, DEFAULT_FEE_BPS};
+use crate::governance::guardian::GuardianContractContractRef;
+use crate::governance::token_list::TokenListContractContractRef;
+use crate::incentives::competition::CompetitionReporterContractRef;
+use crate::incentives::lp_loyalty::LoyaltyRebaterContractRef;
+use crate::sdk::deadline::{deadline_expired, suggest_deadline};
+use crate::sdk::route::{decode_swap_call, decode_typed_route};
 use crate::token::Cep18TokenContractRef;
+use super::factory::{PairKind, PairTypeInfo, DEFAULT_PAIR_TYPE};
+use super::pair_key::{pair_key_preimage, sort_tokens, PairKey};
 
 /// External interface for Pair contract
 #[odra::external_contract]
@@ -18,17 +33,135 @@ pub trait PairContract {
     fn token0(&self) -> Address;
     fn token1(&self) -> Address;
     fn get_reserves(&self) -> (U256, U256, u64);
+    fn total_supply(&self) -> U256;
+    fn fee_bps(&self) -> u16;
     fn mint(&mut self, to: Address) -> U256;
     fn burn(&mut self, to: Address) -> (U256, U256);
     fn swap(&mut self, amount0_out: U256, amount1_out: U256, to: Address);
     fn transfer_from(&mut self, from: Address, to: Address, amount: U256) -> bool;
 }
 
+/// External interface for the WCSPR wrapper
+#[odra::external_contract]
+pub trait WrappedCspr {
+    fn deposit(&mut self) -> U256;
+}
+
 /// External interface for Factory contract
 #[odra::external_contract]
 pub trait FactoryContractRef {
     fn get_pair(&self, token_a: Address, token_b: Address) -> Option<Address>;
     fn create_pair(&mut self, token_a: Address, token_b: Address) -> Address;
+    fn fee_to_setter(&self) -> Address;
+    fn get_pair_with_type(&self, token_a: Address, token_b: Address, pair_type: u8) -> Option<Address>;
+    fn get_pair_type(&self, pair_type: u8) -> Option<PairTypeInfo>;
+}
+
+/// Stable interface for third-party aggregators
+/// Routes are opaque bytes built with `sdk::route::encode_route`, so
+/// integrators do not depend on the Router's path arguments
+#[odra::external_contract]
+pub trait IAggregatorAdapter {
+    /// Output amount for an exact input along the route
+    fn quote_exact_in(&self, amount_in: U256, route: Bytes) -> U256;
+    /// Input amount needed for an exact output along the route
+    fn quote_exact_out(&self, amount_out: U256, route: Bytes) -> U256;
+    /// Swap an exact input along the route, returning the output amount
+    fn swap(&mut self, amount_in: U256, amount_out_min: U256, route: Bytes, to: Address, deadline: u64) -> U256;
+}
+
+/// Result of a swap slippage dry-run
+#[odra::odra_type]
+pub struct SlippageCheck {
+    /// Token the limit applies to (output for exact-in, input for exact-out)
+    pub token: Address,
+    /// Amount the swap would produce or require at current reserves
+    pub expected: U256,
+    /// Caller's minimum output or maximum input
+    pub limit: U256,
+    /// Whether the swap would pass its slippage check
+    pub passed: bool,
+}
+
+/// Largest block-time drift the router may be configured to tolerate (5 minutes)
+pub const MAX_DEADLINE_TOLERANCE: u64 = 5 * 60;
+
+/// Most parallel routes a split swap may use
+pub const MAX_SPLIT_ROUTES: usize = 4;
+
+/// Number of equal chunks the split quoter allocates between routes
+pub const SPLIT_STEPS: u64 = 20;
+
+/// One leg of a split swap: a path with the pair type of each hop
+#[odra::odra_type]
+pub struct Route {
+    /// Token path; every route of a split shares its first and last token
+    pub path: Vec<Address>,
+    /// Pair type of each hop (`path.len() - 1` entries)
+    pub pair_types: Vec<u8>,
+}
+
+/// Seconds a swap commitment must age before it can be revealed (next block)
+pub const MIN_REVEAL_DELAY: u64 = 1;
+
+/// Seconds after which an unrevealed swap commitment expires
+pub const COMMITMENT_TTL: u64 = 10 * 60;
+
+/// A user's pending commit-reveal swap
+#[odra::odra_type]
+pub struct SwapCommitment {
+    /// Hash from `swap_commitment_hash`
+    pub commitment: [u8; 32],
+    /// Block time of the commitment
+    pub committed_at: u64,
+}
+
+/// Most sub-actions a payable multicall may run
+pub const MAX_CSPR_ACTIONS: usize = 8;
+
+/// Kind of a `multicall_cspr` sub-action
+#[odra::odra_type]
+pub enum CsprActionKind {
+    /// Send `cspr_amount` as WCSPR to the recipient
+    Wrap,
+    /// Swap `cspr_amount` of WCSPR along `path`, which starts at WCSPR
+    Swap,
+    /// Pair `cspr_amount` of WCSPR with up to `token_amount` of `path[0]`,
+    /// pulled from the caller
+    AddLiquidity,
+}
+
+/// One sub-action of a payable multicall
+#[odra::odra_type]
+pub struct CsprAction {
+    /// What to do
+    pub kind: CsprActionKind,
+    /// Share of the attached CSPR (in motes) the action consumes
+    pub cspr_amount: U256,
+    /// Swap path, or the token paired with WCSPR; empty for `Wrap`
+    pub path: Vec<Address>,
+    /// Most of the paired token to deposit (`AddLiquidity` only)
+    pub token_amount: U256,
+    /// Minimum output (`Swap`) or LP tokens minted (`AddLiquidity`)
+    pub amount_min: U256,
+}
+
+/// Number of trades kept per user in the history ring buffer
+pub const TRADE_HISTORY_SIZE: u64 = 50;
+
+/// A swap recorded in a user's trade history
+#[odra::odra_type]
+pub struct TradeRecord {
+    /// First token of the path
+    pub token_in: Address,
+    /// Last token of the path
+    pub token_out: Address,
+    /// Amount paid in
+    pub amount_in: U256,
+    /// Amount received
+    pub amount_out: U256,
+    /// Block time of the swap
+    pub timestamp: u64,
 }
 
 /// Router contract for user interactions
@@ -38,6 +171,26 @@ pub struct Router {
     factory: Var<Address>,
     /// WCSPR (Wrapped CSPR) token address for native token swaps
     wcspr: Var<Address>,
+    /// Optional trading competition that swaps are reported to
+    competition: Var<Option<Address>>,
+    /// Optional LP loyalty contract paying fee rebates to loyal LPs
+    loyalty: Var<Option<Address>>,
+    /// Optional guardian that can pause swaps and deposits
+    guardian: Var<Option<Address>>,
+    /// Trade history ring buffer: (user, slot) -> trade
+    trade_history: Mapping<(Address, u64), TradeRecord>,
+    /// Total trades recorded per user (slot = count % TRADE_HISTORY_SIZE)
+    trade_count: Mapping<Address, u64>,
+    /// Seconds past a deadline that are still accepted, to absorb block-time drift
+    deadline_tolerance: Var<u64>,
+    /// Token list consulted in safe mode; `None` disables safe mode
+    token_list: Var<Option<Address>>,
+    /// Pending commit-reveal swap per user
+    swap_commitments: Mapping<Address, Option<SwapCommitment>>,
+    /// Router balance of each token after its last `on_token_received` swap
+    callback_balances: Mapping<Address, U256>,
+    /// Sequence number of the last event emitted
+    event_seq: Var<u64>,
 }
 
 #[odra::module]
@@ -58,6 +211,110 @@ impl Router {
         self.wcspr.get_or_revert_with(DexError::InvalidPair)
     }
 
+    /// Get the trading competition swaps are reported to
+    pub fn competition(&self) -> Option<Address> {
+        self.competition.get_or_default()
+    }
+
+    /// Set (or clear) the trading competition swaps are reported to
+    /// Only callable by the factory's fee_to_setter
+    pub fn set_competition(&mut self, competition: Option<Address>) {
+        let factory_ref = FactoryContractRefContractRef::new(self.env(), self.factory());
+        if self.env().caller() != factory_ref.fee_to_setter() {
+            self.env().revert(DexError::Unauthorized);
+        }
+        self.competition.set(competition);
+    }
+
+    /// Get the LP loyalty contract asked for fee rebates on each hop
+    pub fn loyalty(&self) -> Option<Address> {
+        self.loyalty.get_or_default()
+    }
+
+    /// Set (or clear) the LP loyalty contract asked for fee rebates on each hop
+    /// The router must be a rebater there. Only callable by the factory's fee_to_setter
+    pub fn set_loyalty(&mut self, loyalty: Option<Address>) {
+        let factory_ref = FactoryContractRefContractRef::new(self.env(), self.factory());
+        if self.env().caller() != factory_ref.fee_to_setter() {
+            self.env().revert(DexError::Unauthorized);
+        }
+        self.loyalty.set(loyalty);
+    }
+
+    /// Get the guardian whose pauses apply to this router
+    pub fn guardian(&self) -> Option<Address> {
+        self.guardian.get_or_default()
+    }
+
+    /// Set (or clear) the guardian whose pauses apply to this router
+    /// Only callable by the factory's fee_to_setter
+    pub fn set_guardian(&mut self, guardian: Option<Address>) {
+        let factory_ref = FactoryContractRefContractRef::new(self.env(), self.factory());
+        if self.env().caller() != factory_ref.fee_to_setter() {
+            self.env().revert(DexError::Unauthorized);
+        }
+        self.guardian.set(guardian);
+    }
+
+    /// Get the token list enforced in safe mode, if any
+    pub fn token_list(&self) -> Option<Address> {
+        self.token_list.get_or_default()
+    }
+
+    /// Enter safe mode with a token list, or leave it with `None`
+    /// In safe mode, compromised tokens cannot be bought or deposited as
+    /// liquidity; selling them and removing liquidity keep working
+    /// Only callable by the factory's fee_to_setter
+    pub fn set_token_list(&mut self, token_list: Option<Address>) {
+        let factory_ref = FactoryContractRefContractRef::new(self.env(), self.factory());
+        if self.env().caller() != factory_ref.fee_to_setter() {
+            self.env().revert(DexError::Unauthorized);
+        }
+        self.token_list.set(token_list);
+    }
+
+    /// Check if safe mode flags a token as compromised
+    pub fn is_token_delisted(&self, token: Address) -> bool {
+        match self.token_list.get_or_default() {
+            Some(list) => TokenListContractContractRef::new(self.env(), list).is_compromised(token),
+            None => false,
+        }
+    }
+
+    /// Check if either token of a pair is flagged, for frontend warnings
+    pub fn is_pair_flagged(&self, pair: Address) -> bool {
+        let pair_ref = PairContractContractRef::new(self.env(), pair);
+        self.is_token_delisted(pair_ref.token0()) || self.is_token_delisted(pair_ref.token1())
+    }
+
+    /// Get the block-time drift tolerated past a deadline, in seconds
+    pub fn deadline_tolerance(&self) -> u64 {
+        self.deadline_tolerance.get_or_default()
+    }
+
+    /// Set the block-time drift tolerated past a deadline, in seconds
+    /// Only callable by the factory's fee_to_setter; capped at MAX_DEADLINE_TOLERANCE
+    pub fn set_deadline_tolerance(&mut self, tolerance: u64) {
+        let factory_ref = FactoryContractRefContractRef::new(self.env(), self.factory());
+        if self.env().caller() != factory_ref.fee_to_setter() {
+            self.env().revert(DexError::Unauthorized);
+        }
+        if tolerance > MAX_DEADLINE_TOLERANCE {
+            self.env().revert(DexError::InvalidConfiguration);
+        }
+        self.deadline_tolerance.set(tolerance);
+    }
+
+    /// Get the current block time, for building deadlines off the chain's clock
+    pub fn current_block_time(&self) -> u64 {
+        self.env().get_block_time()
+    }
+
+    /// Suggest a deadline `ttl_secs` from the current block time
+    pub fn suggest_deadline(&self, ttl_secs: u64) -> u64 {
+        suggest_deadline(self.env().get_block_time(), ttl_secs)
+    }
+
     // ============ Liquidity Functions ============
 
     /// Add liquidity to a pair
@@ -74,6 +331,8 @@ impl Router {
         deadline: u64,
     ) -> (U256, U256, U256) {
         self.ensure_deadline(deadline);
+        self.ensure_not_paused();
+        self.ensure_buyable(&[token_a, token_b]);
 
         // Calculate optimal amounts
         let (amount_a, amount_b) = self.calculate_liquidity_amounts(
@@ -96,6 +355,18 @@ impl Router {
         let mut pair_ref = PairContractContractRef::new(self.env(), pair);
         let liquidity = pair_ref.mint(to);
 
+        self.env().emit_event(RouterLiquidityAdded {
+            sender: self.env().caller(),
+            to,
+            pair,
+            token_a,
+            token_b,
+            amount_a,
+            amount_b,
+            liquidity,
+            seq: self.next_event_seq(),
+        });
+
         (amount_a, amount_b, liquidity)
     }
 
@@ -123,7 +394,7 @@ impl Router {
         let (amount0, amount1) = pair_ref.burn(to);
 
         // Sort tokens to match pair order
-        let (token0, _) = self.sort_tokens(token_a, token_b);
+        let (token0, _) = sort_tokens(token_a, token_b);
         let (amount_a, amount_b) = if token_a == token0 {
             (amount0, amount1)
         } else {
@@ -132,15 +403,70 @@ impl Router {
 
         // Check minimum amounts
         if amount_a < amount_a_min {
-            self.env().revert(DexError::InsufficientAmount);
+            self.env().revert(DexError::InsufficientAAmount);
         }
         if amount_b < amount_b_min {
-            self.env().revert(DexError::InsufficientAmount);
+            self.env().revert(DexError::InsufficientBAmount);
         }
 
+        self.env().emit_event(RouterLiquidityRemoved {
+            sender: self.env().caller(),
+            to,
+            pair,
+            token_a,
+            token_b,
+            liquidity,
+            amount_a,
+            amount_b,
+            seq: self.next_event_seq(),
+        });
+
         (amount_a, amount_b)
     }
 
+    /// Move liquidity between two pairs of the same tokens (e.g. fee tiers)
+    /// Burns `liquidity` of `pair_from` and mints into `pair_to` for the caller;
+    /// amounts that do not fit the `pair_to` ratio are refunded.
+    /// Minimums apply to the burned amounts in (token0, token1) order.
+    /// Returns (amount0, amount1, liquidity) deposited into `pair_to`
+    pub fn migrate_liquidity(
+        &mut self,
+        pair_from: Address,
+        pair_to: Address,
+        liquidity: U256,
+        amount0_min: U256,
+        amount1_min: U256,
+        deadline: u64,
+    ) -> (U256, U256, U256) {
+        self.ensure_deadline(deadline);
+        self.ensure_not_paused();
+        self.migrate_internal(pair_from, pair_to, liquidity, amount0_min, amount1_min)
+    }
+
+    /// Move liquidity from an external DEX pair into this DEX
+    /// `external_pair` must expose the standard pair interface (`token0`,
+    /// `token1`, `transfer_from` and `burn(to)`). Its LP is burned and the
+    /// underlying deposited into the factory pair of the same tokens, which
+    /// is created if needed; amounts that do not fit its ratio are refunded.
+    /// Minimums apply to the burned amounts in the external pair's
+    /// (token0, token1) order, as do the returned deposit amounts.
+    /// Returns (amount_a, amount_b, liquidity) deposited
+    pub fn migrate_from(
+        &mut self,
+        external_pair: Address,
+        liquidity: U256,
+        min_a: U256,
+        min_b: U256,
+        deadline: u64,
+    ) -> (U256, U256, U256) {
+        self.ensure_deadline(deadline);
+        self.ensure_not_paused();
+
+        let external_ref = PairContractContractRef::new(self.env(), external_pair);
+        let pair_to = self.get_or_create_pair(external_ref.token0(), external_ref.token1());
+        self.migrate_internal(external_pair, pair_to, liquidity, min_a, min_b)
+    }
+
     // ============ Swap Functions ============
 
     /// Swap exact input amount for output tokens
@@ -154,6 +480,8 @@ impl Router {
         deadline: u64,
     ) -> Vec<U256> {
         self.ensure_deadline(deadline);
+        self.ensure_not_paused();
+        self.ensure_path_buyable(&path);
 
         let amounts = self.get_amounts_out_internal(amount_in, &path);
         
@@ -162,11 +490,217 @@ impl Router {
         }
 
         // Transfer input tokens to first pair
+        let balances_before = self.path_balances(&path);
         let pair = self.get_pair_address(path[0], path[1]);
         self.safe_transfer_from(path[0], self.env().caller(), pair, amounts[0]);
 
         // Execute swaps
         self.execute_swap(&amounts, &path, to);
+        self.record_trade(&amounts, &path);
+        self.emit_router_swap(&amounts, &path, to);
+
+        // Return any residual path tokens this swap left with the router
+        self.refund_dust(&path, &balances_before, self.env().caller());
+
+        amounts
+    }
+
+    /// Commit to a swap without revealing it; see `reveal_swap`
+    /// Replaces any pending commitment of the caller
+    pub fn commit_swap(&mut self, commitment: [u8; 32]) {
+        self.ensure_not_paused();
+        let user = self.env().caller();
+        let timestamp = self.env().get_block_time();
+        self.swap_commitments.set(&user, Some(SwapCommitment { commitment, committed_at: timestamp }));
+        self.env().emit_event(SwapCommitted {
+            user,
+            commitment,
+            timestamp,
+            seq: self.next_event_seq(),
+        });
+    }
+
+    /// Reveal and execute the caller's committed exact-input swap
+    /// The parameters and salt must hash to the commitment, which must be at
+    /// least `MIN_REVEAL_DELAY` and at most `COMMITMENT_TTL` seconds old, so the
+    /// swap was fixed before anyone could see it in the mempool
+    pub fn reveal_swap(
+        &mut self,
+        amount_in: U256,
+        amount_out_min: U256,
+        path: Vec<Address>,
+        to: Address,
+        deadline: u64,
+        salt: [u8; 32],
+    ) -> Vec<U256> {
+        let user = self.env().caller();
+        let pending = self.get_swap_commitment(user)
+            .unwrap_or_revert_with(&self.env(), DexError::InvalidCommitment);
+        let now = self.env().get_block_time();
+        if now < pending.committed_at + MIN_REVEAL_DELAY {
+            self.env().revert(DexError::RevealTooEarly);
+        }
+        if now > pending.committed_at + COMMITMENT_TTL {
+            self.env().revert(DexError::CommitmentExpired);
+        }
+        let hash = self.swap_commitment_hash(amount_in, amount_out_min, path.clone(), to, salt);
+        if hash != pending.commitment {
+            self.env().revert(DexError::InvalidCommitment);
+        }
+        self.swap_commitments.set(&user, None);
+
+        self.swap_exact_tokens_for_tokens(amount_in, amount_out_min, path, to, deadline)
+    }
+
+    /// Get the commitment hash of an exact-input swap, as `reveal_swap` checks it
+    /// The deadline is left out so it can be chosen at reveal time
+    pub fn swap_commitment_hash(
+        &self,
+        amount_in: U256,
+        amount_out_min: U256,
+        path: Vec<Address>,
+        to: Address,
+        salt: [u8; 32],
+    ) -> [u8; 32] {
+        let mut bytes = Vec::new();
+        for part in [
+            amount_in.to_bytes(),
+            amount_out_min.to_bytes(),
+            path.to_bytes(),
+            to.to_bytes(),
+        ] {
+            bytes.extend(part.ok().unwrap_or_revert_with(&self.env(), DexError::InvalidPath));
+        }
+        bytes.extend_from_slice(&salt);
+        self.env().hash(bytes)
+    }
+
+    /// Get a user's pending swap commitment
+    pub fn get_swap_commitment(&self, user: Address) -> Option<SwapCommitment> {
+        self.swap_commitments.get(&user).flatten()
+    }
+
+    /// Swap the CSPR attached to the call for tokens
+    /// The CSPR is wrapped into WCSPR, so `path` must start with WCSPR. Sent with
+    /// attached tokens, this is a single deploy with no prior approval
+    #[odra(payable)]
+    pub fn swap_exact_cspr_for_tokens(
+        &mut self,
+        amount_out_min: U256,
+        path: Vec<Address>,
+        to: Address,
+        deadline: u64,
+    ) -> Vec<U256> {
+        self.ensure_deadline(deadline);
+        self.ensure_not_paused();
+        self.ensure_path_buyable(&path);
+
+        let wcspr = self.wcspr();
+        if path.len() < 2 || path[0] != wcspr {
+            self.env().revert(DexError::InvalidPath);
+        }
+        let attached = self.env().attached_value();
+        if attached.is_zero() {
+            self.env().revert(DexError::InsufficientInputAmount);
+        }
+
+        // Wrap the CSPR; the router now holds the WCSPR
+        let balances_before = self.path_balances(&path);
+        let amount_in = WrappedCsprContractRef::new(self.env(), wcspr)
+            .with_tokens(attached)
+            .deposit();
+
+        let amounts = self.get_amounts_out_internal(amount_in, &path);
+        if amounts[amounts.len() - 1] < amount_out_min {
+            self.env().revert(DexError::InsufficientOutputAmount);
+        }
+
+        let pair = self.get_pair_address(path[0], path[1]);
+        self.safe_transfer(wcspr, pair, amounts[0]);
+
+        self.execute_swap(&amounts, &path, to);
+        self.record_trade(&amounts, &path);
+        self.emit_router_swap(&amounts, &path, to);
+        self.refund_dust(&path, &balances_before, self.env().caller());
+
+        amounts
+    }
+
+    /// Spend the attached CSPR across several sub-actions in one deploy
+    ///
+    /// The CSPR is wrapped once and each action consumes its `cspr_amount` of
+    /// the WCSPR; the amounts must add up to exactly the attached value.
+    /// WCSPR left unpaired by `AddLiquidity` and any path dust are refunded to
+    /// the caller, so the router keeps nothing. Returns, per action, the WCSPR
+    /// sent, the swap output or the liquidity minted
+    #[odra(payable)]
+    pub fn multicall_cspr(&mut self, actions: Vec<CsprAction>, to: Address, deadline: u64) -> Vec<U256> {
+        self.ensure_deadline(deadline);
+        self.ensure_not_paused();
+        if actions.is_empty() || actions.len() > MAX_CSPR_ACTIONS {
+            self.env().revert(DexError::InvalidConfiguration);
+        }
+
+        let attached = self.env().attached_value();
+        let total = actions.iter().try_fold(U256::zero(), |total, action| total.checked_add(action.cspr_amount))
+            .unwrap_or_else(|| self.env().revert(DexError::Overflow));
+        if attached.is_zero() || total.to_u512() != attached {
+            self.env().revert(DexError::UnbalancedValue);
+        }
+
+        // Wrap everything once; the actions spend the router's WCSPR
+        let wcspr = self.wcspr();
+        let mut touched = vec![wcspr];
+        for action in &actions {
+            touched.extend(action.path.iter().copied());
+        }
+        let balances_before = self.path_balances(&touched);
+        let wrapped = WrappedCsprContractRef::new(self.env(), wcspr)
+            .with_tokens(attached)
+            .deposit();
+        if wrapped != total {
+            self.env().revert(DexError::UnbalancedValue);
+        }
+
+        let mut results = Vec::with_capacity(actions.len());
+        for action in actions {
+            results.push(self.run_cspr_action(wcspr, action, to));
+        }
+
+        self.refund_dust(&touched, &balances_before, self.env().caller());
+        results
+    }
+
+    /// Swap exact input along a route that names the pair type of each hop
+    /// `pair_types[i]` selects the pool between `path[i]` and `path[i + 1]`,
+    /// so one route can cross pools of different types and fees
+    pub fn swap_exact_tokens_for_tokens_typed(
+        &mut self,
+        amount_in: U256,
+        amount_out_min: U256,
+        path: Vec<Address>,
+        pair_types: Vec<u8>,
+        to: Address,
+        deadline: u64,
+    ) -> Vec<U256> {
+        self.ensure_deadline(deadline);
+        self.ensure_not_paused();
+        self.ensure_path_buyable(&path);
+
+        let amounts = self.get_amounts_out_typed_internal(amount_in, &path, &pair_types);
+        
+        if amounts[amounts.len() - 1] < amount_out_min {
+            self.env().revert(DexError::InsufficientOutputAmount);
+        }
+
+        let balances_before = self.path_balances(&path);
+        let pair = self.get_typed_pair_address(path[0], path[1], pair_types[0]);
+        self.safe_transfer_from(path[0], self.env().caller(), pair, amounts[0]);
+
+        self.execute_swap_typed(&amounts, &path, &pair_types, to);
+        self.record_trade(&amounts, &path);
+        self.emit_router_swap(&amounts, &path, to);
+        self.refund_dust(&path, &balances_before, self.env().caller());
 
         amounts
     }
@@ -181,6 +715,8 @@ impl Router {
         deadline: u64,
     ) -> Vec<U256> {
         self.ensure_deadline(deadline);
+        self.ensure_not_paused();
+        self.ensure_path_buyable(&path);
 
         let amounts = self.get_amounts_in_internal(amount_out, &path);
         
@@ -189,17 +725,390 @@ impl Router {
         }
 
         // Transfer input tokens to first pair
+        let balances_before = self.path_balances(&path);
         let pair = self.get_pair_address(path[0], path[1]);
         self.safe_transfer_from(path[0], self.env().caller(), pair, amounts[0]);
 
         // Execute swaps
         self.execute_swap(&amounts, &path, to);
+        self.record_trade(&amounts, &path);
+        self.emit_router_swap(&amounts, &path, to);
+
+        // Return any residual path tokens this swap left with the router
+        self.refund_dust(&path, &balances_before, self.env().caller());
 
         amounts
     }
 
+    /// Swap exact input once and split the output between recipients
+    /// Shares are in basis points and must sum to 10000; the last recipient
+    /// receives the rounding remainder so the router keeps nothing
+    pub fn swap_and_distribute(
+        &mut self,
+        amount_in: U256,
+        amount_out_min: U256,
+        path: Vec<Address>,
+        recipients: Vec<(Address, u16)>,
+        deadline: u64,
+    ) -> Vec<U256> {
+        self.ensure_deadline(deadline);
+        self.ensure_not_paused();
+        self.ensure_path_buyable(&path);
+
+        if recipients.is_empty() {
+            self.env().revert(DexError::InvalidConfiguration);
+        }
+        let total_bps: u32 = recipients.iter().map(|(_, bps)| *bps as u32).sum();
+        if total_bps != 10_000 {
+            self.env().revert(DexError::InvalidConfiguration);
+        }
+
+        let amounts = self.get_amounts_out_internal(amount_in, &path);
+        let amount_out = amounts[amounts.len() - 1];
+        if amount_out < amount_out_min {
+            self.env().revert(DexError::InsufficientOutputAmount);
+        }
+
+        // Swap into the router
+        let caller = self.env().caller();
+        let router = self.env().self_address();
+        let balances_before = self.path_balances(&path);
+        let pair = self.get_pair_address(path[0], path[1]);
+        self.safe_transfer_from(path[0], caller, pair, amounts[0]);
+        self.execute_swap(&amounts, &path, router);
+        self.record_trade(&amounts, &path);
+        self.emit_router_swap(&amounts, &path, router);
+
+        // Pay out the shares
+        let token_out = path[path.len() - 1];
+        let last = recipients.len() - 1;
+        let mut distributed = U256::zero();
+        for (i, (recipient, bps)) in recipients.iter().enumerate() {
+            let share = if i == last {
+                self.safe_sub(amount_out, distributed)
+            } else {
+                self.safe_div(self.safe_mul(amount_out, U256::from(*bps)), U256::from(10_000))
+            };
+            distributed = self.safe_add(distributed, share);
+            if !share.is_zero() {
+                self.safe_transfer(token_out, *recipient, share);
+            }
+        }
+
+        self.refund_dust(&path, &balances_before, caller);
+
+        self.env().emit_event(SwapDistributed {
+            sender: caller,
+            token_out,
+            amount_out,
+            recipient_count: recipients.len() as u32,
+            seq: self.next_event_seq(),
+        });
+
+        amounts
+    }
+
+    /// Swap exact input split across parallel routes
+    /// The input is divided by `quote_split`; each leg executes at live reserves,
+    /// and only the total output is checked against `amount_out_min`
+    pub fn swap_split(
+        &mut self,
+        routes: Vec<Route>,
+        amount_in: U256,
+        amount_out_min: U256,
+        to: Address,
+        deadline: u64,
+    ) -> U256 {
+        self.ensure_deadline(deadline);
+        self.ensure_not_paused();
+        for route in &routes {
+            self.ensure_path_buyable(&route.path);
+        }
+
+        let allocations = self.quote_split(routes.clone(), amount_in);
+        let caller = self.env().caller();
+        let touched: Vec<Address> = routes.iter().flat_map(|route| route.path.iter().copied()).collect();
+        let balances_before = self.path_balances(&touched);
+        let mut amount_out = U256::zero();
+        for (route, allocation) in routes.iter().zip(allocations) {
+            if allocation.is_zero() {
+                continue;
+            }
+            let amounts = self.get_amounts_out_typed_internal(allocation, &route.path, &route.pair_types);
+            let pair = self.get_typed_pair_address(route.path[0], route.path[1], route.pair_types[0]);
+            self.safe_transfer_from(route.path[0], caller, pair, allocation);
+            self.execute_swap_typed(&amounts, &route.path, &route.pair_types, to);
+            self.emit_router_swap(&amounts, &route.path, to);
+            amount_out = self.safe_add(amount_out, amounts[amounts.len() - 1]);
+        }
+
+        if amount_out < amount_out_min {
+            self.env().revert(DexError::InsufficientOutputAmount);
+        }
+
+        let first = &routes[0].path;
+        self.record_trade(&[amount_in, amount_out], &[first[0], first[first.len() - 1]]);
+        self.refund_dust(&touched, &balances_before, caller);
+
+        amount_out
+    }
+
+    /// Split an exact input between parallel routes to maximize total output
+    /// The input is handed out in `SPLIT_STEPS` chunks, each to the route whose
+    /// output grows most from it at current reserves. Routes are simulated
+    /// independently, so routes sharing a pool are quoted optimistically
+    pub fn quote_split(&self, routes: Vec<Route>, amount_in: U256) -> Vec<U256> {
+        if routes.is_empty() || routes.len() > MAX_SPLIT_ROUTES {
+            self.env().revert(DexError::InvalidPath);
+        }
+        if amount_in.is_zero() {
+            self.env().revert(DexError::InsufficientInputAmount);
+        }
+        let first = &routes[0].path;
+        let hops: Vec<Vec<(U256, U256, u16)>> = routes.iter().map(|route| {
+            self.ensure_route(&route.path, &route.pair_types);
+            if route.path[0] != first[0] || route.path[route.path.len() - 1] != first[first.len() - 1] {
+                self.env().revert(DexError::InvalidPath);
+            }
+            (0..route.path.len() - 1)
+                .map(|i| self.get_reserves_internal(route.path[i], route.path[i + 1], route.pair_types[i]))
+                .collect()
+        }).collect();
+
+        let mut allocations = vec![U256::zero(); routes.len()];
+        let mut outputs = vec![U256::zero(); routes.len()];
+        let chunk = amount_in / U256::from(SPLIT_STEPS);
+        let mut remaining = amount_in;
+        while !remaining.is_zero() {
+            // The last chunk takes the rounding remainder
+            let step = if chunk.is_zero() || remaining < chunk * U256::from(2) { remaining } else { chunk };
+            let mut best: Option<(usize, U256)> = None;
+            for (i, route_hops) in hops.iter().enumerate() {
+                let output = self.simulate_route(self.safe_add(allocations[i], step), route_hops);
+                if best.map_or(true, |(j, best_output)| output - outputs[i] > best_output - outputs[j]) {
+                    best = Some((i, output));
+                }
+            }
+            let (i, output) = best.unwrap_or_else(|| self.env().revert(DexError::InvalidPath));
+            allocations[i] = self.safe_add(allocations[i], step);
+            outputs[i] = output;
+            remaining = self.safe_sub(remaining, step);
+        }
+
+        allocations
+    }
+
+    /// Get a user's most recent trades, newest first
+    /// Only the last `TRADE_HISTORY_SIZE` trades are kept
+    pub fn get_trade_history(&self, user: Address, offset: u64, limit: u64) -> Vec<TradeRecord> {
+        let count = self.trade_count.get(&user).unwrap_or_default();
+        let available = count.min(TRADE_HISTORY_SIZE);
+        let mut trades = Vec::new();
+        let mut index = offset;
+        while index < available && (trades.len() as u64) < limit {
+            let slot = (count - 1 - index) % TRADE_HISTORY_SIZE;
+            if let Some(trade) = self.trade_history.get(&(user, slot)) {
+                trades.push(trade);
+            }
+            index += 1;
+        }
+        trades
+    }
+
+    /// Get the total number of trades a user has made through the router
+    pub fn get_trade_count(&self, user: Address) -> u64 {
+        self.trade_count.get(&user).unwrap_or_default()
+    }
+
     // ============ Quote Functions ============
 
+    /// Dry-run the slippage check of `swap_exact_tokens_for_tokens`
+    /// Reverts only carry an error code, so clients call this to learn the
+    /// expected output against their minimum before (or after) a failed swap
+    pub fn check_swap_exact_tokens_for_tokens(
+        &self,
+        amount_in: U256,
+        amount_out_min: U256,
+        path: Vec<Address>,
+    ) -> SlippageCheck {
+        let amounts = self.get_amounts_out_internal(amount_in, &path);
+        let expected = amounts[amounts.len() - 1];
+        SlippageCheck {
+            token: path[path.len() - 1],
+            expected,
+            limit: amount_out_min,
+            passed: expected >= amount_out_min,
+        }
+    }
+
+    /// Dry-run the slippage check of `swap_tokens_for_exact_tokens`
+    /// Returns the required input against the caller's maximum
+    pub fn check_swap_tokens_for_exact_tokens(
+        &self,
+        amount_out: U256,
+        amount_in_max: U256,
+        path: Vec<Address>,
+    ) -> SlippageCheck {
+        let amounts = self.get_amounts_in_internal(amount_out, &path);
+        let expected = amounts[0];
+        SlippageCheck {
+            token: path[0],
+            expected,
+            limit: amount_in_max,
+            passed: expected <= amount_in_max,
+        }
+    }
+
+    /// Dry-run the minimum checks of `add_liquidity`
+    /// Returns one check per token (A, then B): the amount that would be
+    /// deposited at current reserves against the caller's minimum
+    pub fn check_add_liquidity(
+        &self,
+        token_a: Address,
+        token_b: Address,
+        amount_a_desired: U256,
+        amount_b_desired: U256,
+        amount_a_min: U256,
+        amount_b_min: U256,
+    ) -> Vec<SlippageCheck> {
+        let factory_ref = FactoryContractRefContractRef::new(self.env(), self.factory());
+        let reserves = factory_ref.get_pair(token_a, token_b).map(|pair| {
+            let (token0, _) = sort_tokens(token_a, token_b);
+            let (reserve0, reserve1, _) = PairContractContractRef::new(self.env(), pair).get_reserves();
+            if token_a == token0 { (reserve0, reserve1) } else { (reserve1, reserve0) }
+        });
+        let (amount_a, amount_b) = match reserves {
+            Some((reserve_a, reserve_b)) if !reserve_a.is_zero() && !reserve_b.is_zero() => {
+                let amount_b_optimal = self.quote_internal(amount_a_desired, reserve_a, reserve_b);
+                if amount_b_optimal <= amount_b_desired {
+                    (amount_a_desired, amount_b_optimal)
+                } else {
+                    (self.quote_internal(amount_b_desired, reserve_b, reserve_a), amount_b_desired)
+                }
+            }
+            _ => (amount_a_desired, amount_b_desired),
+        };
+        vec![
+            SlippageCheck { token: token_a, expected: amount_a, limit: amount_a_min, passed: amount_a >= amount_a_min },
+            SlippageCheck { token: token_b, expected: amount_b, limit: amount_b_min, passed: amount_b >= amount_b_min },
+        ]
+    }
+
+    /// Dry-run the minimum checks of `remove_liquidity`
+    /// Returns one check per token (A, then B): the amount burning `liquidity`
+    /// would return at current reserves against the caller's minimum
+    pub fn check_remove_liquidity(
+        &self,
+        token_a: Address,
+        token_b: Address,
+        liquidity: U256,
+        amount_a_min: U256,
+        amount_b_min: U256,
+    ) -> Vec<SlippageCheck> {
+        let pair_ref = PairContractContractRef::new(self.env(), self.get_pair_address(token_a, token_b));
+        let (token0, _) = sort_tokens(token_a, token_b);
+        let (reserve0, reserve1, _) = pair_ref.get_reserves();
+        let (reserve_a, reserve_b) = if token_a == token0 { (reserve0, reserve1) } else { (reserve1, reserve0) };
+        let total_supply = pair_ref.total_supply();
+        if total_supply.is_zero() {
+            self.env().revert(DexError::InsufficientLiquidity);
+        }
+        let amount_a = self.safe_div(self.safe_mul(liquidity, reserve_a), total_supply);
+        let amount_b = self.safe_div(self.safe_mul(liquidity, reserve_b), total_supply);
+        vec![
+            SlippageCheck { token: token_a, expected: amount_a, limit: amount_a_min, passed: amount_a >= amount_a_min },
+            SlippageCheck { token: token_b, expected: amount_b, limit: amount_b_min, passed: amount_b >= amount_b_min },
+        ]
+    }
+
+    // ============ Aggregator Interface ============
+
+    /// Quote an exact-input swap along an encoded route
+    pub fn quote_exact_in(&self, amount_in: U256, route: Bytes) -> U256 {
+        let (path, pair_types) = self.route_hops(&route);
+        let amounts = self.get_amounts_out_typed_internal(amount_in, &path, &pair_types);
+        amounts[amounts.len() - 1]
+    }
+
+    /// Quote the input needed for an exact output along an encoded route
+    pub fn quote_exact_out(&self, amount_out: U256, route: Bytes) -> U256 {
+        let (path, pair_types) = self.route_hops(&route);
+        self.get_amounts_in_typed_internal(amount_out, &path, &pair_types)[0]
+    }
+
+    /// Swap an exact input along an encoded route
+    pub fn swap(
+        &mut self,
+        amount_in: U256,
+        amount_out_min: U256,
+        route: Bytes,
+        to: Address,
+        deadline: u64,
+    ) -> U256 {
+        let (path, pair_types) = self.route_hops(&route);
+        let amounts = self.swap_exact_tokens_for_tokens_typed(
+            amount_in,
+            amount_out_min,
+            path,
+            pair_types,
+            to,
+            deadline,
+        );
+        amounts[amounts.len() - 1]
+    }
+
+    /// Get the canonical token order and key of a token pair
+    /// The key is the factory's pair salt, computed without calling the factory
+    pub fn pair_key_for(&self, token_a: Address, token_b: Address) -> PairKey {
+        let (token0, token1) = sort_tokens(token_a, token_b);
+        let bytes = pair_key_preimage(token_a, token_b, DEFAULT_PAIR_TYPE)
+            .unwrap_or_revert_with(&self.env(), DexError::InvalidPair);
+        PairKey { token0, token1, key: self.env().hash(bytes) }
+    }
+
+    // ============ Token Callbacks ============
+
+    /// Swap tokens sent with `transfer_and_call`, with no prior approval
+    /// `data` comes from `sdk::route::encode_swap_call` and its route must start
+    /// at the calling token. The whole amount is swapped; the trade is recorded
+    /// for `from`, who also receives any dust. The router's balance of the
+    /// token must have grown by `amount` since its last callback, so a token
+    /// that calls without transferring is rejected
+    pub fn on_token_received(&mut self, from: Address, amount: U256, data: Bytes) {
+        let token = self.env().caller();
+        let call = decode_swap_call(&data)
+            .unwrap_or_else(|| self.env().revert(DexError::InvalidPath));
+        let (path, pair_types) = self.route_hops(&call.route);
+        if path[0] != token {
+            self.env().revert(DexError::InvalidPath);
+        }
+        let router = self.env().self_address();
+        let expected = self.safe_add(self.callback_balances.get(&token).unwrap_or_default(), amount);
+        if Cep18TokenContractRef::new(self.env(), token).balance_of(router) < expected {
+            self.env().revert(DexError::InsufficientInputAmount);
+        }
+        self.ensure_deadline(call.deadline);
+        self.ensure_not_paused();
+        self.ensure_path_buyable(&path);
+
+        let amounts = self.get_amounts_out_typed_internal(amount, &path, &pair_types);
+        if amounts[amounts.len() - 1] < call.amount_out_min {
+            self.env().revert(DexError::InsufficientOutputAmount);
+        }
+
+        // The token already sits with the router
+        let mut balances_before = self.path_balances(&path);
+        balances_before[0] = balances_before[0].saturating_sub(amount);
+        let pair = self.get_typed_pair_address(path[0], path[1], pair_types[0]);
+        self.safe_transfer(token, pair, amounts[0]);
+
+        self.execute_swap_typed_for(from, &amounts, &path, &pair_types, call.to);
+        self.record_trade_for(from, &amounts, &path);
+        self.emit_router_swap_from(from, &amounts, &path, call.to);
+        self.refund_dust(&path, &balances_before, from);
+        self.callback_balances.set(&token, Cep18TokenContractRef::new(self.env(), token).balance_of(router));
+    }
+
     /// Get the output amount for a given input amount
     pub fn get_amount_out(
         &self,
@@ -207,7 +1116,7 @@ impl Router {
         reserve_in: U256,
         reserve_out: U256,
     ) -> U256 {
-        self.get_amount_out_internal(amount_in, reserve_in, reserve_out)
+        self.get_amount_out_internal(amount_in, reserve_in, reserve_out, DEFAULT_FEE_BPS)
     }
 
     /// Get the input amount required for a given output amount
@@ -217,7 +1126,7 @@ impl Router {
         reserve_in: U256,
         reserve_out: U256,
     ) -> U256 {
-        self.get_amount_in_internal(amount_out, reserve_in, reserve_out)
+        self.get_amount_in_internal(amount_out, reserve_in, reserve_out, DEFAULT_FEE_BPS)
     }
 
     /// Get output amounts for a swap path
@@ -238,6 +1147,84 @@ impl Router {
         self.get_amounts_in_internal(amount_out, &path)
     }
 
+    /// Get output amounts for a swap path with a pair type per hop
+    pub fn get_amounts_out_typed(
+        &self,
+        amount_in: U256,
+        path: Vec<Address>,
+        pair_types: Vec<u8>,
+    ) -> Vec<U256> {
+        self.get_amounts_out_typed_internal(amount_in, &path, &pair_types)
+    }
+
+    /// Get input amounts for a swap path with a pair type per hop
+    pub fn get_amounts_in_typed(
+        &self,
+        amount_out: U256,
+        path: Vec<Address>,
+        pair_types: Vec<u8>,
+    ) -> Vec<U256> {
+        self.get_amounts_in_typed_internal(amount_out, &path, &pair_types)
+    }
+
+    /// Output of a swap path for an exact input, both in `NORMALIZED_DECIMALS` units
+    /// Token decimals along the path are queried, so mixed-decimal routes show
+    /// human amounts; the raw output is truncated like the swap itself
+    pub fn amount_out_for_exact_in(&self, path: Vec<Address>, amount_in: U256) -> U256 {
+        if path.len() < 2 {
+            self.env().revert(DexError::InvalidPath);
+        }
+        let raw_in = self.from_normalized(path[0], amount_in, false);
+        let amounts = self.get_amounts_out_internal(raw_in, &path);
+        self.to_normalized(path[path.len() - 1], amounts[amounts.len() - 1], false)
+    }
+
+    /// Input of a swap path for an exact output, both in `NORMALIZED_DECIMALS` units
+    /// The output is rounded up to the token's precision and the input rounded
+    /// up, so paying the quoted input always yields at least `amount_out`
+    pub fn amount_in_for_exact_out(&self, path: Vec<Address>, amount_out: U256) -> U256 {
+        if path.len() < 2 {
+            self.env().revert(DexError::InvalidPath);
+        }
+        let raw_out = self.from_normalized(path[path.len() - 1], amount_out, true);
+        let amounts = self.get_amounts_in_internal(raw_out, &path);
+        self.to_normalized(path[0], amounts[0], true)
+    }
+
+    /// Spot equivalent of `amount_a` of token A in token B, both in `NORMALIZED_DECIMALS` units
+    /// Reserves are normalized first, so the quote reads the same whatever
+    /// decimals the two tokens use
+    pub fn quote_normalized(&self, amount_a: U256, token_a: Address, token_b: Address) -> U256 {
+        let (reserve_a, reserve_b) = self.get_reserves(token_a, token_b);
+        let reserve_a = self.to_normalized(token_a, reserve_a, false);
+        let reserve_b = self.to_normalized(token_b, reserve_b, false);
+        if amount_a.is_zero() {
+            self.env().revert(DexError::InsufficientAmount);
+        }
+        if reserve_a.is_zero() || reserve_b.is_zero() {
+            self.env().revert(DexError::InsufficientLiquidity);
+        }
+        SafeMath::mul_div(amount_a, reserve_b, reserve_a).unwrap_or_else(|e| self.env().revert(e))
+    }
+
+    /// Spot prices of a pair scaled by 1e18, independent of token decimals
+    /// Returns (price of one token0 in token1, price of one token1 in token0)
+    pub fn get_price_normalized(&self, pair: Address) -> (U256, U256) {
+        let pair_ref = PairContractContractRef::new(self.env(), pair);
+        let (reserve0, reserve1, _) = pair_ref.get_reserves();
+        let reserve0 = self.to_normalized(pair_ref.token0(), reserve0, false);
+        let reserve1 = self.to_normalized(pair_ref.token1(), reserve1, false);
+        if reserve0.is_zero() || reserve1.is_zero() {
+            self.env().revert(DexError::InsufficientLiquidity);
+        }
+
+        let one = DecimalMath::to_normalized(U256::one(), 0, false)
+            .unwrap_or_else(|e| self.env().revert(e));
+        let price0 = SafeMath::mul_div(reserve1, one, reserve0).unwrap_or_else(|e| self.env().revert(e));
+        let price1 = SafeMath::mul_div(reserve0, one, reserve1).unwrap_or_else(|e| self.env().revert(e));
+        (price0, price1)
+    }
+
     /// Quote the amount of token B for a given amount of token A
     pub fn quote(
         &self,
@@ -254,7 +1241,7 @@ impl Router {
         token_a: Address,
         token_b: Address,
     ) -> (U256, U256) {
-        let (token0, _) = self.sort_tokens(token_a, token_b);
+        let (token0, _) = sort_tokens(token_a, token_b);
         let pair = self.get_pair_address(token_a, token_b);
         
         let pair_ref = PairContractContractRef::new(self.env(), pair);
@@ -269,19 +1256,59 @@ impl Router {
 
     // ============ Internal Functions ============
 
-    /// Ensure the deadline has not passed
-    fn ensure_deadline(&self, deadline: u64) {
-        if self.env().get_block_time() > deadline {
-            self.env().revert(DexError::DeadlineExpired);
+    /// Revert while the guardian has the router paused
+    /// Removing liquidity stays available so funds are never locked
+    fn ensure_not_paused(&self) {
+        if let Some(guardian) = self.guardian.get_or_default() {
+            let guardian = GuardianContractContractRef::new(self.env(), guardian);
+            if guardian.is_paused(self.env().self_address()) {
+                self.env().revert(DexError::ContractPaused);
+            }
         }
     }
 
-    /// Sort two token addresses
-    fn sort_tokens(&self, token_a: Address, token_b: Address) -> (Address, Address) {
-        if token_a < token_b {
-            (token_a, token_b)
-        } else {
-            (token_b, token_a)
+    /// Revert in safe mode if any of `tokens` is flagged as compromised
+    fn ensure_buyable(&self, tokens: &[Address]) {
+        let Some(list) = self.token_list.get_or_default() else {
+            return;
+        };
+        let list = TokenListContractContractRef::new(self.env(), list);
+        if tokens.iter().any(|token| list.is_compromised(*token)) {
+            self.env().revert(DexError::TokenDelisted);
+        }
+    }
+
+    /// Revert in safe mode if a swap path buys a compromised token
+    /// Selling one (the first token) is always allowed
+    fn ensure_path_buyable(&self, path: &[Address]) {
+        if path.len() > 1 {
+            self.ensure_buyable(&path[1..]);
+        }
+    }
+
+    /// Convert a raw token amount to normalized units
+    fn to_normalized(&self, token: Address, amount: U256, round_up: bool) -> U256 {
+        let decimals = Cep18TokenContractRef::new(self.env(), token).decimals();
+        DecimalMath::to_normalized(amount, decimals, round_up)
+            .unwrap_or_else(|e| self.env().revert(e))
+    }
+
+    /// Convert a normalized amount to raw token units
+    fn from_normalized(&self, token: Address, amount: U256, round_up: bool) -> U256 {
+        let decimals = Cep18TokenContractRef::new(self.env(), token).decimals();
+        DecimalMath::from_normalized(amount, decimals, round_up)
+            .unwrap_or_else(|e| self.env().revert(e))
+    }
+
+    /// Decode aggregator route bytes into a token path and per-hop pair types
+    fn route_hops(&self, route: &[u8]) -> (Vec<Address>, Vec<u8>) {
+        decode_typed_route(route).unwrap_or_else(|| self.env().revert(DexError::InvalidPath))
+    }
+
+    /// Ensure the deadline has not passed, allowing the configured drift tolerance
+    fn ensure_deadline(&self, deadline: u64) {
+        if deadline_expired(self.env().get_block_time(), deadline, self.deadline_tolerance()) {
+            self.env().revert(DexError::DeadlineExpired);
         }
     }
 
@@ -296,6 +1323,21 @@ impl Router {
         }
     }
 
+    /// Get pair address for two tokens and a pair type
+    fn get_typed_pair_address(&self, token_a: Address, token_b: Address, pair_type: u8) -> Address {
+        if pair_type == DEFAULT_PAIR_TYPE {
+            return self.get_pair_address(token_a, token_b);
+        }
+        let factory_ref = FactoryContractRefContractRef::new(self.env(), self.factory());
+        factory_ref.get_pair_with_type(token_a, token_b, pair_type)
+            .unwrap_or_else(|| self.env().revert(DexError::PairNotFound))
+    }
+
+    /// Default pair type for every hop of a path
+    fn default_pair_types(&self, path: &[Address]) -> Vec<u8> {
+        vec![DEFAULT_PAIR_TYPE; path.len().saturating_sub(1)]
+    }
+
     /// Get or create pair for two tokens
     fn get_or_create_pair(
         &mut self,
@@ -314,6 +1356,85 @@ impl Router {
         }
     }
 
+    /// Burn `liquidity` of `pair_from` into the router and mint into `pair_to`
+    /// for the caller, refunding what does not fit. Amounts are in
+    /// `pair_from` token order
+    fn migrate_internal(
+        &mut self,
+        pair_from: Address,
+        pair_to: Address,
+        liquidity: U256,
+        amount0_min: U256,
+        amount1_min: U256,
+    ) -> (U256, U256, U256) {
+        if pair_from == pair_to {
+            self.env().revert(DexError::IdenticalAddresses);
+        }
+
+        let caller = self.env().caller();
+        let router = self.env().self_address();
+        let mut from_ref = PairContractContractRef::new(self.env(), pair_from);
+        let mut to_ref = PairContractContractRef::new(self.env(), pair_to);
+        let token0 = from_ref.token0();
+        let token1 = from_ref.token1();
+        let flipped = to_ref.token0() == token1 && to_ref.token1() == token0;
+        if !flipped && (to_ref.token0() != token0 || to_ref.token1() != token1) {
+            self.env().revert(DexError::InvalidPair);
+        }
+
+        // Burn into the router, measuring what actually arrived
+        let token0_ref = Cep18TokenContractRef::new(self.env(), token0);
+        let token1_ref = Cep18TokenContractRef::new(self.env(), token1);
+        let balance0_before = token0_ref.balance_of(router);
+        let balance1_before = token1_ref.balance_of(router);
+        let balances_before = [balance0_before, balance1_before];
+        if !from_ref.transfer_from(caller, pair_from, liquidity) {
+            self.env().revert(DexError::TransferFailed);
+        }
+        from_ref.burn(router);
+        let amount0 = self.safe_sub(token0_ref.balance_of(router), balance0_before);
+        let amount1 = self.safe_sub(token1_ref.balance_of(router), balance1_before);
+
+        if amount0 < amount0_min || amount1 < amount1_min {
+            self.env().revert(DexError::InsufficientAmount);
+        }
+
+        // Fit the amounts to the destination ratio
+        let (reserve0, reserve1, _) = to_ref.get_reserves();
+        let (reserve0, reserve1) = if flipped { (reserve1, reserve0) } else { (reserve0, reserve1) };
+        let (deposit0, deposit1) = if reserve0.is_zero() || reserve1.is_zero() {
+            (amount0, amount1)
+        } else {
+            let amount1_optimal = self.quote_internal(amount0, reserve0, reserve1);
+            if amount1_optimal <= amount1 {
+                (amount0, amount1_optimal)
+            } else {
+                (self.quote_internal(amount1, reserve1, reserve0), amount1)
+            }
+        };
+
+        self.safe_transfer(token0, pair_to, deposit0);
+        self.safe_transfer(token1, pair_to, deposit1);
+        let minted = to_ref.mint(caller);
+
+        // Refund what did not fit
+        self.refund_dust(&[token0, token1], &balances_before, caller);
+
+        let (event0, event1) = if flipped { (deposit1, deposit0) } else { (deposit0, deposit1) };
+        self.env().emit_event(LiquidityMigrated {
+            provider: caller,
+            pair_from,
+            pair_to,
+            liquidity_burned: liquidity,
+            liquidity_minted: minted,
+            amount0: event0,
+            amount1: event1,
+            seq: self.next_event_seq(),
+        });
+
+        (deposit0, deposit1, minted)
+    }
+
     /// Calculate optimal liquidity amounts
     fn calculate_liquidity_amounts(
         &self,
@@ -329,7 +1450,7 @@ impl Router {
         
         match factory_ref.get_pair(token_a, token_b) {
             Some(pair) => {
-                let (token0, _) = self.sort_tokens(token_a, token_b);
+                let (token0, _) = sort_tokens(token_a, token_b);
                 let pair_ref = PairContractContractRef::new(self.env(), pair);
                 let (reserve0, reserve1, _) = pair_ref.get_reserves();
                 
@@ -348,7 +1469,7 @@ impl Router {
                 
                 if amount_b_optimal <= amount_b_desired {
                     if amount_b_optimal < amount_b_min {
-                        self.env().revert(DexError::InsufficientAmount);
+                        self.env().revert(DexError::InsufficientBAmount);
                     }
                     (amount_a_desired, amount_b_optimal)
                 } else {
@@ -359,7 +1480,7 @@ impl Router {
                         self.env().revert(DexError::InsufficientAmount);
                     }
                     if amount_a_optimal < amount_a_min {
-                        self.env().revert(DexError::InsufficientAmount);
+                        self.env().revert(DexError::InsufficientAAmount);
                     }
                     (amount_a_optimal, amount_b_desired)
                 }
@@ -371,75 +1492,142 @@ impl Router {
         }
     }
 
-    /// Internal get_amounts_out calculation
+    /// Internal get_amounts_out calculation over default pairs
     fn get_amounts_out_internal(
         &self,
         amount_in: U256,
         path: &[Address],
     ) -> Vec<U256> {
-        if path.len() < 2 {
-            self.env().revert(DexError::InvalidPath);
-        }
+        self.get_amounts_out_typed_internal(amount_in, path, &self.default_pair_types(path))
+    }
+
+    /// Internal get_amounts_in calculation over default pairs
+    fn get_amounts_in_internal(
+        &self,
+        amount_out: U256,
+        path: &[Address],
+    ) -> Vec<U256> {
+        self.get_amounts_in_typed_internal(amount_out, path, &self.default_pair_types(path))
+    }
+
+    /// Internal get_amounts_out calculation with a pair type per hop
+    fn get_amounts_out_typed_internal(
+        &self,
+        amount_in: U256,
+        path: &[Address],
+        pair_types: &[u8],
+    ) -> Vec<U256> {
+        self.ensure_route(path, pair_types);
 
         let mut amounts = Vec::with_capacity(path.len());
         amounts.push(amount_in);
 
         for i in 0..path.len() - 1 {
-            let (reserve_in, reserve_out) = self.get_reserves_internal(path[i], path[i + 1]);
-            let amount_out = self.get_amount_out_internal(amounts[i], reserve_in, reserve_out);
+            let (reserve_in, reserve_out, fee_bps) =
+                self.get_reserves_internal(path[i], path[i + 1], pair_types[i]);
+            let amount_out = self.get_amount_out_internal(amounts[i], reserve_in, reserve_out, fee_bps);
             amounts.push(amount_out);
         }
 
         amounts
     }
 
-    /// Internal get_amounts_in calculation
-    fn get_amounts_in_internal(
+    /// Internal get_amounts_in calculation with a pair type per hop
+    fn get_amounts_in_typed_internal(
         &self,
         amount_out: U256,
         path: &[Address],
+        pair_types: &[u8],
     ) -> Vec<U256> {
-        if path.len() < 2 {
-            self.env().revert(DexError::InvalidPath);
-        }
+        self.ensure_route(path, pair_types);
 
         let mut amounts = vec![U256::zero(); path.len()];
         amounts[path.len() - 1] = amount_out;
 
         for i in (0..path.len() - 1).rev() {
-            let (reserve_in, reserve_out) = self.get_reserves_internal(path[i], path[i + 1]);
-            let amount_in = self.get_amount_in_internal(amounts[i + 1], reserve_in, reserve_out);
+            let (reserve_in, reserve_out, fee_bps) =
+                self.get_reserves_internal(path[i], path[i + 1], pair_types[i]);
+            let amount_in = self.get_amount_in_internal(amounts[i + 1], reserve_in, reserve_out, fee_bps);
             amounts[i] = amount_in;
         }
 
         amounts
     }
 
-    /// Internal get reserves for a token pair
-    fn get_reserves_internal(&self, token_a: Address, token_b: Address) -> (U256, U256) {
-        let (token0, _) = self.sort_tokens(token_a, token_b);
-        let pair = self.get_pair_address(token_a, token_b);
+    /// Ensure a route has at least one hop, a pair type per hop, and that every
+    /// hop's pair type has a quote implementation in the router
+    fn ensure_route(&self, path: &[Address], pair_types: &[u8]) {
+        if path.len() < 2 || pair_types.len() != path.len() - 1 {
+            self.env().revert(DexError::InvalidPath);
+        }
+        let factory_ref = FactoryContractRefContractRef::new(self.env(), self.factory());
+        for pair_type in pair_types {
+            if *pair_type == DEFAULT_PAIR_TYPE {
+                continue;
+            }
+            // Only constant product math is implemented; other curves need their own quote
+            let info = factory_ref.get_pair_type(*pair_type)
+                .unwrap_or_else(|| self.env().revert(DexError::InvalidPath));
+            if info.kind != PairKind::ConstantProduct {
+                self.env().revert(DexError::InvalidPath);
+            }
+        }
+    }
+
+    /// Internal get reserves and swap fee for a token pair of a given type
+    /// The fee is read from the pair so quotes follow its pair type
+    fn get_reserves_internal(&self, token_a: Address, token_b: Address, pair_type: u8) -> (U256, U256, u16) {
+        let (token0, _) = sort_tokens(token_a, token_b);
+        let pair = self.get_typed_pair_address(token_a, token_b, pair_type);
         
         let pair_ref = PairContractContractRef::new(self.env(), pair);
         let (reserve0, reserve1, _) = pair_ref.get_reserves();
+        let fee_bps = pair_ref.fee_bps();
 
         if token_a == token0 {
-            (reserve0, reserve1)
+            (reserve0, reserve1, fee_bps)
         } else {
-            (reserve1, reserve0)
+            (reserve1, reserve0, fee_bps)
         }
     }
 
-    /// Execute a multi-hop swap
+    /// Execute a multi-hop swap over default pairs
     fn execute_swap(
         &self,
         amounts: &[U256],
         path: &[Address],
         to: Address,
     ) {
+        self.execute_swap_typed(amounts, path, &self.default_pair_types(path), to);
+    }
+
+    /// Execute a multi-hop swap with a pair type per hop
+    fn execute_swap_typed(
+        &self,
+        amounts: &[U256],
+        path: &[Address],
+        pair_types: &[u8],
+        to: Address,
+    ) {
+        self.execute_swap_typed_for(self.env().caller(), amounts, path, pair_types, to);
+    }
+
+    /// Execute a multi-hop swap on behalf of `trader`
+    /// The trader is credited with competition volume and loyalty rebates
+    fn execute_swap_typed_for(
+        &self,
+        trader: Address,
+        amounts: &[U256],
+        path: &[Address],
+        pair_types: &[u8],
+        to: Address,
+    ) {
+        let competition = self.competition.get_or_default();
+        let loyalty = self.loyalty.get_or_default();
+
         for i in 0..path.len() - 1 {
             let (input, output) = (path[i], path[i + 1]);
-            let (token0, _) = self.sort_tokens(input, output);
+            let (token0, _) = sort_tokens(input, output);
             let amount_out = amounts[i + 1];
 
             let (amount0_out, amount1_out) = if input == token0 {
@@ -450,14 +1638,183 @@ impl Router {
 
             // Determine recipient
             let recipient = if i < path.len() - 2 {
-                self.get_pair_address(output, path[i + 2])
+                self.get_typed_pair_address(output, path[i + 2], pair_types[i + 1])
             } else {
                 to
             };
 
-            let pair = self.get_pair_address(input, output);
+            let pair = self.get_typed_pair_address(input, output, pair_types[i]);
             let mut pair_ref = PairContractContractRef::new(self.env(), pair);
             pair_ref.swap(amount0_out, amount1_out, recipient);
+
+            // Report the hop's token0 volume to the running competition
+            if let Some(competition) = competition {
+                let volume = if input == token0 { amounts[i] } else { amount_out };
+                CompetitionReporterContractRef::new(self.env(), competition)
+                    .report_volume(pair, trader, volume);
+            }
+
+            // Rebate part of the hop's fee, charged in its input token, to loyal LPs
+            if let Some(loyalty) = loyalty {
+                let fee = self.safe_div(
+                    self.safe_mul(amounts[i], U256::from(pair_ref.fee_bps())),
+                    U256::from(BPS_DENOMINATOR),
+                );
+                if !fee.is_zero() {
+                    LoyaltyRebaterContractRef::new(self.env(), loyalty).rebate(trader, pair, input, fee);
+                }
+            }
+        }
+    }
+
+    /// Run one `multicall_cspr` sub-action with WCSPR the router holds
+    fn run_cspr_action(&mut self, wcspr: Address, action: CsprAction, to: Address) -> U256 {
+        match action.kind {
+            CsprActionKind::Wrap => {
+                self.safe_transfer(wcspr, to, action.cspr_amount);
+                action.cspr_amount
+            }
+            CsprActionKind::Swap => {
+                let path = action.path;
+                if path.len() < 2 || path[0] != wcspr {
+                    self.env().revert(DexError::InvalidPath);
+                }
+                self.ensure_path_buyable(&path);
+                let amounts = self.get_amounts_out_internal(action.cspr_amount, &path);
+                if amounts[amounts.len() - 1] < action.amount_min {
+                    self.env().revert(DexError::InsufficientOutputAmount);
+                }
+                let pair = self.get_pair_address(path[0], path[1]);
+                self.safe_transfer(wcspr, pair, amounts[0]);
+                self.execute_swap(&amounts, &path, to);
+                self.record_trade(&amounts, &path);
+                self.emit_router_swap(&amounts, &path, to);
+                amounts[amounts.len() - 1]
+            }
+            CsprActionKind::AddLiquidity => {
+                if action.path.len() != 1 || action.path[0] == wcspr {
+                    self.env().revert(DexError::InvalidPath);
+                }
+                let token = action.path[0];
+                self.ensure_buyable(&[wcspr, token]);
+                let (amount_wcspr, amount_token) = self.calculate_liquidity_amounts(
+                    wcspr,
+                    token,
+                    action.cspr_amount,
+                    action.token_amount,
+                    U256::zero(),
+                    U256::zero(),
+                );
+                let pair = self.get_or_create_pair(wcspr, token);
+                self.safe_transfer(wcspr, pair, amount_wcspr);
+                self.safe_transfer_from(token, self.env().caller(), pair, amount_token);
+                let liquidity = PairContractContractRef::new(self.env(), pair).mint(to);
+                if liquidity < action.amount_min {
+                    self.env().revert(DexError::InsufficientLiquidityMinted);
+                }
+
+                self.env().emit_event(RouterLiquidityAdded {
+                    sender: self.env().caller(),
+                    to,
+                    pair,
+                    token_a: wcspr,
+                    token_b: token,
+                    amount_a: amount_wcspr,
+                    amount_b: amount_token,
+                    liquidity,
+                    seq: self.next_event_seq(),
+                });
+                liquidity
+            }
+        }
+    }
+
+    /// Output of a route for an exact input, given each hop's reserves and fee
+    fn simulate_route(&self, amount_in: U256, hops: &[(U256, U256, u16)]) -> U256 {
+        hops.iter().fold(amount_in, |amount, (reserve_in, reserve_out, fee_bps)| {
+            if amount.is_zero() {
+                return amount;
+            }
+            self.get_amount_out_internal(amount, *reserve_in, *reserve_out, *fee_bps)
+        })
+    }
+
+    /// Emit the router-level record of a swap along `path`
+    fn emit_router_swap(&mut self, amounts: &[U256], path: &[Address], to: Address) {
+        self.emit_router_swap_from(self.env().caller(), amounts, path, to);
+    }
+
+    /// Emit the router-level swap event for a given sender
+    fn emit_router_swap_from(&mut self, sender: Address, amounts: &[U256], path: &[Address], to: Address) {
+        self.env().emit_event(RouterSwap {
+            sender,
+            to,
+            path: path.to_vec(),
+            amounts: amounts.to_vec(),
+            seq: self.next_event_seq(),
+        });
+    }
+
+    /// Append a swap to the caller's trade history
+    fn record_trade(&mut self, amounts: &[U256], path: &[Address]) {
+        self.record_trade_for(self.env().caller(), amounts, path);
+    }
+
+    /// Append a swap to a user's trade history
+    fn record_trade_for(&mut self, user: Address, amounts: &[U256], path: &[Address]) {
+        let count = self.trade_count.get(&user).unwrap_or_default();
+        let record = TradeRecord {
+            token_in: path[0],
+            token_out: path[path.len() - 1],
+            amount_in: amounts[0],
+            amount_out: amounts[amounts.len() - 1],
+            timestamp: self.env().get_block_time(),
+        };
+        self.trade_history.set(&(user, count % TRADE_HISTORY_SIZE), record);
+        self.trade_count.set(&user, count + 1);
+    }
+
+    /// Router balance of each path token, taken before a call moves any of them
+    fn path_balances(&self, path: &[Address]) -> Vec<U256> {
+        let router = self.env().self_address();
+        path.iter()
+            .map(|token| Cep18TokenContractRef::new(self.env(), *token).balance_of(router))
+            .collect()
+    }
+
+    /// Send `to` what the router gained of each path token since `before`
+    /// (from `path_balances`). Rounding or fee-on-transfer tokens can leave
+    /// dust behind after a swap; balances the router already held, such as
+    /// tokens sent to it by mistake, are left alone
+    fn refund_dust(&mut self, path: &[Address], before: &[U256], to: Address) {
+        let router = self.env().self_address();
+        for (i, token) in path.iter().enumerate() {
+            // Skip tokens that appear earlier in the path
+            if path[..i].contains(token) {
+                continue;
+            }
+            let mut token_ref = Cep18TokenContractRef::new(self.env(), *token);
+            let amount = token_ref.balance_of(router).saturating_sub(before[i]);
+            if amount.is_zero() {
+                continue;
+            }
+            if !token_ref.transfer(to, amount) {
+                self.env().revert(DexError::TransferFailed);
+            }
+            self.env().emit_event(DustRefunded {
+                token: *token,
+                to,
+                amount,
+                seq: self.next_event_seq(),
+            });
+        }
+    }
+
+    /// Safe transfer tokens held by the router
+    fn safe_transfer(&self, token: Address, to: Address, amount: U256) {
+        let mut token_ref = Cep18TokenContractRef::new(self.env(), token);
+        if !token_ref.transfer(to, amount) {
+            self.env().revert(DexError::TransferFailed);
         }
     }
 
@@ -489,39 +1846,51 @@ impl Router {
     }
 
     /// Internal get_amount_out calculation
-    fn get_amount_out_internal(&self, amount_in: U256, reserve_in: U256, reserve_out: U256) -> U256 {
+    fn get_amount_out_internal(
+        &self,
+        amount_in: U256,
+        reserve_in: U256,
+        reserve_out: U256,
+        fee_bps: u16,
+    ) -> U256 {
         if amount_in.is_zero() {
             self.env().revert(DexError::InsufficientInputAmount);
         }
         if reserve_in.is_zero() || reserve_out.is_zero() {
             self.env().revert(DexError::InsufficientLiquidity);
         }
-        // amount_out = (amount_in * 997 * reserve_out) / (reserve_in * 1000 + amount_in * 997)
-        let amount_in_with_fee = self.safe_mul(amount_in, U256::from(997));
+        // amount_out = (amount_in * (10000 - fee) * reserve_out) / (reserve_in * 10000 + amount_in * (10000 - fee))
+        let amount_in_with_fee = self.safe_mul(amount_in, U256::from(BPS_DENOMINATOR - fee_bps as u128));
         let numerator = self.safe_mul(amount_in_with_fee, reserve_out);
         let denominator = self.safe_add(
-            self.safe_mul(reserve_in, U256::from(1000)),
+            self.safe_mul(reserve_in, U256::from(BPS_DENOMINATOR)),
             amount_in_with_fee,
         );
         self.safe_div(numerator, denominator)
     }
 
     /// Internal get_amount_in calculation
-    fn get_amount_in_internal(&self, amount_out: U256, reserve_in: U256, reserve_out: U256) -> U256 {
+    fn get_amount_in_internal(
+        &self,
+        amount_out: U256,
+        reserve_in: U256,
+        reserve_out: U256,
+        fee_bps: u16,
+    ) -> U256 {
         if amount_out.is_zero() {
             self.env().revert(DexError::InsufficientOutputAmount);
         }
         if reserve_in.is_zero() || reserve_out.is_zero() {
             self.env().revert(DexError::InsufficientLiquidity);
         }
-        // amount_in = (reserve_in * amount_out * 1000) / ((reserve_out - amount_out) * 997) + 1
+        // amount_in = (reserve_in * amount_out * 10000) / ((reserve_out - amount_out) * (10000 - fee)) + 1
         let numerator = self.safe_mul(
             self.safe_mul(reserve_in, amount_out),
-            U256::from(1000),
+            U256::from(BPS_DENOMINATOR),
         );
         let denominator = self.safe_mul(
             self.safe_sub(reserve_out, amount_out),
-            U256::from(997),
+            U256::from(BPS_DENOMINATOR - fee_bps as u128),
         );
         self.safe_add(self.safe_div(numerator, denominator), U256::one())
     }
@@ -554,6 +1923,18 @@ impl Router {
         }
         a / b
     }
+
+    /// Sequence number of the last event emitted; indexers page from here
+    pub fn event_seq(&self) -> u64 {
+        self.event_seq.get_or_default()
+    }
+
+    /// Advance the event sequence and return the number for the next event
+    fn next_event_seq(&mut self) -> u64 {
+        let seq = self.event_seq.get_or_default() + 1;
+        self.event_seq.set(seq);
+        seq
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,680 @@
+//! Single-sided liquidity wrapper for the DEX
+//!
+//! Lets users provide liquidity with only one token of a pair:
+//! - Swaps the optimal portion of the deposit into the other token
+//! - Adds both sides to the pair and holds the LP tokens on the user's behalf
+//! - Tracks the entry price and principal of each position
+//! - On withdrawal, optionally compensates impermanent loss from a
+//!   protocol-funded reserve, capped at a share of the principal. The other
+//!   token is valued at the pair's TWAP since the last deposit, so the spot
+//!   price cannot be pushed around to inflate the loss
+//!
+//! Only pairs registered with the factory are accepted.
+use odra::prelude::*;
+use odra::casper_types::U256;
+use odra::ContractRef;
+use crate::errors::DexError;
+use crate::events::{SingleSidedDeposited, SingleSidedWithdrawn};
+use crate::math::SafeMath;
+use crate::token::Cep18TokenContractRef;
+use super::factory::FactoryContractContractRef;
+use super::pair::PairContractRef;
+use super::router::PairContractContractRef;
+
+/// Basis points denominator
+const BPS_DENOMINATOR: u32 = 10_000;
+
+/// Price scaling factor (1e18)
+const PRICE_SCALE: u128 = 1_000_000_000_000_000_000;
+
+/// A single-sided liquidity position
+#[odra::odra_type]
+pub struct SingleSidedPosition {
+    /// Pair the liquidity was added to
+    pub pair: Address,
+    /// Token the user deposited
+    pub token_in: Address,
+    /// LP tokens held for the user
+    pub liquidity: U256,
+    /// Amount of `token_in` deposited and not yet withdrawn
+    pub principal: U256,
+    /// Principal-weighted entry price (other token per `token_in`, scaled by 1e18)
+    pub entry_price: U256,
+    /// Timestamp of the last deposit
+    pub deposited_at: u64,
+    /// Cumulative price of `token_in` at the last deposit, for the withdrawal TWAP
+    pub entry_cumulative: U256,
+}
+
+/// Single-sided liquidity wrapper contract
+#[odra::module]
+pub struct SingleSidedLp {
+    /// Admin address
+    admin: Var<Address>,
+    /// Factory whose registered pairs are accepted
+    factory: Var<Address>,
+    /// Whether impermanent loss compensation is paid on withdrawal
+    protection_enabled: Var<bool>,
+    /// Maximum compensation as a share of the withdrawn principal (bps)
+    max_compensation_bps: Var<u32>,
+    /// Compensation reserve balance per token
+    reserves: Mapping<Address, U256>,
+    /// Positions: (user, pair) -> SingleSidedPosition
+    positions: Mapping<(Address, Address), SingleSidedPosition>,
+    /// Sequence number of the last event emitted
+    event_seq: Var<u64>,
+}
+
+#[odra::module]
+impl SingleSidedLp {
+    /// Initialize the wrapper with the factory and the compensation cap
+    pub fn init(&mut self, factory: Address, max_compensation_bps: u32) {
+        if max_compensation_bps > BPS_DENOMINATOR {
+            self.env().revert(DexError::InvalidFee);
+        }
+        self.admin.set(self.env().caller());
+        self.factory.set(factory);
+        self.protection_enabled.set(true);
+        self.max_compensation_bps.set(max_compensation_bps);
+    }
+
+    // ============ Liquidity Functions ============
+
+    /// Deposit `amount_in` of one pair token
+    /// Returns the LP tokens credited to the caller's position
+    pub fn deposit(
+        &mut self,
+        pair: Address,
+        token_in: Address,
+        amount_in: U256,
+        min_liquidity: U256,
+        deadline: u64,
+    ) -> U256 {
+        self.ensure_deadline(deadline);
+        self.ensure_registered_pair(pair);
+        if amount_in.is_zero() {
+            self.env().revert(DexError::InsufficientInputAmount);
+        }
+
+        let caller = self.env().caller();
+        let this = self.env().self_address();
+        let mut pair_ref = PairContractContractRef::new(self.env(), pair);
+        let token0 = pair_ref.token0();
+        let token1 = pair_ref.token1();
+        if token_in != token0 && token_in != token1 {
+            self.env().revert(DexError::InvalidPair);
+        }
+        let token_other = if token_in == token0 { token1 } else { token0 };
+
+        let mut position = self.positions.get(&(caller, pair));
+        if let Some(existing) = &position {
+            if existing.token_in != token_in {
+                self.env().revert(DexError::InvalidPair);
+            }
+        }
+
+        self.safe_transfer_from(token_in, caller, this, amount_in);
+
+        // Swap the optimal share of the deposit into the other token
+        // at the pair's own fee
+        let (reserve_in, reserve_out) = self.reserves_for(&pair_ref, token_in);
+        let fee_bps = pair_ref.fee_bps();
+        let swap_amount = self.swap_amount_internal(amount_in, reserve_in, fee_bps);
+        let amount_other = self.amount_out_internal(swap_amount, reserve_in, reserve_out, fee_bps);
+        let entry_price = self.price_internal(reserve_in, reserve_out);
+
+        self.safe_transfer(token_in, pair, swap_amount);
+        let (amount0_out, amount1_out) = if token_in == token0 {
+            (U256::zero(), amount_other)
+        } else {
+            (amount_other, U256::zero())
+        };
+        pair_ref.swap(amount0_out, amount1_out, this);
+
+        // Add both sides and keep the LP tokens
+        let remaining_in = self.sub(amount_in, swap_amount);
+        self.safe_transfer(token_in, pair, remaining_in);
+        self.safe_transfer(token_other, pair, amount_other);
+        let liquidity = pair_ref.mint(this);
+        if liquidity < min_liquidity {
+            self.env().revert(DexError::InsufficientLiquidityMinted);
+        }
+        let entry_cumulative = self.cumulative_price(pair, token_in);
+
+        let updated = match position.take() {
+            Some(mut existing) => {
+                let total_principal = self.add(existing.principal, amount_in);
+                let weighted = self.add(
+                    self.mul(existing.entry_price, existing.principal),
+                    self.mul(entry_price, amount_in),
+                );
+                existing.entry_price = self.div(weighted, total_principal);
+                existing.principal = total_principal;
+                existing.liquidity = self.add(existing.liquidity, liquidity);
+                existing.deposited_at = self.env().get_block_time();
+                existing.entry_cumulative = entry_cumulative;
+                existing
+            }
+            None => SingleSidedPosition {
+                pair,
+                token_in,
+                liquidity,
+                principal: amount_in,
+                entry_price,
+                deposited_at: self.env().get_block_time(),
+                entry_cumulative,
+            },
+        };
+        self.positions.set(&(caller, pair), updated);
+
+        self.env().emit_event(SingleSidedDeposited {
+            user: caller,
+            pair,
+            token_in,
+            amount_in,
+            swap_amount,
+            liquidity,
+            seq: self.next_event_seq(),
+        });
+
+        liquidity
+    }
+
+    /// Withdraw `liquidity` from the caller's position in `pair`
+    /// Both underlying tokens are returned, plus any compensation paid in the
+    /// deposited token. The other token is valued at the pair's TWAP since
+    /// the last deposit; nothing is compensated in the block of a deposit.
+    /// Returns (amount0, amount1, compensation)
+    pub fn withdraw(
+        &mut self,
+        pair: Address,
+        liquidity: U256,
+        deadline: u64,
+    ) -> (U256, U256, U256) {
+        self.ensure_deadline(deadline);
+        self.ensure_registered_pair(pair);
+
+        let caller = self.env().caller();
+        let mut position = self.positions.get(&(caller, pair))
+            .unwrap_or_revert_with(&self.env(), DexError::InsufficientLiquidity);
+        if liquidity.is_zero() || liquidity > position.liquidity {
+            self.env().revert(DexError::InsufficientLiquidity);
+        }
+
+        let principal = self.div(self.mul(position.principal, liquidity), position.liquidity);
+        let twap = self.twap_since(pair, &position);
+
+        // Return the LP tokens to the pair and burn them straight to the user
+        self.safe_transfer(pair, pair, liquidity);
+        let mut pair_ref = PairContractContractRef::new(self.env(), pair);
+        let (amount0, amount1) = pair_ref.burn(caller);
+
+        let compensation = if self.protection_enabled.get_or_default() {
+            let token0 = pair_ref.token0();
+            let (amount_in, amount_other) = if position.token_in == token0 {
+                (amount0, amount1)
+            } else {
+                (amount1, amount0)
+            };
+            match twap {
+                Some(price) => {
+                    let value = self.add(amount_in, self.value_in_token_in(amount_other, price));
+                    self.pay_compensation(position.token_in, caller, principal, value)
+                }
+                None => U256::zero(),
+            }
+        } else {
+            U256::zero()
+        };
+
+        position.liquidity = self.sub(position.liquidity, liquidity);
+        position.principal = self.sub(position.principal, principal);
+        self.positions.set(&(caller, pair), position);
+
+        self.env().emit_event(SingleSidedWithdrawn {
+            user: caller,
+            pair,
+            liquidity,
+            amount0,
+            amount1,
+            compensation,
+            seq: self.next_event_seq(),
+        });
+
+        (amount0, amount1, compensation)
+    }
+
+    // ============ Reserve Functions ============
+
+    /// Add tokens to the compensation reserve
+    pub fn fund_reserve(&mut self, token: Address, amount: U256) {
+        let caller = self.env().caller();
+        self.safe_transfer_from(token, caller, self.env().self_address(), amount);
+        let balance = self.reserve_of(token);
+        self.reserves.set(&token, self.add(balance, amount));
+    }
+
+    /// Withdraw tokens from the compensation reserve (admin only)
+    pub fn withdraw_reserve(&mut self, token: Address, amount: U256, to: Address) {
+        self.only_admin();
+        let balance = self.reserve_of(token);
+        if amount > balance {
+            self.env().revert(DexError::InsufficientAmount);
+        }
+        self.reserves.set(&token, balance - amount);
+        self.safe_transfer(token, to, amount);
+    }
+
+    // ============ View Functions ============
+
+    /// Get a user's position in a pair
+    pub fn get_position(&self, user: Address, pair: Address) -> Option<SingleSidedPosition> {
+        self.positions.get(&(user, pair))
+    }
+
+    /// Get the compensation reserve for a token
+    pub fn reserve_of(&self, token: Address) -> U256 {
+        self.reserves.get(&token).unwrap_or_default()
+    }
+
+    /// Get the amount of a single-sided deposit that is swapped before adding
+    /// liquidity to a pair charging `fee_bps`
+    pub fn get_swap_amount(&self, amount_in: U256, reserve_in: U256, fee_bps: u16) -> U256 {
+        self.swap_amount_internal(amount_in, reserve_in, fee_bps)
+    }
+
+    /// Get the compensation cap (bps of withdrawn principal)
+    pub fn max_compensation_bps(&self) -> u32 {
+        self.max_compensation_bps.get_or_default()
+    }
+
+    /// Check whether impermanent loss compensation is enabled
+    pub fn is_protection_enabled(&self) -> bool {
+        self.protection_enabled.get_or_default()
+    }
+
+    // ============ Admin Functions ============
+
+    /// Update the compensation cap
+    pub fn set_max_compensation_bps(&mut self, max_compensation_bps: u32) {
+        self.only_admin();
+        if max_compensation_bps > BPS_DENOMINATOR {
+            self.env().revert(DexError::InvalidFee);
+        }
+        self.max_compensation_bps.set(max_compensation_bps);
+    }
+
+    /// Enable or disable impermanent loss compensation
+    pub fn set_protection_enabled(&mut self, enabled: bool) {
+        self.only_admin();
+        self.protection_enabled.set(enabled);
+    }
+
+    // ============ Internal Functions ============
+
+    /// Optimal swap share for a pool charging `fee_bps`, with g = D - fee
+    /// and D = 10_000:
+    /// (sqrt(r * ((D + g)^2 * r + 4 * g * D * a)) - (D + g) * r) / (2 * g)
+    fn swap_amount_internal(&self, amount_in: U256, reserve_in: U256, fee_bps: u16) -> U256 {
+        if reserve_in.is_zero() {
+            self.env().revert(DexError::InsufficientLiquidity);
+        }
+        if fee_bps as u32 >= BPS_DENOMINATOR {
+            self.env().revert(DexError::InvalidFee);
+        }
+        let denominator = U256::from(BPS_DENOMINATOR);
+        let gamma = U256::from(BPS_DENOMINATOR - fee_bps as u32);
+        let sum = self.add(denominator, gamma);
+        let inner = self.add(
+            self.mul(reserve_in, self.mul(sum, sum)),
+            self.mul(amount_in, self.mul(U256::from(4u64), self.mul(gamma, denominator))),
+        );
+        let root = SafeMath::sqrt(self.mul(reserve_in, inner));
+        let offset = self.mul(reserve_in, sum);
+        self.div(self.sub(root, offset), self.mul(U256::from(2u64), gamma))
+    }
+
+    /// Output of swapping `amount_in` through a pool charging `fee_bps`
+    fn amount_out_internal(&self, amount_in: U256, reserve_in: U256, reserve_out: U256, fee_bps: u16) -> U256 {
+        if reserve_in.is_zero() || reserve_out.is_zero() {
+            self.env().revert(DexError::InsufficientLiquidity);
+        }
+        let amount_in_with_fee = self.mul(amount_in, U256::from(BPS_DENOMINATOR - fee_bps as u32));
+        self.div(
+            self.mul(amount_in_with_fee, reserve_out),
+            self.add(self.mul(reserve_in, U256::from(BPS_DENOMINATOR)), amount_in_with_fee),
+        )
+    }
+
+    /// Pay compensation for the shortfall of `value` against `principal`
+    fn pay_compensation(&mut self, token: Address, to: Address, principal: U256, value: U256) -> U256 {
+        if value >= principal {
+            return U256::zero();
+        }
+        let cap = self.div(
+            self.mul(principal, U256::from(self.max_compensation_bps())),
+            U256::from(BPS_DENOMINATOR),
+        );
+        let available = self.reserve_of(token);
+        let compensation = SafeMath::min(SafeMath::min(principal - value, cap), available);
+        if compensation.is_zero() {
+            return compensation;
+        }
+        self.reserves.set(&token, available - compensation);
+        self.safe_transfer(token, to, compensation);
+        compensation
+    }
+
+    /// Value of `amount_other` in the deposited token at `price` (other token
+    /// per deposited token, scaled by 1e18)
+    fn value_in_token_in(&self, amount_other: U256, price: U256) -> U256 {
+        if amount_other.is_zero() || price.is_zero() {
+            return U256::zero();
+        }
+        SafeMath::mul_div(amount_other, U256::from(PRICE_SCALE), price)
+            .unwrap_or_else(|e| self.env().revert(e))
+    }
+
+    /// Cumulative price of `token_in` in the other token (scaled by 1e18),
+    /// extended to the current block time
+    fn cumulative_price(&self, pair: Address, token_in: Address) -> U256 {
+        let pair_ref = PairContractRef::new(self.env(), pair);
+        let (cumulative0, cumulative1) = pair_ref.get_price_cumulatives();
+        let (reserve0, reserve1, updated_at) = pair_ref.get_reserves();
+        let (cumulative, reserve_in, reserve_out) = if token_in == pair_ref.token0() {
+            (cumulative0, reserve0, reserve1)
+        } else {
+            (cumulative1, reserve1, reserve0)
+        };
+        if reserve_in.is_zero() {
+            return cumulative;
+        }
+        let price = self.price_internal(reserve_in, reserve_out);
+        let elapsed = U256::from(self.env().get_block_time().saturating_sub(updated_at));
+        cumulative.overflowing_add(price.overflowing_mul(elapsed).0).0
+    }
+
+    /// Average price of the deposited token since the position's last deposit,
+    /// or None in the block of the deposit
+    fn twap_since(&self, pair: Address, position: &SingleSidedPosition) -> Option<U256> {
+        let elapsed = self.env().get_block_time().saturating_sub(position.deposited_at);
+        if elapsed == 0 {
+            return None;
+        }
+        let cumulative = self.cumulative_price(pair, position.token_in);
+        let delta = cumulative.overflowing_sub(position.entry_cumulative).0;
+        Some(self.div(delta, U256::from(elapsed)))
+    }
+
+    /// Revert unless `pair` is registered with the factory
+    fn ensure_registered_pair(&self, pair: Address) {
+        let factory = self.factory.get_or_revert_with(DexError::InvalidConfiguration);
+        if FactoryContractContractRef::new(self.env(), factory).pair_type_of(pair).is_none() {
+            self.env().revert(DexError::PairNotFound);
+        }
+    }
+
+    /// Spot price of the deposited token in the other token (scaled by 1e18)
+    fn price_internal(&self, reserve_in: U256, reserve_out: U256) -> U256 {
+        self.div(self.mul(reserve_out, U256::from(PRICE_SCALE)), reserve_in)
+    }
+
+    /// Get (reserve_in, reserve_out) for the deposited token
+    fn reserves_for(&self, pair_ref: &PairContractContractRef, token_in: Address) -> (U256, U256) {
+        let (reserve0, reserve1, _) = pair_ref.get_reserves();
+        if token_in == pair_ref.token0() {
+            (reserve0, reserve1)
+        } else {
+            (reserve1, reserve0)
+        }
+    }
+
+    /// Ensure the deadline has not passed
+    fn ensure_deadline(&self, deadline: u64) {
+        if self.env().get_block_time() > deadline {
+            self.env().revert(DexError::DeadlineExpired);
+        }
+    }
+
+    fn only_admin(&self) {
+        let admin = self.admin.get_or_revert_with(DexError::Unauthorized);
+        if self.env().caller() != admin {
+            self.env().revert(DexError::Unauthorized);
+        }
+    }
+
+    fn safe_transfer(&self, token: Address, to: Address, amount: U256) {
+        let mut token_ref = Cep18TokenContractRef::new(self.env(), token);
+        if !token_ref.transfer(to, amount) {
+            self.env().revert(DexError::TransferFailed);
+        }
+    }
+
+    fn safe_transfer_from(&self, token: Address, from: Address, to: Address, amount: U256) {
+        let mut token_ref = Cep18TokenContractRef::new(self.env(), token);
+        if !token_ref.transfer_from(from, to, amount) {
+            self.env().revert(DexError::TransferFailed);
+        }
+    }
+
+    fn add(&self, a: U256, b: U256) -> U256 {
+        SafeMath::add(a, b).unwrap_or_else(|e| self.env().revert(e))
+    }
+
+    fn sub(&self, a: U256, b: U256) -> U256 {
+        SafeMath::sub(a, b).unwrap_or_else(|e| self.env().revert(e))
+    }
+
+    fn mul(&self, a: U256, b: U256) -> U256 {
+        SafeMath::mul(a, b).unwrap_or_else(|e| self.env().revert(e))
+    }
+
+    fn div(&self, a: U256, b: U256) -> U256 {
+        SafeMath::div(a, b).unwrap_or_else(|e| self.env().revert(e))
+    }
+
+    /// Sequence number of the last event emitted; indexers page from here
+    pub fn event_seq(&self) -> u64 {
+        self.event_seq.get_or_default()
+    }
+
+    /// Advance the event sequence and return the number for the next event
+    fn next_event_seq(&mut self) -> u64 {
+        let seq = self.event_seq.get_or_default() + 1;
+        self.event_seq.set(seq);
+        seq
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use odra::host::{Deployer, HostEnv};
+    use crate::dex::factory::DEFAULT_PAIR_TYPE;
+    use crate::dex::pair::{Pair, PairInitArgs};
+    use crate::testing::{deploy_token, seed_pair, MockFactory, MockFactoryInitArgs};
+    use crate::token::{LpToken, LpTokenInitArgs};
+
+    fn setup() -> (HostEnv, SingleSidedLpHostRef) {
+        let env = odra_test::env();
+        let factory = MockFactory::deploy(&env, MockFactoryInitArgs {
+            fee_to_setter: env.get_account(0),
+        });
+        let wrapper = SingleSidedLp::deploy(&env, SingleSidedLpInitArgs {
+            factory: factory.address().clone(),
+            max_compensation_bps: 500,
+        });
+        (env, wrapper)
+    }
+
+    /// Pair impostor: reports chosen tokens and reserves, keeps whatever it
+    /// is sent and pays nothing back on burn
+    #[odra::module]
+    pub struct FakePair {
+        token0: Var<Address>,
+        token1: Var<Address>,
+    }
+
+    #[odra::module]
+    impl FakePair {
+        pub fn init(&mut self, token0: Address, token1: Address) {
+            self.token0.set(token0);
+            self.token1.set(token1);
+        }
+
+        pub fn token0(&self) -> Address {
+            self.token0.get_or_revert_with(DexError::InvalidPair)
+        }
+
+        pub fn token1(&self) -> Address {
+            self.token1.get_or_revert_with(DexError::InvalidPair)
+        }
+
+        pub fn get_reserves(&self) -> (U256, U256, u64) {
+            (U256::from(1_000_000), U256::from(1_000_000), 0)
+        }
+
+        pub fn swap(&mut self, _amount0_out: U256, _amount1_out: U256, _to: Address) {}
+
+        pub fn mint(&mut self, _to: Address) -> U256 {
+            U256::from(1_000_000)
+        }
+
+        pub fn burn(&mut self, _to: Address) -> (U256, U256) {
+            (U256::zero(), U256::zero())
+        }
+
+        pub fn transfer(&mut self, _to: Address, _amount: U256) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn test_unregistered_pair_cannot_reach_the_reserve() {
+        let (env, mut wrapper) = setup();
+        let admin = env.get_account(0);
+        let attacker = env.get_account(1);
+        let mut token_a = deploy_token(&env, "Token A", "TKA");
+        let token_b = deploy_token(&env, "Token B", "TKB");
+        let token = token_a.address().clone();
+
+        token_a.mint(admin, U256::from(100_000));
+        token_a.approve(wrapper.address().clone(), U256::from(100_000));
+        wrapper.fund_reserve(token, U256::from(100_000));
+
+        // A deposit through the impostor would be "lost" in full and
+        // compensated out of the shared reserve on withdrawal
+        let fake = FakePair::deploy(&env, FakePairInitArgs {
+            token0: token,
+            token1: token_b.address().clone(),
+        });
+        token_a.mint(attacker, U256::from(10_000));
+        env.set_caller(attacker);
+        token_a.approve(wrapper.address().clone(), U256::from(10_000));
+        assert_eq!(
+            wrapper.try_deposit(fake.address().clone(), token, U256::from(10_000), U256::zero(), u64::MAX),
+            Err(DexError::PairNotFound.into())
+        );
+        assert_eq!(
+            wrapper.try_withdraw(fake.address().clone(), U256::from(1_000_000), u64::MAX),
+            Err(DexError::PairNotFound.into())
+        );
+        assert_eq!(wrapper.reserve_of(token), U256::from(100_000));
+        assert_eq!(token_a.balance_of(attacker), U256::from(10_000));
+    }
+
+    #[test]
+    fn test_init() {
+        let (_, wrapper) = setup();
+        assert_eq!(wrapper.max_compensation_bps(), 500);
+        assert!(wrapper.is_protection_enabled());
+    }
+
+    #[test]
+    fn test_swap_amount() {
+        let (_, wrapper) = setup();
+        assert_eq!(
+            wrapper.get_swap_amount(U256::from(1000), U256::from(1_000_000), 30),
+            U256::from(500)
+        );
+        assert_eq!(
+            wrapper.get_swap_amount(U256::from(1_000_000_000u64), U256::from(1_000_000_000_000u64), 30),
+            U256::from(500_626_001u64)
+        );
+        // Higher fees swap more of the deposit, no fee a little under half
+        assert_eq!(
+            wrapper.get_swap_amount(U256::from(1_000_000_000u64), U256::from(1_000_000_000_000u64), 100),
+            U256::from(502_387_000u64)
+        );
+        assert_eq!(
+            wrapper.get_swap_amount(U256::from(1_000_000_000u64), U256::from(1_000_000_000_000u64), 0),
+            U256::from(499_875_062u64)
+        );
+    }
+
+    #[test]
+    fn test_deposit_into_high_fee_pair() {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let user = env.get_account(1);
+        let mut token_a = deploy_token(&env, "Token A", "TKA");
+        let mut token_b = deploy_token(&env, "Token B", "TKB");
+        let mut factory = MockFactory::deploy(&env, MockFactoryInitArgs { fee_to_setter: admin });
+        let mut pair = Pair::deploy(&env, PairInitArgs {
+            token0: token_a.address().clone(),
+            token1: token_b.address().clone(),
+            factory: admin,
+        });
+        let pair_addr = pair.address().clone();
+        factory.register_pair(token_a.address().clone(), token_b.address().clone(), DEFAULT_PAIR_TYPE, pair_addr);
+        pair.set_fee_bps(100);
+        seed_pair(&mut pair, &mut token_a, &mut token_b, U256::from(1_000_000), U256::from(1_000_000), admin);
+
+        let mut wrapper = SingleSidedLp::deploy(&env, SingleSidedLpInitArgs {
+            factory: factory.address().clone(),
+            max_compensation_bps: 500,
+        });
+        let wrapper_addr = wrapper.address().clone();
+        token_a.mint(user, U256::from(100_000));
+        env.set_caller(user);
+        token_a.approve(wrapper_addr, U256::from(100_000));
+
+        // At 1% a 0.3% split would ask the pair for more than the fee allows
+        let liquidity = wrapper.deposit(pair_addr, token_a.address().clone(), U256::from(100_000), U256::one(), u64::MAX);
+        assert!(!liquidity.is_zero());
+        assert!(token_a.balance_of(wrapper_addr) < U256::from(100));
+        assert!(token_b.balance_of(wrapper_addr) < U256::from(100));
+
+        env.advance_block_time(24 * 60 * 60);
+        let (amount0, amount1, _) = wrapper.withdraw(pair_addr, liquidity, u64::MAX);
+        assert!(!amount0.is_zero() && !amount1.is_zero());
+        assert!(wrapper.get_position(user, pair_addr).unwrap().liquidity.is_zero());
+    }
+
+    #[test]
+    fn test_fund_and_withdraw_reserve() {
+        let (env, mut wrapper) = setup();
+        let admin = env.get_account(0);
+        let mut token = LpToken::deploy(&env, LpTokenInitArgs {
+            name: String::from("Token A"),
+            symbol: String::from("TKA"),
+        });
+        token.mint(admin, U256::from(1000));
+        token.approve(wrapper.address().clone(), U256::from(1000));
+
+        wrapper.fund_reserve(token.address().clone(), U256::from(1000));
+        assert_eq!(wrapper.reserve_of(token.address().clone()), U256::from(1000));
+
+        wrapper.withdraw_reserve(token.address().clone(), U256::from(400), admin);
+        assert_eq!(wrapper.reserve_of(token.address().clone()), U256::from(600));
+        assert_eq!(token.balance_of(admin), U256::from(400));
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized")]
+    fn test_set_cap_unauthorized() {
+        let (env, mut wrapper) = setup();
+        env.set_caller(env.get_account(1));
+        wrapper.set_max_compensation_bps(1000);
+    }
+}
@@ -16,6 +16,8 @@ mod integration_tests {
     use crate::dex::router::{Router, RouterInitArgs, RouterHostRef};
     use crate::dex::pair::{Pair, PairInitArgs, PairFactory};
     use crate::token::{LpToken, LpTokenInitArgs, LpTokenHostRef};
+    use crate::dex::factory::DEFAULT_PAIR_TYPE;
+    use crate::testing::{DexFixture, RoutedDex};
 
     /// Helper struct to set up test environment
     struct TestEnv {
@@ -30,38 +32,7 @@ mod integration_tests {
     impl TestEnv {
         fn new() -> Self {
             let env = odra_test::env();
-            let admin = env.get_account(0);
-
-            // Deploy mock tokens
-            let token_a = LpToken::deploy(&env, LpTokenInitArgs {
-                name: String::from("Token A"),
-                symbol: String::from("TKA"),
-            });
-
-            let token_b = LpToken::deploy(&env, LpTokenInitArgs {
-                name: String::from("Token B"),
-                symbol: String::from("TKB"),
-            });
-
-            let wcspr = LpToken::deploy(&env, LpTokenInitArgs {
-                name: String::from("Wrapped CSPR"),
-                symbol: String::from("WCSPR"),
-            });
-
-            // Deploy PairFactory (generated by factory=on)
-            let pair_factory = PairFactory::deploy(&env, odra::host::NoArgs);
-
-            // Deploy factory
-            let factory = Factory::deploy(&env, FactoryInitArgs {
-                fee_to_setter: admin,
-                pair_factory: pair_factory.address().clone(),
-            });
-
-            // Deploy router
-            let router = Router::deploy(&env, RouterInitArgs {
-                factory: factory.address().clone(),
-                wcspr: wcspr.address().clone(),
-            });
+            let DexFixture { factory, router, token_a, token_b, wcspr } = DexFixture::deploy(&env);
 
             TestEnv {
                 env,
@@ -80,6 +51,32 @@ mod integration_tests {
         }
     }
 
+    /// Router on a `MockFactory` serving an empty A/B pair, so router flows
+    /// run in the mock VM
+    struct RoutedEnv {
+        env: HostEnv,
+        dex: RoutedDex,
+        token_a: LpTokenHostRef,
+        token_b: LpTokenHostRef,
+        wcspr: LpTokenHostRef,
+    }
+
+    impl RoutedEnv {
+        fn new() -> Self {
+            let env = odra_test::env();
+            let DexFixture { token_a, token_b, wcspr, .. } = DexFixture::deploy(&env);
+            let mut dex = RoutedDex::deploy(&env, wcspr.address().clone());
+            dex.add_pair(&env, token_a.address().clone(), token_b.address().clone(), DEFAULT_PAIR_TYPE);
+
+            RoutedEnv { env, dex, token_a, token_b, wcspr }
+        }
+
+        fn mint_tokens(&mut self, user: Address, amount: U256) {
+            self.token_a.mint(user, amount);
+            self.token_b.mint(user, amount);
+        }
+    }
+
     #[test]
     fn test_factory_deployment() {
         let test_env = TestEnv::new();
@@ -118,6 +115,332 @@ mod integration_tests {
         test_env.factory.create_pair(token_a_addr, token_b_addr);
     }
 
+    #[test]
+    fn test_router_refunds_only_its_own_dust() {
+        use crate::testing::seed_pair;
+
+        let env = odra_test::env();
+        let DexFixture { mut token_a, mut token_b, wcspr, .. } = DexFixture::deploy(&env);
+        let user = env.get_account(1);
+        let token_a_addr = token_a.address().clone();
+        let token_b_addr = token_b.address().clone();
+        let mut dex = RoutedDex::deploy(&env, wcspr.address().clone());
+        let router_addr = dex.router.address().clone();
+        let mut pair = dex.add_pair(&env, token_a_addr, token_b_addr, DEFAULT_PAIR_TYPE);
+        let pair_addr = pair.address().clone();
+        seed_pair(&mut pair, &mut token_a, &mut token_b, U256::from(100_000_000u64), U256::from(100_000_000u64), user);
+
+        // Tokens sent to the router by mistake are not anyone's dust
+        let stranded = U256::from(777u64);
+        token_a.mint(router_addr, stranded);
+        token_b.mint(router_addr, stranded);
+
+        let amount = U256::from(1_000_000_000u64);
+        env.set_caller(user);
+        token_a.mint(user, amount);
+        token_b.mint(user, amount);
+        token_a.approve(router_addr, amount);
+        token_b.approve(router_addr, amount);
+
+        let path = vec![token_a_addr, token_b_addr];
+        dex.router.swap_exact_tokens_for_tokens(
+            U256::from(1_000_000u64),
+            U256::zero(),
+            path.clone(),
+            user,
+            u64::MAX,
+        );
+        dex.router.swap_tokens_for_exact_tokens(
+            U256::from(500_000u64),
+            U256::from(1_000_000u64),
+            path.into_iter().rev().collect(),
+            user,
+            u64::MAX,
+        );
+
+        assert_eq!(token_a.balance_of(router_addr), stranded);
+        assert_eq!(token_b.balance_of(router_addr), stranded);
+
+        // Both swaps are in the user's history, newest first
+        let history = dex.router.get_trade_history(user, 0, 10);
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].token_in, token_b_addr);
+        assert_eq!(history[0].amount_out, U256::from(500_000u64));
+        assert_eq!(history[1].token_in, token_a_addr);
+
+        // Migrating into a 1:2 pool refunds the token A that does not fit,
+        // and only that
+        let mut pair_to = dex.add_pair(&env, token_a_addr, token_b_addr, 1);
+        let pair_to_addr = pair_to.address().clone();
+        seed_pair(&mut pair_to, &mut token_a, &mut token_b, U256::from(1_000_000u64), U256::from(2_000_000u64), user);
+        let liquidity = pair.balance_of(user) / 10;
+        pair.approve(router_addr, liquidity);
+        let balance_a = token_a.balance_of(user);
+        let balance_b = token_b.balance_of(user);
+        let (_, _, minted) = dex.router.migrate_liquidity(
+            pair_addr,
+            pair_to_addr,
+            liquidity,
+            U256::zero(),
+            U256::zero(),
+            u64::MAX,
+        );
+
+        assert!(!minted.is_zero());
+        assert!(token_a.balance_of(user) > balance_a);
+        assert_eq!(token_b.balance_of(user), balance_b);
+        assert_eq!(token_a.balance_of(router_addr), stranded);
+        assert_eq!(token_b.balance_of(router_addr), stranded);
+    }
+
+    #[test]
+    fn test_dry_runs_report_expected_amounts_and_limits() {
+        use crate::testing::seed_pair;
+
+        let env = odra_test::env();
+        let DexFixture { mut token_a, mut token_b, wcspr, .. } = DexFixture::deploy(&env);
+        let token_a_addr = token_a.address().clone();
+        let token_b_addr = token_b.address().clone();
+        let mut dex = RoutedDex::deploy(&env, wcspr.address().clone());
+        let mut pair = dex.add_pair(&env, token_a_addr, token_b_addr, DEFAULT_PAIR_TYPE);
+        let provider = env.get_account(1);
+        let liquidity = seed_pair(&mut pair, &mut token_a, &mut token_b, U256::from(1_000_000u64), U256::from(2_000_000u64), provider);
+
+        // Swaps: the expected amount is the quote, checked against the limit
+        let path = vec![token_a_addr, token_b_addr];
+        let quoted = dex.router.get_amounts_out(U256::from(1_000u64), path.clone())[1];
+        let check = dex.router.check_swap_exact_tokens_for_tokens(U256::from(1_000u64), quoted + 1, path.clone());
+        assert_eq!((check.token, check.expected, check.passed), (token_b_addr, quoted, false));
+        let needed = dex.router.get_amounts_in(U256::from(1_000u64), path.clone())[0];
+        let check = dex.router.check_swap_tokens_for_exact_tokens(U256::from(1_000u64), needed, path);
+        assert_eq!((check.token, check.expected, check.passed), (token_a_addr, needed, true));
+
+        // Adding at 1:2 uses 2_000 of B, short of a 2_100 minimum
+        let checks = dex.router.check_add_liquidity(
+            token_a_addr,
+            token_b_addr,
+            U256::from(1_000u64),
+            U256::from(5_000u64),
+            U256::from(1_000u64),
+            U256::from(2_100u64),
+        );
+        assert_eq!((checks[0].token, checks[0].expected, checks[0].passed), (token_a_addr, U256::from(1_000u64), true));
+        assert_eq!((checks[1].token, checks[1].expected, checks[1].passed), (token_b_addr, U256::from(2_000u64), false));
+
+        // Removing half the provider's liquidity returns a pro-rata share
+        let half = liquidity / 2;
+        let supply = pair.total_supply();
+        let checks = dex.router.check_remove_liquidity(token_b_addr, token_a_addr, half, U256::zero(), U256::MAX);
+        assert_eq!(checks[0].token, token_b_addr);
+        assert_eq!(checks[0].expected, half * U256::from(2_000_000u64) / supply);
+        assert!(checks[0].passed);
+        assert_eq!(checks[1].expected, half * U256::from(1_000_000u64) / supply);
+        assert!(!checks[1].passed);
+    }
+
+    #[test]
+    fn test_router_emits_path_level_events() {
+        use crate::events::{RouterLiquidityAdded, RouterSwap};
+
+        let mut test_env = RoutedEnv::new();
+        let user = test_env.env.get_account(1);
+        let recipient = test_env.env.get_account(2);
+        let amount = U256::from(1_000_000_000u64);
+        test_env.mint_tokens(user, amount);
+
+        let token_a_addr = test_env.token_a.address().clone();
+        let token_b_addr = test_env.token_b.address().clone();
+        let router_addr = test_env.dex.router.address().clone();
+
+        test_env.env.set_caller(user);
+        test_env.token_a.approve(router_addr, amount);
+        test_env.token_b.approve(router_addr, amount);
+        let deposit = U256::from(100_000_000u64);
+        let (amount_a, amount_b, liquidity) = test_env.dex.router.add_liquidity(
+            token_a_addr, token_b_addr, deposit, deposit, U256::zero(), U256::zero(), user, u64::MAX,
+        );
+        let pair = test_env.dex.factory.get_pair(token_a_addr, token_b_addr).unwrap();
+        assert!(test_env.env.emitted_event(&router_addr, RouterLiquidityAdded {
+            sender: user,
+            to: user,
+            pair,
+            token_a: token_a_addr,
+            token_b: token_b_addr,
+            amount_a,
+            amount_b,
+            liquidity,
+            seq: test_env.dex.router.event_seq(),
+        }));
+
+        let path = vec![token_a_addr, token_b_addr];
+        let amounts = test_env.dex.router.swap_exact_tokens_for_tokens(
+            U256::from(1_000_000u64), U256::zero(), path.clone(), recipient, u64::MAX,
+        );
+        assert!(test_env.env.emitted_event(&router_addr, RouterSwap {
+            sender: user,
+            to: recipient,
+            path,
+            amounts,
+            seq: test_env.dex.router.event_seq(),
+        }));
+    }
+
+    #[test]
+    fn test_swap_and_distribute_splits_output() {
+        let mut test_env = RoutedEnv::new();
+        let user = test_env.env.get_account(1);
+        let payee_a = test_env.env.get_account(2);
+        let payee_b = test_env.env.get_account(3);
+        let amount = U256::from(1_000_000_000u64);
+        test_env.mint_tokens(user, amount);
+
+        let token_a_addr = test_env.token_a.address().clone();
+        let token_b_addr = test_env.token_b.address().clone();
+        let router_addr = test_env.dex.router.address().clone();
+
+        test_env.env.set_caller(user);
+        test_env.token_a.approve(router_addr, amount);
+        test_env.token_b.approve(router_addr, amount);
+        test_env.dex.router.add_liquidity(
+            token_a_addr,
+            token_b_addr,
+            U256::from(100_000_000u64),
+            U256::from(100_000_000u64),
+            U256::zero(),
+            U256::zero(),
+            user,
+            u64::MAX,
+        );
+
+        let amounts = test_env.dex.router.swap_and_distribute(
+            U256::from(1_000_000u64),
+            U256::zero(),
+            vec![token_a_addr, token_b_addr],
+            vec![(payee_a, 7_000), (payee_b, 3_000)],
+            u64::MAX,
+        );
+        let amount_out = amounts[1];
+        let share_a = amount_out * U256::from(7_000) / U256::from(10_000);
+
+        assert_eq!(test_env.token_b.balance_of(payee_a), share_a);
+        assert_eq!(test_env.token_b.balance_of(payee_b), amount_out - share_a);
+        assert_eq!(test_env.token_b.balance_of(router_addr), U256::zero());
+    }
+
+    #[test]
+    fn test_swap_exact_cspr_for_tokens() {
+        use odra::casper_types::U512;
+        use crate::tokens::WcsprToken;
+
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let user = env.get_account(1);
+
+        let mut wcspr = WcsprToken::deploy(&env, odra::host::NoArgs);
+        let mut token = LpToken::deploy(&env, LpTokenInitArgs {
+            name: String::from("Token A"),
+            symbol: String::from("TKA"),
+        });
+        let wcspr_addr = wcspr.address().clone();
+        let token_addr = token.address().clone();
+        let mut dex = RoutedDex::deploy(&env, wcspr_addr);
+        dex.add_pair(&env, wcspr_addr, token_addr, DEFAULT_PAIR_TYPE);
+        let router = &mut dex.router;
+        let router_addr = router.address().clone();
+
+        // Seed a WCSPR/TKA pool
+        let seed = U256::from(100_000_000_000u64);
+        wcspr.with_tokens(U512::from(100_000_000_000u64)).deposit();
+        token.mint(admin, seed);
+        wcspr.approve(router_addr, seed);
+        token.approve(router_addr, seed);
+        router.add_liquidity(wcspr_addr, token_addr, seed, seed, U256::zero(), U256::zero(), admin, u64::MAX);
+
+        // The user swaps CSPR with no WCSPR balance and no approval
+        env.set_caller(user);
+        let path = vec![wcspr_addr, token_addr];
+        let quoted = router.get_amounts_out(U256::from(1_000_000_000u64), path.clone());
+        let amounts = router
+            .with_tokens(U512::from(1_000_000_000u64))
+            .swap_exact_cspr_for_tokens(U256::zero(), path, user, u64::MAX);
+
+        assert_eq!(amounts, quoted);
+        assert_eq!(token.balance_of(user), quoted[1]);
+        assert_eq!(wcspr.balance_of(router_addr), U256::zero());
+    }
+
+    #[test]
+    fn test_multicall_cspr_accounts_for_attached_value() {
+        use odra::casper_types::U512;
+        use crate::dex::router::{CsprAction, CsprActionKind};
+        use crate::errors::DexError;
+        use crate::tokens::WcsprToken;
+
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let user = env.get_account(1);
+        let recipient = env.get_account(2);
+
+        let wcspr = WcsprToken::deploy(&env, odra::host::NoArgs);
+        let pair_factory = PairFactory::deploy(&env, odra::host::NoArgs);
+        let factory = Factory::deploy(&env, FactoryInitArgs {
+            fee_to_setter: admin,
+            pair_factory: pair_factory.address().clone(),
+        });
+        let mut router = Router::deploy(&env, RouterInitArgs {
+            factory: factory.address().clone(),
+            wcspr: wcspr.address().clone(),
+        });
+        let wrap = |amount: u64| CsprAction {
+            kind: CsprActionKind::Wrap,
+            cspr_amount: U256::from(amount),
+            path: vec![],
+            token_amount: U256::zero(),
+            amount_min: U256::zero(),
+        };
+
+        // Actions must spend exactly the attached value
+        env.set_caller(user);
+        assert_eq!(
+            router.with_tokens(U512::from(5_000u64))
+                .try_multicall_cspr(vec![wrap(3_000), wrap(1_000)], recipient, u64::MAX),
+            Err(DexError::UnbalancedValue.into())
+        );
+        assert_eq!(
+            router.with_tokens(U512::from(5_000u64))
+                .try_multicall_cspr(vec![wrap(3_000), wrap(3_000)], recipient, u64::MAX),
+            Err(DexError::UnbalancedValue.into())
+        );
+
+        let results = router.with_tokens(U512::from(5_000u64))
+            .multicall_cspr(vec![wrap(3_000), wrap(2_000)], recipient, u64::MAX);
+        assert_eq!(results, vec![U256::from(3_000u64), U256::from(2_000u64)]);
+        assert_eq!(wcspr.balance_of(recipient), U256::from(5_000u64));
+        assert_eq!(wcspr.balance_of(router.address().clone()), U256::zero());
+    }
+
+    #[test]
+    fn test_wcspr_wrap_and_unwrap() {
+        use odra::casper_types::U512;
+        use crate::tokens::WcsprToken;
+
+        let env = odra_test::env();
+        let user = env.get_account(1);
+        let mut wcspr = WcsprToken::deploy(&env, odra::host::NoArgs);
+
+        env.set_caller(user);
+        let balance_before = env.balance_of(&user);
+        let minted = wcspr.with_tokens(U512::from(5_000u64)).deposit();
+        assert_eq!(minted, U256::from(5_000u64));
+        assert_eq!(wcspr.balance_of(user), U256::from(5_000u64));
+        assert_eq!(wcspr.total_supply(), U256::from(5_000u64));
+
+        wcspr.withdraw(U256::from(2_000u64));
+        assert_eq!(wcspr.balance_of(user), U256::from(3_000u64));
+        assert_eq!(env.balance_of(&wcspr.address().clone()), U512::from(3_000u64));
+        assert!(env.balance_of(&user) < balance_before);
+    }
+
     #[test]
     fn test_router_deployment() {
         let test_env = TestEnv::new();
@@ -126,6 +449,504 @@ mod integration_tests {
         assert_eq!(test_env.router.wcspr(), test_env.wcspr.address().clone());
     }
 
+    #[test]
+    fn test_deadline_tolerance_setting() {
+        use crate::errors::DexError;
+
+        let mut test_env = TestEnv::new();
+        let now = test_env.router.current_block_time();
+
+        assert_eq!(test_env.router.deadline_tolerance(), 0);
+        assert_eq!(test_env.router.suggest_deadline(600), now + 600);
+
+        test_env.router.set_deadline_tolerance(30);
+        assert_eq!(test_env.router.deadline_tolerance(), 30);
+
+        let too_large = crate::dex::router::MAX_DEADLINE_TOLERANCE + 1;
+        assert_eq!(
+            test_env.router.try_set_deadline_tolerance(too_large),
+            Err(DexError::InvalidConfiguration.into())
+        );
+
+        test_env.env.set_caller(test_env.env.get_account(1));
+        assert_eq!(
+            test_env.router.try_set_deadline_tolerance(10),
+            Err(DexError::Unauthorized.into())
+        );
+    }
+
+    #[test]
+    fn test_swap_commitment_reveal_window() {
+        use crate::dex::router::COMMITMENT_TTL;
+        use crate::errors::DexError;
+
+        let mut test_env = TestEnv::new();
+        let user = test_env.env.get_account(1);
+        let path = vec![test_env.token_a.address().clone(), test_env.token_b.address().clone()];
+        let amount_in = U256::from(1_000_000u64);
+        let salt = [7u8; 32];
+        let commitment = test_env.router.swap_commitment_hash(amount_in, U256::zero(), path.clone(), user, salt);
+
+        test_env.env.set_caller(user);
+        assert_eq!(
+            test_env.router.try_reveal_swap(amount_in, U256::zero(), path.clone(), user, u64::MAX, salt),
+            Err(DexError::InvalidCommitment.into())
+        );
+        test_env.router.commit_swap(commitment);
+        assert_eq!(test_env.router.get_swap_commitment(user).map(|c| c.commitment), Some(commitment));
+
+        // Same block: too early
+        assert_eq!(
+            test_env.router.try_reveal_swap(amount_in, U256::zero(), path.clone(), user, u64::MAX, salt),
+            Err(DexError::RevealTooEarly.into())
+        );
+
+        // Different parameters do not match the commitment
+        test_env.env.advance_block_time(1);
+        assert_eq!(
+            test_env.router.try_reveal_swap(amount_in, U256::one(), path.clone(), user, u64::MAX, salt),
+            Err(DexError::InvalidCommitment.into())
+        );
+
+        test_env.env.advance_block_time(COMMITMENT_TTL);
+        assert_eq!(
+            test_env.router.try_reveal_swap(amount_in, U256::zero(), path, user, u64::MAX, salt),
+            Err(DexError::CommitmentExpired.into())
+        );
+    }
+
+    #[test]
+    fn test_transfer_and_call_rejects_foreign_route() {
+        use crate::errors::DexError;
+        use crate::sdk::route::{encode_route, encode_swap_call};
+
+        let mut test_env = TestEnv::new();
+        let user = test_env.env.get_account(1);
+        let amount = U256::from(1_000_000u64);
+        test_env.mint_tokens(user, amount);
+
+        let token_a_addr = test_env.token_a.address().clone();
+        let token_b_addr = test_env.token_b.address().clone();
+        let router_addr = test_env.router.address().clone();
+
+        // A route starting at token B cannot spend token A sent to the router
+        let route = encode_route(&[token_b_addr, token_a_addr]).unwrap();
+        let data = encode_swap_call(&route, U256::zero(), user, u64::MAX).unwrap();
+        test_env.env.set_caller(user);
+        assert_eq!(
+            test_env.token_a.try_transfer_and_call(router_addr, amount, data),
+            Err(DexError::InvalidPath.into())
+        );
+        // The revert undoes the transfer
+        assert_eq!(test_env.token_a.balance_of(user), amount);
+        assert_eq!(test_env.token_a.balance_of(router_addr), U256::zero());
+    }
+
+    /// Token that calls the router's swap callback without moving any balance
+    #[odra::module]
+    pub struct ForgingToken {}
+
+    #[odra::module]
+    impl ForgingToken {
+        pub fn balance_of(&self, _owner: Address) -> U256 {
+            U256::zero()
+        }
+
+        pub fn forge(&mut self, router: Address, from: Address, amount: U256, data: odra::casper_types::bytesrepr::Bytes) {
+            crate::token::TokenReceiverContractRef::new(self.env(), router).on_token_received(from, amount, data);
+        }
+    }
+
+    #[test]
+    fn test_transfer_and_call_credits_only_received_tokens() {
+        use crate::errors::DexError;
+        use crate::sdk::route::{encode_route, encode_swap_call};
+
+        let mut test_env = RoutedEnv::new();
+        let env = test_env.env.clone();
+        let user = env.get_account(1);
+        let amount = U256::from(1_000_000u64);
+        test_env.mint_tokens(user, amount * 2);
+
+        let token_a_addr = test_env.token_a.address().clone();
+        let token_b_addr = test_env.token_b.address().clone();
+        let router_addr = test_env.dex.router.address().clone();
+
+        env.set_caller(user);
+        test_env.token_a.approve(router_addr, amount);
+        test_env.token_b.approve(router_addr, amount);
+        test_env.dex.router.add_liquidity(
+            token_a_addr, token_b_addr, amount, amount, U256::zero(), U256::zero(), user, u64::MAX,
+        );
+
+        // A real transfer is swapped and recorded
+        let route = encode_route(&[token_a_addr, token_b_addr]).unwrap();
+        let data = encode_swap_call(&route, U256::zero(), user, u64::MAX).unwrap();
+        let b_before = test_env.token_b.balance_of(user);
+        test_env.token_a.transfer_and_call(router_addr, U256::from(1_000u64), data);
+        assert!(test_env.token_b.balance_of(user) > b_before);
+        assert_eq!(test_env.dex.router.get_trade_count(user), 1);
+
+        // A token that only claims a transfer is rejected, even with a pool
+        let mut forger = ForgingToken::deploy(&env, odra::host::NoArgs);
+        let forger_addr = forger.address().clone();
+        test_env.dex.add_pair(&env, forger_addr, token_b_addr, DEFAULT_PAIR_TYPE);
+        let route = encode_route(&[forger_addr, token_b_addr]).unwrap();
+        let data = encode_swap_call(&route, U256::zero(), user, u64::MAX).unwrap();
+        assert_eq!(
+            forger.try_forge(router_addr, user, U256::from(1_000u64), data),
+            Err(DexError::InsufficientInputAmount.into())
+        );
+        assert_eq!(test_env.dex.router.get_trade_count(user), 1);
+    }
+
+    #[test]
+    fn test_revealed_swap_executes_once() {
+        let mut test_env = RoutedEnv::new();
+        let user = test_env.env.get_account(1);
+        let amount = U256::from(1_000_000_000u64);
+        test_env.mint_tokens(user, amount);
+
+        let token_a_addr = test_env.token_a.address().clone();
+        let token_b_addr = test_env.token_b.address().clone();
+        let router_addr = test_env.dex.router.address().clone();
+
+        test_env.env.set_caller(user);
+        test_env.token_a.approve(router_addr, amount);
+        test_env.token_b.approve(router_addr, amount);
+        test_env.dex.router.add_liquidity(
+            token_a_addr,
+            token_b_addr,
+            U256::from(100_000_000u64),
+            U256::from(100_000_000u64),
+            U256::zero(),
+            U256::zero(),
+            user,
+            u64::MAX,
+        );
+
+        let path = vec![token_a_addr, token_b_addr];
+        let amount_in = U256::from(1_000_000u64);
+        let salt = [1u8; 32];
+        let commitment = test_env.dex.router.swap_commitment_hash(amount_in, U256::zero(), path.clone(), user, salt);
+        test_env.dex.router.commit_swap(commitment);
+        test_env.env.advance_block_time(1);
+
+        let before = test_env.token_b.balance_of(user);
+        let amounts = test_env.dex.router.reveal_swap(amount_in, U256::zero(), path.clone(), user, u64::MAX, salt);
+        assert_eq!(test_env.token_b.balance_of(user) - before, amounts[1]);
+        assert!(test_env.dex.router.get_swap_commitment(user).is_none());
+        assert!(test_env.dex.router.try_reveal_swap(amount_in, U256::zero(), path, user, u64::MAX, salt).is_err());
+    }
+
+    #[test]
+    fn test_trade_history_empty_for_new_user() {
+        let test_env = TestEnv::new();
+        let user = test_env.env.get_account(1);
+
+        assert_eq!(test_env.router.get_trade_count(user), 0);
+        assert!(test_env.router.get_trade_history(user, 0, 10).is_empty());
+    }
+
+    #[test]
+    fn test_mixed_pair_type_route() {
+        use crate::dex::factory::PairKind;
+        use crate::errors::DexError;
+        use crate::sdk::encode_typed_route;
+        use crate::testing::seed_pair;
+
+        let mut test_env = RoutedEnv::new();
+        let env = test_env.env.clone();
+        let user = env.get_account(1);
+        let amount = U256::from(1_000_000_000u64);
+        test_env.mint_tokens(user, amount);
+
+        let token_a_addr = test_env.token_a.address().clone();
+        let token_b_addr = test_env.token_b.address().clone();
+        let wcspr_addr = test_env.wcspr.address().clone();
+        let router_addr = test_env.dex.router.address().clone();
+
+        // Type 1: constant product; type 2: stable (no quote yet)
+        test_env.dex.factory.set_pair_type(1, PairKind::ConstantProduct, 5, true);
+        test_env.dex.factory.set_pair_type(2, PairKind::Stable, 4, true);
+
+        // Type 1 B/WCSPR pair with a 0.05% fee, funded directly
+        let mut typed_pair = test_env.dex.add_pair(&env, token_b_addr, wcspr_addr, 1);
+        typed_pair.set_fee_bps(5);
+        seed_pair(
+            &mut typed_pair,
+            &mut test_env.token_b,
+            &mut test_env.wcspr,
+            U256::from(100_000_000u64),
+            U256::from(100_000_000u64),
+            user,
+        );
+
+        // Default A/B pair through the router
+        env.set_caller(user);
+        test_env.token_a.approve(router_addr, amount);
+        test_env.token_b.approve(router_addr, amount);
+        test_env.dex.router.add_liquidity(
+            token_a_addr,
+            token_b_addr,
+            U256::from(100_000_000u64),
+            U256::from(100_000_000u64),
+            U256::zero(),
+            U256::zero(),
+            user,
+            u64::MAX,
+        );
+
+        let amount_in = U256::from(1_000_000u64);
+        let path = vec![token_a_addr, token_b_addr, wcspr_addr];
+        let amounts = test_env.dex.router.get_amounts_out_typed(amount_in, path.clone(), vec![0, 1]);
+        let first_hop = test_env.dex.router.get_amounts_out(amount_in, vec![token_a_addr, token_b_addr])[1];
+        assert_eq!(amounts[1], first_hop);
+        // The cheaper type 1 hop beats a default-fee quote for the same reserves
+        assert!(amounts[2] > test_env.dex.router.get_amount_out(first_hop, U256::from(100_000_000u64), U256::from(100_000_000u64)));
+
+        let route = encode_typed_route(&path, &[0, 1]).unwrap();
+        assert_eq!(test_env.dex.router.quote_exact_in(amount_in, route.clone()), amounts[2]);
+        let before = test_env.wcspr.balance_of(user);
+        test_env.dex.router.swap(amount_in, amounts[2], route, user, u64::MAX);
+        assert_eq!(test_env.wcspr.balance_of(user) - before, amounts[2]);
+
+        // Pair types without a router quote are rejected
+        assert_eq!(
+            test_env.dex.router.try_get_amounts_out_typed(amount_in, path, vec![0, 2]),
+            Err(DexError::InvalidPath.into())
+        );
+    }
+
+    #[test]
+    fn test_aggregator_route_matches_path_swap() {
+        use crate::sdk::encode_route;
+
+        let mut test_env = RoutedEnv::new();
+        let user = test_env.env.get_account(1);
+        let amount = U256::from(1_000_000_000u64);
+        test_env.mint_tokens(user, amount);
+
+        let token_a_addr = test_env.token_a.address().clone();
+        let token_b_addr = test_env.token_b.address().clone();
+        let router_addr = test_env.dex.router.address().clone();
+
+        test_env.env.set_caller(user);
+        test_env.token_a.approve(router_addr, amount);
+        test_env.token_b.approve(router_addr, amount);
+        test_env.dex.router.add_liquidity(
+            token_a_addr,
+            token_b_addr,
+            U256::from(100_000_000u64),
+            U256::from(100_000_000u64),
+            U256::zero(),
+            U256::zero(),
+            user,
+            u64::MAX,
+        );
+
+        let path = vec![token_a_addr, token_b_addr];
+        let route = encode_route(&path).unwrap();
+        let amount_in = U256::from(1_000_000u64);
+        let quoted = test_env.dex.router.quote_exact_in(amount_in, route.clone());
+        assert_eq!(quoted, test_env.dex.router.get_amounts_out(amount_in, path.clone())[1]);
+        assert_eq!(
+            test_env.dex.router.quote_exact_out(quoted, route.clone()),
+            test_env.dex.router.get_amounts_in(quoted, path)[0]
+        );
+
+        let amount_out = test_env.dex.router.swap(amount_in, quoted, route, user, u64::MAX);
+        assert_eq!(amount_out, quoted);
+    }
+
+    #[test]
+    fn test_split_swap_across_parallel_routes() {
+        use crate::dex::router::Route;
+
+        let mut test_env = RoutedEnv::new();
+        let user = test_env.env.get_account(1);
+        let amount = U256::from(1_000_000_000u64);
+        test_env.mint_tokens(user, amount);
+        test_env.wcspr.mint(user, amount);
+
+        let token_a_addr = test_env.token_a.address().clone();
+        let token_b_addr = test_env.token_b.address().clone();
+        let wcspr_addr = test_env.wcspr.address().clone();
+        let router_addr = test_env.dex.router.address().clone();
+
+        let env = test_env.env.clone();
+        test_env.dex.add_pair(&env, token_a_addr, wcspr_addr, 0);
+        test_env.dex.add_pair(&env, wcspr_addr, token_b_addr, 0);
+
+        test_env.env.set_caller(user);
+        test_env.token_a.approve(router_addr, amount);
+        test_env.token_b.approve(router_addr, amount);
+        test_env.wcspr.approve(router_addr, amount);
+        let liquidity = U256::from(100_000_000u64);
+        for (x, y) in [(token_a_addr, token_b_addr), (token_a_addr, wcspr_addr), (wcspr_addr, token_b_addr)] {
+            test_env.dex.router.add_liquidity(x, y, liquidity, liquidity, U256::zero(), U256::zero(), user, u64::MAX);
+        }
+
+        let direct = Route { path: vec![token_a_addr, token_b_addr], pair_types: vec![0] };
+        let via_wcspr = Route { path: vec![token_a_addr, wcspr_addr, token_b_addr], pair_types: vec![0, 0] };
+        let routes = vec![direct.clone(), via_wcspr];
+        let amount_in = U256::from(20_000_000u64);
+
+        // A large trade uses both routes, and beats the direct route alone
+        let split = test_env.dex.router.quote_split(routes.clone(), amount_in);
+        assert!(!split[0].is_zero() && !split[1].is_zero());
+        assert_eq!(split[0] + split[1], amount_in);
+        let direct_only = test_env.dex.router.get_amounts_out(amount_in, direct.path)[1];
+
+        let before = test_env.token_b.balance_of(user);
+        let amount_out = test_env.dex.router.swap_split(routes, amount_in, direct_only, user, u64::MAX);
+        assert!(amount_out > direct_only);
+        assert_eq!(test_env.token_b.balance_of(user) - before, amount_out);
+        assert_eq!(test_env.token_a.balance_of(router_addr), U256::zero());
+        assert_eq!(test_env.wcspr.balance_of(router_addr), U256::zero());
+    }
+
+    #[test]
+    fn test_safe_mode_blocks_buys_of_delisted_tokens() {
+        use crate::errors::DexError;
+        use crate::governance::TokenList;
+        use odra::host::NoArgs;
+
+        let mut test_env = TestEnv::new();
+        let admin = test_env.env.get_account(0);
+        let token_a = test_env.token_a.address().clone();
+        let token_b = test_env.token_b.address().clone();
+        let standalone = Pair::deploy(&test_env.env, PairInitArgs { token0: token_a, token1: token_b, factory: admin });
+
+        let mut list = TokenList::deploy(&test_env.env, NoArgs);
+        list.set_compromised(token_b, true);
+        assert!(!test_env.router.is_token_delisted(token_b));
+        test_env.router.set_token_list(Some(list.address().clone()));
+        assert!(test_env.router.is_token_delisted(token_b));
+        assert!(test_env.router.is_pair_flagged(standalone.address().clone()));
+
+        // Buying or depositing the flagged token is refused
+        let one = U256::from(1_000u64);
+        assert_eq!(
+            test_env.router.try_swap_exact_tokens_for_tokens(one, U256::zero(), vec![token_a, token_b], admin, u64::MAX),
+            Err(DexError::TokenDelisted.into())
+        );
+        assert_eq!(
+            test_env.router.try_add_liquidity(token_a, token_b, one, one, U256::zero(), U256::zero(), admin, u64::MAX),
+            Err(DexError::TokenDelisted.into())
+        );
+
+        // Selling it passes safe mode (and fails later only for lack of a factory pair)
+        assert_eq!(
+            test_env.router.try_swap_exact_tokens_for_tokens(one, U256::zero(), vec![token_b, token_a], admin, u64::MAX),
+            Err(DexError::PairNotFound.into())
+        );
+
+        test_env.env.set_caller(test_env.env.get_account(1));
+        assert_eq!(test_env.router.try_set_token_list(None), Err(DexError::Unauthorized.into()));
+    }
+
+    #[test]
+    fn test_normalized_prices_ignore_token_decimals() {
+        use crate::tokens::{UsdcToken, WcsprToken, WethToken};
+        use odra::host::NoArgs;
+
+        let test_env = TestEnv::new();
+        let env = &test_env.env;
+        let admin = env.get_account(0);
+        let mut usdc = UsdcToken::deploy(env, NoArgs); // 6 decimals
+        let mut wcspr = WcsprToken::deploy(env, NoArgs); // 9 decimals
+        let mut weth = WethToken::deploy(env, NoArgs); // 18 decimals
+        let mut ecto = crate::testing::deploy_token(env, "Ecto", "ECTO"); // 18 decimals
+        let e18 = U256::from(1_000_000_000_000_000_000u128);
+
+        // Seed a standalone pair and return (price of `base` in `quote`, inverse)
+        let price_of = |base: Address, quote: Address, seed: &mut dyn FnMut(Address)| {
+            let mut pair = Pair::deploy(env, PairInitArgs { token0: base, token1: quote, factory: admin });
+            seed(pair.address().clone());
+            pair.mint(admin);
+            let (price0, price1) = test_env.router.get_price_normalized(pair.address().clone());
+            if pair.token0() == base { (price0, price1) } else { (price1, price0) }
+        };
+
+        // 1 WETH (18) = 2,000 USDC (6)
+        let (weth_in_usdc, usdc_in_weth) = price_of(weth.address().clone(), usdc.address().clone(), &mut |pair| {
+            weth.mint(pair, e18);
+            usdc.mint(pair, U256::from(2_000_000_000u64));
+        });
+        assert_eq!(weth_in_usdc, e18 * 2_000);
+        assert_eq!(usdc_in_weth, e18 / 2_000);
+
+        // 1,000 WCSPR (9) = 20 USDC (6)
+        let (wcspr_in_usdc, usdc_in_wcspr) = price_of(wcspr.address().clone(), usdc.address().clone(), &mut |pair| {
+            wcspr.mint(pair, U256::from(1_000_000_000_000u64));
+            usdc.mint(pair, U256::from(20_000_000u64));
+        });
+        assert_eq!(wcspr_in_usdc, e18 / 50);
+        assert_eq!(usdc_in_wcspr, e18 * 50);
+
+        // 1 WETH (18) = 100 ECTO (18)
+        let (weth_in_ecto, ecto_in_weth) = price_of(weth.address().clone(), ecto.address().clone(), &mut |pair| {
+            weth.mint(pair, e18);
+            ecto.mint(pair, e18 * 100);
+        });
+        assert_eq!(weth_in_ecto, e18 * 100);
+        assert_eq!(ecto_in_weth, e18 / 100);
+    }
+
+    #[test]
+    fn test_quote_normalized_across_decimals() {
+        use crate::tokens::UsdcToken;
+        use odra::host::NoArgs;
+
+        let mut test_env = RoutedEnv::new();
+        let admin = test_env.env.get_account(0);
+        let mut usdc = UsdcToken::deploy(&test_env.env, NoArgs);
+        let usdc_addr = usdc.address().clone();
+        let token_a_addr = test_env.token_a.address().clone();
+        let router_addr = test_env.dex.router.address().clone();
+        let env = test_env.env.clone();
+        test_env.dex.add_pair(&env, token_a_addr, usdc_addr, DEFAULT_PAIR_TYPE);
+        let e18 = U256::from(1_000_000_000_000_000_000u128);
+
+        // 1 token A (18) = 2 USDC (6)
+        test_env.token_a.mint(admin, e18 * 1_000);
+        usdc.mint(admin, U256::from(2_000_000_000u64));
+        test_env.token_a.approve(router_addr, e18 * 1_000);
+        usdc.approve(router_addr, U256::from(2_000_000_000u64));
+        test_env.dex.router.add_liquidity(
+            token_a_addr, usdc_addr, e18 * 1_000, U256::from(2_000_000_000u64),
+            U256::zero(), U256::zero(), admin, u64::MAX,
+        );
+
+        assert_eq!(test_env.dex.router.quote_normalized(e18, token_a_addr, usdc_addr), e18 * 2);
+        assert_eq!(test_env.dex.router.quote_normalized(e18, usdc_addr, token_a_addr), e18 / 2);
+    }
+
+    #[test]
+    fn test_split_quote_rejects_bad_routes() {
+        use crate::dex::router::{Route, MAX_SPLIT_ROUTES};
+        use crate::errors::DexError;
+
+        let test_env = TestEnv::new();
+        let token_a_addr = test_env.token_a.address().clone();
+        let token_b_addr = test_env.token_b.address().clone();
+        let route = Route { path: vec![token_a_addr, token_b_addr], pair_types: vec![0] };
+
+        assert_eq!(
+            test_env.router.try_quote_split(vec![], U256::from(1_000)),
+            Err(DexError::InvalidPath.into())
+        );
+        assert_eq!(
+            test_env.router.try_quote_split(vec![route.clone(); MAX_SPLIT_ROUTES + 1], U256::from(1_000)),
+            Err(DexError::InvalidPath.into())
+        );
+        assert_eq!(
+            test_env.router.try_quote_split(vec![route], U256::zero()),
+            Err(DexError::InsufficientInputAmount.into())
+        );
+    }
+
     #[test]
     fn test_amm_math_get_amount_out() {
         use crate::math::AmmMath;
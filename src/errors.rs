@@ -73,6 +73,45 @@ pub enum DexError {
     
     /// Invalid configuration
     InvalidConfiguration = 23,
+    
+    /// Amount of token A below the caller's minimum
+    InsufficientAAmount = 24,
+    
+    /// Amount of token B below the caller's minimum
+    InsufficientBAmount = 25,
+    
+    /// Contract is paused
+    ContractPaused = 26,
+    
+    /// Swap moved the price further than the pair's circuit breaker allows
+    PriceMoveTooLarge = 27,
+    
+    /// Lock has not reached its unlock time
+    LockActive = 28,
+    
+    /// Attached CSPR does not cover the pair creation fee
+    InsufficientCreationFee = 29,
+    
+    /// Flash loan was not repaid with its fee before the callback returned
+    FlashLoanNotRepaid = 30,
+    
+    /// Token is flagged as compromised; it can be sold but not bought
+    TokenDelisted = 31,
+    
+    /// Flash loan receiver is not on the pair's allowlist
+    FlashReceiverNotAllowed = 32,
+    
+    /// No pending swap commitment, or the reveal does not match it
+    InvalidCommitment = 33,
+    
+    /// Swap revealed before the commitment's minimum delay passed
+    RevealTooEarly = 34,
+    
+    /// Swap commitment is older than its reveal window
+    CommitmentExpired = 35,
+    
+    /// Sub-actions of a payable multicall do not add up to the attached CSPR
+    UnbalancedValue = 36,
 }
 
 /// Custom errors for the LP Token contract
@@ -83,4 +122,10 @@ pub enum TokenError {
     
     /// Insufficient balance for operation
     InsufficientBalance = 101,
+    
+    /// Sender or recipient is on the enforced denylist
+    AddressDenied = 102,
+    
+    /// More transfer listeners than MAX_TRANSFER_LISTENERS
+    TooManyListeners = 103,
 }
\ No newline at end of file
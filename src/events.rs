@@ -1,7 +1,8 @@
 //! Event definitions for the DEX smart contract
 use odra::prelude::*;
-use odra::casper_types::U256;
+use odra::casper_types::{U256, U512};
 use odra::prelude::Address;
+use crate::dex::factory::PairKind;
 
 /// Event emitted when a new pair is created
 #[odra::event]
@@ -14,6 +15,8 @@ pub struct PairCreated {
     pub pair: Address,
     /// Total number of pairs
     pub pair_count: u32,
+    /// Per-contract event sequence number
+    pub seq: u64,
 }
 
 /// Event emitted when liquidity is added to a pool
@@ -29,6 +32,8 @@ pub struct LiquidityAdded {
     pub amount1: U256,
     /// LP tokens minted
     pub liquidity: U256,
+    /// Per-contract event sequence number
+    pub seq: u64,
 }
 
 /// Event emitted when liquidity is removed from a pool
@@ -44,6 +49,8 @@ pub struct LiquidityRemoved {
     pub amount1: U256,
     /// LP tokens burned
     pub liquidity: U256,
+    /// Per-contract event sequence number
+    pub seq: u64,
 }
 
 /// Event emitted when a swap occurs
@@ -63,6 +70,8 @@ pub struct Swap {
     pub amount1_out: U256,
     /// Address receiving the output
     pub to: Address,
+    /// Per-contract event sequence number
+    pub seq: u64,
 }
 
 /// Event emitted when reserves are synced
@@ -74,6 +83,36 @@ pub struct Sync {
     pub reserve0: U256,
     /// Reserve of token1
     pub reserve1: U256,
+    /// Per-contract event sequence number
+    pub seq: u64,
+}
+
+/// Event emitted when a pair lends out reserves for one call
+#[odra::event]
+pub struct FlashLoan {
+    /// Address of the pair
+    pub pair: Address,
+    /// Account that requested the loan
+    pub initiator: Address,
+    /// Contract that received the funds and the callback
+    pub receiver: Address,
+    /// Token borrowed
+    pub token: Address,
+    /// Amount borrowed
+    pub amount: U256,
+    /// Fee paid on top, left in the reserves for LPs
+    pub fee: U256,
+    /// Per-contract event sequence number
+    pub seq: u64,
+}
+
+/// Event emitted when an LP token changes the contracts it notifies on transfers
+#[odra::event]
+pub struct TransferListenersUpdated {
+    /// Contracts notified after every transfer, mint and burn
+    pub listeners: Vec<Address>,
+    /// Per-contract event sequence number
+    pub seq: u64,
 }
 
 /// Event emitted when LP tokens are transferred
@@ -85,6 +124,8 @@ pub struct Transfer {
     pub to: Address,
     /// Amount transferred
     pub value: U256,
+    /// Per-contract event sequence number
+    pub seq: u64,
 }
 
 /// Event emitted when approval is granted
@@ -96,6 +137,8 @@ pub struct Approval {
     pub spender: Address,
     /// Amount approved
     pub value: U256,
+    /// Per-contract event sequence number
+    pub seq: u64,
 }
 
 /// Event emitted when fee is collected
@@ -107,4 +150,194 @@ pub struct FeeCollected {
     pub recipient: Address,
     /// Amount collected
     pub amount: U256,
-}
\ No newline at end of file
+    /// Per-contract event sequence number
+    pub seq: u64,
+}
+
+/// Event emitted when the router returns leftover path tokens to the caller
+#[odra::event]
+pub struct DustRefunded {
+    /// Token address
+    pub token: Address,
+    /// Recipient of the dust
+    pub to: Address,
+    /// Amount returned
+    pub amount: U256,
+    /// Per-contract event sequence number
+    pub seq: u64,
+}
+
+/// Event emitted when liquidity is added with a single token
+#[odra::event]
+pub struct SingleSidedDeposited {
+    /// Address of the depositor
+    pub user: Address,
+    /// Address of the pair
+    pub pair: Address,
+    /// Token deposited
+    pub token_in: Address,
+    /// Amount deposited
+    pub amount_in: U256,
+    /// Portion of the deposit swapped into the other token
+    pub swap_amount: U256,
+    /// LP tokens credited to the position
+    pub liquidity: U256,
+    /// Per-contract event sequence number
+    pub seq: u64,
+}
+
+/// Event emitted when single-sided liquidity is withdrawn
+#[odra::event]
+pub struct SingleSidedWithdrawn {
+    /// Address of the depositor
+    pub user: Address,
+    /// Address of the pair
+    pub pair: Address,
+    /// LP tokens burned
+    pub liquidity: U256,
+    /// Amount of token0 returned
+    pub amount0: U256,
+    /// Amount of token1 returned
+    pub amount1: U256,
+    /// Impermanent loss compensation paid in the deposited token
+    pub compensation: U256,
+    /// Per-contract event sequence number
+    pub seq: u64,
+}
+
+/// Event emitted when liquidity is migrated between pairs of the same tokens
+#[odra::event]
+pub struct LiquidityMigrated {
+    /// Address of the liquidity provider
+    pub provider: Address,
+    /// Pair the liquidity was removed from
+    pub pair_from: Address,
+    /// Pair the liquidity was added to
+    pub pair_to: Address,
+    /// LP tokens burned in `pair_from`
+    pub liquidity_burned: U256,
+    /// LP tokens minted in `pair_to`
+    pub liquidity_minted: U256,
+    /// Amount of token0 deposited into `pair_to`
+    pub amount0: U256,
+    /// Amount of token1 deposited into `pair_to`
+    pub amount1: U256,
+    /// Per-contract event sequence number
+    pub seq: u64,
+}
+
+/// Event emitted when a user commits to a swap to be revealed later
+#[odra::event]
+pub struct SwapCommitted {
+    /// Address that will reveal the swap
+    pub user: Address,
+    /// Hash of the swap parameters and salt
+    pub commitment: [u8; 32],
+    /// Block time of the commitment
+    pub timestamp: u64,
+    /// Per-contract event sequence number
+    pub seq: u64,
+}
+
+/// Event emitted by the router for every swap it routes
+/// Pairs emit `Swap` per hop; this records the whole path and who paid
+#[odra::event]
+pub struct RouterSwap {
+    /// Address that paid the input
+    pub sender: Address,
+    /// Recipient of the final output
+    pub to: Address,
+    /// Token path of the swap
+    pub path: Vec<Address>,
+    /// Amount at each step of the path (input first, output last)
+    pub amounts: Vec<U256>,
+    /// Per-contract event sequence number
+    pub seq: u64,
+}
+
+/// Event emitted when liquidity is added through the router
+#[odra::event]
+pub struct RouterLiquidityAdded {
+    /// Address that supplied the tokens
+    pub sender: Address,
+    /// Recipient of the LP tokens
+    pub to: Address,
+    /// Pair the liquidity went into
+    pub pair: Address,
+    /// First token as passed by the caller
+    pub token_a: Address,
+    /// Second token as passed by the caller
+    pub token_b: Address,
+    /// Amount of token A deposited
+    pub amount_a: U256,
+    /// Amount of token B deposited
+    pub amount_b: U256,
+    /// LP tokens minted
+    pub liquidity: U256,
+    /// Per-contract event sequence number
+    pub seq: u64,
+}
+
+/// Event emitted when liquidity is removed through the router
+#[odra::event]
+pub struct RouterLiquidityRemoved {
+    /// Address that supplied the LP tokens
+    pub sender: Address,
+    /// Recipient of the underlying tokens
+    pub to: Address,
+    /// Pair the liquidity was taken from
+    pub pair: Address,
+    /// First token as passed by the caller
+    pub token_a: Address,
+    /// Second token as passed by the caller
+    pub token_b: Address,
+    /// LP tokens burned
+    pub liquidity: U256,
+    /// Amount of token A returned
+    pub amount_a: U256,
+    /// Amount of token B returned
+    pub amount_b: U256,
+    /// Per-contract event sequence number
+    pub seq: u64,
+}
+
+/// Event emitted when a swap output is split between several recipients
+#[odra::event]
+pub struct SwapDistributed {
+    /// Address that paid the input
+    pub sender: Address,
+    /// Token distributed to the recipients
+    pub token_out: Address,
+    /// Total output distributed
+    pub amount_out: U256,
+    /// Number of recipients paid
+    pub recipient_count: u32,
+    /// Per-contract event sequence number
+    pub seq: u64,
+}
+
+/// Event emitted when a pair type is registered or updated
+#[odra::event]
+pub struct PairTypeRegistered {
+    /// Pair type id
+    pub pair_type: u8,
+    /// Curve implemented by the type
+    pub kind: PairKind,
+    /// Swap fee in basis points
+    pub fee_bps: u16,
+    /// Whether new pairs of this type can be created
+    pub enabled: bool,
+    /// Per-contract event sequence number
+    pub seq: u64,
+}
+
+/// Event emitted when the pair creation fee changes
+#[odra::event]
+pub struct PairCreationFeeUpdated {
+    /// New fee in motes
+    pub fee: U512,
+    /// Fee setter that made the change
+    pub updated_by: Address,
+    /// Per-contract event sequence number
+    pub seq: u64,
+}
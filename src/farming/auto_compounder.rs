@@ -0,0 +1,475 @@
+//! Auto Compounder - Vault that restakes farm rewards as LP
+//!
+//! One compounder serves one StakingPool pool. Users deposit the pool's LP
+//! tokens and receive shares; the compounder stakes everything in the farm.
+//! Harvesting claims the farm rewards, pays the caller a small call fee,
+//! swaps half of the rest into each pool token through the Router, adds the
+//! liquidity and stakes the new LP. Every share then redeems for more LP, so
+//! the price per share only grows.
+//!
+//! Harvesting is limited to the admin and whitelisted keepers, since the
+//! caller picks the swap bounds and an open harvest could be sandwiched; the
+//! call fee pays keepers for the gas.
+//! Pool-token dust left over from adding liquidity stays in the compounder
+//! and is used by the next harvest.
+
+use odra::prelude::*;
+use odra::casper_types::U256;
+use odra::ContractRef;
+use super::errors::FarmingError;
+use super::events::{CompounderDeposited, CompounderHarvested, CompounderWithdrawn};
+use super::staking_pool::StakingPoolContractRef;
+use crate::dex::router::{PairContractContractRef, RouterContractRef};
+use crate::math::SafeMath;
+use crate::token::Cep18TokenContractRef;
+
+/// Basis points denominator
+const BPS_DENOMINATOR: u32 = 10_000;
+
+/// Price-per-share scale (1e18)
+const SHARE_PRICE_SCALE: u128 = 1_000_000_000_000_000_000;
+
+/// Default share of harvested rewards paid to the caller (0.5%)
+pub const DEFAULT_CALL_FEE_BPS: u32 = 50;
+
+/// Largest call fee the admin can set (3%)
+pub const MAX_CALL_FEE_BPS: u32 = 300;
+
+/// Auto Compounder contract
+#[odra::module]
+pub struct AutoCompounder {
+    /// Farm the LP is staked in
+    staking_pool: Var<Address>,
+    /// Farm pool id
+    pool_id: Var<u32>,
+    /// LP token (the pair) of the pool
+    lp_token: Var<Address>,
+    /// Farm reward token
+    reward_token: Var<Address>,
+    /// DEX router used to swap rewards and add liquidity
+    router: Var<Address>,
+    /// Contract admin
+    admin: Var<Address>,
+    /// Share of harvested rewards paid to the caller, in basis points
+    call_fee_bps: Var<u32>,
+    /// Keepers allowed to harvest besides the admin
+    keepers: Mapping<Address, bool>,
+    /// Total shares
+    total_shares: Var<U256>,
+    /// Shares per user
+    shares: Mapping<Address, U256>,
+}
+
+#[odra::module]
+impl AutoCompounder {
+    /// Initialize the compounder for one farm pool
+    pub fn init(&mut self, staking_pool: Address, pool_id: u32, reward_token: Address, router: Address) {
+        let pool = StakingPoolContractRef::new(self.env(), staking_pool)
+            .get_pool_info(pool_id)
+            .unwrap_or_revert_with(&self.env(), FarmingError::PoolNotFound);
+        self.staking_pool.set(staking_pool);
+        self.pool_id.set(pool_id);
+        self.lp_token.set(pool.lp_token);
+        self.reward_token.set(reward_token);
+        self.router.set(router);
+        self.admin.set(self.env().caller());
+        self.call_fee_bps.set(DEFAULT_CALL_FEE_BPS);
+        self.total_shares.set(U256::zero());
+    }
+
+    // ========================================
+    // User Functions
+    // ========================================
+
+    /// Deposit LP tokens and receive shares at the current price per share
+    pub fn deposit(&mut self, lp_amount: U256) -> U256 {
+        if lp_amount.is_zero() {
+            self.env().revert(FarmingError::ZeroAmount);
+        }
+        let caller = self.env().caller();
+
+        // Price the deposit before it changes the stake
+        let total_lp = self.total_lp();
+        let total_shares = self.total_shares.get_or_default();
+        let shares = if total_shares.is_zero() || total_lp.is_zero() {
+            lp_amount
+        } else {
+            self.mul_div(lp_amount, total_shares, total_lp)
+        };
+        if shares.is_zero() {
+            self.env().revert(FarmingError::ZeroAmount);
+        }
+
+        let lp_token = self.lp_token();
+        let mut lp_ref = Cep18TokenContractRef::new(self.env(), lp_token);
+        if !lp_ref.transfer_from(caller, self.env().self_address(), lp_amount) {
+            self.env().revert(FarmingError::InsufficientBalance);
+        }
+        self.stake_lp(lp_amount);
+
+        self.total_shares.set(total_shares + shares);
+        self.shares.set(&caller, self.shares_of(caller) + shares);
+
+        self.env().emit_event(CompounderDeposited {
+            user: caller,
+            pool_id: self.pool_id(),
+            lp_amount,
+            shares,
+        });
+
+        shares
+    }
+
+    /// Burn shares for their slice of the staked LP
+    pub fn withdraw(&mut self, shares: U256) -> U256 {
+        let caller = self.env().caller();
+        let user_shares = self.shares_of(caller);
+        if shares.is_zero() || shares > user_shares {
+            self.env().revert(FarmingError::InsufficientBalance);
+        }
+        let total_shares = self.total_shares.get_or_default();
+        let lp_amount = self.mul_div(self.total_lp(), shares, total_shares);
+        if lp_amount.is_zero() {
+            self.env().revert(FarmingError::ZeroAmount);
+        }
+
+        self.shares.set(&caller, user_shares - shares);
+        self.total_shares.set(total_shares - shares);
+
+        let pool_id = self.pool_id();
+        StakingPoolContractRef::new(self.env(), self.staking_pool()).unstake(pool_id, lp_amount);
+        Cep18TokenContractRef::new(self.env(), self.lp_token()).transfer(caller, lp_amount);
+
+        self.env().emit_event(CompounderWithdrawn {
+            user: caller,
+            pool_id,
+            shares,
+            lp_amount,
+        });
+
+        lp_amount
+    }
+
+    // ========================================
+    // Keeper Functions
+    // ========================================
+
+    /// Claim farm rewards, pay the call fee and restake the rest as LP
+    /// `min_out0` / `min_out1` bound the reward swaps into the pool tokens
+    /// Only the admin or a keeper can harvest
+    /// Returns the LP tokens added to the stake
+    pub fn harvest(&mut self, min_out0: U256, min_out1: U256, deadline: u64) -> U256 {
+        self.only_keeper();
+        let caller = self.env().caller();
+        let compounder = self.env().self_address();
+        let pool_id = self.pool_id();
+        let reward_token = self.reward_token.get_or_revert_with(FarmingError::Unauthorized);
+        let mut reward_ref = Cep18TokenContractRef::new(self.env(), reward_token);
+
+        // Reverts with NoRewardsToClaim when nothing accrued
+        let before = reward_ref.balance_of(compounder);
+        StakingPoolContractRef::new(self.env(), self.staking_pool()).claim_rewards(pool_id);
+        let rewards = reward_ref.balance_of(compounder) - before;
+
+        let call_fee = self.mul_div(rewards, U256::from(self.call_fee_bps()), U256::from(BPS_DENOMINATOR));
+        if !call_fee.is_zero() {
+            reward_ref.transfer(caller, call_fee);
+        }
+
+        // Swap half of the rest into each pool token
+        let pair = PairContractContractRef::new(self.env(), self.lp_token());
+        let (token0, token1) = (pair.token0(), pair.token1());
+        let router_address = self.router.get_or_revert_with(FarmingError::Unauthorized);
+        let mut router = RouterContractRef::new(self.env(), router_address);
+        let to_compound = rewards - call_fee;
+        let half = to_compound / 2;
+        reward_ref.approve(router_address, to_compound);
+        for (token, amount, min_out) in [(token0, half, min_out0), (token1, to_compound - half, min_out1)] {
+            if token != reward_token && !amount.is_zero() {
+                router.swap_exact_tokens_for_tokens(amount, min_out, vec![reward_token, token], compounder, deadline);
+            }
+        }
+
+        // Add everything held of both tokens, including dust from earlier harvests
+        let amount0 = Cep18TokenContractRef::new(self.env(), token0).balance_of(compounder);
+        let amount1 = Cep18TokenContractRef::new(self.env(), token1).balance_of(compounder);
+        Cep18TokenContractRef::new(self.env(), token0).approve(router_address, amount0);
+        Cep18TokenContractRef::new(self.env(), token1).approve(router_address, amount1);
+        let (_, _, lp_added) = router.add_liquidity(
+            token0,
+            token1,
+            amount0,
+            amount1,
+            U256::zero(),
+            U256::zero(),
+            compounder,
+            deadline,
+        );
+        self.stake_lp(lp_added);
+
+        self.env().emit_event(CompounderHarvested {
+            caller,
+            pool_id,
+            rewards,
+            call_fee,
+            lp_added,
+            price_per_share: self.price_per_share(),
+        });
+
+        lp_added
+    }
+
+    // ========================================
+    // Views
+    // ========================================
+
+    /// LP staked in the farm on behalf of all shareholders
+    pub fn total_lp(&self) -> U256 {
+        StakingPoolContractRef::new(self.env(), self.staking_pool())
+            .get_user_stake(self.env().self_address(), self.pool_id())
+            .map(|stake| stake.amount)
+            .unwrap_or_default()
+    }
+
+    /// LP redeemable for one share, scaled by 1e18
+    pub fn price_per_share(&self) -> U256 {
+        let total_shares = self.total_shares.get_or_default();
+        if total_shares.is_zero() {
+            return U256::from(SHARE_PRICE_SCALE);
+        }
+        self.mul_div(self.total_lp(), U256::from(SHARE_PRICE_SCALE), total_shares)
+    }
+
+    /// LP redeemable for a user's shares
+    pub fn balance_of_underlying(&self, user: Address) -> U256 {
+        let total_shares = self.total_shares.get_or_default();
+        if total_shares.is_zero() {
+            return U256::zero();
+        }
+        self.mul_div(self.total_lp(), self.shares_of(user), total_shares)
+    }
+
+    /// Shares held by a user
+    pub fn shares_of(&self, user: Address) -> U256 {
+        self.shares.get(&user).unwrap_or_default()
+    }
+
+    /// Total shares outstanding
+    pub fn total_shares(&self) -> U256 {
+        self.total_shares.get_or_default()
+    }
+
+    /// Farm the LP is staked in
+    pub fn staking_pool(&self) -> Address {
+        self.staking_pool.get_or_revert_with(FarmingError::PoolNotFound)
+    }
+
+    /// Farm pool id
+    pub fn pool_id(&self) -> u32 {
+        self.pool_id.get_or_default()
+    }
+
+    /// LP token of the pool
+    pub fn lp_token(&self) -> Address {
+        self.lp_token.get_or_revert_with(FarmingError::PoolNotFound)
+    }
+
+    /// Share of harvested rewards paid to the caller, in basis points
+    pub fn call_fee_bps(&self) -> u32 {
+        self.call_fee_bps.get_or_default()
+    }
+
+    /// Whether an account is a whitelisted keeper
+    pub fn is_keeper(&self, account: Address) -> bool {
+        self.keepers.get(&account).unwrap_or_default()
+    }
+
+    // ========================================
+    // Admin Functions
+    // ========================================
+
+    /// Set the share of harvested rewards paid to the caller
+    pub fn set_call_fee(&mut self, call_fee_bps: u32) {
+        self.only_admin();
+        if call_fee_bps > MAX_CALL_FEE_BPS {
+            self.env().revert(FarmingError::InvalidFee);
+        }
+        self.call_fee_bps.set(call_fee_bps);
+    }
+
+    /// Add or remove a keeper allowed to harvest
+    pub fn set_keeper(&mut self, keeper: Address, allowed: bool) {
+        self.only_admin();
+        self.keepers.set(&keeper, allowed);
+    }
+
+    /// Transfer admin rights
+    pub fn transfer_admin(&mut self, new_admin: Address) {
+        self.only_admin();
+        self.admin.set(new_admin);
+    }
+
+    // ========================================
+    // Internal Functions
+    // ========================================
+
+    /// Stake LP held by the compounder in the farm
+    fn stake_lp(&self, amount: U256) {
+        if amount.is_zero() {
+            return;
+        }
+        let staking_pool = self.staking_pool();
+        Cep18TokenContractRef::new(self.env(), self.lp_token()).approve(staking_pool, amount);
+        StakingPoolContractRef::new(self.env(), staking_pool).stake(self.pool_id(), amount);
+    }
+
+    fn mul_div(&self, a: U256, b: U256, c: U256) -> U256 {
+        SafeMath::mul_div(a, b, c).unwrap_or_else(|e| self.env().revert(e))
+    }
+
+    fn only_admin(&self) {
+        let admin = self.admin.get_or_revert_with(FarmingError::Unauthorized);
+        if self.env().caller() != admin {
+            self.env().revert(FarmingError::Unauthorized);
+        }
+    }
+
+    fn only_keeper(&self) {
+        let caller = self.env().caller();
+        if !self.is_keeper(caller) && Some(caller) != self.admin.get() {
+            self.env().revert(FarmingError::Unauthorized);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use odra::host::{Deployer, HostEnv};
+    use crate::dex::pair::PairHostRef;
+    use crate::farming::staking_pool::{StakingPool, StakingPoolHostRef, StakingPoolInitArgs};
+    use crate::dex::factory::DEFAULT_PAIR_TYPE;
+    use crate::testing::{deploy_token, seed_pair, DexFixture, RoutedDex};
+    use crate::token::LpTokenHostRef;
+
+    struct Setup {
+        env: HostEnv,
+        dex: DexFixture,
+        routed: RoutedDex,
+        pair: PairHostRef,
+        farm: StakingPoolHostRef,
+        ecto: LpTokenHostRef,
+        compounder: AutoCompounderHostRef,
+    }
+
+    fn setup() -> Setup {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let mut dex = DexFixture::deploy(&env);
+        let mut ecto = deploy_token(&env, "Ecto", "ECTO");
+        let mut farm = StakingPool::deploy(&env, StakingPoolInitArgs {
+            reward_token_address: ecto.address().clone(),
+        });
+
+        // Seed the pair so the admin holds LP to hand out
+        let mut pair = dex.deploy_pair(&env);
+        let pair_address = pair.address().clone();
+        dex.token_a.mint(pair_address, U256::from(1_000_000_000u64));
+        dex.token_b.mint(pair_address, U256::from(1_000_000_000u64));
+        pair.mint(admin);
+
+        // The compounder swaps and adds liquidity through a Router serving the pair
+        let mut routed = RoutedDex::deploy(&env, dex.wcspr.address().clone());
+        routed.factory.register_pair(
+            dex.token_a.address().clone(),
+            dex.token_b.address().clone(),
+            DEFAULT_PAIR_TYPE,
+            pair_address,
+        );
+
+        let pool_id = farm.create_pool(pair_address, U256::from(1_000u64));
+        ecto.mint(farm.address().clone(), U256::from(1_000_000_000u64));
+        let compounder = AutoCompounder::deploy(&env, AutoCompounderInitArgs {
+            staking_pool: farm.address().clone(),
+            pool_id,
+            reward_token: ecto.address().clone(),
+            router: routed.router.address().clone(),
+        });
+
+        Setup { env, dex, routed, pair, farm, ecto, compounder }
+    }
+
+    #[test]
+    fn test_deposit_and_withdraw_track_shares() {
+        let Setup { env, mut pair, farm, mut compounder, .. } = setup();
+        let user = env.get_account(1);
+        let compounder_address = compounder.address().clone();
+        pair.transfer(user, U256::from(10_000u64));
+
+        env.set_caller(user);
+        pair.approve(compounder_address, U256::from(10_000u64));
+        assert_eq!(compounder.deposit(U256::from(4_000u64)), U256::from(4_000u64));
+        assert_eq!(compounder.total_lp(), U256::from(4_000u64));
+        assert_eq!(farm.get_user_stake(compounder_address, 0).unwrap().amount, U256::from(4_000u64));
+        assert_eq!(compounder.price_per_share(), U256::from(SHARE_PRICE_SCALE));
+
+        assert_eq!(compounder.withdraw(U256::from(1_000u64)), U256::from(1_000u64));
+        assert_eq!(compounder.balance_of_underlying(user), U256::from(3_000u64));
+        assert_eq!(pair.balance_of(user), U256::from(7_000u64));
+        assert_eq!(
+            compounder.try_withdraw(U256::from(3_001u64)),
+            Err(FarmingError::InsufficientBalance.into())
+        );
+    }
+
+    #[test]
+    fn test_call_fee_is_capped() {
+        let Setup { env, mut compounder, .. } = setup();
+        assert_eq!(
+            compounder.try_set_call_fee(MAX_CALL_FEE_BPS + 1),
+            Err(FarmingError::InvalidFee.into())
+        );
+        compounder.set_call_fee(MAX_CALL_FEE_BPS);
+        assert_eq!(compounder.call_fee_bps(), MAX_CALL_FEE_BPS);
+
+        env.set_caller(env.get_account(1));
+        assert_eq!(compounder.try_set_call_fee(0), Err(FarmingError::Unauthorized.into()));
+    }
+
+    #[test]
+    fn test_harvest_compounds_rewards_into_lp() {
+        let Setup { env, mut dex, mut routed, mut pair, mut ecto, mut compounder, .. } = setup();
+        let admin = env.get_account(0);
+        let keeper = env.get_account(2);
+        let ecto_address = ecto.address().clone();
+
+        // ECTO/token pairs the rewards are swapped through
+        let liquidity = U256::from(1_000_000_000u64);
+        for token in [&mut dex.token_a, &mut dex.token_b] {
+            let mut reward_pair = routed.add_pair(&env, ecto_address, token.address().clone(), DEFAULT_PAIR_TYPE);
+            seed_pair(&mut reward_pair, &mut ecto, token, liquidity, liquidity, admin);
+        }
+
+        pair.approve(compounder.address().clone(), U256::from(10_000u64));
+        compounder.deposit(U256::from(10_000u64));
+        env.advance_block_time(100);
+
+        // Only whitelisted keepers pick the swap bounds
+        env.set_caller(keeper);
+        assert_eq!(
+            compounder.try_harvest(U256::zero(), U256::zero(), u64::MAX),
+            Err(FarmingError::Unauthorized.into())
+        );
+        env.set_caller(admin);
+        compounder.set_keeper(keeper, true);
+        assert!(compounder.is_keeper(keeper));
+
+        env.set_caller(keeper);
+        let lp_added = compounder.harvest(U256::zero(), U256::zero(), u64::MAX);
+        assert!(!lp_added.is_zero());
+        assert_eq!(compounder.total_lp(), U256::from(10_000u64) + lp_added);
+        assert!(compounder.price_per_share() > U256::from(SHARE_PRICE_SCALE));
+        // 100 seconds at 1,000 ECTO per second, 0.5% to the caller
+        assert_eq!(ecto.balance_of(keeper), U256::from(500u64));
+    }
+}
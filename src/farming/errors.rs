@@ -22,4 +22,14 @@ pub enum FarmingError {
     NoRewardsToClaim = 8,
     /// Pool not active
     PoolNotActive = 9,
+    /// Emergency mode is active
+    EmergencyActive = 10,
+    /// Stake is locked
+    LockActive = 11,
+    /// Lock length out of range or shorter than the current lock
+    InvalidLockDuration = 12,
+    /// Stake has no active lock
+    NotLocked = 13,
+    /// Fee above the allowed maximum
+    InvalidFee = 14,
 }
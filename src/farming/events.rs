@@ -47,3 +47,76 @@ pub struct RewardRateUpdated {
     pub new_rate: U256,
     pub updated_by: Address,
 }
+
+/// Event emitted when a stake is withdrawn without rewards
+#[odra::event]
+pub struct EmergencyWithdrawn {
+    pub user: Address,
+    pub pool_id: u32,
+    pub amount: U256,
+    pub forfeited_rewards: U256,
+    pub timestamp: u64,
+}
+
+/// Event emitted when emergency mode is toggled
+#[odra::event]
+pub struct EmergencyModeUpdated {
+    pub enabled: bool,
+    pub updated_by: Address,
+}
+
+/// Event emitted when a stake is locked for a boost
+#[odra::event]
+pub struct StakeLocked {
+    pub user: Address,
+    pub pool_id: u32,
+    pub amount: U256,
+    pub lock_end: u64,
+    pub boost_bps: u32,
+}
+
+/// Event emitted when a lock is left early
+#[odra::event]
+pub struct LockExitedEarly {
+    pub user: Address,
+    pub pool_id: u32,
+    pub penalty: U256,
+    pub redistributed: bool,
+}
+
+/// Event emitted when a locker claims early-exit penalties
+#[odra::event]
+pub struct PenaltyShareClaimed {
+    pub user: Address,
+    pub pool_id: u32,
+    pub amount: U256,
+}
+
+/// Event emitted when LP tokens are deposited into an auto-compounder
+#[odra::event]
+pub struct CompounderDeposited {
+    pub user: Address,
+    pub pool_id: u32,
+    pub lp_amount: U256,
+    pub shares: U256,
+}
+
+/// Event emitted when auto-compounder shares are redeemed for LP tokens
+#[odra::event]
+pub struct CompounderWithdrawn {
+    pub user: Address,
+    pub pool_id: u32,
+    pub shares: U256,
+    pub lp_amount: U256,
+}
+
+/// Event emitted when an auto-compounder turns farm rewards into staked LP
+#[odra::event]
+pub struct CompounderHarvested {
+    pub caller: Address,
+    pub pool_id: u32,
+    pub rewards: U256,
+    pub call_fee: U256,
+    pub lp_added: U256,
+    pub price_per_share: U256,
+}
@@ -1,13 +1,16 @@
 //! Yield Farming - LP token staking with ECTO rewards
 //! 
-//! Users can stake LP tokens (e.g., sCSPR/ECTO) to earn ECTO rewards
+//! Users can stake LP tokens (e.g., sCSPR/ECTO) to earn ECTO rewards, or
+//! deposit them into an AutoCompounder that restakes the rewards as LP
 
 pub mod staking_pool;
 pub mod rewards_distributor;
+pub mod auto_compounder;
 pub mod errors;
 pub mod events;
 
 pub use staking_pool::StakingPool;
 pub use rewards_distributor::RewardsDistributor;
+pub use auto_compounder::AutoCompounder;
 pub use errors::FarmingError;
 pub use events::*;
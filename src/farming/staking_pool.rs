@@ -2,6 +2,12 @@
 //! 
 //! Users stake LP tokens (e.g., sCSPR/ECTO LP) and earn ECTO rewards
 //! based on their share of the pool and time staked.
+//!
+//! Lock-and-boost: stakers may lock their stake for 1-12 months. Rewards are
+//! shared by boosted balance, where the boost grows linearly with the lock
+//! length up to 2.5x (vote-escrow style). Leaving a lock early costs a penalty
+//! in LP tokens that decays with the time served; it is shared among the
+//! remaining lockers by boosted balance.
 
 use odra::prelude::*;
 use odra::casper_types::U256;
@@ -10,6 +16,24 @@ use super::errors::FarmingError;
 use super::events::*;
 use crate::token::Cep18TokenContractRef;
 
+/// Fixed-point precision for per-token accumulators (1e18)
+const PRECISION: u128 = 1_000_000_000_000_000_000;
+
+/// Length of one lock month (30 days)
+pub const LOCK_MONTH: u64 = 30 * 24 * 60 * 60;
+
+/// Longest lock in months
+pub const MAX_LOCK_MONTHS: u8 = 12;
+
+/// Multiplier of an unlocked stake (1x) in basis points
+pub const BOOST_BASE_BPS: u32 = 10_000;
+
+/// Multiplier of a 12-month lock (2.5x) in basis points
+pub const MAX_BOOST_BPS: u32 = 25_000;
+
+/// Early-exit penalty at the start of a lock, decaying linearly to zero
+pub const EARLY_EXIT_PENALTY_BPS: u32 = 5_000;
+
 /// Pool information
 #[odra::odra_type]
 pub struct PoolInfo {
@@ -23,10 +47,16 @@ pub struct PoolInfo {
     pub total_staked: U256,
     /// Last update timestamp
     pub last_update: u64,
-    /// Accumulated reward per token
+    /// Accumulated reward per boosted token
     pub reward_per_token_stored: U256,
     /// Is pool active
     pub is_active: bool,
+    /// Total boosted balance rewards are shared by
+    pub total_boosted: U256,
+    /// Boosted balance of stakes under lock
+    pub total_locked_boost: U256,
+    /// Accumulated early-exit penalty (LP) per locked boosted token
+    pub penalty_per_boost: U256,
 }
 
 /// User stake information
@@ -40,6 +70,37 @@ pub struct UserStake {
     pub pending_rewards: U256,
     /// Last update timestamp
     pub last_update: u64,
+    /// Reward multiplier in basis points (10000 = 1x)
+    pub boost_bps: u32,
+    /// End of the lock (0 if not locked)
+    pub lock_end: u64,
+    /// Length of the current lock in seconds
+    pub lock_duration: u64,
+    /// Penalty accumulator at the last update
+    pub penalty_debt: U256,
+    /// Early-exit penalties (LP) earned and not yet claimed
+    pub penalty_rewards: U256,
+}
+
+impl UserStake {
+    fn new(timestamp: u64) -> Self {
+        UserStake {
+            amount: U256::zero(),
+            reward_debt: U256::zero(),
+            pending_rewards: U256::zero(),
+            last_update: timestamp,
+            boost_bps: BOOST_BASE_BPS,
+            lock_end: 0,
+            lock_duration: 0,
+            penalty_debt: U256::zero(),
+            penalty_rewards: U256::zero(),
+        }
+    }
+
+    /// Balance rewards are shared by
+    pub fn boosted_amount(&self) -> U256 {
+        self.amount * U256::from(self.boost_bps) / U256::from(BOOST_BASE_BPS)
+    }
 }
 
 /// Staking Pool contract
@@ -57,6 +118,10 @@ pub struct StakingPool {
     admin: Var<Address>,
     /// Paused state
     paused: Var<bool>,
+    /// Emergency mode: reward accrual stops, emergency withdrawals stay open
+    emergency: Var<bool>,
+    /// Periphery allowed to claim on behalf of the user who called it
+    claim_router: Var<Option<Address>>,
 }
 
 #[odra::module]
@@ -96,6 +161,9 @@ impl StakingPool {
             last_update: self.env().get_block_time(),
             reward_per_token_stored: U256::zero(),
             is_active: true,
+            total_boosted: U256::zero(),
+            total_locked_boost: U256::zero(),
+            penalty_per_boost: U256::zero(),
         };
         
         self.pools.set(&pool_id, pool);
@@ -152,6 +220,9 @@ impl StakingPool {
     /// Stake LP tokens
     pub fn stake(&mut self, pool_id: u32, amount: U256) {
         self.ensure_not_paused();
+        if self.emergency.get_or_default() {
+            self.env().revert(FarmingError::EmergencyActive);
+        }
         
         if amount == U256::zero() {
             self.env().revert(FarmingError::ZeroAmount);
@@ -177,21 +248,17 @@ impl StakingPool {
         let mut lp_token = Cep18TokenContractRef::new(self.env(), pool.lp_token);
         lp_token.transfer_from(caller, Address::from(self.env().self_address()), amount);
         
-        // Update user stake
+        // Update user stake; stake added during a lock joins the lock
         let mut user_stake = self.user_stakes.get(&(caller, pool_id))
-            .unwrap_or(UserStake {
-                amount: U256::zero(),
-                reward_debt: U256::zero(),
-                pending_rewards: U256::zero(),
-                last_update: self.env().get_block_time(),
-            });
-        
-        user_stake.amount = user_stake.amount + amount;
+            .unwrap_or(UserStake::new(self.env().get_block_time()));
+        let mut pool = self.pools.get(&pool_id).unwrap();
+        
+        let new_amount = user_stake.amount + amount;
+        self.set_position(&mut pool, &mut user_stake, new_amount, user_stake.boost_bps, user_stake.lock_end);
         user_stake.last_update = self.env().get_block_time();
         self.user_stakes.set(&(caller, pool_id), user_stake);
         
         // Update pool total
-        let mut pool = self.pools.get(&pool_id).unwrap();
         pool.total_staked = pool.total_staked + amount;
         self.pools.set(&pool_id, pool);
         
@@ -227,15 +294,17 @@ impl StakingPool {
         if user_stake.amount < amount {
             self.env().revert(FarmingError::InsufficientBalance);
         }
+        if user_stake.lock_end > self.env().get_block_time() {
+            self.env().revert(FarmingError::LockActive);
+        }
         
-        // Update user stake
-        user_stake.amount = user_stake.amount - amount;
-        user_stake.last_update = self.env().get_block_time();
-        self.user_stakes.set(&(caller, pool_id), user_stake);
-        
-        // Get pool info before updating
+        // Update user stake; an expired lock falls back to 1x
         let mut pool = self.pools.get(&pool_id).unwrap();
         let lp_token_address = pool.lp_token;
+        let new_amount = user_stake.amount - amount;
+        self.set_position(&mut pool, &mut user_stake, new_amount, BOOST_BASE_BPS, 0);
+        user_stake.last_update = self.env().get_block_time();
+        self.user_stakes.set(&(caller, pool_id), user_stake);
         
         // Update pool total
         pool.total_staked = pool.total_staked - amount;
@@ -256,18 +325,236 @@ impl StakingPool {
     
     /// Claim pending rewards
     pub fn claim_rewards(&mut self, pool_id: u32) {
+        let caller = self.env().caller();
+        self.claim_rewards_of(caller, pool_id);
+    }
+    
+    /// Claim pending rewards for `user`, paid to `user`
+    /// Only callable by the claim router, which acts for its own caller
+    pub fn claim_rewards_for(&mut self, user: Address, pool_id: u32) -> U256 {
+        self.only_claim_router();
+        self.claim_rewards_of(user, pool_id)
+    }
+    
+    /// Withdraw the full stake immediately, forfeiting pending rewards
+    /// Works while paused or in emergency mode so stakers are never trapped
+    pub fn emergency_withdraw(&mut self, pool_id: u32) {
+        let caller = self.env().caller();
+        
+        let mut user_stake = self.user_stakes.get(&(caller, pool_id))
+            .unwrap_or_revert_with(&self.env(), FarmingError::InsufficientBalance);
+        let amount = user_stake.amount;
+        if amount == U256::zero() {
+            self.env().revert(FarmingError::InsufficientBalance);
+        }
+        // Locks only give way when the protocol itself is in trouble
+        let emergency = self.emergency.get_or_default() || self.paused.get_or_default();
+        if user_stake.lock_end > self.env().get_block_time() && !emergency {
+            self.env().revert(FarmingError::LockActive);
+        }
+        
+        // Settle the pool for the remaining stakers unless accounting is frozen
+        if !self.emergency.get_or_default() {
+            self.update_pool_rewards(pool_id);
+        }
+        
+        let mut pool = self.pools.get(&pool_id)
+            .unwrap_or_revert_with(&self.env(), FarmingError::PoolNotFound);
+        let lp_token_address = pool.lp_token;
+        
+        let forfeited_rewards = user_stake.pending_rewards;
+        self.set_position(&mut pool, &mut user_stake, U256::zero(), BOOST_BASE_BPS, 0);
+        user_stake.pending_rewards = U256::zero();
+        user_stake.reward_debt = pool.reward_per_token_stored;
+        user_stake.last_update = self.env().get_block_time();
+        self.user_stakes.set(&(caller, pool_id), user_stake);
+        
+        pool.total_staked = pool.total_staked - amount;
+        self.pools.set(&pool_id, pool);
+        
+        let mut lp_token = Cep18TokenContractRef::new(self.env(), lp_token_address);
+        lp_token.transfer(caller, amount);
+        
+        let timestamp = self.env().get_block_time();
+        self.env().emit_event(EmergencyWithdrawn {
+            user: caller,
+            pool_id,
+            amount,
+            forfeited_rewards,
+            timestamp,
+        });
+    }
+    
+    // ========================================
+    // Lock and Boost
+    // ========================================
+    
+    /// Lock the caller's whole stake in a pool for `months` (1-12)
+    /// A lock can be extended but never shortened; the boost follows the new length
+    pub fn lock(&mut self, pool_id: u32, months: u8) {
+        self.ensure_not_paused();
+        if months == 0 || months > MAX_LOCK_MONTHS {
+            self.env().revert(FarmingError::InvalidLockDuration);
+        }
+        
+        let caller = self.env().caller();
+        self.update_pool_rewards(pool_id);
+        self.update_user_rewards(caller, pool_id);
+        
+        let mut user_stake = self.user_stakes.get(&(caller, pool_id))
+            .unwrap_or_revert_with(&self.env(), FarmingError::InsufficientBalance);
+        if user_stake.amount == U256::zero() {
+            self.env().revert(FarmingError::ZeroAmount);
+        }
+        
+        let now = self.env().get_block_time();
+        let duration = months as u64 * LOCK_MONTH;
+        let lock_end = now + duration;
+        if lock_end < user_stake.lock_end {
+            self.env().revert(FarmingError::InvalidLockDuration);
+        }
+        
+        let boost_bps = Self::boost_for_months(months);
+        let mut pool = self.pools.get(&pool_id).unwrap();
+        let amount = user_stake.amount;
+        self.set_position(&mut pool, &mut user_stake, amount, boost_bps, lock_end);
+        user_stake.lock_duration = duration;
+        user_stake.last_update = now;
+        self.user_stakes.set(&(caller, pool_id), user_stake);
+        self.pools.set(&pool_id, pool);
+        
+        self.env().emit_event(StakeLocked {
+            user: caller,
+            pool_id,
+            amount,
+            lock_end,
+            boost_bps,
+        });
+    }
+    
+    /// Leave a lock before it ends, paying the early-exit penalty in LP
+    /// The stake stays in the pool unlocked at 1x; the penalty goes to the
+    /// remaining lockers (or the admin if there are none)
+    pub fn exit_lock_early(&mut self, pool_id: u32) {
         self.ensure_not_paused();
         
         let caller = self.env().caller();
+        self.update_pool_rewards(pool_id);
+        self.update_user_rewards(caller, pool_id);
+        
+        let mut user_stake = self.user_stakes.get(&(caller, pool_id))
+            .unwrap_or_revert_with(&self.env(), FarmingError::InsufficientBalance);
+        let now = self.env().get_block_time();
+        if user_stake.lock_end <= now {
+            self.env().revert(FarmingError::NotLocked);
+        }
+        
+        // Penalty decays linearly over the lock
+        let remaining = user_stake.lock_end - now;
+        let penalty = user_stake.amount * U256::from(EARLY_EXIT_PENALTY_BPS) * U256::from(remaining)
+            / U256::from(BOOST_BASE_BPS)
+            / U256::from(user_stake.lock_duration);
+        
+        let mut pool = self.pools.get(&pool_id).unwrap();
+        let new_amount = user_stake.amount - penalty;
+        self.set_position(&mut pool, &mut user_stake, new_amount, BOOST_BASE_BPS, 0);
+        user_stake.lock_duration = 0;
+        user_stake.last_update = now;
+        self.user_stakes.set(&(caller, pool_id), user_stake);
+        
+        pool.total_staked = pool.total_staked - penalty;
+        let lp_token_address = pool.lp_token;
+        let shared = !pool.total_locked_boost.is_zero() && !penalty.is_zero();
+        if shared {
+            pool.penalty_per_boost = pool.penalty_per_boost
+                + penalty * U256::from(PRECISION) / pool.total_locked_boost;
+        }
+        self.pools.set(&pool_id, pool);
+        
+        if !shared && !penalty.is_zero() {
+            let admin = self.admin.get_or_revert_with(FarmingError::Unauthorized);
+            let mut lp_token = Cep18TokenContractRef::new(self.env(), lp_token_address);
+            lp_token.transfer(admin, penalty);
+        }
+        
+        self.env().emit_event(LockExitedEarly {
+            user: caller,
+            pool_id,
+            penalty,
+            redistributed: shared,
+        });
+    }
+    
+    /// Claim early-exit penalties earned as a locker
+    pub fn claim_penalty_share(&mut self, pool_id: u32) {
+        let caller = self.env().caller();
+        self.update_user_rewards(caller, pool_id);
+        
+        let mut user_stake = self.user_stakes.get(&(caller, pool_id))
+            .unwrap_or_revert_with(&self.env(), FarmingError::NoRewardsToClaim);
+        let amount = user_stake.penalty_rewards;
+        if amount == U256::zero() {
+            self.env().revert(FarmingError::NoRewardsToClaim);
+        }
+        user_stake.penalty_rewards = U256::zero();
+        self.user_stakes.set(&(caller, pool_id), user_stake);
+        
+        let pool = self.pools.get(&pool_id)
+            .unwrap_or_revert_with(&self.env(), FarmingError::PoolNotFound);
+        let mut lp_token = Cep18TokenContractRef::new(self.env(), pool.lp_token);
+        lp_token.transfer(caller, amount);
+        
+        self.env().emit_event(PenaltyShareClaimed {
+            user: caller,
+            pool_id,
+            amount,
+        });
+    }
+    
+    /// Drop the boost of an expired lock back to 1x
+    /// Callable by anyone so expired boosts cannot keep diluting other stakers
+    pub fn poke(&mut self, user: Address, pool_id: u32) {
+        let user_stake = self.user_stakes.get(&(user, pool_id))
+            .unwrap_or_revert_with(&self.env(), FarmingError::InsufficientBalance);
+        let now = self.env().get_block_time();
+        if user_stake.lock_end == 0 || user_stake.lock_end > now {
+            self.env().revert(FarmingError::NotLocked);
+        }
+        
+        self.update_pool_rewards(pool_id);
+        self.update_user_rewards(user, pool_id);
+        
+        let mut user_stake = self.user_stakes.get(&(user, pool_id)).unwrap();
+        let mut pool = self.pools.get(&pool_id).unwrap();
+        let amount = user_stake.amount;
+        self.set_position(&mut pool, &mut user_stake, amount, BOOST_BASE_BPS, 0);
+        user_stake.lock_duration = 0;
+        self.user_stakes.set(&(user, pool_id), user_stake);
+        self.pools.set(&pool_id, pool);
+    }
+    
+    /// Reward multiplier of a lock in basis points (1x plus 1.5x pro rata to 12 months)
+    pub fn boost_for_months(months: u8) -> u32 {
+        let months = months.min(MAX_LOCK_MONTHS) as u32;
+        BOOST_BASE_BPS + (MAX_BOOST_BPS - BOOST_BASE_BPS) * months / MAX_LOCK_MONTHS as u32
+    }
+    
+    // ========================================
+    // Internal Functions
+    // ========================================
+    
+    /// Pay `user` their pending rewards in `pool_id`
+    fn claim_rewards_of(&mut self, user: Address, pool_id: u32) -> U256 {
+        self.ensure_not_paused();
         
         // Update pool rewards
         self.update_pool_rewards(pool_id);
         
         // Update user rewards
-        self.update_user_rewards(caller, pool_id);
+        self.update_user_rewards(user, pool_id);
         
         // Get user stake
-        let mut user_stake = self.user_stakes.get(&(caller, pool_id))
+        let mut user_stake = self.user_stakes.get(&(user, pool_id))
             .unwrap_or_revert_with(&self.env(), FarmingError::NoRewardsToClaim);
         
         let rewards = user_stake.pending_rewards;
@@ -278,30 +565,58 @@ impl StakingPool {
         
         // Reset pending rewards
         user_stake.pending_rewards = U256::zero();
-        self.user_stakes.set(&(caller, pool_id), user_stake);
+        self.user_stakes.set(&(user, pool_id), user_stake);
         
         // Transfer ECTO rewards to user
         let reward_token_address = self.reward_token.get_or_revert_with(FarmingError::Unauthorized);
         let mut reward_token = Cep18TokenContractRef::new(self.env(), reward_token_address);
-        reward_token.transfer(caller, rewards);
+        reward_token.transfer(user, rewards);
         
         let timestamp = self.env().get_block_time();
         self.env().emit_event(RewardsClaimed {
-            user: caller,
+            user,
             pool_id,
             reward_amount: rewards,
             timestamp,
         });
+        rewards
     }
     
-    // ========================================
-    // Internal Functions
-    // ========================================
+    
+    /// Move a settled position to a new amount, boost and lock end, keeping
+    /// the pool's boosted totals in step. Does not touch `total_staked`
+    fn set_position(
+        &self,
+        pool: &mut PoolInfo,
+        user_stake: &mut UserStake,
+        amount: U256,
+        boost_bps: u32,
+        lock_end: u64,
+    ) {
+        let old_boosted = user_stake.boosted_amount();
+        pool.total_boosted = pool.total_boosted - old_boosted;
+        if user_stake.lock_end != 0 {
+            pool.total_locked_boost = pool.total_locked_boost - old_boosted;
+        }
+        
+        user_stake.amount = amount;
+        user_stake.boost_bps = boost_bps;
+        user_stake.lock_end = lock_end;
+        
+        let new_boosted = user_stake.boosted_amount();
+        pool.total_boosted = pool.total_boosted + new_boosted;
+        if lock_end != 0 {
+            pool.total_locked_boost = pool.total_locked_boost + new_boosted;
+        }
+        // Penalties are only earned from here on
+        user_stake.penalty_debt = pool.penalty_per_boost;
+    }
     
     fn update_pool_rewards(&mut self, pool_id: u32) {
         let mut pool = self.pools.get(&pool_id).unwrap();
         
-        if pool.total_staked == U256::zero() {
+        // No accrual while in emergency mode
+        if pool.total_boosted == U256::zero() || self.emergency.get_or_default() {
             pool.last_update = self.env().get_block_time();
             self.pools.set(&pool_id, pool);
             return;
@@ -314,7 +629,7 @@ impl StakingPool {
         let rewards = pool.reward_rate * U256::from(time_elapsed);
         
         // Update reward per token
-        let reward_per_token_increase = (rewards * U256::from(1_000_000_000_000_000_000u128)) / pool.total_staked;
+        let reward_per_token_increase = (rewards * U256::from(PRECISION)) / pool.total_boosted;
         pool.reward_per_token_stored = pool.reward_per_token_stored + reward_per_token_increase;
         pool.last_update = current_time;
         
@@ -324,21 +639,25 @@ impl StakingPool {
     fn update_user_rewards(&mut self, user: Address, pool_id: u32) {
         let pool = self.pools.get(&pool_id).unwrap();
         let mut user_stake = self.user_stakes.get(&(user, pool_id))
-            .unwrap_or(UserStake {
-                amount: U256::zero(),
-                reward_debt: U256::zero(),
-                pending_rewards: U256::zero(),
-                last_update: self.env().get_block_time(),
-            });
+            .unwrap_or(UserStake::new(self.env().get_block_time()));
         
         if user_stake.amount > U256::zero() {
-            // Calculate pending rewards
+            // Calculate pending rewards on the boosted balance
+            let boosted = user_stake.boosted_amount();
             let reward_per_token_delta = pool.reward_per_token_stored - user_stake.reward_debt;
-            let new_rewards = (user_stake.amount * reward_per_token_delta) / U256::from(1_000_000_000_000_000_000u128);
+            let new_rewards = (boosted * reward_per_token_delta) / U256::from(PRECISION);
             user_stake.pending_rewards = user_stake.pending_rewards + new_rewards;
+            
+            // Lockers also earn early-exit penalties
+            if user_stake.lock_end != 0 {
+                let penalty_delta = pool.penalty_per_boost - user_stake.penalty_debt;
+                let new_penalties = (boosted * penalty_delta) / U256::from(PRECISION);
+                user_stake.penalty_rewards = user_stake.penalty_rewards + new_penalties;
+            }
         }
         
         user_stake.reward_debt = pool.reward_per_token_stored;
+        user_stake.penalty_debt = pool.penalty_per_boost;
         self.user_stakes.set(&(user, pool_id), user_stake);
     }
     
@@ -354,6 +673,10 @@ impl StakingPool {
         self.user_stakes.get(&(user, pool_id))
     }
     
+    pub fn is_emergency(&self) -> bool {
+        self.emergency.get_or_default()
+    }
+    
     pub fn get_pending_rewards(&self, user: Address, pool_id: u32) -> U256 {
         let user_stake = self.user_stakes.get(&(user, pool_id));
         if let Some(stake) = user_stake {
@@ -377,6 +700,47 @@ impl StakingPool {
         self.paused.set(false);
     }
     
+    /// Enable or disable emergency mode
+    /// Entering it settles every pool first, so rewards earned up to the
+    /// freeze are kept; leaving it restarts accrual from now for every pool
+    pub fn set_emergency(&mut self, enabled: bool) {
+        self.only_admin();
+        
+        if enabled && !self.emergency.get_or_default() {
+            for pool_id in 0..self.next_pool_id.get_or_default() {
+                if self.pools.get(&pool_id).is_some() {
+                    self.update_pool_rewards(pool_id);
+                }
+            }
+        }
+        if !enabled && self.emergency.get_or_default() {
+            let now = self.env().get_block_time();
+            for pool_id in 0..self.next_pool_id.get_or_default() {
+                if let Some(mut pool) = self.pools.get(&pool_id) {
+                    pool.last_update = now;
+                    self.pools.set(&pool_id, pool);
+                }
+            }
+        }
+        self.emergency.set(enabled);
+        
+        let admin = self.admin.get_or_revert_with(FarmingError::Unauthorized);
+        self.env().emit_event(EmergencyModeUpdated {
+            enabled,
+            updated_by: admin,
+        });
+    }
+    
+    /// Set (or clear) the periphery allowed to claim on behalf of users
+    pub fn set_claim_router(&mut self, router: Option<Address>) {
+        self.only_admin();
+        self.claim_router.set(router);
+    }
+    
+    pub fn get_claim_router(&self) -> Option<Address> {
+        self.claim_router.get_or_default()
+    }
+    
     fn only_admin(&self) {
         let caller = self.env().caller();
         let admin = self.admin.get_or_revert_with(FarmingError::Unauthorized);
@@ -385,6 +749,12 @@ impl StakingPool {
         }
     }
     
+    fn only_claim_router(&self) {
+        if self.get_claim_router() != Some(self.env().caller()) {
+            self.env().revert(FarmingError::Unauthorized);
+        }
+    }
+    
     fn ensure_not_paused(&self) {
         if self.paused.get_or_default() {
             self.env().revert(FarmingError::ContractPaused);
@@ -0,0 +1,200 @@
+//! Denylist - Shared sanctions list with opt-in enforcement by tokens
+//!
+//! Compliance officers list and unlist addresses. Token contracts that hold
+//! the denylist address and have enforcement switched on reject any transfer,
+//! mint or burn touching a listed address. Listing alone changes nothing for
+//! tokens that have not opted in.
+
+use odra::prelude::*;
+use super::errors::GovernanceError;
+use super::events::{ComplianceOfficerUpdated, DenylistUpdated};
+
+/// Denylist contract
+#[odra::module]
+pub struct Denylist {
+    /// Contract admin (governance)
+    admin: Var<Address>,
+    /// Addresses holding the compliance role
+    officers: Mapping<Address, bool>,
+    /// Listed addresses
+    listed: Mapping<Address, bool>,
+    /// Number of listed addresses
+    listed_count: Var<u32>,
+}
+
+#[odra::module]
+impl Denylist {
+    /// Initialize with the caller as admin
+    pub fn init(&mut self) {
+        let caller = self.env().caller();
+        self.admin.set(caller);
+    }
+
+    // ========================================
+    // Views
+    // ========================================
+
+    /// Check if an address is listed
+    pub fn is_listed(&self, account: Address) -> bool {
+        self.listed.get(&account).unwrap_or(false)
+    }
+
+    /// Number of listed addresses
+    pub fn listed_count(&self) -> u32 {
+        self.listed_count.get_or_default()
+    }
+
+    /// Check if an address holds the compliance role
+    pub fn is_compliance_officer(&self, account: Address) -> bool {
+        self.officers.get(&account).unwrap_or(false)
+    }
+
+    /// Get admin address
+    pub fn get_admin(&self) -> Address {
+        self.admin.get_or_revert_with(GovernanceError::Unauthorized)
+    }
+
+    // ========================================
+    // Compliance Functions
+    // ========================================
+
+    /// List or unlist an address
+    /// Callable by a compliance officer or the admin
+    pub fn set_listed(&mut self, account: Address, listed: bool) {
+        let caller = self.env().caller();
+        if !self.is_compliance_officer(caller) && caller != self.get_admin() {
+            self.env().revert(GovernanceError::Unauthorized);
+        }
+
+        let was_listed = self.is_listed(account);
+        if was_listed == listed {
+            return;
+        }
+        self.listed.set(&account, listed);
+        let count = self.listed_count();
+        self.listed_count.set(if listed { count + 1 } else { count - 1 });
+
+        self.env().emit_event(DenylistUpdated {
+            account,
+            listed,
+            updated_by: caller,
+        });
+    }
+
+    // ========================================
+    // Admin Functions
+    // ========================================
+
+    /// Grant or revoke the compliance role
+    pub fn set_compliance_officer(&mut self, officer: Address, enabled: bool) {
+        self.only_admin();
+        self.officers.set(&officer, enabled);
+
+        self.env().emit_event(ComplianceOfficerUpdated {
+            officer,
+            enabled,
+            updated_by: self.env().caller(),
+        });
+    }
+
+    /// Transfer admin rights
+    pub fn transfer_admin(&mut self, new_admin: Address) {
+        self.only_admin();
+        self.admin.set(new_admin);
+    }
+
+    // ========================================
+    // Internal Functions
+    // ========================================
+
+    fn only_admin(&self) {
+        if self.env().caller() != self.get_admin() {
+            self.env().revert(GovernanceError::Unauthorized);
+        }
+    }
+}
+
+/// External interface used by enforcing token contracts
+#[odra::external_contract]
+pub trait DenylistContract {
+    fn is_listed(&self, account: Address) -> bool;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use odra::host::{Deployer, HostEnv, NoArgs};
+
+    fn setup() -> (HostEnv, DenylistHostRef, Address) {
+        let env = odra_test::env();
+        let mut denylist = Denylist::deploy(&env, NoArgs);
+        let officer = env.get_account(1);
+        denylist.set_compliance_officer(officer, true);
+        (env, denylist, officer)
+    }
+
+    #[test]
+    fn test_officer_lists_and_unlists() {
+        let (env, mut denylist, officer) = setup();
+        let account = env.get_account(5);
+
+        env.set_caller(officer);
+        denylist.set_listed(account, true);
+        assert!(denylist.is_listed(account));
+        assert_eq!(denylist.listed_count(), 1);
+
+        // Listing twice does not double count
+        denylist.set_listed(account, true);
+        assert_eq!(denylist.listed_count(), 1);
+
+        denylist.set_listed(account, false);
+        assert!(!denylist.is_listed(account));
+        assert_eq!(denylist.listed_count(), 0);
+    }
+
+    #[test]
+    fn test_only_officers_can_list() {
+        let (env, mut denylist, _officer) = setup();
+        env.set_caller(env.get_account(2));
+        assert_eq!(
+            denylist.try_set_listed(env.get_account(5), true),
+            Err(GovernanceError::Unauthorized.into())
+        );
+    }
+
+    #[test]
+    fn test_token_enforcement_is_opt_in() {
+        use crate::errors::TokenError;
+        use crate::lst::scspr_token::{ScsprToken, ScsprTokenInitArgs};
+        use odra::casper_types::U256;
+
+        let (env, mut denylist, officer) = setup();
+        let admin = env.get_account(0);
+        let holder = env.get_account(2);
+        let sanctioned = env.get_account(5);
+
+        let mut token = ScsprToken::deploy(&env, ScsprTokenInitArgs { staking_manager: admin });
+        token.mint(holder, U256::from(1_000));
+
+        env.set_caller(officer);
+        denylist.set_listed(sanctioned, true);
+
+        // Not enforced yet: listing alone changes nothing
+        env.set_caller(holder);
+        token.transfer(sanctioned, U256::from(100));
+
+        env.set_caller(admin);
+        token.set_denylist(denylist.address().clone(), true);
+        env.set_caller(holder);
+        assert_eq!(
+            token.try_transfer(sanctioned, U256::from(100)),
+            Err(TokenError::AddressDenied.into())
+        );
+        env.set_caller(sanctioned);
+        assert_eq!(
+            token.try_transfer(holder, U256::from(100)),
+            Err(TokenError::AddressDenied.into())
+        );
+        assert_eq!(token.balance_of(sanctioned), U256::from(100));
+    }
+}
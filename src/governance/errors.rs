@@ -0,0 +1,33 @@
+//! Error definitions for the governance contracts
+use odra::prelude::*;
+
+/// Custom errors for the governance contracts
+#[odra::odra_error]
+pub enum GovernanceError {
+    /// Caller is not allowed to perform the operation
+    Unauthorized = 300,
+
+    /// Parameter key is empty
+    InvalidKey = 301,
+
+    /// Parameter is not set in the registry
+    ParameterNotFound = 302,
+
+    /// Contract is not registered with the guardian
+    TargetNotRegistered = 303,
+
+    /// Contract is not paused
+    NotPaused = 304,
+
+    /// Schedule delay exceeds the maximum
+    InvalidDelay = 305,
+
+    /// No change is scheduled for the parameter
+    NotScheduled = 306,
+
+    /// Scheduled change is not due yet
+    ScheduleNotReady = 307,
+
+    /// List has reached its maximum size
+    ListFull = 308,
+}
@@ -0,0 +1,160 @@
+//! Event definitions for the governance contracts
+use odra::prelude::*;
+use odra::casper_types::U256;
+
+/// Event emitted when a registry parameter is written
+#[odra::event]
+pub struct ParameterUpdated {
+    /// Parameter key
+    pub key: String,
+    /// Registry version after the update
+    pub version: u64,
+    /// Updated by
+    pub updated_by: Address,
+}
+
+/// Event emitted when a parameter manager role is granted or revoked
+#[odra::event]
+pub struct ParameterManagerUpdated {
+    /// Manager address
+    pub manager: Address,
+    /// Whether the role is now held
+    pub enabled: bool,
+    /// Updated by (admin address)
+    pub updated_by: Address,
+}
+
+/// Event emitted when a consumer contract pulls parameters from the registry
+#[odra::event]
+pub struct ParametersSynced {
+    /// Registry address
+    pub registry: Address,
+    /// Registry version that was applied
+    pub version: u64,
+}
+
+/// Event emitted when a guardian pauses a contract
+#[odra::event]
+pub struct GuardianPaused {
+    /// Paused contract
+    pub target: Address,
+    /// Paused by (guardian or admin address)
+    pub paused_by: Address,
+    /// When the pause lapses unless ratified
+    pub expires_at: u64,
+}
+
+/// Event emitted when the admin makes a guardian pause indefinite
+#[odra::event]
+pub struct PauseRatified {
+    /// Paused contract
+    pub target: Address,
+    /// Ratified by (admin address)
+    pub ratified_by: Address,
+}
+
+/// Event emitted when a pause is lifted
+#[odra::event]
+pub struct GuardianUnpaused {
+    /// Unpaused contract
+    pub target: Address,
+    /// Unpaused by (admin address)
+    pub unpaused_by: Address,
+}
+
+/// Event emitted when the guardian role is granted or revoked
+#[odra::event]
+pub struct GuardianUpdated {
+    /// Guardian address
+    pub guardian: Address,
+    /// Whether the role is now held
+    pub enabled: bool,
+    /// Updated by (admin address)
+    pub updated_by: Address,
+}
+
+/// Event emitted when an address is listed or unlisted on the denylist
+#[odra::event]
+pub struct DenylistUpdated {
+    /// Affected address
+    pub account: Address,
+    /// Whether the address is now listed
+    pub listed: bool,
+    /// Updated by (compliance officer or admin address)
+    pub updated_by: Address,
+}
+
+/// Event emitted when the compliance role is granted or revoked
+#[odra::event]
+pub struct ComplianceOfficerUpdated {
+    /// Officer address
+    pub officer: Address,
+    /// Whether the role is now held
+    pub enabled: bool,
+    /// Updated by (admin address)
+    pub updated_by: Address,
+}
+
+/// Event emitted when a token changes its denylist configuration
+#[odra::event]
+pub struct DenylistEnforcementUpdated {
+    /// Denylist contract the token consults
+    pub denylist: Address,
+    /// Whether transfers touching listed addresses are rejected
+    pub enforced: bool,
+}
+
+/// Event emitted when governance flags or clears a compromised token
+#[odra::event]
+pub struct TokenDelistingUpdated {
+    /// Token address
+    pub token: Address,
+    /// Whether the token is now flagged
+    pub compromised: bool,
+    /// Updated by (admin address)
+    pub updated_by: Address,
+}
+
+/// Event emitted as soon as a delayed parameter change is queued
+#[odra::event]
+pub struct ParameterScheduled {
+    /// Parameter name
+    pub parameter: String,
+    /// New value
+    pub value: U256,
+    /// Block time from which the change can be applied
+    pub effective_at: u64,
+    /// Scheduled by
+    pub scheduled_by: Address,
+}
+
+/// Event emitted when a scheduled parameter change takes effect
+#[odra::event]
+pub struct ScheduledParameterApplied {
+    /// Parameter name
+    pub parameter: String,
+    /// Value now in effect
+    pub value: U256,
+}
+
+/// Event emitted when a scheduled parameter change is dropped
+#[odra::event]
+pub struct ScheduledParameterCancelled {
+    /// Parameter name
+    pub parameter: String,
+    /// Cancelled by
+    pub cancelled_by: Address,
+}
+
+/// Event emitted when a contract closes its metrics epoch and starts a new one
+#[odra::event]
+pub struct MetricsRotated {
+    /// Contract whose metrics rotated
+    pub contract: Address,
+    /// Epoch that was closed
+    pub closed_epoch: u64,
+    /// Epoch now being recorded
+    pub epoch: u64,
+    /// Block time the new epoch started
+    pub started_at: u64,
+}
@@ -0,0 +1,239 @@
+//! Guardian - Fast incident-response pauses that expire unless ratified
+//!
+//! A guardian can pause any registered contract immediately. The pause lapses
+//! on its own after `PAUSE_DURATION` unless the admin ratifies it, so a
+//! compromised or overzealous guardian cannot freeze the protocol for long.
+//! Consumer contracts hold the guardian address and check `is_paused(self)`
+//! in their own pause guard; nothing needs to run for a pause to expire.
+
+use odra::prelude::*;
+use super::errors::GovernanceError;
+use super::events::{GuardianPaused, GuardianUnpaused, GuardianUpdated, PauseRatified};
+
+/// How long an unratified guardian pause lasts (72 hours)
+pub const PAUSE_DURATION: u64 = 72 * 60 * 60;
+
+/// Pause state of a registered contract
+#[odra::odra_type]
+pub struct PauseState {
+    /// Whether a pause was issued and not lifted
+    pub paused: bool,
+    /// When the pause lapses unless ratified
+    pub expires_at: u64,
+    /// Whether the admin made the pause indefinite
+    pub ratified: bool,
+}
+
+/// Guardian contract
+#[odra::module]
+pub struct Guardian {
+    /// Contract admin (governance)
+    admin: Var<Address>,
+    /// Addresses holding the guardian role
+    guardians: Mapping<Address, bool>,
+    /// Contracts the guardian may pause
+    registered: Mapping<Address, bool>,
+    /// Pause state per registered contract
+    pauses: Mapping<Address, PauseState>,
+}
+
+#[odra::module]
+impl Guardian {
+    /// Initialize with the caller as admin
+    pub fn init(&mut self) {
+        let caller = self.env().caller();
+        self.admin.set(caller);
+    }
+
+    // ========================================
+    // Views
+    // ========================================
+
+    /// Check if a contract is currently paused
+    pub fn is_paused(&self, target: Address) -> bool {
+        match self.pauses.get(&target) {
+            Some(state) => {
+                state.paused && (state.ratified || self.env().get_block_time() < state.expires_at)
+            }
+            None => false,
+        }
+    }
+
+    /// Get the raw pause state of a contract
+    pub fn get_pause_state(&self, target: Address) -> Option<PauseState> {
+        self.pauses.get(&target)
+    }
+
+    /// Check if an address holds the guardian role
+    pub fn is_guardian(&self, account: Address) -> bool {
+        self.guardians.get(&account).unwrap_or(false)
+    }
+
+    /// Check if a contract is registered
+    pub fn is_registered(&self, target: Address) -> bool {
+        self.registered.get(&target).unwrap_or(false)
+    }
+
+    /// Get admin address
+    pub fn get_admin(&self) -> Address {
+        self.admin.get_or_revert_with(GovernanceError::Unauthorized)
+    }
+
+    // ========================================
+    // Guardian Functions
+    // ========================================
+
+    /// Pause a registered contract for `PAUSE_DURATION`
+    /// Callable by a guardian or the admin
+    pub fn pause(&mut self, target: Address) {
+        let caller = self.env().caller();
+        if !self.is_guardian(caller) && caller != self.get_admin() {
+            self.env().revert(GovernanceError::Unauthorized);
+        }
+        if !self.is_registered(target) {
+            self.env().revert(GovernanceError::TargetNotRegistered);
+        }
+
+        let expires_at = self.env().get_block_time() + PAUSE_DURATION;
+        self.pauses.set(&target, PauseState {
+            paused: true,
+            expires_at,
+            ratified: false,
+        });
+
+        self.env().emit_event(GuardianPaused {
+            target,
+            paused_by: caller,
+            expires_at,
+        });
+    }
+
+    // ========================================
+    // Admin Functions
+    // ========================================
+
+    /// Make an active pause indefinite
+    pub fn ratify(&mut self, target: Address) {
+        self.only_admin();
+        if !self.is_paused(target) {
+            self.env().revert(GovernanceError::NotPaused);
+        }
+
+        let mut state = self.pauses.get(&target)
+            .unwrap_or_revert_with(&self.env(), GovernanceError::NotPaused);
+        state.ratified = true;
+        self.pauses.set(&target, state);
+
+        self.env().emit_event(PauseRatified {
+            target,
+            ratified_by: self.env().caller(),
+        });
+    }
+
+    /// Lift a pause (ratified or not)
+    pub fn unpause(&mut self, target: Address) {
+        self.only_admin();
+        self.pauses.set(&target, PauseState {
+            paused: false,
+            expires_at: 0,
+            ratified: false,
+        });
+
+        self.env().emit_event(GuardianUnpaused {
+            target,
+            unpaused_by: self.env().caller(),
+        });
+    }
+
+    /// Register or unregister a pausable contract
+    pub fn set_registered(&mut self, target: Address, registered: bool) {
+        self.only_admin();
+        self.registered.set(&target, registered);
+    }
+
+    /// Grant or revoke the guardian role
+    pub fn set_guardian(&mut self, guardian: Address, enabled: bool) {
+        self.only_admin();
+        self.guardians.set(&guardian, enabled);
+
+        self.env().emit_event(GuardianUpdated {
+            guardian,
+            enabled,
+            updated_by: self.env().caller(),
+        });
+    }
+
+    /// Transfer admin rights
+    pub fn transfer_admin(&mut self, new_admin: Address) {
+        self.only_admin();
+        self.admin.set(new_admin);
+    }
+
+    // ========================================
+    // Internal Functions
+    // ========================================
+
+    fn only_admin(&self) {
+        if self.env().caller() != self.get_admin() {
+            self.env().revert(GovernanceError::Unauthorized);
+        }
+    }
+}
+
+/// External interface used by pausable contracts
+#[odra::external_contract]
+pub trait GuardianContract {
+    fn is_paused(&self, target: Address) -> bool;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use odra::host::{Deployer, HostEnv, NoArgs};
+
+    fn setup() -> (HostEnv, GuardianHostRef, Address, Address) {
+        let env = odra_test::env();
+        let mut guardian = Guardian::deploy(&env, NoArgs);
+        let guardian_account = env.get_account(1);
+        let target = env.get_account(5);
+        guardian.set_guardian(guardian_account, true);
+        guardian.set_registered(target, true);
+        (env, guardian, guardian_account, target)
+    }
+
+    #[test]
+    fn test_pause_expires() {
+        let (env, mut guardian, guardian_account, target) = setup();
+
+        env.set_caller(guardian_account);
+        guardian.pause(target);
+        assert!(guardian.is_paused(target));
+
+        env.advance_block_time(PAUSE_DURATION);
+        assert!(!guardian.is_paused(target));
+    }
+
+    #[test]
+    fn test_ratified_pause_persists() {
+        let (env, mut guardian, guardian_account, target) = setup();
+
+        env.set_caller(guardian_account);
+        guardian.pause(target);
+        env.set_caller(env.get_account(0));
+        guardian.ratify(target);
+
+        env.advance_block_time(PAUSE_DURATION);
+        assert!(guardian.is_paused(target));
+
+        guardian.unpause(target);
+        assert!(!guardian.is_paused(target));
+    }
+
+    #[test]
+    #[should_panic(expected = "TargetNotRegistered")]
+    fn test_cannot_pause_unregistered() {
+        let (env, mut guardian, guardian_account, _target) = setup();
+        env.set_caller(guardian_account);
+        guardian.pause(env.get_account(6));
+    }
+}
@@ -0,0 +1,122 @@
+//! Protocol Metrics - Opt-in per-entrypoint call and fee counters
+//!
+//! Embedded as a submodule by contracts whose revenue is reported on
+//! (pairs, the staking manager). While enabled, every tracked entrypoint adds
+//! one call and the fee it charged to the current epoch; rotating starts a new
+//! epoch from zero and keeps the closed one readable, so revenue reports can
+//! be built from views instead of scraping events. Recording is a no-op while
+//! disabled, so contracts that never opt in pay no storage for it. The owning
+//! contract does the access control.
+
+use odra::prelude::*;
+use odra::casper_types::U256;
+use super::events::MetricsRotated;
+
+/// Most distinct entrypoints tracked per epoch
+pub const MAX_TRACKED_ENTRYPOINTS: usize = 16;
+
+/// Counters of one entrypoint in one epoch
+#[odra::odra_type]
+#[derive(Default)]
+pub struct EntrypointMetrics {
+    /// Successful calls
+    pub calls: u64,
+    /// Fees charged, in the unit the owning contract documents for the entrypoint
+    pub fees: U256,
+}
+
+/// Protocol Metrics submodule
+#[odra::module]
+pub struct ProtocolMetrics {
+    /// Whether calls are being recorded
+    enabled: Var<bool>,
+    /// Current epoch, starting at 0
+    epoch: Var<u64>,
+    /// Block time the current epoch started
+    epoch_started_at: Var<u64>,
+    /// Counters per (epoch, entrypoint)
+    counters: Mapping<(u64, String), EntrypointMetrics>,
+    /// Entrypoints recorded in each epoch
+    entrypoints: Mapping<u64, Vec<String>>,
+}
+
+#[odra::module]
+impl ProtocolMetrics {
+    /// Whether calls are being recorded
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.get_or_default()
+    }
+
+    /// Current epoch
+    pub fn epoch(&self) -> u64 {
+        self.epoch.get_or_default()
+    }
+
+    /// Block time the current epoch started
+    pub fn epoch_started_at(&self) -> u64 {
+        self.epoch_started_at.get_or_default()
+    }
+
+    /// Counters of `entrypoint` in `epoch`; zero if it was never recorded
+    pub fn get(&self, epoch: u64, entrypoint: String) -> EntrypointMetrics {
+        self.counters.get(&(epoch, entrypoint)).unwrap_or_default()
+    }
+
+    /// Entrypoints recorded in `epoch`
+    pub fn entrypoints(&self, epoch: u64) -> Vec<String> {
+        self.entrypoints.get(&epoch).unwrap_or_default()
+    }
+}
+
+impl ProtocolMetrics {
+    /// Start or stop recording
+    pub(crate) fn set_enabled(&mut self, enabled: bool) {
+        if enabled && !self.is_enabled() && self.epoch_started_at.get().is_none() {
+            self.epoch_started_at.set(self.env().get_block_time());
+        }
+        self.enabled.set(enabled);
+    }
+
+    /// Count a call to `entrypoint` charging `fee` in the current epoch
+    /// Entrypoints beyond `MAX_TRACKED_ENTRYPOINTS` per epoch are not recorded
+    pub(crate) fn record(&mut self, entrypoint: &str, fee: U256) {
+        if !self.is_enabled() {
+            return;
+        }
+        let epoch = self.epoch();
+        let key = (epoch, String::from(entrypoint));
+        let mut counters = match self.counters.get(&key) {
+            Some(counters) => counters,
+            None => {
+                let mut names = self.entrypoints(epoch);
+                if names.len() >= MAX_TRACKED_ENTRYPOINTS {
+                    return;
+                }
+                names.push(String::from(entrypoint));
+                self.entrypoints.set(&epoch, names);
+                EntrypointMetrics::default()
+            }
+        };
+        counters.calls += 1;
+        counters.fees = counters.fees.saturating_add(fee);
+        self.counters.set(&key, counters);
+    }
+
+    /// Close the current epoch and start a new one from zero
+    /// Returns the new epoch
+    pub(crate) fn rotate(&mut self) -> u64 {
+        let closed_epoch = self.epoch();
+        let epoch = closed_epoch + 1;
+        let started_at = self.env().get_block_time();
+        self.epoch.set(epoch);
+        self.epoch_started_at.set(started_at);
+
+        self.env().emit_event(MetricsRotated {
+            contract: self.env().self_address(),
+            closed_epoch,
+            epoch,
+            started_at,
+        });
+        epoch
+    }
+}
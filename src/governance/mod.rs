@@ -0,0 +1,27 @@
+//! Governance module
+//!
+//! Protocol-wide configuration shared by the DEX and LST contracts:
+//! - ParameterRegistry: role-gated store of typed protocol parameters
+//! - Guardian: expiring emergency pauses for registered contracts
+//! - Denylist: sanctions list that tokens can opt in to enforcing
+//! - ParameterSchedule: delayed, announced admin parameter changes
+//! - TokenList: compromised-token flags enforced by the Router's safe mode
+//! - ProtocolMetrics: opt-in per-entrypoint call and fee counters
+
+pub mod parameter_registry;
+pub mod guardian;
+pub mod denylist;
+pub mod schedule;
+pub mod token_list;
+pub mod metrics;
+pub mod errors;
+pub mod events;
+
+pub use parameter_registry::ParameterRegistry;
+pub use guardian::Guardian;
+pub use denylist::Denylist;
+pub use schedule::{ParameterSchedule, ScheduledParameter};
+pub use token_list::TokenList;
+pub use metrics::{EntrypointMetrics, ProtocolMetrics};
+pub use errors::GovernanceError;
+pub use events::*;
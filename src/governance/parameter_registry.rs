@@ -0,0 +1,228 @@
+//! Parameter Registry - Central store for protocol parameters
+//!
+//! Fees, thresholds and pause flags used by the DEX and LST contracts are
+//! written here by governance. Consumer contracts keep a local copy of the
+//! values they use and pull updates via `sync_parameters()`, comparing the
+//! registry `version` to skip the reads when nothing changed.
+
+use odra::prelude::*;
+use odra::casper_types::U256;
+use super::errors::GovernanceError;
+use super::events::{ParameterManagerUpdated, ParameterUpdated};
+
+/// DEX: protocol fee recipient (address)
+pub const KEY_DEX_FEE_TO: &str = "dex.fee_to";
+
+/// LST: minimum stake amount in motes (U256)
+pub const KEY_LST_MINIMUM_STAKE: &str = "lst.minimum_stake";
+
+/// LST: unstaking period in seconds (u64)
+pub const KEY_LST_UNSTAKING_PERIOD: &str = "lst.unstaking_period";
+
+/// LST: paused flag (bool)
+pub const KEY_LST_PAUSED: &str = "lst.paused";
+
+/// Parameter Registry contract
+#[odra::module]
+pub struct ParameterRegistry {
+    /// Contract admin
+    admin: Var<Address>,
+    /// Addresses allowed to write parameters
+    managers: Mapping<Address, bool>,
+    /// Incremented on every parameter write
+    version: Var<u64>,
+    /// U256 parameters
+    u256_params: Mapping<String, U256>,
+    /// u64 parameters
+    u64_params: Mapping<String, u64>,
+    /// bool parameters
+    bool_params: Mapping<String, bool>,
+    /// Address parameters
+    address_params: Mapping<String, Address>,
+}
+
+#[odra::module]
+impl ParameterRegistry {
+    /// Initialize the registry with the caller as admin
+    pub fn init(&mut self) {
+        let caller = self.env().caller();
+        self.admin.set(caller);
+        self.version.set(0);
+    }
+
+    // ========================================
+    // Getters
+    // ========================================
+
+    /// Get the registry version (changes whenever any parameter is written)
+    pub fn version(&self) -> u64 {
+        self.version.get_or_default()
+    }
+
+    /// Get a U256 parameter
+    pub fn get_u256(&self, key: String) -> Option<U256> {
+        self.u256_params.get(&key)
+    }
+
+    /// Get a u64 parameter
+    pub fn get_u64(&self, key: String) -> Option<u64> {
+        self.u64_params.get(&key)
+    }
+
+    /// Get a bool parameter
+    pub fn get_bool(&self, key: String) -> Option<bool> {
+        self.bool_params.get(&key)
+    }
+
+    /// Get an address parameter
+    pub fn get_address(&self, key: String) -> Option<Address> {
+        self.address_params.get(&key)
+    }
+
+    /// Check if an address may write parameters
+    pub fn is_manager(&self, account: Address) -> bool {
+        account == self.get_admin() || self.managers.get(&account).unwrap_or(false)
+    }
+
+    /// Get admin address
+    pub fn get_admin(&self) -> Address {
+        self.admin.get_or_revert_with(GovernanceError::Unauthorized)
+    }
+
+    // ========================================
+    // Setters (managers only)
+    // ========================================
+
+    /// Set a U256 parameter
+    pub fn set_u256(&mut self, key: String, value: U256) {
+        self.ensure_writable(&key);
+        self.u256_params.set(&key, value);
+        self.bump_version(key);
+    }
+
+    /// Set a u64 parameter
+    pub fn set_u64(&mut self, key: String, value: u64) {
+        self.ensure_writable(&key);
+        self.u64_params.set(&key, value);
+        self.bump_version(key);
+    }
+
+    /// Set a bool parameter
+    pub fn set_bool(&mut self, key: String, value: bool) {
+        self.ensure_writable(&key);
+        self.bool_params.set(&key, value);
+        self.bump_version(key);
+    }
+
+    /// Set an address parameter
+    pub fn set_address(&mut self, key: String, value: Address) {
+        self.ensure_writable(&key);
+        self.address_params.set(&key, value);
+        self.bump_version(key);
+    }
+
+    // ========================================
+    // Admin Functions
+    // ========================================
+
+    /// Grant or revoke the parameter manager role
+    pub fn set_manager(&mut self, manager: Address, enabled: bool) {
+        self.only_admin();
+        self.managers.set(&manager, enabled);
+
+        self.env().emit_event(ParameterManagerUpdated {
+            manager,
+            enabled,
+            updated_by: self.env().caller(),
+        });
+    }
+
+    /// Transfer admin rights
+    pub fn transfer_admin(&mut self, new_admin: Address) {
+        self.only_admin();
+        self.admin.set(new_admin);
+    }
+
+    // ========================================
+    // Internal Functions
+    // ========================================
+
+    fn bump_version(&mut self, key: String) {
+        let version = self.version.get_or_default() + 1;
+        self.version.set(version);
+
+        self.env().emit_event(ParameterUpdated {
+            key,
+            version,
+            updated_by: self.env().caller(),
+        });
+    }
+
+    fn ensure_writable(&self, key: &str) {
+        if !self.is_manager(self.env().caller()) {
+            self.env().revert(GovernanceError::Unauthorized);
+        }
+        if key.is_empty() {
+            self.env().revert(GovernanceError::InvalidKey);
+        }
+    }
+
+    fn only_admin(&self) {
+        if self.env().caller() != self.get_admin() {
+            self.env().revert(GovernanceError::Unauthorized);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use odra::host::{Deployer, HostEnv, NoArgs};
+
+    fn setup() -> (HostEnv, ParameterRegistryHostRef) {
+        let env = odra_test::env();
+        let registry = ParameterRegistry::deploy(&env, NoArgs);
+        (env, registry)
+    }
+
+    #[test]
+    fn test_set_and_get_parameters() {
+        let (env, mut registry) = setup();
+        let fee_to = env.get_account(3);
+
+        assert_eq!(registry.version(), 0);
+        assert_eq!(registry.get_u256(String::from(KEY_LST_MINIMUM_STAKE)), None);
+
+        registry.set_u256(String::from(KEY_LST_MINIMUM_STAKE), U256::from(50));
+        registry.set_u64(String::from(KEY_LST_UNSTAKING_PERIOD), 3_600);
+        registry.set_bool(String::from(KEY_LST_PAUSED), true);
+        registry.set_address(String::from(KEY_DEX_FEE_TO), fee_to);
+
+        assert_eq!(registry.get_u256(String::from(KEY_LST_MINIMUM_STAKE)), Some(U256::from(50)));
+        assert_eq!(registry.get_u64(String::from(KEY_LST_UNSTAKING_PERIOD)), Some(3_600));
+        assert_eq!(registry.get_bool(String::from(KEY_LST_PAUSED)), Some(true));
+        assert_eq!(registry.get_address(String::from(KEY_DEX_FEE_TO)), Some(fee_to));
+        assert_eq!(registry.version(), 4);
+    }
+
+    #[test]
+    fn test_manager_can_write() {
+        let (env, mut registry) = setup();
+        let manager = env.get_account(1);
+
+        registry.set_manager(manager, true);
+        assert!(registry.is_manager(manager));
+
+        env.set_caller(manager);
+        registry.set_bool(String::from(KEY_LST_PAUSED), false);
+        assert_eq!(registry.get_bool(String::from(KEY_LST_PAUSED)), Some(false));
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized")]
+    fn test_non_manager_cannot_write() {
+        let (env, mut registry) = setup();
+        env.set_caller(env.get_account(2));
+        registry.set_u64(String::from(KEY_LST_UNSTAKING_PERIOD), 1);
+    }
+}
@@ -0,0 +1,122 @@
+//! Parameter Schedule - Delayed admin parameter changes
+//!
+//! Embedded as a submodule by contracts whose admin setters move fees. A
+//! scheduled change is announced with `ParameterScheduled` as soon as it is
+//! queued and can only be applied once the contract's delay has passed, so
+//! users get notice before the new value takes effect. Shortening the delay
+//! is itself scheduled, so it cannot be used to skip the notice. The owning
+//! contract does the access control and applies the value itself.
+
+use odra::prelude::*;
+use odra::casper_types::U256;
+use super::errors::GovernanceError;
+use super::events::{ParameterScheduled, ScheduledParameterApplied, ScheduledParameterCancelled};
+
+/// Longest delay a contract can configure (30 days)
+pub const MAX_SCHEDULE_DELAY: u64 = 30 * 24 * 60 * 60;
+
+/// Schedule key of a pending delay reduction
+pub const PARAM_SCHEDULE_DELAY: &str = "schedule_delay";
+
+/// A queued parameter change
+#[odra::odra_type]
+pub struct ScheduledParameter {
+    /// New value
+    pub value: U256,
+    /// Block time from which the change can be applied
+    pub effective_at: u64,
+}
+
+/// Parameter Schedule submodule
+#[odra::module]
+pub struct ParameterSchedule {
+    /// Delay in seconds between scheduling and applying a change
+    delay: Var<u64>,
+    /// Pending change per parameter name
+    scheduled: Mapping<String, Option<ScheduledParameter>>,
+}
+
+#[odra::module]
+impl ParameterSchedule {
+    /// Delay in seconds between scheduling and applying a change
+    pub fn delay(&self) -> u64 {
+        self.delay.get_or_default()
+    }
+
+    /// Pending change for `parameter`, if any
+    pub fn get(&self, parameter: String) -> Option<ScheduledParameter> {
+        self.scheduled.get(&parameter).flatten()
+    }
+}
+
+impl ParameterSchedule {
+    /// Change the delay applied to changes scheduled from now on
+    /// A longer delay applies at once and drops any pending reduction; a
+    /// shorter one is scheduled behind the current delay. Returns whether
+    /// the delay changed immediately
+    pub(crate) fn set_delay(&mut self, delay: u64) -> bool {
+        if delay > MAX_SCHEDULE_DELAY {
+            self.env().revert(GovernanceError::InvalidDelay);
+        }
+        if delay < self.delay() {
+            self.schedule(PARAM_SCHEDULE_DELAY, U256::from(delay));
+            return false;
+        }
+        self.scheduled.set(&String::from(PARAM_SCHEDULE_DELAY), None);
+        self.delay.set(delay);
+        true
+    }
+
+    /// Apply a scheduled delay reduction once it is due and return the new delay
+    pub(crate) fn apply_delay(&mut self) -> u64 {
+        let delay = self.take_due(PARAM_SCHEDULE_DELAY).as_u64();
+        self.delay.set(delay);
+        delay
+    }
+
+    /// Queue `value` for `parameter`, replacing any pending change
+    /// Returns the block time from which it can be applied
+    pub(crate) fn schedule(&mut self, parameter: &str, value: U256) -> u64 {
+        let effective_at = self.env().get_block_time() + self.delay();
+        self.scheduled.set(&String::from(parameter), Some(ScheduledParameter { value, effective_at }));
+
+        self.env().emit_event(ParameterScheduled {
+            parameter: String::from(parameter),
+            value,
+            effective_at,
+            scheduled_by: self.env().caller(),
+        });
+        effective_at
+    }
+
+    /// Remove the pending change for `parameter` once it is due and return its value
+    pub(crate) fn take_due(&mut self, parameter: &str) -> U256 {
+        let key = String::from(parameter);
+        let scheduled = self.scheduled.get(&key).flatten()
+            .unwrap_or_else(|| self.env().revert(GovernanceError::NotScheduled));
+        if self.env().get_block_time() < scheduled.effective_at {
+            self.env().revert(GovernanceError::ScheduleNotReady);
+        }
+        self.scheduled.set(&key, None);
+
+        self.env().emit_event(ScheduledParameterApplied {
+            parameter: key,
+            value: scheduled.value,
+        });
+        scheduled.value
+    }
+
+    /// Drop the pending change for `parameter`
+    pub(crate) fn cancel(&mut self, parameter: &str) {
+        let key = String::from(parameter);
+        if self.scheduled.get(&key).flatten().is_none() {
+            self.env().revert(GovernanceError::NotScheduled);
+        }
+        self.scheduled.set(&key, None);
+
+        self.env().emit_event(ScheduledParameterCancelled {
+            parameter: key,
+            cancelled_by: self.env().caller(),
+        });
+    }
+}
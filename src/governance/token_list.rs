@@ -0,0 +1,133 @@
+//! Token List - Governance flags for compromised tokens
+//!
+//! Governance marks a token as compromised (exploited, depegged, malicious
+//! upgrade). A Router in safe mode consults the list and refuses new buys of
+//! flagged tokens while sells and liquidity removals keep working, so holders
+//! can always exit. Frontends read the same flags to show warnings.
+
+use odra::prelude::*;
+use super::errors::GovernanceError;
+use super::events::TokenDelistingUpdated;
+
+/// Most tokens that can be flagged at once
+pub const MAX_COMPROMISED_TOKENS: usize = 64;
+
+/// Token List contract
+#[odra::module]
+pub struct TokenList {
+    /// Contract admin (governance)
+    admin: Var<Address>,
+    /// Compromised flag per token
+    compromised: Mapping<Address, bool>,
+    /// Flagged tokens, for enumeration
+    compromised_tokens: Var<Vec<Address>>,
+}
+
+#[odra::module]
+impl TokenList {
+    /// Initialize with the caller as admin
+    pub fn init(&mut self) {
+        let caller = self.env().caller();
+        self.admin.set(caller);
+    }
+
+    // ========================================
+    // Views
+    // ========================================
+
+    /// Check if a token is flagged as compromised
+    pub fn is_compromised(&self, token: Address) -> bool {
+        self.compromised.get(&token).unwrap_or(false)
+    }
+
+    /// All tokens currently flagged as compromised
+    pub fn get_compromised_tokens(&self) -> Vec<Address> {
+        self.compromised_tokens.get_or_default()
+    }
+
+    /// Get admin address
+    pub fn get_admin(&self) -> Address {
+        self.admin.get_or_revert_with(GovernanceError::Unauthorized)
+    }
+
+    // ========================================
+    // Admin Functions
+    // ========================================
+
+    /// Flag or clear a token as compromised
+    pub fn set_compromised(&mut self, token: Address, compromised: bool) {
+        self.only_admin();
+        if self.is_compromised(token) == compromised {
+            return;
+        }
+
+        let mut tokens = self.get_compromised_tokens();
+        if compromised {
+            if tokens.len() >= MAX_COMPROMISED_TOKENS {
+                self.env().revert(GovernanceError::ListFull);
+            }
+            tokens.push(token);
+        } else {
+            tokens.retain(|listed| *listed != token);
+        }
+        self.compromised_tokens.set(tokens);
+        self.compromised.set(&token, compromised);
+
+        self.env().emit_event(TokenDelistingUpdated {
+            token,
+            compromised,
+            updated_by: self.env().caller(),
+        });
+    }
+
+    /// Transfer admin rights
+    pub fn transfer_admin(&mut self, new_admin: Address) {
+        self.only_admin();
+        self.admin.set(new_admin);
+    }
+
+    // ========================================
+    // Internal Functions
+    // ========================================
+
+    fn only_admin(&self) {
+        if self.env().caller() != self.get_admin() {
+            self.env().revert(GovernanceError::Unauthorized);
+        }
+    }
+}
+
+/// External interface used by the Router's safe mode
+#[odra::external_contract]
+pub trait TokenListContract {
+    fn is_compromised(&self, token: Address) -> bool;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use odra::host::{Deployer, NoArgs};
+
+    #[test]
+    fn test_flag_and_clear_compromised_token() {
+        let env = odra_test::env();
+        let mut list = TokenList::deploy(&env, NoArgs);
+        let (token_a, token_b) = (env.get_account(5), env.get_account(6));
+
+        list.set_compromised(token_a, true);
+        list.set_compromised(token_b, true);
+        list.set_compromised(token_a, true);
+        assert!(list.is_compromised(token_a));
+        assert_eq!(list.get_compromised_tokens(), vec![token_a, token_b]);
+
+        list.set_compromised(token_a, false);
+        assert!(!list.is_compromised(token_a));
+        assert_eq!(list.get_compromised_tokens(), vec![token_b]);
+
+        env.set_caller(env.get_account(1));
+        assert_eq!(
+            list.try_set_compromised(token_a, true),
+            Err(GovernanceError::Unauthorized.into())
+        );
+    }
+}
@@ -0,0 +1,427 @@
+//! Trading Competition
+//!
+//! Admin opens a time-boxed competition on a pair with a funded prize pool.
+//! The Router reports each swap through the pair (volume measured in token0),
+//! the contract keeps a top-N leaderboard, and once the window ends prizes are
+//! claimed either by rank or pro-rata to volume.
+
+use odra::prelude::*;
+use odra::casper_types::U256;
+use odra::ContractRef;
+use crate::errors::DexError;
+use crate::token::Cep18TokenContractRef;
+
+/// Competition configuration and state
+#[odra::odra_type]
+pub struct CompetitionInfo {
+    /// Pair whose volume is counted
+    pub pair: Address,
+    /// Token the prizes are paid in
+    pub prize_token: Address,
+    /// Total prize pool
+    pub prize_pool: U256,
+    /// Start timestamp (inclusive)
+    pub start_time: u64,
+    /// End timestamp (exclusive)
+    pub end_time: u64,
+    /// Number of traders kept on the leaderboard
+    pub leaderboard_size: u32,
+    /// Prize share per rank in basis points; empty means pro-rata to volume
+    pub rank_shares_bps: Vec<u16>,
+    /// Total volume reported
+    pub total_volume: U256,
+    /// Whether the competition has been closed
+    pub closed: bool,
+}
+
+/// Trading Competition contract
+#[odra::module]
+pub struct Competition {
+    /// Admin address
+    admin: Var<Address>,
+    /// Contracts allowed to report volume (the Router)
+    reporters: Mapping<Address, bool>,
+    /// Competitions by id
+    competitions: Mapping<u32, CompetitionInfo>,
+    /// Next competition id
+    next_competition_id: Var<u32>,
+    /// Competition currently attached to a pair
+    active_by_pair: Mapping<Address, u32>,
+    /// Volume per (competition, trader)
+    volumes: Mapping<(u32, Address), U256>,
+    /// Leaderboard per competition, sorted by volume descending
+    leaderboards: Mapping<u32, Vec<(Address, U256)>>,
+    /// Whether a trader has claimed their prize
+    claimed: Mapping<(u32, Address), bool>,
+}
+
+#[odra::module]
+impl Competition {
+    /// Initialize with the caller as admin
+    pub fn init(&mut self) {
+        let caller = self.env().caller();
+        self.admin.set(caller);
+        self.next_competition_id.set(0);
+    }
+
+    // ========================================
+    // Admin Functions
+    // ========================================
+
+    /// Open a competition and pull the prize pool from the admin
+    ///
+    /// # Arguments
+    /// * `rank_shares_bps` - Prize share of each rank (must sum to 10000), or
+    ///   empty to split the pool pro-rata to volume
+    pub fn create_competition(
+        &mut self,
+        pair: Address,
+        prize_token: Address,
+        prize_pool: U256,
+        start_time: u64,
+        end_time: u64,
+        leaderboard_size: u32,
+        rank_shares_bps: Vec<u16>,
+    ) -> u32 {
+        self.only_admin();
+
+        if end_time <= start_time || end_time <= self.env().get_block_time() {
+            self.env().revert(DexError::InvalidConfiguration);
+        }
+        if prize_pool.is_zero() || leaderboard_size == 0 {
+            self.env().revert(DexError::InvalidConfiguration);
+        }
+        if !rank_shares_bps.is_empty() {
+            let total_bps: u32 = rank_shares_bps.iter().map(|bps| *bps as u32).sum();
+            if total_bps != 10_000 || rank_shares_bps.len() as u32 > leaderboard_size {
+                self.env().revert(DexError::InvalidConfiguration);
+            }
+        }
+
+        // One running competition per pair
+        if let Some(active_id) = self.active_by_pair.get(&pair) {
+            if let Some(active) = self.competitions.get(&active_id) {
+                if !active.closed {
+                    self.env().revert(DexError::InvalidConfiguration);
+                }
+            }
+        }
+
+        let caller = self.env().caller();
+        let mut token = Cep18TokenContractRef::new(self.env(), prize_token);
+        if !token.transfer_from(caller, self.env().self_address(), prize_pool) {
+            self.env().revert(DexError::TransferFailed);
+        }
+
+        let competition_id = self.next_competition_id.get_or_default();
+        self.competitions.set(&competition_id, CompetitionInfo {
+            pair,
+            prize_token,
+            prize_pool,
+            start_time,
+            end_time,
+            leaderboard_size,
+            rank_shares_bps,
+            total_volume: U256::zero(),
+            closed: false,
+        });
+        self.active_by_pair.set(&pair, competition_id);
+        self.next_competition_id.set(competition_id + 1);
+
+        self.env().emit_event(CompetitionCreated {
+            competition_id,
+            pair,
+            prize_token,
+            prize_pool,
+            start_time,
+            end_time,
+        });
+
+        competition_id
+    }
+
+    /// Grant or revoke the volume reporter role
+    pub fn set_reporter(&mut self, reporter: Address, enabled: bool) {
+        self.only_admin();
+        self.reporters.set(&reporter, enabled);
+    }
+
+    /// Transfer admin rights
+    pub fn transfer_admin(&mut self, new_admin: Address) {
+        self.only_admin();
+        self.admin.set(new_admin);
+    }
+
+    // ========================================
+    // Reporting
+    // ========================================
+
+    /// Record swap volume for a trader on a pair
+    /// Ignored when the pair has no competition running
+    pub fn report_volume(&mut self, pair: Address, trader: Address, volume: U256) {
+        if !self.reporters.get(&self.env().caller()).unwrap_or(false) {
+            self.env().revert(DexError::Unauthorized);
+        }
+
+        let competition_id = match self.active_by_pair.get(&pair) {
+            Some(id) => id,
+            None => return,
+        };
+        let mut competition = match self.competitions.get(&competition_id) {
+            Some(competition) => competition,
+            None => return,
+        };
+        let now = self.env().get_block_time();
+        if competition.closed || now < competition.start_time || now >= competition.end_time {
+            return;
+        }
+
+        let trader_volume = self.volumes.get(&(competition_id, trader)).unwrap_or_default() + volume;
+        self.volumes.set(&(competition_id, trader), trader_volume);
+        competition.total_volume = competition.total_volume + volume;
+        let leaderboard_size = competition.leaderboard_size as usize;
+        self.competitions.set(&competition_id, competition);
+
+        // Re-insert the trader at their new rank
+        let mut leaderboard = self.leaderboards.get(&competition_id).unwrap_or_default();
+        leaderboard.retain(|(account, _)| *account != trader);
+        let position = leaderboard.iter()
+            .position(|(_, v)| *v < trader_volume)
+            .unwrap_or(leaderboard.len());
+        leaderboard.insert(position, (trader, trader_volume));
+        leaderboard.truncate(leaderboard_size);
+        self.leaderboards.set(&competition_id, leaderboard);
+    }
+
+    // ========================================
+    // Settlement
+    // ========================================
+
+    /// Close a finished competition
+    /// Prize shares of unfilled ranks (or the whole pool if nobody traded) go back to the admin
+    pub fn close(&mut self, competition_id: u32) {
+        let mut competition = self.competitions.get(&competition_id)
+            .unwrap_or_revert_with(&self.env(), DexError::InvalidConfiguration);
+        if competition.closed || self.env().get_block_time() < competition.end_time {
+            self.env().revert(DexError::InvalidConfiguration);
+        }
+
+        let leaderboard = self.leaderboards.get(&competition_id).unwrap_or_default();
+        let unallocated = if competition.total_volume.is_zero() {
+            competition.prize_pool
+        } else if competition.rank_shares_bps.is_empty() {
+            U256::zero()
+        } else {
+            let unfilled_bps: u32 = competition.rank_shares_bps.iter()
+                .skip(leaderboard.len())
+                .map(|bps| *bps as u32)
+                .sum();
+            competition.prize_pool * U256::from(unfilled_bps) / U256::from(10_000)
+        };
+
+        competition.closed = true;
+        let prize_token = competition.prize_token;
+        self.competitions.set(&competition_id, competition);
+
+        if !unallocated.is_zero() {
+            let admin = self.admin.get_or_revert_with(DexError::Unauthorized);
+            let mut token = Cep18TokenContractRef::new(self.env(), prize_token);
+            token.transfer(admin, unallocated);
+        }
+
+        self.env().emit_event(CompetitionClosed {
+            competition_id,
+            winners: leaderboard.len() as u32,
+            unallocated,
+        });
+    }
+
+    /// Claim the caller's prize from a closed competition
+    pub fn claim(&mut self, competition_id: u32) -> U256 {
+        let caller = self.env().caller();
+        let competition = self.competitions.get(&competition_id)
+            .unwrap_or_revert_with(&self.env(), DexError::InvalidConfiguration);
+        if !competition.closed {
+            self.env().revert(DexError::InvalidConfiguration);
+        }
+        if self.claimed.get(&(competition_id, caller)).unwrap_or(false) {
+            self.env().revert(DexError::InsufficientAmount);
+        }
+
+        let prize = self.get_prize(competition_id, caller);
+        if prize.is_zero() {
+            self.env().revert(DexError::InsufficientAmount);
+        }
+        self.claimed.set(&(competition_id, caller), true);
+
+        let mut token = Cep18TokenContractRef::new(self.env(), competition.prize_token);
+        token.transfer(caller, prize);
+
+        self.env().emit_event(PrizeClaimed {
+            competition_id,
+            trader: caller,
+            amount: prize,
+        });
+
+        prize
+    }
+
+    // ========================================
+    // View Functions
+    // ========================================
+
+    pub fn get_competition(&self, competition_id: u32) -> Option<CompetitionInfo> {
+        self.competitions.get(&competition_id)
+    }
+
+    pub fn get_active_competition(&self, pair: Address) -> Option<u32> {
+        self.active_by_pair.get(&pair)
+    }
+
+    pub fn get_volume(&self, competition_id: u32, trader: Address) -> U256 {
+        self.volumes.get(&(competition_id, trader)).unwrap_or_default()
+    }
+
+    pub fn get_leaderboard(&self, competition_id: u32) -> Vec<(Address, U256)> {
+        self.leaderboards.get(&competition_id).unwrap_or_default()
+    }
+
+    /// Prize a trader is entitled to (zero until the competition has volume)
+    pub fn get_prize(&self, competition_id: u32, trader: Address) -> U256 {
+        let competition = match self.competitions.get(&competition_id) {
+            Some(competition) => competition,
+            None => return U256::zero(),
+        };
+        if competition.total_volume.is_zero() || self.claimed.get(&(competition_id, trader)).unwrap_or(false) {
+            return U256::zero();
+        }
+
+        if competition.rank_shares_bps.is_empty() {
+            let volume = self.volumes.get(&(competition_id, trader)).unwrap_or_default();
+            return competition.prize_pool * volume / competition.total_volume;
+        }
+
+        let leaderboard = self.leaderboards.get(&competition_id).unwrap_or_default();
+        match leaderboard.iter().position(|(account, _)| *account == trader) {
+            Some(rank) if rank < competition.rank_shares_bps.len() => {
+                competition.prize_pool * U256::from(competition.rank_shares_bps[rank]) / U256::from(10_000)
+            }
+            _ => U256::zero(),
+        }
+    }
+
+    pub fn is_reporter(&self, account: Address) -> bool {
+        self.reporters.get(&account).unwrap_or(false)
+    }
+
+    pub fn get_admin(&self) -> Address {
+        self.admin.get_or_revert_with(DexError::Unauthorized)
+    }
+
+    // ========================================
+    // Internal Functions
+    // ========================================
+
+    fn only_admin(&self) {
+        if self.env().caller() != self.get_admin() {
+            self.env().revert(DexError::Unauthorized);
+        }
+    }
+}
+
+/// External interface for volume reporting
+#[odra::external_contract]
+pub trait CompetitionReporter {
+    fn report_volume(&mut self, pair: Address, trader: Address, volume: U256);
+}
+
+// Events
+
+#[odra::event]
+pub struct CompetitionCreated {
+    pub competition_id: u32,
+    pub pair: Address,
+    pub prize_token: Address,
+    pub prize_pool: U256,
+    pub start_time: u64,
+    pub end_time: u64,
+}
+
+#[odra::event]
+pub struct CompetitionClosed {
+    pub competition_id: u32,
+    pub winners: u32,
+    pub unallocated: U256,
+}
+
+#[odra::event]
+pub struct PrizeClaimed {
+    pub competition_id: u32,
+    pub trader: Address,
+    pub amount: U256,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use odra::host::{Deployer, HostEnv, NoArgs};
+    use crate::token::{LpToken, LpTokenInitArgs, LpTokenHostRef};
+
+    fn setup() -> (HostEnv, CompetitionHostRef, LpTokenHostRef) {
+        let env = odra_test::env();
+        let competition = Competition::deploy(&env, NoArgs);
+        let mut prize = LpToken::deploy(&env, LpTokenInitArgs {
+            name: String::from("Prize"),
+            symbol: String::from("PRZ"),
+        });
+        prize.mint(env.get_account(0), U256::from(1_000));
+        prize.approve(competition.address().clone(), U256::from(1_000));
+        (env, competition, prize)
+    }
+
+    #[test]
+    fn test_ranked_competition() {
+        let (env, mut competition, prize) = setup();
+        let pair = env.get_account(5);
+        let reporter = env.get_account(6);
+        let (alice, bob, carol) = (env.get_account(1), env.get_account(2), env.get_account(3));
+
+        let now = env.block_time();
+        let id = competition.create_competition(
+            pair,
+            prize.address().clone(),
+            U256::from(1_000),
+            now,
+            now + 1_000,
+            2,
+            vec![7_000, 3_000],
+        );
+        competition.set_reporter(reporter, true);
+
+        env.set_caller(reporter);
+        competition.report_volume(pair, alice, U256::from(100));
+        competition.report_volume(pair, bob, U256::from(300));
+        competition.report_volume(pair, carol, U256::from(50));
+        competition.report_volume(pair, alice, U256::from(250));
+
+        let leaderboard = competition.get_leaderboard(id);
+        assert_eq!(leaderboard, vec![(alice, U256::from(350)), (bob, U256::from(300))]);
+
+        env.advance_block_time(1_000);
+        competition.close(id);
+
+        env.set_caller(alice);
+        assert_eq!(competition.claim(id), U256::from(700));
+        env.set_caller(bob);
+        assert_eq!(competition.claim(id), U256::from(300));
+        assert_eq!(competition.get_prize(id, carol), U256::zero());
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized")]
+    fn test_only_reporter_can_report() {
+        let (env, mut competition, _prize) = setup();
+        env.set_caller(env.get_account(1));
+        competition.report_volume(env.get_account(5), env.get_account(1), U256::from(1));
+    }
+}
@@ -0,0 +1,454 @@
+//! LP Loyalty
+//!
+//! Rewards sticky liquidity with swap-fee rebates. Registered as a transfer
+//! listener on pairs, the contract follows every LP mint, burn and transfer,
+//! tracking per user and pair how long they have held without interruption
+//! and their cumulative liquidity-seconds. Each tier sets a minimum share of
+//! the pair's LP supply and holding time. The share is the average balance
+//! over the holding time, so topping up a long-held dust position right
+//! before trading does not unlock a tier; when a loyal LP trades through a
+//! pair the Router asks for a rebate of the hop's fee, paid in the hop's input
+//! token by the pair out of the protocol's share of that fee. LP fees are
+//! never rebated, so round-trip trades cannot earn more than the protocol took.
+//! Only pairs registered with the factory are tracked or rebated.
+
+use odra::prelude::*;
+use odra::casper_types::U256;
+use odra::ContractRef;
+use crate::dex::factory::FactoryContractContractRef;
+use crate::dex::pair::PairContractRef;
+use crate::errors::DexError;
+use crate::math::{SafeMath, BPS_DENOMINATOR};
+use crate::token::Cep18TokenContractRef;
+
+/// Most tiers that can be configured
+pub const MAX_LOYALTY_TIERS: usize = 4;
+
+/// Largest share of a swap fee a tier may rebate (50%)
+pub const MAX_LOYALTY_DISCOUNT_BPS: u16 = 5_000;
+
+/// Requirements and rebate of a loyalty tier
+#[odra::odra_type]
+pub struct LoyaltyTier {
+    /// Minimum share of the pair's LP supply held on average since the
+    /// position opened, in basis points
+    pub min_share_bps: u16,
+    /// Minimum time the position has been held without dropping to zero
+    pub min_duration: u64,
+    /// Share of the swap fee rebated, in basis points
+    pub discount_bps: u16,
+}
+
+/// A user's liquidity history in one pair
+#[odra::odra_type]
+#[derive(Default)]
+pub struct LoyaltyPosition {
+    /// LP balance at the last update
+    pub lp_amount: U256,
+    /// Block time the balance last rose from zero (0 while empty)
+    pub held_since: u64,
+    /// Sum of LP balance times seconds held, up to `last_update`
+    pub liquidity_seconds: U256,
+    /// `liquidity_seconds` when the balance last rose from zero
+    pub entry_liquidity_seconds: U256,
+    /// Block time of the last update
+    pub last_update: u64,
+}
+
+/// LP Loyalty contract
+#[odra::module]
+pub struct LpLoyalty {
+    /// Admin address
+    admin: Var<Address>,
+    /// Factory whose registered pairs are tracked and rebated
+    factory: Var<Address>,
+    /// Contracts allowed to request rebates (the Router)
+    rebaters: Mapping<Address, bool>,
+    /// Tiers, from lowest to highest discount
+    tiers: Var<Vec<LoyaltyTier>>,
+    /// Position per (user, pair)
+    positions: Mapping<(Address, Address), LoyaltyPosition>,
+}
+
+#[odra::module]
+impl LpLoyalty {
+    /// Initialize with the caller as admin, following `factory`'s pairs
+    pub fn init(&mut self, factory: Address) {
+        let caller = self.env().caller();
+        self.admin.set(caller);
+        self.factory.set(factory);
+    }
+
+    // ========================================
+    // Admin Functions
+    // ========================================
+
+    /// Replace the tiers; discounts must strictly increase from tier to tier
+    pub fn set_tiers(&mut self, tiers: Vec<LoyaltyTier>) {
+        self.only_admin();
+        if tiers.len() > MAX_LOYALTY_TIERS {
+            self.env().revert(DexError::InvalidConfiguration);
+        }
+        let mut previous_discount = 0;
+        for tier in &tiers {
+            if tier.discount_bps <= previous_discount
+                || tier.discount_bps > MAX_LOYALTY_DISCOUNT_BPS
+                || tier.min_share_bps as u128 > BPS_DENOMINATOR
+            {
+                self.env().revert(DexError::InvalidConfiguration);
+            }
+            previous_discount = tier.discount_bps;
+        }
+        self.tiers.set(tiers.clone());
+
+        self.env().emit_event(LoyaltyTiersUpdated { tiers });
+    }
+
+    /// Allow or disallow a contract to request rebates
+    pub fn set_rebater(&mut self, account: Address, allowed: bool) {
+        self.only_admin();
+        self.rebaters.set(&account, allowed);
+    }
+
+    // ========================================
+    // Hooks
+    // ========================================
+
+    /// LP transfer hook: follow both sides' balances in the calling pair
+    /// Mints come from and burns go to the pair itself, which is not tracked
+    /// Only callable by pairs registered with the factory
+    pub fn on_lp_transfer(&mut self, from: Address, to: Address, _amount: U256) {
+        let pair = self.env().caller();
+        if !self.is_registered_pair(pair) {
+            self.env().revert(DexError::Unauthorized);
+        }
+        let now = self.env().get_block_time();
+        for user in [from, to] {
+            if user == pair {
+                continue;
+            }
+            let mut position = self.get_position(user, pair);
+            let balance = Cep18TokenContractRef::new(self.env(), pair).balance_of(user);
+            if balance.is_zero() {
+                position.held_since = 0;
+            } else if position.lp_amount.is_zero() {
+                position.held_since = now;
+                position.entry_liquidity_seconds = position.liquidity_seconds;
+            }
+            position.lp_amount = balance;
+            self.positions.set(&(user, pair), position.clone());
+
+            self.env().emit_event(LoyaltyPositionUpdated {
+                user,
+                pair,
+                lp_amount: position.lp_amount,
+                held_since: position.held_since,
+            });
+        }
+    }
+
+    /// Pay `trader` their tier's share of a swap fee charged by `pair` in `token`
+    /// Only callable by a rebater, for pairs registered with the factory. Pays
+    /// nothing below the first tier or on pairs that have not made this
+    /// contract their rebater; otherwise the rebate is capped at the pair's
+    /// protocol share of the fee and paid by the pair out of its accrued
+    /// protocol fees. Returns the rebate paid
+    pub fn rebate(&mut self, trader: Address, pair: Address, token: Address, fee: U256) -> U256 {
+        if !self.is_rebater(self.env().caller()) {
+            self.env().revert(DexError::Unauthorized);
+        }
+        if !self.is_registered_pair(pair) {
+            self.env().revert(DexError::PairNotFound);
+        }
+        let mut pair_ref = PairContractRef::new(self.env(), pair);
+        // Pairs that do not pay rebates through this contract rebate nothing
+        if pair_ref.rebater() != Some(self.env().self_address()) {
+            return U256::zero();
+        }
+        let rebate_bps = self.get_discount_bps(trader, pair).min(pair_ref.protocol_fee_share_bps());
+        let amount = SafeMath::mul_div(fee, U256::from(rebate_bps), U256::from(BPS_DENOMINATOR))
+            .unwrap_or_else(|e| self.env().revert(e));
+        if amount.is_zero() {
+            return amount;
+        }
+        let amount = pair_ref.pay_rebate(token, trader, amount);
+        if amount.is_zero() {
+            return amount;
+        }
+
+        self.env().emit_event(LoyaltyRebatePaid { trader, pair, token, amount });
+        amount
+    }
+
+    // ========================================
+    // View Functions
+    // ========================================
+
+    /// A user's position in a pair, with liquidity-seconds accrued to now
+    pub fn get_position(&self, user: Address, pair: Address) -> LoyaltyPosition {
+        let mut position = self.positions.get(&(user, pair)).unwrap_or_default();
+        let now = self.env().get_block_time();
+        let elapsed = now.saturating_sub(position.last_update);
+        position.liquidity_seconds = position.liquidity_seconds
+            .saturating_add(position.lp_amount.saturating_mul(U256::from(elapsed)));
+        position.last_update = now;
+        position
+    }
+
+    /// Index of the highest tier a user qualifies for in a pair
+    /// The share is the time-weighted balance since the position opened,
+    /// capped at the current balance
+    pub fn get_tier(&self, user: Address, pair: Address) -> Option<u8> {
+        let position = self.get_position(user, pair);
+        if position.lp_amount.is_zero() {
+            return None;
+        }
+        let supply = Cep18TokenContractRef::new(self.env(), pair).total_supply();
+        if supply.is_zero() {
+            return None;
+        }
+        let held = self.env().get_block_time().saturating_sub(position.held_since);
+        let average = if held == 0 {
+            position.lp_amount
+        } else {
+            let held_seconds = position.liquidity_seconds.saturating_sub(position.entry_liquidity_seconds);
+            (held_seconds / U256::from(held)).min(position.lp_amount)
+        };
+        let share_bps = SafeMath::mul_div(average, U256::from(BPS_DENOMINATOR), supply)
+            .unwrap_or_else(|e| self.env().revert(e));
+        self.get_tiers()
+            .iter()
+            .rposition(|tier| share_bps >= U256::from(tier.min_share_bps) && held >= tier.min_duration)
+            .map(|index| index as u8)
+    }
+
+    /// Share of swap fees rebated to a user trading through a pair, in basis points
+    pub fn get_discount_bps(&self, user: Address, pair: Address) -> u16 {
+        match self.get_tier(user, pair) {
+            Some(index) => self.get_tiers()[index as usize].discount_bps,
+            None => 0,
+        }
+    }
+
+    pub fn get_tiers(&self) -> Vec<LoyaltyTier> {
+        self.tiers.get_or_default()
+    }
+
+    pub fn is_rebater(&self, account: Address) -> bool {
+        self.rebaters.get(&account).unwrap_or(false)
+    }
+
+    pub fn get_admin(&self) -> Address {
+        self.admin.get_or_revert_with(DexError::Unauthorized)
+    }
+
+    pub fn get_factory(&self) -> Address {
+        self.factory.get_or_revert_with(DexError::InvalidConfiguration)
+    }
+
+    // ========================================
+    // Internal Functions
+    // ========================================
+
+    fn only_admin(&self) {
+        if self.env().caller() != self.get_admin() {
+            self.env().revert(DexError::Unauthorized);
+        }
+    }
+
+    /// Whether `pair` was created by (or registered with) the factory
+    fn is_registered_pair(&self, pair: Address) -> bool {
+        FactoryContractContractRef::new(self.env(), self.get_factory())
+            .pair_type_of(pair)
+            .is_some()
+    }
+}
+
+/// External interface for requesting loyalty rebates
+#[odra::external_contract]
+pub trait LoyaltyRebater {
+    fn rebate(&mut self, trader: Address, pair: Address, token: Address, fee: U256) -> U256;
+}
+
+// Events
+
+#[odra::event]
+pub struct LoyaltyTiersUpdated {
+    pub tiers: Vec<LoyaltyTier>,
+}
+
+#[odra::event]
+pub struct LoyaltyPositionUpdated {
+    pub user: Address,
+    pub pair: Address,
+    pub lp_amount: U256,
+    pub held_since: u64,
+}
+
+#[odra::event]
+pub struct LoyaltyRebatePaid {
+    pub trader: Address,
+    pub pair: Address,
+    pub token: Address,
+    pub amount: U256,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use odra::host::Deployer;
+    use crate::dex::factory::DEFAULT_PAIR_TYPE;
+    use crate::testing::{Fixture, RoutedDex};
+
+    const DAY: u64 = 24 * 60 * 60;
+
+    #[test]
+    fn test_tiers_follow_lp_history() {
+        let mut fixture = Fixture::new();
+        let env = fixture.env.clone();
+        let admin = fixture.admin;
+        let provider = fixture.account(1);
+        let router = fixture.account(3);
+        let token_a = fixture.dex.token_a.address().clone();
+        let token_b = fixture.dex.token_b.address().clone();
+        let mut dex = RoutedDex::deploy(&env, fixture.dex.wcspr.address().clone());
+        let mut pair = dex.add_pair(&env, token_a, token_b, DEFAULT_PAIR_TYPE);
+        let pair_addr = pair.address().clone();
+
+        let mut loyalty = LpLoyalty::deploy(&env, LpLoyaltyInitArgs {
+            factory: dex.factory.address().clone(),
+        });
+        assert_eq!(loyalty.get_admin(), admin);
+        loyalty.set_tiers(vec![
+            LoyaltyTier { min_share_bps: 1_000, min_duration: 7 * DAY, discount_bps: 2_500 },
+            LoyaltyTier { min_share_bps: 5_000, min_duration: 30 * DAY, discount_bps: 5_000 },
+        ]);
+        loyalty.set_rebater(router, true);
+        // Account 0 is the pair's factory; the protocol keeps 20% of each fee
+        pair.set_transfer_listeners(vec![loyalty.address().clone()]);
+        pair.set_protocol_fee_share_bps(2_000);
+        pair.set_rebater(Some(loyalty.address().clone()));
+
+        env.set_caller(provider);
+        fixture.dex.token_a.transfer(pair_addr, U256::from(1_000_000u64));
+        fixture.dex.token_b.transfer(pair_addr, U256::from(1_000_000u64));
+        let lp_amount = pair.mint(provider);
+        assert_eq!(loyalty.get_position(provider, pair_addr).lp_amount, lp_amount);
+        assert_eq!(loyalty.get_tier(provider, pair_addr), None);
+
+        // A 300 fee on 100_000 in sets 60 aside for the protocol
+        fixture.dex.token_a.transfer(pair_addr, U256::from(100_000u64));
+        pair.swap_exact_in(U256::zero(), provider);
+        assert_eq!(pair.protocol_fees(token_a), U256::from(60u64));
+
+        // A week in: first tier, but its 25% is capped at the protocol's 20%
+        env.advance_block_time(7 * DAY);
+        assert_eq!(loyalty.get_tier(provider, pair_addr), Some(0));
+        assert_eq!(
+            loyalty.get_position(provider, pair_addr).liquidity_seconds,
+            lp_amount * U256::from(7 * DAY)
+        );
+        let before = fixture.dex.token_a.balance_of(provider);
+        env.set_caller(router);
+        assert_eq!(loyalty.rebate(provider, pair_addr, token_a, U256::from(100u64)), U256::from(20u64));
+        assert_eq!(fixture.dex.token_a.balance_of(provider) - before, U256::from(20u64));
+        assert_eq!(pair.protocol_fees(token_a), U256::from(40u64));
+
+        // Pairs the factory does not know are never rebated
+        let stray = fixture.dex.deploy_pair(&env);
+        assert_eq!(
+            loyalty.try_rebate(provider, stray.address().clone(), token_a, U256::from(100u64)),
+            Err(DexError::PairNotFound.into())
+        );
+
+        // Only rebaters can pay out
+        env.set_caller(provider);
+        assert_eq!(
+            loyalty.try_rebate(provider, pair_addr, token_a, U256::from(100u64)),
+            Err(DexError::Unauthorized.into())
+        );
+
+        // Exiting the position resets the holding time
+        pair.transfer(pair_addr, lp_amount);
+        pair.burn(provider);
+        assert_eq!(loyalty.get_position(provider, pair_addr).held_since, 0);
+        assert_eq!(loyalty.get_discount_bps(provider, pair_addr), 0);
+    }
+
+    #[test]
+    fn test_only_registered_pairs_report_transfers() {
+        let mut fixture = Fixture::new();
+        let env = fixture.env.clone();
+        let provider = fixture.account(1);
+        let token_a = fixture.dex.token_a.address().clone();
+        let token_b = fixture.dex.token_b.address().clone();
+        let mut dex = RoutedDex::deploy(&env, fixture.dex.wcspr.address().clone());
+        let pair = dex.add_pair(&env, token_a, token_b, DEFAULT_PAIR_TYPE);
+
+        let mut loyalty = LpLoyalty::deploy(&env, LpLoyaltyInitArgs {
+            factory: dex.factory.address().clone(),
+        });
+
+        // A caller posing as a pair cannot forge a position
+        env.set_caller(provider);
+        assert_eq!(
+            loyalty.try_on_lp_transfer(pair.address().clone(), provider, U256::from(1_000u64)),
+            Err(DexError::Unauthorized.into())
+        );
+
+        // Nor can a pair the factory never registered
+        let mut stray = fixture.dex.deploy_pair(&env);
+        env.set_caller(env.get_account(0));
+        stray.set_transfer_listeners(vec![loyalty.address().clone()]);
+        let stray_addr = stray.address().clone();
+        env.set_caller(provider);
+        fixture.dex.token_a.transfer(stray_addr, U256::from(1_000_000u64));
+        fixture.dex.token_b.transfer(stray_addr, U256::from(1_000_000u64));
+        assert_eq!(stray.try_mint(provider), Err(DexError::Unauthorized.into()));
+    }
+
+    #[test]
+    fn test_top_up_before_trade_does_not_unlock_a_tier() {
+        let mut fixture = Fixture::new();
+        let env = fixture.env.clone();
+        let provider = fixture.account(1);
+        let other = fixture.account(2);
+        let token_a = fixture.dex.token_a.address().clone();
+        let token_b = fixture.dex.token_b.address().clone();
+        let mut dex = RoutedDex::deploy(&env, fixture.dex.wcspr.address().clone());
+        let mut pair = dex.add_pair(&env, token_a, token_b, DEFAULT_PAIR_TYPE);
+        let pair_addr = pair.address().clone();
+
+        let mut loyalty = LpLoyalty::deploy(&env, LpLoyaltyInitArgs {
+            factory: dex.factory.address().clone(),
+        });
+        loyalty.set_tiers(vec![
+            LoyaltyTier { min_share_bps: 1_000, min_duration: 7 * DAY, discount_bps: 2_500 },
+        ]);
+        pair.set_transfer_listeners(vec![loyalty.address().clone()]);
+
+        env.set_caller(other);
+        fixture.dex.token_a.transfer(pair_addr, U256::from(1_000_000u64));
+        fixture.dex.token_b.transfer(pair_addr, U256::from(1_000_000u64));
+        pair.mint(other);
+
+        // Dust held for a month, then topped up to half the supply
+        env.set_caller(provider);
+        fixture.dex.token_a.transfer(pair_addr, U256::from(1_000u64));
+        fixture.dex.token_b.transfer(pair_addr, U256::from(1_000u64));
+        pair.mint(provider);
+        env.advance_block_time(30 * DAY);
+        fixture.dex.token_a.transfer(pair_addr, U256::from(1_000_000u64));
+        fixture.dex.token_b.transfer(pair_addr, U256::from(1_000_000u64));
+        pair.mint(provider);
+        assert_eq!(loyalty.get_tier(provider, pair_addr), None);
+
+        // A day later the average is still far below the tier's share
+        env.advance_block_time(DAY);
+        assert_eq!(loyalty.get_tier(provider, pair_addr), None);
+
+        // Holding the larger balance long enough earns the tier
+        env.advance_block_time(9 * DAY);
+        assert_eq!(loyalty.get_tier(provider, pair_addr), Some(0));
+    }
+}
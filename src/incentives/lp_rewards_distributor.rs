@@ -9,6 +9,10 @@
 //! - +0.5x: Active borrower (borrowing ECTO)
 //! - +0.2x: Hold sCSPR (supporting network security)
 //! - Max: 2.0x total multiplier
+//!
+//! Pairs can register this contract as an LP transfer listener; positions
+//! then follow the holder's LP balance automatically, with rewards earned
+//! up to the transfer kept aside for the next claim.
 
 use odra::prelude::*;
 use odra::casper_types::U256;
@@ -62,6 +66,8 @@ pub struct LpRewardsDistributor {
     admin: Var<Address>,
     /// LP positions (user + pair -> LpPosition)
     lp_positions: Mapping<(Address, Address), LpPosition>,
+    /// Rewards earned before the position last changed, not yet claimed
+    accrued_rewards: Mapping<(Address, Address), U256>,
     /// Total rewards distributed
     total_rewards_distributed: Var<U256>,
     /// Rewards pool balance
@@ -227,19 +233,9 @@ impl LpRewardsDistributor {
         
         let position = position.unwrap();
         
-        // Calculate rewards since last update
         let current_time = self.env().get_block_time();
-        let time_elapsed = current_time - position.last_update;
-        
-        // Calculate rewards based on effective APR
-        // rewards = (lp_amount * effective_apr * time_elapsed) / (365 days * 1e18)
-        let seconds_per_year = U256::from(365 * 24 * 60 * 60);
-        let scale = U256::from(10u128.pow(18));
-        
-        let rewards = position.lp_amount
-            * position.effective_apr
-            * U256::from(time_elapsed)
-            / (seconds_per_year * scale);
+        let rewards = self.rewards_since_update(&position)
+            + self.accrued_rewards.get(&(caller, pair)).unwrap_or_default();
         
         if rewards == U256::zero() {
             return U256::zero();
@@ -259,6 +255,7 @@ impl LpRewardsDistributor {
         self.total_rewards_distributed.set(total + rewards);
         
         // Update position timestamp
+        self.accrued_rewards.set(&(caller, pair), U256::zero());
         let mut updated_position = position;
         updated_position.last_update = current_time;
         self.lp_positions.set(&(caller, pair), updated_position);
@@ -294,6 +291,7 @@ impl LpRewardsDistributor {
         }
         
         let mut position = position.unwrap();
+        self.checkpoint(&position);
         
         // Recalculate boost
         let boost_factors = self.calculate_boost_factors(user);
@@ -311,6 +309,31 @@ impl LpRewardsDistributor {
         self.lp_positions.set(&(user, pair), position);
     }
     
+    /// LP transfer hook: resync the positions of both sides to their LP balance
+    /// The caller is the pair; only already registered positions are touched
+    pub fn on_lp_transfer(&mut self, from: Address, to: Address, _amount: U256) {
+        let pair = self.env().caller();
+        for user in [from, to] {
+            if user == pair {
+                continue;
+            }
+            let Some(mut position) = self.lp_positions.get(&(user, pair)) else {
+                continue;
+            };
+            self.checkpoint(&position);
+            position.lp_amount = Cep18TokenContractRef::new(self.env(), pair).balance_of(user);
+            position.last_update = self.env().get_block_time();
+            self.lp_positions.set(&(user, pair), position.clone());
+
+            self.env().emit_event(LpPositionSynced {
+                user,
+                pair,
+                lp_amount: position.lp_amount,
+                timestamp: position.last_update,
+            });
+        }
+    }
+    
     // Note: Odra Mapping doesn't support remove()
     // To "remove" a position, set lp_amount to zero using update_lp_position
     // /// Remove an LP position
@@ -328,6 +351,26 @@ impl LpRewardsDistributor {
     // Helper Functions
     // ========================================
     
+    /// Rewards earned by a position since its last update
+    fn rewards_since_update(&self, position: &LpPosition) -> U256 {
+        // rewards = (lp_amount * effective_apr * time_elapsed) / (365 days * 1e18)
+        let time_elapsed = self.env().get_block_time() - position.last_update;
+        let seconds_per_year = U256::from(365 * 24 * 60 * 60);
+        let scale = U256::from(10u128.pow(18));
+        
+        position.lp_amount
+            * position.effective_apr
+            * U256::from(time_elapsed)
+            / (seconds_per_year * scale)
+    }
+    
+    /// Set aside rewards earned so far before a position changes
+    fn checkpoint(&mut self, position: &LpPosition) {
+        let key = (position.user, position.pair);
+        let accrued = self.accrued_rewards.get(&key).unwrap_or_default();
+        self.accrued_rewards.set(&key, accrued + self.rewards_since_update(position));
+    }
+    
     /// Get user's aECTO balance
     fn get_aecto_balance(&self, user: Address) -> U256 {
         let aecto_address = match self.aecto_token.get() {
@@ -400,19 +443,8 @@ impl LpRewardsDistributor {
         }
         
         let position = position.unwrap();
-        
-        let current_time = self.env().get_block_time();
-        let time_elapsed = current_time - position.last_update;
-        
-        let seconds_per_year = U256::from(365 * 24 * 60 * 60);
-        let scale = U256::from(10u128.pow(18));
-        
-        let rewards = position.lp_amount
-            * position.effective_apr
-            * U256::from(time_elapsed)
-            / (seconds_per_year * scale);
-        
-        rewards
+        self.rewards_since_update(&position)
+            + self.accrued_rewards.get(&(user, pair)).unwrap_or_default()
     }
     
     // ========================================
@@ -522,6 +554,14 @@ pub struct LpPositionRegistered {
     pub timestamp: u64,
 }
 
+#[odra::event]
+pub struct LpPositionSynced {
+    pub user: Address,
+    pub pair: Address,
+    pub lp_amount: U256,
+    pub timestamp: u64,
+}
+
 #[odra::event]
 pub struct LpPositionRemoved {
     pub user: Address,
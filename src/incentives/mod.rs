@@ -1,7 +1,11 @@
 pub mod gas_discount;
 pub mod lp_rewards_distributor;
 pub mod incentive_manager;
+pub mod competition;
+pub mod lp_loyalty;
 
 pub use gas_discount::*;
 pub use lp_rewards_distributor::*;
 pub use incentive_manager::*;
+pub use competition::*;
+pub use lp_loyalty::*;
@@ -27,3 +27,16 @@ pub mod farming;
 
 // Incentive System modules
 pub mod incentives;
+
+// Governance modules
+pub mod governance;
+
+// Periphery modules
+pub mod periphery;
+
+// Integration helpers
+pub mod sdk;
+
+// Test fixtures
+#[cfg(test)]
+pub mod testing;
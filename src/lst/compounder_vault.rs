@@ -0,0 +1,532 @@
+//! Compounder Vault - Auto-compounds sCSPR staking yield into DEX liquidity
+//!
+//! Users deposit sCSPR and receive vault shares. sCSPR gains value as the
+//! exchange rate grows; on each harvest the vault takes part of the CSPR value
+//! gained since the last harvest, swaps half of it to WCSPR on the DEX and adds
+//! both sides to the sCSPR/WCSPR pair. The vault then earns LP fees on top of
+//! the staking APY.
+//!
+//! Shares are priced in CSPR (idle sCSPR at the staking exchange rate, LP at
+//! its share of the pair reserves). Withdrawals are paid in kind: a pro-rata
+//! slice of the idle sCSPR, WCSPR and LP tokens, so exits never depend on the
+//! pool price.
+
+use odra::prelude::*;
+use odra::casper_types::U256;
+use odra::ContractRef;
+use super::errors::LstError;
+use super::events::{VaultDeposited, VaultWithdrawn, YieldCompounded};
+use super::staking_manager::StakingManagerContractRef;
+use crate::dex::router::{PairContractContractRef, RouterContractRef};
+use crate::math::SafeMath;
+use crate::token::Cep18TokenContractRef;
+
+/// Basis points denominator
+const BPS_DENOMINATOR: u32 = 10_000;
+
+/// Price-per-share scale (1e18)
+const SHARE_PRICE_SCALE: u128 = 1_000_000_000_000_000_000;
+
+/// Default share of harvested yield moved into LP (50%)
+const DEFAULT_COMPOUND_BPS: u32 = 5_000;
+
+/// Compounder Vault contract
+#[odra::module]
+pub struct CompounderVault {
+    /// sCSPR token address
+    scspr_token: Var<Address>,
+    /// Staking manager used for exchange-rate conversions
+    staking_manager: Var<Address>,
+    /// DEX router used to swap and add liquidity
+    router: Var<Address>,
+    /// WCSPR token address
+    wcspr: Var<Address>,
+    /// sCSPR/WCSPR pair (LP token) address
+    pair: Var<Address>,
+    /// Contract admin
+    admin: Var<Address>,
+    /// Keeper allowed to harvest besides the admin
+    keeper: Var<Option<Address>>,
+    /// Share of harvested yield moved into LP, in basis points
+    compound_bps: Var<u32>,
+    /// Total vault shares
+    total_shares: Var<U256>,
+    /// Vault shares per user
+    shares: Mapping<Address, U256>,
+    /// sCSPR held by the vault and not in LP
+    idle_scspr: Var<U256>,
+    /// WCSPR left over from adding liquidity
+    idle_wcspr: Var<U256>,
+    /// LP tokens held by the vault
+    lp_balance: Var<U256>,
+    /// CSPR value of the idle sCSPR at the last checkpoint
+    checkpoint_value: Var<U256>,
+    /// Sequence number of the last event emitted
+    event_seq: Var<u64>,
+}
+
+#[odra::module]
+impl CompounderVault {
+    /// Initialize the vault
+    pub fn init(
+        &mut self,
+        scspr_token: Address,
+        staking_manager: Address,
+        router: Address,
+        wcspr: Address,
+        pair: Address,
+    ) {
+        self.scspr_token.set(scspr_token);
+        self.staking_manager.set(staking_manager);
+        self.router.set(router);
+        self.wcspr.set(wcspr);
+        self.pair.set(pair);
+        self.admin.set(self.env().caller());
+        self.compound_bps.set(DEFAULT_COMPOUND_BPS);
+        self.total_shares.set(U256::zero());
+    }
+
+    // ========================================
+    // User Functions
+    // ========================================
+
+    /// Deposit sCSPR and receive vault shares priced in CSPR
+    pub fn deposit(&mut self, scspr_amount: U256) -> U256 {
+        if scspr_amount.is_zero() {
+            self.env().revert(LstError::InvalidAmount);
+        }
+        let caller = self.env().caller();
+
+        // Price the deposit before it changes the vault
+        let total_value = self.total_value();
+        let deposit_value = self.cspr_value(scspr_amount);
+        let total_shares = self.total_shares.get_or_default();
+        let shares = if total_shares.is_zero() || total_value.is_zero() {
+            deposit_value
+        } else {
+            self.mul_div(deposit_value, total_shares, total_value)
+        };
+        if shares.is_zero() {
+            self.env().revert(LstError::InvalidAmount);
+        }
+
+        let scspr = self.scspr_token.get_or_revert_with(LstError::InvalidAmount);
+        self.safe_transfer_from(scspr, caller, self.env().self_address(), scspr_amount);
+
+        // Keep unharvested yield out of the new deposit's checkpoint
+        let pending = self.pending_yield();
+        let idle = self.idle_scspr.get_or_default() + scspr_amount;
+        self.idle_scspr.set(idle);
+        self.checkpoint_value.set(self.cspr_value(idle).saturating_sub(pending));
+
+        self.total_shares.set(total_shares + shares);
+        let user_shares = self.shares.get(&caller).unwrap_or_default();
+        self.shares.set(&caller, user_shares + shares);
+
+        self.env().emit_event(VaultDeposited {
+            user: caller,
+            scspr_amount,
+            shares,
+            seq: self.next_event_seq(),
+        });
+
+        shares
+    }
+
+    /// Burn shares for a pro-rata slice of idle sCSPR, WCSPR and LP tokens
+    /// Returns (sCSPR, WCSPR, LP) paid out
+    pub fn withdraw(&mut self, shares: U256) -> (U256, U256, U256) {
+        let caller = self.env().caller();
+        let user_shares = self.shares.get(&caller).unwrap_or_default();
+        if shares.is_zero() || shares > user_shares {
+            self.env().revert(LstError::InvalidAmount);
+        }
+        let total_shares = self.total_shares.get_or_default();
+
+        let idle = self.idle_scspr.get_or_default();
+        let idle_wcspr = self.idle_wcspr.get_or_default();
+        let lp = self.lp_balance.get_or_default();
+        let scspr_out = self.mul_div(idle, shares, total_shares);
+        let wcspr_out = self.mul_div(idle_wcspr, shares, total_shares);
+        let lp_out = self.mul_div(lp, shares, total_shares);
+
+        // Unharvested yield leaves with the withdrawn sCSPR pro rata
+        let pending = self.pending_yield();
+        let remaining = idle - scspr_out;
+        let remaining_pending = if idle.is_zero() {
+            U256::zero()
+        } else {
+            self.mul_div(pending, remaining, idle)
+        };
+        self.idle_scspr.set(remaining);
+        self.idle_wcspr.set(idle_wcspr - wcspr_out);
+        self.lp_balance.set(lp - lp_out);
+        self.checkpoint_value.set(self.cspr_value(remaining).saturating_sub(remaining_pending));
+
+        self.shares.set(&caller, user_shares - shares);
+        self.total_shares.set(total_shares - shares);
+
+        if !scspr_out.is_zero() {
+            let scspr = self.scspr_token.get_or_revert_with(LstError::InvalidAmount);
+            self.safe_transfer(scspr, caller, scspr_out);
+        }
+        if !wcspr_out.is_zero() {
+            let wcspr = self.wcspr.get_or_revert_with(LstError::InvalidAmount);
+            self.safe_transfer(wcspr, caller, wcspr_out);
+        }
+        if !lp_out.is_zero() {
+            let pair = self.pair.get_or_revert_with(LstError::InvalidAmount);
+            self.safe_transfer(pair, caller, lp_out);
+        }
+
+        self.env().emit_event(VaultWithdrawn {
+            user: caller,
+            shares,
+            scspr_amount: scspr_out,
+            wcspr_amount: wcspr_out,
+            lp_amount: lp_out,
+            seq: self.next_event_seq(),
+        });
+
+        (scspr_out, wcspr_out, lp_out)
+    }
+
+    // ========================================
+    // Keeper Functions
+    // ========================================
+
+    /// Move `compound_bps` of the yield since the last harvest into LP
+    /// `min_wcspr_out` bounds the swap against sandwiching
+    /// Returns the LP tokens minted (zero if there was nothing to compound)
+    pub fn harvest(&mut self, min_wcspr_out: U256, deadline: u64) -> U256 {
+        self.only_keeper();
+
+        let yield_cspr = self.pending_yield();
+        let compound_cspr = self.mul_div(
+            yield_cspr,
+            U256::from(self.compound_bps.get_or_default()),
+            U256::from(BPS_DENOMINATOR),
+        );
+        let staking_manager = self.staking_manager_ref();
+        let compound_scspr = staking_manager.get_scspr_by_cspr(compound_cspr);
+        let swap_amount = compound_scspr / 2;
+        if swap_amount.is_zero() {
+            return U256::zero();
+        }
+
+        let scspr = self.scspr_token.get_or_revert_with(LstError::InvalidAmount);
+        let wcspr = self.wcspr.get_or_revert_with(LstError::InvalidAmount);
+        let router_address = self.router.get_or_revert_with(LstError::InvalidAmount);
+        let vault = self.env().self_address();
+        let mut router = RouterContractRef::new(self.env(), router_address);
+
+        // Swap half of the compounded sCSPR to WCSPR
+        self.safe_approve(scspr, router_address, compound_scspr);
+        let amounts = router.swap_exact_tokens_for_tokens(
+            swap_amount,
+            min_wcspr_out,
+            vec![scspr, wcspr],
+            vault,
+            deadline,
+        );
+        let wcspr_received = amounts[amounts.len() - 1];
+
+        // Add both sides; leftovers stay idle in the vault
+        let idle_wcspr = self.idle_wcspr.get_or_default() + wcspr_received;
+        self.safe_approve(wcspr, router_address, idle_wcspr);
+        let (scspr_used, wcspr_used, lp_minted) = router.add_liquidity(
+            scspr,
+            wcspr,
+            compound_scspr - swap_amount,
+            idle_wcspr,
+            U256::zero(),
+            U256::zero(),
+            vault,
+            deadline,
+        );
+
+        let idle = self.idle_scspr.get_or_default() - swap_amount - scspr_used;
+        self.idle_scspr.set(idle);
+        self.idle_wcspr.set(idle_wcspr - wcspr_used);
+        self.lp_balance.set(self.lp_balance.get_or_default() + lp_minted);
+        self.checkpoint_value.set(self.cspr_value(idle));
+
+        self.env().emit_event(YieldCompounded {
+            yield_cspr,
+            scspr_compounded: compound_scspr,
+            wcspr_received,
+            lp_minted,
+            timestamp: self.env().get_block_time(),
+            seq: self.next_event_seq(),
+        });
+
+        lp_minted
+    }
+
+    // ========================================
+    // Views
+    // ========================================
+
+    /// Total vault value in CSPR (idle sCSPR, idle WCSPR and LP)
+    pub fn total_value(&self) -> U256 {
+        let idle_value = self.cspr_value(self.idle_scspr.get_or_default());
+        idle_value + self.idle_wcspr.get_or_default() + self.lp_value()
+    }
+
+    /// CSPR value of one share, scaled by 1e18
+    pub fn price_per_share(&self) -> U256 {
+        let total_shares = self.total_shares.get_or_default();
+        if total_shares.is_zero() {
+            return U256::from(SHARE_PRICE_SCALE);
+        }
+        self.mul_div(self.total_value(), U256::from(SHARE_PRICE_SCALE), total_shares)
+    }
+
+    /// CSPR yield accrued on idle sCSPR since the last checkpoint
+    pub fn pending_yield(&self) -> U256 {
+        let value = self.cspr_value(self.idle_scspr.get_or_default());
+        value.saturating_sub(self.checkpoint_value.get_or_default())
+    }
+
+    /// Shares held by a user
+    pub fn shares_of(&self, user: Address) -> U256 {
+        self.shares.get(&user).unwrap_or_default()
+    }
+
+    /// Total shares outstanding
+    pub fn total_shares(&self) -> U256 {
+        self.total_shares.get_or_default()
+    }
+
+    /// Vault holdings as (idle sCSPR, idle WCSPR, LP tokens)
+    pub fn holdings(&self) -> (U256, U256, U256) {
+        (
+            self.idle_scspr.get_or_default(),
+            self.idle_wcspr.get_or_default(),
+            self.lp_balance.get_or_default(),
+        )
+    }
+
+    /// Share of harvested yield moved into LP, in basis points
+    pub fn compound_bps(&self) -> u32 {
+        self.compound_bps.get_or_default()
+    }
+
+    /// Get the keeper address
+    pub fn keeper(&self) -> Option<Address> {
+        self.keeper.get_or_default()
+    }
+
+    // ========================================
+    // Admin Functions
+    // ========================================
+
+    /// Set the share of harvested yield moved into LP
+    pub fn set_compound_bps(&mut self, compound_bps: u32) {
+        self.only_admin();
+        if compound_bps > BPS_DENOMINATOR {
+            self.env().revert(LstError::InvalidAmount);
+        }
+        self.compound_bps.set(compound_bps);
+    }
+
+    /// Set (or clear) the keeper allowed to harvest
+    pub fn set_keeper(&mut self, keeper: Option<Address>) {
+        self.only_admin();
+        self.keeper.set(keeper);
+    }
+
+    /// Transfer admin rights
+    pub fn transfer_admin(&mut self, new_admin: Address) {
+        self.only_admin();
+        self.admin.set(new_admin);
+    }
+
+    // ========================================
+    // Internal Functions
+    // ========================================
+
+    fn staking_manager_ref(&self) -> StakingManagerContractRef {
+        let address = self.staking_manager.get_or_revert_with(LstError::InvalidAmount);
+        StakingManagerContractRef::new(self.env(), address)
+    }
+
+    /// CSPR value of an sCSPR amount at the current exchange rate
+    fn cspr_value(&self, scspr_amount: U256) -> U256 {
+        if scspr_amount.is_zero() {
+            return U256::zero();
+        }
+        self.staking_manager_ref().get_cspr_by_scspr(scspr_amount)
+    }
+
+    /// CSPR value of the vault's LP tokens at the pair reserves
+    fn lp_value(&self) -> U256 {
+        let lp = self.lp_balance.get_or_default();
+        if lp.is_zero() {
+            return U256::zero();
+        }
+        let pair = self.pair.get_or_revert_with(LstError::InvalidAmount);
+        let supply = Cep18TokenContractRef::new(self.env(), pair).total_supply();
+        if supply.is_zero() {
+            return U256::zero();
+        }
+        let pair_ref = PairContractContractRef::new(self.env(), pair);
+        let (reserve0, reserve1, _) = pair_ref.get_reserves();
+        let scspr = self.scspr_token.get_or_revert_with(LstError::InvalidAmount);
+        let (scspr_reserve, wcspr_reserve) = if pair_ref.token0() == scspr {
+            (reserve0, reserve1)
+        } else {
+            (reserve1, reserve0)
+        };
+
+        let scspr_share = self.mul_div(scspr_reserve, lp, supply);
+        let wcspr_share = self.mul_div(wcspr_reserve, lp, supply);
+        self.cspr_value(scspr_share) + wcspr_share
+    }
+
+    fn mul_div(&self, a: U256, b: U256, c: U256) -> U256 {
+        SafeMath::mul_div(a, b, c)
+            .unwrap_or_else(|_| self.env().revert(LstError::ExchangeRateError))
+    }
+
+    fn safe_transfer(&self, token: Address, to: Address, amount: U256) {
+        let mut token_ref = Cep18TokenContractRef::new(self.env(), token);
+        if !token_ref.transfer(to, amount) {
+            self.env().revert(LstError::WithdrawalFailed);
+        }
+    }
+
+    fn safe_transfer_from(&self, token: Address, from: Address, to: Address, amount: U256) {
+        let mut token_ref = Cep18TokenContractRef::new(self.env(), token);
+        if !token_ref.transfer_from(from, to, amount) {
+            self.env().revert(LstError::InsufficientScsprBalance);
+        }
+    }
+
+    fn safe_approve(&self, token: Address, spender: Address, amount: U256) {
+        let mut token_ref = Cep18TokenContractRef::new(self.env(), token);
+        if !token_ref.approve(spender, amount) {
+            self.env().revert(LstError::StakingFailed);
+        }
+    }
+
+    fn only_admin(&self) {
+        let admin = self.admin.get_or_revert_with(LstError::Unauthorized);
+        if self.env().caller() != admin {
+            self.env().revert(LstError::Unauthorized);
+        }
+    }
+
+    fn only_keeper(&self) {
+        let caller = self.env().caller();
+        let is_keeper = self.keeper.get_or_default() == Some(caller);
+        let admin = self.admin.get_or_revert_with(LstError::Unauthorized);
+        if !is_keeper && caller != admin {
+            self.env().revert(LstError::Unauthorized);
+        }
+    }
+
+    /// Sequence number of the last event emitted; indexers page from here
+    pub fn event_seq(&self) -> u64 {
+        self.event_seq.get_or_default()
+    }
+
+    /// Advance the event sequence and return the number for the next event
+    fn next_event_seq(&mut self) -> u64 {
+        let seq = self.event_seq.get_or_default() + 1;
+        self.event_seq.set(seq);
+        seq
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use odra::host::{Deployer, HostEnv};
+    use crate::lst::scspr_token::{ScsprToken, ScsprTokenHostRef, ScsprTokenInitArgs};
+    use crate::lst::staking_manager::{StakingManager, StakingManagerHostRef, StakingManagerInitArgs};
+
+    fn cspr(amount: u64) -> U256 {
+        U256::from(amount) * U256::from(1_000_000_000u64)
+    }
+
+    fn setup() -> (HostEnv, CompounderVaultHostRef, StakingManagerHostRef, ScsprTokenHostRef) {
+        let env = odra_test::env();
+        let mut scspr_token = ScsprToken::deploy(&env, ScsprTokenInitArgs {
+            staking_manager: env.get_account(8),
+        });
+        let staking_manager = StakingManager::deploy(&env, StakingManagerInitArgs {
+            scspr_token_address: scspr_token.address(),
+        });
+        scspr_token.set_staking_manager(staking_manager.address());
+
+        // The DEX is not touched until the first harvest
+        let vault = CompounderVault::deploy(&env, CompounderVaultInitArgs {
+            scspr_token: scspr_token.address(),
+            staking_manager: staking_manager.address(),
+            router: env.get_account(5),
+            wcspr: env.get_account(6),
+            pair: env.get_account(7),
+        });
+        (env, vault, staking_manager, scspr_token)
+    }
+
+    #[test]
+    fn test_share_price_tracks_staking_yield() {
+        let (env, mut vault, mut staking_manager, mut scspr_token) = setup();
+        let user = env.get_account(2);
+
+        env.set_caller(user);
+        let scspr = staking_manager.stake(cspr(1000));
+        scspr_token.approve(vault.address(), scspr);
+        let shares = vault.deposit(scspr);
+        assert_eq!(shares, cspr(1000));
+        assert_eq!(vault.price_per_share(), U256::from(SHARE_PRICE_SCALE));
+
+        // 10% staking rewards lift the share price and show up as pending yield
+        env.set_caller(env.get_account(0));
+        staking_manager.distribute_rewards(cspr(100));
+        assert_eq!(vault.pending_yield(), cspr(100));
+        assert_eq!(vault.price_per_share(), U256::from(SHARE_PRICE_SCALE) * 11 / 10);
+
+        // Withdrawal pays the full sCSPR back and clears the pending yield
+        env.set_caller(user);
+        let (scspr_out, wcspr_out, lp_out) = vault.withdraw(shares);
+        assert_eq!(scspr_out, scspr);
+        assert!(wcspr_out.is_zero() && lp_out.is_zero());
+        assert_eq!(scspr_token.balance_of(user), scspr);
+        assert_eq!(vault.pending_yield(), U256::zero());
+    }
+
+    #[test]
+    fn test_late_depositor_does_not_take_pending_yield() {
+        let (env, mut vault, mut staking_manager, mut scspr_token) = setup();
+        let early = env.get_account(2);
+        let late = env.get_account(3);
+
+        env.set_caller(early);
+        let scspr = staking_manager.stake(cspr(1000));
+        scspr_token.approve(vault.address(), scspr);
+        let early_shares = vault.deposit(scspr);
+
+        env.set_caller(env.get_account(0));
+        staking_manager.distribute_rewards(cspr(100));
+
+        env.set_caller(late);
+        let scspr = staking_manager.stake(cspr(1100));
+        scspr_token.approve(vault.address(), scspr);
+        let late_shares = vault.deposit(scspr);
+
+        // Same CSPR value in, same shares out; pending yield is unchanged
+        assert_eq!(late_shares, early_shares);
+        assert_eq!(vault.pending_yield(), cspr(100));
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized")]
+    fn test_only_keeper_harvests() {
+        let (env, mut vault, _, _) = setup();
+        env.set_caller(env.get_account(4));
+        vault.harvest(U256::zero(), u64::MAX);
+    }
+}
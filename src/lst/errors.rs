@@ -66,4 +66,37 @@ pub enum LstError {
     
     /// Transfer to validator failed
     TransferToValidatorFailed = 220,
+    
+    /// Parameter registry is not configured
+    ParameterRegistryNotSet = 221,
+    
+    /// Validator score values out of range
+    InvalidScore = 222,
+    
+    /// New stakes are paused
+    DepositsPaused = 223,
+    
+    /// Contract is in withdrawals-only mode
+    WithdrawalsOnly = 224,
+    
+    /// Peg arbitrage is not configured
+    PegArbitrageNotConfigured = 225,
+    
+    /// DEX price is within the peg threshold
+    PegWithinThreshold = 226,
+    
+    /// Peg buffer holds too little WCSPR
+    InsufficientPegBuffer = 227,
+    
+    /// Current fee epoch has not ended
+    EpochNotFinished = 228,
+    
+    /// No WCSPR waiting to be distributed
+    NothingToDistribute = 229,
+    
+    /// Withdrawal would take the insurance fund below its target
+    InsuranceBelowTarget = 230,
+    
+    /// No peg price observation inside the TWAP window
+    PegTwapNotReady = 231,
 }
@@ -1,6 +1,7 @@
 //! Event definitions for the Liquid Staking Token (LST) system
 use odra::prelude::*;
 use odra::casper_types::U256;
+use super::staking_manager::PauseMode;
 
 /// Event emitted when CSPR is staked
 #[odra::event]
@@ -15,6 +16,8 @@ pub struct Staked {
     pub exchange_rate: U256,
     /// Timestamp of the stake
     pub timestamp: u64,
+    /// Per-contract event sequence number
+    pub seq: u64,
 }
 
 /// Event emitted when sCSPR is unstaked
@@ -32,6 +35,34 @@ pub struct Unstaked {
     pub exchange_rate: U256,
     /// Timestamp when funds will be withdrawable
     pub withdrawable_at: u64,
+    /// Per-contract event sequence number
+    pub seq: u64,
+}
+
+/// Event emitted when an unstake request changes hands
+#[odra::event]
+pub struct UnstakeRequestTransferred {
+    /// Unstake request ID
+    pub request_id: u64,
+    /// Previous holder
+    pub from: Address,
+    /// New holder
+    pub to: Address,
+    /// Per-contract event sequence number
+    pub seq: u64,
+}
+
+/// Event emitted when an operator is approved to transfer an unstake request
+#[odra::event]
+pub struct UnstakeRequestApproval {
+    /// Unstake request ID
+    pub request_id: u64,
+    /// Holder of the request
+    pub owner: Address,
+    /// Approved operator (None clears the approval)
+    pub operator: Option<Address>,
+    /// Per-contract event sequence number
+    pub seq: u64,
 }
 
 /// Event emitted when unstaked CSPR is withdrawn
@@ -45,6 +76,8 @@ pub struct Withdrawn {
     pub request_id: u64,
     /// Timestamp of the withdrawal
     pub timestamp: u64,
+    /// Per-contract event sequence number
+    pub seq: u64,
 }
 
 /// Event emitted when staking rewards are distributed
@@ -60,6 +93,8 @@ pub struct RewardsDistributed {
     pub new_exchange_rate: U256,
     /// Timestamp of distribution
     pub timestamp: u64,
+    /// Per-contract event sequence number
+    pub seq: u64,
 }
 
 /// Event emitted when the exchange rate is updated
@@ -75,6 +110,8 @@ pub struct ExchangeRateUpdated {
     pub total_scspr: U256,
     /// Timestamp of update
     pub timestamp: u64,
+    /// Per-contract event sequence number
+    pub seq: u64,
 }
 
 /// Event emitted when a validator is added
@@ -86,6 +123,8 @@ pub struct ValidatorAdded {
     pub added_by: Address,
     /// Timestamp
     pub timestamp: u64,
+    /// Per-contract event sequence number
+    pub seq: u64,
 }
 
 /// Event emitted when a validator is removed
@@ -97,6 +136,8 @@ pub struct ValidatorRemoved {
     pub removed_by: Address,
     /// Timestamp
     pub timestamp: u64,
+    /// Per-contract event sequence number
+    pub seq: u64,
 }
 
 /// Event emitted when the contract is paused
@@ -106,6 +147,8 @@ pub struct ContractPaused {
     pub paused_by: Address,
     /// Timestamp
     pub timestamp: u64,
+    /// Per-contract event sequence number
+    pub seq: u64,
 }
 
 /// Event emitted when the contract is unpaused
@@ -115,6 +158,23 @@ pub struct ContractUnpaused {
     pub unpaused_by: Address,
     /// Timestamp
     pub timestamp: u64,
+    /// Per-contract event sequence number
+    pub seq: u64,
+}
+
+/// Event emitted when the pause mode changes
+#[odra::event]
+pub struct PauseModeChanged {
+    /// Previous mode
+    pub old_mode: PauseMode,
+    /// New mode
+    pub new_mode: PauseMode,
+    /// Updated by (admin address)
+    pub updated_by: Address,
+    /// Timestamp
+    pub timestamp: u64,
+    /// Per-contract event sequence number
+    pub seq: u64,
 }
 
 /// Event emitted when minimum stake amount is updated
@@ -126,6 +186,8 @@ pub struct MinimumStakeUpdated {
     pub new_minimum: U256,
     /// Updated by (admin address)
     pub updated_by: Address,
+    /// Per-contract event sequence number
+    pub seq: u64,
 }
 
 /// Event emitted when unstaking period is updated
@@ -137,4 +199,346 @@ pub struct UnstakingPeriodUpdated {
     pub new_period: u64,
     /// Updated by (admin address)
     pub updated_by: Address,
+    /// Per-contract event sequence number
+    pub seq: u64,
+}
+
+/// Event emitted when a validator score is reported
+#[odra::event]
+pub struct ValidatorScoreReported {
+    /// Validator address
+    pub validator: Address,
+    /// Uptime in basis points
+    pub uptime_bps: u16,
+    /// Commission in basis points
+    pub commission_bps: u16,
+    /// Resulting score (0-10000)
+    pub score: u32,
+    /// Reported by (oracle or admin address)
+    pub reported_by: Address,
+    /// Per-contract event sequence number
+    pub seq: u64,
+}
+
+/// Event emitted when stake is assigned to a validator
+#[odra::event]
+pub struct DelegationAssigned {
+    /// Validator address
+    pub validator: Address,
+    /// Amount of CSPR delegated
+    pub amount: U256,
+    /// Timestamp
+    pub timestamp: u64,
+    /// Per-contract event sequence number
+    pub seq: u64,
+}
+
+/// Event emitted when a validator's stake is queued for undelegation
+#[odra::event]
+pub struct UndelegationQueued {
+    /// Validator address
+    pub validator: Address,
+    /// Amount of CSPR queued
+    pub amount: U256,
+    /// Timestamp
+    pub timestamp: u64,
+    /// Per-contract event sequence number
+    pub seq: u64,
+}
+
+/// Event emitted when a contract wiring setting changes
+/// (score oracle, guardian, parameter registry, minimum validator score)
+#[odra::event]
+pub struct SettingUpdated {
+    /// Name of the setting
+    pub setting: String,
+    /// Updated by (admin address)
+    pub updated_by: Address,
+    /// Timestamp
+    pub timestamp: u64,
+    /// Per-contract event sequence number
+    pub seq: u64,
+}
+
+/// Event emitted when admin rights are transferred
+#[odra::event]
+pub struct AdminTransferred {
+    /// Previous admin
+    pub previous_admin: Address,
+    /// New admin
+    pub new_admin: Address,
+    /// Per-contract event sequence number
+    pub seq: u64,
+}
+
+/// Event emitted when sCSPR is deposited into the compounder vault
+#[odra::event]
+pub struct VaultDeposited {
+    /// Depositor
+    pub user: Address,
+    /// sCSPR deposited
+    pub scspr_amount: U256,
+    /// Vault shares minted
+    pub shares: U256,
+    /// Per-contract event sequence number
+    pub seq: u64,
+}
+
+/// Event emitted when vault shares are redeemed
+#[odra::event]
+pub struct VaultWithdrawn {
+    /// Withdrawer
+    pub user: Address,
+    /// Vault shares burned
+    pub shares: U256,
+    /// sCSPR paid out
+    pub scspr_amount: U256,
+    /// WCSPR paid out
+    pub wcspr_amount: U256,
+    /// LP tokens paid out
+    pub lp_amount: U256,
+    /// Per-contract event sequence number
+    pub seq: u64,
+}
+
+/// Event emitted when staking yield is compounded into LP
+#[odra::event]
+pub struct YieldCompounded {
+    /// CSPR yield accrued since the last harvest
+    pub yield_cspr: U256,
+    /// sCSPR moved into the pair (half swapped to WCSPR)
+    pub scspr_compounded: U256,
+    /// WCSPR received from the swap
+    pub wcspr_received: U256,
+    /// LP tokens minted to the vault
+    pub lp_minted: U256,
+    /// Timestamp
+    pub timestamp: u64,
+    /// Per-contract event sequence number
+    pub seq: u64,
+}
+
+/// Event emitted when the keeper trades the DEX price back toward the exchange rate
+#[odra::event]
+pub struct PegArbitraged {
+    /// True if sCSPR traded at a premium (minted and sold), false at a discount (bought and redeemed)
+    pub premium: bool,
+    /// DEX price before the trade (WCSPR per sCSPR, scaled by 1e18)
+    pub dex_price: U256,
+    /// Exchange-rate price (CSPR per sCSPR, scaled by 1e18)
+    pub fair_price: U256,
+    /// sCSPR minted and sold, or bought and redeemed
+    pub scspr_amount: U256,
+    /// WCSPR taken from the peg buffer
+    pub wcspr_spent: U256,
+    /// WCSPR sent to the treasury (premium) or CSPR queued for it (discount), net of cost
+    pub profit: U256,
+    /// Unstake request queued for the treasury (discount only)
+    pub request_id: Option<u64>,
+    /// Per-contract event sequence number
+    pub seq: u64,
+}
+
+/// Event emitted when the keeper opens a DEX price window for peg arbitrage
+#[odra::event]
+pub struct PegObserved {
+    /// Cumulative DEX price of sCSPR in WCSPR (scaled by 1e18) at the observation
+    pub cumulative: U256,
+    /// Block time of the observation
+    pub timestamp: u64,
+    /// Per-contract event sequence number
+    pub seq: u64,
+}
+
+/// Event emitted when WCSPR is added to or taken from the peg buffer
+#[odra::event]
+pub struct PegBufferUpdated {
+    /// WCSPR added or withdrawn
+    pub amount: U256,
+    /// Whether the amount was added
+    pub funded: bool,
+    /// Buffer balance after the update
+    pub balance: U256,
+    /// Account that funded or received the WCSPR
+    pub account: Address,
+    /// Per-contract event sequence number
+    pub seq: u64,
+}
+
+/// Event emitted when sCSPR is locked in the fee distributor
+#[odra::event]
+pub struct FeeStakeDeposited {
+    /// Locker
+    pub user: Address,
+    /// sCSPR locked
+    pub amount: U256,
+    /// Per-contract event sequence number
+    pub seq: u64,
+}
+
+/// Event emitted when sCSPR is unlocked from the fee distributor
+#[odra::event]
+pub struct FeeStakeWithdrawn {
+    /// Locker
+    pub user: Address,
+    /// sCSPR unlocked
+    pub amount: U256,
+    /// Per-contract event sequence number
+    pub seq: u64,
+}
+
+/// Event emitted when a locker claims WCSPR fees
+#[odra::event]
+pub struct FeeRewardsClaimed {
+    /// Locker
+    pub user: Address,
+    /// WCSPR paid
+    pub amount: U256,
+    /// Per-contract event sequence number
+    pub seq: u64,
+}
+
+/// Event emitted when a fee token is swapped to WCSPR
+#[odra::event]
+pub struct FeesConverted {
+    /// Fee token sold
+    pub token: Address,
+    /// Amount sold
+    pub amount_in: U256,
+    /// WCSPR received
+    pub wcspr_out: U256,
+    /// Per-contract event sequence number
+    pub seq: u64,
+}
+
+/// Event emitted when a fee distribution epoch starts
+#[odra::event]
+pub struct EpochStarted {
+    /// Epoch number
+    pub epoch: u64,
+    /// WCSPR streamed over the epoch
+    pub amount: U256,
+    /// End of the epoch
+    pub period_finish: u64,
+    /// Per-contract event sequence number
+    pub seq: u64,
+}
+
+/// Event emitted when CSPR is added to the insurance fund
+#[odra::event]
+pub struct InsuranceDeposited {
+    /// Depositor (the staking manager for reward fees)
+    pub from: Address,
+    /// CSPR added
+    pub amount: U256,
+    /// Fund balance afterwards
+    pub balance: U256,
+    /// Per-contract event sequence number
+    pub seq: u64,
+}
+
+/// Event emitted when the insurance fund pays out slash cover
+#[odra::event]
+pub struct InsuranceCoverPaid {
+    /// Loss the staking manager asked to cover
+    pub requested: U256,
+    /// CSPR actually paid
+    pub covered: U256,
+    /// Fund balance afterwards
+    pub balance: U256,
+    /// Per-contract event sequence number
+    pub seq: u64,
+}
+
+/// Event emitted when excess CSPR is withdrawn from the insurance fund
+#[odra::event]
+pub struct InsuranceWithdrawn {
+    /// Recipient
+    pub to: Address,
+    /// CSPR withdrawn
+    pub amount: U256,
+    /// Fund balance afterwards
+    pub balance: U256,
+    /// Per-contract event sequence number
+    pub seq: u64,
+}
+
+/// Event emitted when the staking manager receives insurance cover
+#[odra::event]
+pub struct InsuranceCoverReceived {
+    /// Insurance fund that paid
+    pub fund: Address,
+    /// CSPR received
+    pub amount: U256,
+    /// Per-contract event sequence number
+    pub seq: u64,
+}
+
+/// Event emitted when the staking manager pays owed reward fees to the insurance fund
+#[odra::event]
+pub struct InsuranceFeesPaid {
+    /// Insurance fund that was paid
+    pub fund: Address,
+    /// CSPR paid
+    pub amount: U256,
+    /// Fees still owed afterwards
+    pub owed: U256,
+    /// Per-contract event sequence number
+    pub seq: u64,
+}
+
+/// Event emitted when a slash is reported
+#[odra::event]
+pub struct SlashReported {
+    /// Slashed validator
+    pub validator: Address,
+    /// CSPR lost to the slash
+    pub amount: U256,
+    /// Part covered by the insurance fund
+    pub covered: U256,
+    /// Exchange rate after the uncovered loss
+    pub new_exchange_rate: U256,
+    /// Timestamp of the report
+    pub timestamp: u64,
+    /// Per-contract event sequence number
+    pub seq: u64,
+}
+
+/// Event emitted when a user switches auto-restake on or off
+#[odra::event]
+pub struct AutoRestakeUpdated {
+    /// User whose preference changed
+    pub user: Address,
+    /// Whether rewards are now restaked as sCSPR appreciation
+    pub enabled: bool,
+    /// Timestamp of the change
+    pub timestamp: u64,
+    /// Per-contract event sequence number
+    pub seq: u64,
+}
+
+/// Event emitted when an income user's rewards are settled into claimable CSPR
+#[odra::event]
+pub struct IncomeRewardsSettled {
+    /// Income user
+    pub user: Address,
+    /// CSPR credited as claimable
+    pub cspr_amount: U256,
+    /// sCSPR burned to back the credit
+    pub scspr_burned: U256,
+    /// Per-contract event sequence number
+    pub seq: u64,
+}
+
+/// Event emitted when an income user claims CSPR rewards
+#[odra::event]
+pub struct IncomeRewardsClaimed {
+    /// Income user
+    pub user: Address,
+    /// CSPR paid out
+    pub cspr_amount: U256,
+    /// Timestamp of the claim
+    pub timestamp: u64,
+    /// Per-contract event sequence number
+    pub seq: u64,
 }
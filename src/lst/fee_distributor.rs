@@ -0,0 +1,520 @@
+//! Fee Distributor - Pays DEX protocol fees to sCSPR lockers as WCSPR
+//!
+//! Protocol fees (LP tokens or any other token sent here, e.g. by pointing the
+//! Factory's `fee_to` at this contract) are converted to WCSPR through the
+//! Router by a keeper. WCSPR is paid out in weekly epochs: each epoch streams
+//! the WCSPR collected before it started evenly over the week, shared by the
+//! sCSPR locked in this contract. Rewards that accrue while nothing is locked
+//! roll over into the next epoch.
+
+use odra::prelude::*;
+use odra::casper_types::U256;
+use odra::ContractRef;
+use super::errors::LstError;
+use super::events::{EpochStarted, FeeRewardsClaimed, FeeStakeDeposited, FeeStakeWithdrawn, FeesConverted};
+use crate::dex::router::RouterContractRef;
+use crate::math::SafeMath;
+use crate::token::Cep18TokenContractRef;
+
+/// Length of one distribution epoch (7 days)
+pub const EPOCH_DURATION: u64 = 7 * 24 * 60 * 60;
+
+/// Reward-per-token scale (1e18)
+const REWARD_SCALE: u128 = 1_000_000_000_000_000_000;
+
+/// Fee Distributor contract
+#[odra::module]
+pub struct FeeDistributor {
+    /// sCSPR token address (locked by users)
+    scspr_token: Var<Address>,
+    /// WCSPR token address (paid out)
+    wcspr: Var<Address>,
+    /// DEX router used to convert fees
+    router: Var<Address>,
+    /// Contract admin
+    admin: Var<Address>,
+    /// Keeper allowed to convert fees besides the admin
+    keeper: Var<Option<Address>>,
+    /// Total sCSPR locked
+    total_locked: Var<U256>,
+    /// sCSPR locked per user
+    locked: Mapping<Address, U256>,
+    /// Current epoch number (0 before the first epoch)
+    epoch: Var<u64>,
+    /// WCSPR streamed per second in the current epoch, scaled by 1e18
+    reward_rate: Var<U256>,
+    /// End of the current epoch
+    period_finish: Var<u64>,
+    /// Last time the accumulator was updated
+    last_update: Var<u64>,
+    /// Accumulated WCSPR per locked sCSPR, scaled by 1e18
+    reward_per_token_stored: Var<U256>,
+    /// Accumulator value at each user's last update
+    user_reward_per_token_paid: Mapping<Address, U256>,
+    /// WCSPR earned and not yet claimed per user
+    rewards: Mapping<Address, U256>,
+    /// WCSPR owed to users or still streaming; the rest of the balance funds the next epoch
+    committed: Var<U256>,
+    /// Periphery allowed to claim on behalf of the user who called it
+    claim_router: Var<Option<Address>>,
+    /// Sequence number of the last event emitted
+    event_seq: Var<u64>,
+}
+
+#[odra::module]
+impl FeeDistributor {
+    /// Initialize the distributor
+    pub fn init(&mut self, scspr_token: Address, wcspr: Address, router: Address) {
+        self.scspr_token.set(scspr_token);
+        self.wcspr.set(wcspr);
+        self.router.set(router);
+        self.admin.set(self.env().caller());
+        self.total_locked.set(U256::zero());
+        self.committed.set(U256::zero());
+    }
+
+    // ========================================
+    // User Functions
+    // ========================================
+
+    /// Lock sCSPR to earn a share of protocol fees
+    pub fn deposit(&mut self, amount: U256) {
+        if amount.is_zero() {
+            self.env().revert(LstError::InvalidAmount);
+        }
+        let caller = self.env().caller();
+        self.update_reward(Some(caller));
+
+        let scspr = self.scspr_token.get_or_revert_with(LstError::InvalidAmount);
+        let mut token = Cep18TokenContractRef::new(self.env(), scspr);
+        if !token.transfer_from(caller, self.env().self_address(), amount) {
+            self.env().revert(LstError::InsufficientScsprBalance);
+        }
+
+        self.locked.set(&caller, self.locked_of(caller) + amount);
+        self.total_locked.set(self.total_locked() + amount);
+
+        self.env().emit_event(FeeStakeDeposited {
+            user: caller,
+            amount,
+            seq: self.next_event_seq(),
+        });
+    }
+
+    /// Unlock sCSPR; earned WCSPR stays claimable
+    pub fn withdraw(&mut self, amount: U256) {
+        let caller = self.env().caller();
+        let locked = self.locked_of(caller);
+        if amount.is_zero() || amount > locked {
+            self.env().revert(LstError::InvalidAmount);
+        }
+        self.update_reward(Some(caller));
+
+        self.locked.set(&caller, locked - amount);
+        self.total_locked.set(self.total_locked() - amount);
+
+        let scspr = self.scspr_token.get_or_revert_with(LstError::InvalidAmount);
+        let mut token = Cep18TokenContractRef::new(self.env(), scspr);
+        if !token.transfer(caller, amount) {
+            self.env().revert(LstError::WithdrawalFailed);
+        }
+
+        self.env().emit_event(FeeStakeWithdrawn {
+            user: caller,
+            amount,
+            seq: self.next_event_seq(),
+        });
+    }
+
+    /// Claim earned WCSPR
+    pub fn claim(&mut self) -> U256 {
+        let caller = self.env().caller();
+        self.claim_of(caller)
+    }
+
+    /// Claim earned WCSPR for `user`, paid to `user`
+    /// Only callable by the claim router, which acts for its own caller
+    pub fn claim_for(&mut self, user: Address) -> U256 {
+        if self.claim_router() != Some(self.env().caller()) {
+            self.env().revert(LstError::Unauthorized);
+        }
+        self.claim_of(user)
+    }
+
+    // ========================================
+    // Keeper Functions
+    // ========================================
+
+    /// Swap a fee token held by the distributor to WCSPR through the Router
+    /// `path` must start with the fee token and end with WCSPR
+    /// sCSPR can only be converted out of what sits above the locked total,
+    /// so lockers' principal is never swapped away
+    /// Returns the WCSPR received, which funds the next epoch
+    pub fn convert_fees(
+        &mut self,
+        path: Vec<Address>,
+        amount_in: U256,
+        amount_out_min: U256,
+        deadline: u64,
+    ) -> U256 {
+        self.only_keeper();
+        let wcspr = self.wcspr.get_or_revert_with(LstError::InvalidAmount);
+        if path.len() < 2 || path[path.len() - 1] != wcspr || path[0] == wcspr {
+            self.env().revert(LstError::InvalidAmount);
+        }
+        let token_in = path[0];
+        let scspr = self.scspr_token.get_or_revert_with(LstError::InvalidAmount);
+        if token_in == scspr {
+            let balance = Cep18TokenContractRef::new(self.env(), scspr).balance_of(self.env().self_address());
+            let surplus = balance.saturating_sub(self.total_locked.get_or_default());
+            if amount_in > surplus {
+                self.env().revert(LstError::InvalidAmount);
+            }
+        }
+        let router_address = self.router.get_or_revert_with(LstError::InvalidAmount);
+
+        let mut token = Cep18TokenContractRef::new(self.env(), token_in);
+        token.approve(router_address, amount_in);
+        let mut router = RouterContractRef::new(self.env(), router_address);
+        let amounts = router.swap_exact_tokens_for_tokens(
+            amount_in,
+            amount_out_min,
+            path,
+            self.env().self_address(),
+            deadline,
+        );
+        let wcspr_out = amounts[amounts.len() - 1];
+
+        self.env().emit_event(FeesConverted {
+            token: token_in,
+            amount_in,
+            wcspr_out,
+            seq: self.next_event_seq(),
+        });
+        wcspr_out
+    }
+
+    /// Start the next epoch with all uncommitted WCSPR
+    /// Callable by anyone once the current epoch has ended
+    pub fn start_epoch(&mut self) -> u64 {
+        let now = self.env().get_block_time();
+        if now < self.period_finish.get_or_default() {
+            self.env().revert(LstError::EpochNotFinished);
+        }
+        self.update_reward(None);
+
+        let amount = self.undistributed();
+        if amount.is_zero() {
+            self.env().revert(LstError::NothingToDistribute);
+        }
+        let rate = self.mul_div(amount, U256::from(REWARD_SCALE), U256::from(EPOCH_DURATION));
+        let period_finish = now + EPOCH_DURATION;
+        let epoch = self.epoch.get_or_default() + 1;
+
+        self.reward_rate.set(rate);
+        self.period_finish.set(period_finish);
+        self.last_update.set(now);
+        self.epoch.set(epoch);
+        self.committed.set(self.committed.get_or_default() + amount);
+
+        self.env().emit_event(EpochStarted {
+            epoch,
+            amount,
+            period_finish,
+            seq: self.next_event_seq(),
+        });
+        epoch
+    }
+
+    // ========================================
+    // Views
+    // ========================================
+
+    /// WCSPR earned by a user and not yet claimed
+    pub fn earned(&self, user: Address) -> U256 {
+        let paid = self.user_reward_per_token_paid.get(&user).unwrap_or_default();
+        let accrued = self.mul_div(
+            self.locked_of(user),
+            self.reward_per_token() - paid,
+            U256::from(REWARD_SCALE),
+        );
+        self.rewards.get(&user).unwrap_or_default() + accrued
+    }
+
+    /// Accumulated WCSPR per locked sCSPR, scaled by 1e18
+    pub fn reward_per_token(&self) -> U256 {
+        let stored = self.reward_per_token_stored.get_or_default();
+        let total = self.total_locked();
+        if total.is_zero() {
+            return stored;
+        }
+        let elapsed = self.last_time_reward_applicable() - self.last_update.get_or_default();
+        stored + self.mul_div(self.reward_rate.get_or_default(), U256::from(elapsed), total)
+    }
+
+    /// WCSPR held but not yet assigned to an epoch
+    pub fn undistributed(&self) -> U256 {
+        let wcspr = self.wcspr.get_or_revert_with(LstError::InvalidAmount);
+        let balance = Cep18TokenContractRef::new(self.env(), wcspr).balance_of(self.env().self_address());
+        balance.saturating_sub(self.committed.get_or_default())
+    }
+
+    /// sCSPR locked by a user
+    pub fn locked_of(&self, user: Address) -> U256 {
+        self.locked.get(&user).unwrap_or_default()
+    }
+
+    /// Total sCSPR locked
+    pub fn total_locked(&self) -> U256 {
+        self.total_locked.get_or_default()
+    }
+
+    /// Current epoch number and its end time
+    pub fn current_epoch(&self) -> (u64, u64) {
+        (self.epoch.get_or_default(), self.period_finish.get_or_default())
+    }
+
+    /// Get the keeper address
+    pub fn keeper(&self) -> Option<Address> {
+        self.keeper.get_or_default()
+    }
+
+    /// Get the periphery allowed to claim on behalf of users
+    pub fn claim_router(&self) -> Option<Address> {
+        self.claim_router.get_or_default()
+    }
+
+    // ========================================
+    // Admin Functions
+    // ========================================
+
+    /// Set (or clear) the keeper allowed to convert fees
+    pub fn set_keeper(&mut self, keeper: Option<Address>) {
+        self.only_admin();
+        self.keeper.set(keeper);
+    }
+
+    /// Set (or clear) the periphery allowed to claim on behalf of users
+    pub fn set_claim_router(&mut self, router: Option<Address>) {
+        self.only_admin();
+        self.claim_router.set(router);
+    }
+
+    /// Transfer admin rights
+    pub fn transfer_admin(&mut self, new_admin: Address) {
+        self.only_admin();
+        self.admin.set(new_admin);
+    }
+
+    // ========================================
+    // Internal Functions
+    // ========================================
+
+    /// Pay `user` their earned WCSPR
+    fn claim_of(&mut self, user: Address) -> U256 {
+        self.update_reward(Some(user));
+
+        let amount = self.rewards.get(&user).unwrap_or_default();
+        if amount.is_zero() {
+            return amount;
+        }
+        self.rewards.set(&user, U256::zero());
+        self.committed.set(self.committed.get_or_default() - amount);
+
+        let wcspr = self.wcspr.get_or_revert_with(LstError::InvalidAmount);
+        let mut token = Cep18TokenContractRef::new(self.env(), wcspr);
+        if !token.transfer(user, amount) {
+            self.env().revert(LstError::WithdrawalFailed);
+        }
+
+        self.env().emit_event(FeeRewardsClaimed {
+            user,
+            amount,
+            seq: self.next_event_seq(),
+        });
+        amount
+    }
+
+    fn last_time_reward_applicable(&self) -> u64 {
+        self.env().get_block_time().min(self.period_finish.get_or_default())
+    }
+
+    /// Bring the accumulator (and optionally a user) up to date
+    fn update_reward(&mut self, user: Option<Address>) {
+        let applicable = self.last_time_reward_applicable();
+        let last_update = self.last_update.get_or_default();
+        if self.total_locked().is_zero() && applicable > last_update {
+            // Nobody was locked: release the streamed WCSPR for the next epoch
+            let unassigned = self.mul_div(
+                self.reward_rate.get_or_default(),
+                U256::from(applicable - last_update),
+                U256::from(REWARD_SCALE),
+            );
+            self.committed.set(self.committed.get_or_default().saturating_sub(unassigned));
+        }
+        self.reward_per_token_stored.set(self.reward_per_token());
+        if applicable > last_update {
+            self.last_update.set(applicable);
+        }
+
+        if let Some(user) = user {
+            self.rewards.set(&user, self.earned(user));
+            self.user_reward_per_token_paid.set(&user, self.reward_per_token_stored.get_or_default());
+        }
+    }
+
+    fn mul_div(&self, a: U256, b: U256, c: U256) -> U256 {
+        SafeMath::mul_div(a, b, c)
+            .unwrap_or_else(|_| self.env().revert(LstError::ExchangeRateError))
+    }
+
+    fn only_admin(&self) {
+        let admin = self.admin.get_or_revert_with(LstError::Unauthorized);
+        if self.env().caller() != admin {
+            self.env().revert(LstError::Unauthorized);
+        }
+    }
+
+    fn only_keeper(&self) {
+        let caller = self.env().caller();
+        let is_keeper = self.keeper.get_or_default() == Some(caller);
+        let admin = self.admin.get_or_revert_with(LstError::Unauthorized);
+        if !is_keeper && caller != admin {
+            self.env().revert(LstError::Unauthorized);
+        }
+    }
+
+    /// Sequence number of the last event emitted; indexers page from here
+    pub fn event_seq(&self) -> u64 {
+        self.event_seq.get_or_default()
+    }
+
+    /// Advance the event sequence and return the number for the next event
+    fn next_event_seq(&mut self) -> u64 {
+        let seq = self.event_seq.get_or_default() + 1;
+        self.event_seq.set(seq);
+        seq
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use odra::host::{Deployer, HostEnv};
+    use crate::lst::scspr_token::{ScsprToken, ScsprTokenHostRef, ScsprTokenInitArgs};
+    use crate::testing::cspr;
+    use crate::token::{LpToken, LpTokenHostRef, LpTokenInitArgs};
+
+    fn setup() -> (HostEnv, FeeDistributorHostRef, ScsprTokenHostRef, LpTokenHostRef) {
+        let env = odra_test::env();
+        // Account 0 acts as the staking manager so the test can mint sCSPR
+        let scspr = ScsprToken::deploy(&env, ScsprTokenInitArgs {
+            staking_manager: env.get_account(0),
+        });
+        let wcspr = LpToken::deploy(&env, LpTokenInitArgs {
+            name: String::from("Wrapped CSPR"),
+            symbol: String::from("WCSPR"),
+        });
+        // The router is only used by convert_fees
+        let distributor = FeeDistributor::deploy(&env, FeeDistributorInitArgs {
+            scspr_token: scspr.address(),
+            wcspr: wcspr.address(),
+            router: env.get_account(5),
+        });
+        (env, distributor, scspr, wcspr)
+    }
+
+    fn lock(env: &HostEnv, distributor: &mut FeeDistributorHostRef, scspr: &mut ScsprTokenHostRef, user: Address, amount: U256) {
+        env.set_caller(env.get_account(0));
+        scspr.mint(user, amount);
+        env.set_caller(user);
+        scspr.approve(distributor.address(), amount);
+        distributor.deposit(amount);
+    }
+
+    #[test]
+    fn test_epoch_rewards_are_pro_rata() {
+        let (env, mut distributor, mut scspr, mut wcspr) = setup();
+        let alice = env.get_account(2);
+        let bob = env.get_account(3);
+
+        lock(&env, &mut distributor, &mut scspr, alice, cspr(300));
+        lock(&env, &mut distributor, &mut scspr, bob, cspr(100));
+
+        // One week of fees arrives and a new epoch starts
+        wcspr.mint(distributor.address(), cspr(40));
+        assert_eq!(distributor.undistributed(), cspr(40));
+        distributor.start_epoch();
+        assert_eq!(distributor.undistributed(), U256::zero());
+
+        env.advance_block_time(EPOCH_DURATION);
+        env.set_caller(alice);
+        let alice_claimed = distributor.claim();
+        env.set_caller(bob);
+        let bob_claimed = distributor.claim();
+
+        // 3:1 split, allowing for per-second rounding
+        assert!(cspr(30) - alice_claimed < U256::from(1_000));
+        assert!(cspr(10) - bob_claimed < U256::from(1_000));
+        assert_eq!(wcspr.balance_of(alice), alice_claimed);
+    }
+
+    #[test]
+    fn test_unlocked_time_rolls_over() {
+        let (env, mut distributor, mut scspr, mut wcspr) = setup();
+        let alice = env.get_account(2);
+
+        // Nobody locked for the first half of the epoch
+        wcspr.mint(distributor.address(), cspr(14));
+        distributor.start_epoch();
+        env.advance_block_time(EPOCH_DURATION / 2);
+        lock(&env, &mut distributor, &mut scspr, alice, cspr(100));
+
+        env.advance_block_time(EPOCH_DURATION / 2);
+        let claimed = distributor.claim();
+        assert!(cspr(7) - claimed < U256::from(1_000));
+
+        // The unassigned half funds the next epoch
+        assert!(cspr(7) - distributor.undistributed() < U256::from(1_000));
+        assert_eq!(distributor.current_epoch().0, 1);
+        distributor.start_epoch();
+        assert_eq!(distributor.current_epoch().0, 2);
+    }
+
+    #[test]
+    fn test_locked_scspr_cannot_be_converted() {
+        let (env, mut distributor, mut scspr, wcspr) = setup();
+        let alice = env.get_account(2);
+        lock(&env, &mut distributor, &mut scspr, alice, cspr(100));
+
+        // 5 sCSPR of fee income sits on top of the locked principal
+        env.set_caller(env.get_account(0));
+        scspr.mint(distributor.address(), cspr(5));
+        let path = vec![scspr.address(), wcspr.address()];
+        assert_eq!(
+            distributor.try_convert_fees(path.clone(), cspr(100), U256::zero(), u64::MAX),
+            Err(LstError::InvalidAmount.into())
+        );
+        assert_eq!(
+            distributor.try_convert_fees(path, cspr(5) + 1, U256::zero(), u64::MAX),
+            Err(LstError::InvalidAmount.into())
+        );
+        assert_eq!(scspr.balance_of(distributor.address()), cspr(105));
+
+        env.set_caller(alice);
+        distributor.withdraw(cspr(100));
+        assert_eq!(scspr.balance_of(alice), cspr(100));
+    }
+
+    #[test]
+    fn test_epoch_cannot_restart_early() {
+        let (env, mut distributor, _scspr, mut wcspr) = setup();
+        assert_eq!(distributor.try_start_epoch(), Err(LstError::NothingToDistribute.into()));
+
+        wcspr.mint(distributor.address(), cspr(1));
+        distributor.start_epoch();
+        wcspr.mint(distributor.address(), cspr(1));
+        env.advance_block_time(EPOCH_DURATION - 1);
+        assert_eq!(distributor.try_start_epoch(), Err(LstError::EpochNotFinished.into()));
+    }
+}
@@ -0,0 +1,299 @@
+//! Insurance Fund - CSPR reserve that absorbs validator slashes
+//!
+//! The StakingManager owes a slice of every reward distribution to the fund,
+//! which anyone can collect into it with `collect_fees`. When a
+//! slash is reported, the manager draws on the fund first and only the
+//! uncovered part lowers the sCSPR exchange rate. Anyone can top the fund up;
+//! the admin can only withdraw what exceeds the target ratio of total stake.
+
+use odra::prelude::*;
+use odra::casper_types::{U256, U512};
+use odra::uints::ToU512;
+use odra::ContractRef;
+use super::errors::LstError;
+use super::events::{InsuranceCoverPaid, InsuranceDeposited, InsuranceWithdrawn};
+use super::staking_manager::StakingManagerContractRef;
+use crate::math::{SafeMath, BPS_DENOMINATOR};
+
+/// Insurance Fund contract
+#[odra::module]
+pub struct InsuranceFund {
+    /// Contract admin (governance)
+    admin: Var<Address>,
+    /// Staking manager allowed to draw cover
+    staking_manager: Var<Address>,
+    /// Reserve kept back from withdrawals, in basis points of total CSPR staked
+    target_ratio_bps: Var<u16>,
+    /// Sequence number of the last event emitted
+    event_seq: Var<u64>,
+}
+
+#[odra::module]
+impl InsuranceFund {
+    /// Initialize with the insured staking manager and the target ratio
+    pub fn init(&mut self, staking_manager: Address, target_ratio_bps: u16) {
+        if target_ratio_bps as u128 > BPS_DENOMINATOR {
+            self.env().revert(LstError::InvalidAmount);
+        }
+        self.admin.set(self.env().caller());
+        self.staking_manager.set(staking_manager);
+        self.target_ratio_bps.set(target_ratio_bps);
+    }
+
+    /// Add the attached CSPR to the fund
+    /// Used both for reward fees paid by the staking manager and for top-ups
+    #[odra(payable)]
+    pub fn deposit(&mut self) {
+        let amount = self.to_u256(self.env().attached_value());
+        if amount.is_zero() {
+            self.env().revert(LstError::InvalidAmount);
+        }
+
+        self.env().emit_event(InsuranceDeposited {
+            from: self.env().caller(),
+            amount,
+            balance: self.get_balance(),
+            seq: self.next_event_seq(),
+        });
+    }
+
+    /// Pull the reward fees the staking manager owes the fund
+    /// Callable by anyone; returns the amount collected
+    pub fn collect_fees(&mut self) -> U256 {
+        StakingManagerContractRef::new(self.env(), self.get_staking_manager()).pay_insurance_fees()
+    }
+
+    /// Cover a slash loss of `amount` CSPR, up to the fund balance
+    /// Only callable by the staking manager; returns the amount covered
+    pub fn cover(&mut self, amount: U256) -> U256 {
+        let staking_manager = self.get_staking_manager();
+        if self.env().caller() != staking_manager {
+            self.env().revert(LstError::Unauthorized);
+        }
+
+        let covered = amount.min(self.get_balance());
+        if !covered.is_zero() {
+            StakingManagerContractRef::new(self.env(), staking_manager)
+                .with_tokens(covered.to_u512())
+                .receive_insurance_cover();
+        }
+
+        self.env().emit_event(InsuranceCoverPaid {
+            requested: amount,
+            covered,
+            balance: self.get_balance(),
+            seq: self.next_event_seq(),
+        });
+
+        covered
+    }
+
+    // ========================================
+    // Views
+    // ========================================
+
+    /// CSPR held by the fund
+    pub fn get_balance(&self) -> U256 {
+        self.to_u256(self.env().self_balance())
+    }
+
+    /// Balance the fund keeps back from withdrawals
+    pub fn get_target_balance(&self) -> U256 {
+        let total_staked = StakingManagerContractRef::new(self.env(), self.get_staking_manager())
+            .get_total_cspr_staked();
+        total_staked * U256::from(self.get_target_ratio_bps()) / U256::from(BPS_DENOMINATOR)
+    }
+
+    /// Balance above the target that the admin may withdraw
+    pub fn get_excess(&self) -> U256 {
+        self.get_balance().saturating_sub(self.get_target_balance())
+    }
+
+    /// Target ratio in basis points of total CSPR staked
+    pub fn get_target_ratio_bps(&self) -> u16 {
+        self.target_ratio_bps.get_or_default()
+    }
+
+    /// Insured staking manager
+    pub fn get_staking_manager(&self) -> Address {
+        self.staking_manager.get_or_revert_with(LstError::Unauthorized)
+    }
+
+    /// Get admin address
+    pub fn get_admin(&self) -> Address {
+        self.admin.get_or_revert_with(LstError::Unauthorized)
+    }
+
+    // ========================================
+    // Admin Functions
+    // ========================================
+
+    /// Withdraw CSPR above the target balance
+    pub fn withdraw_excess(&mut self, amount: U256, to: Address) {
+        self.only_admin();
+        if amount.is_zero() || amount > self.get_excess() {
+            self.env().revert(LstError::InsuranceBelowTarget);
+        }
+        self.env().transfer_tokens(&to, &amount.to_u512());
+
+        self.env().emit_event(InsuranceWithdrawn {
+            to,
+            amount,
+            balance: self.get_balance(),
+            seq: self.next_event_seq(),
+        });
+    }
+
+    /// Set the target ratio in basis points of total CSPR staked
+    pub fn set_target_ratio(&mut self, target_ratio_bps: u16) {
+        self.only_admin();
+        if target_ratio_bps as u128 > BPS_DENOMINATOR {
+            self.env().revert(LstError::InvalidAmount);
+        }
+        self.target_ratio_bps.set(target_ratio_bps);
+    }
+
+    /// Transfer admin rights
+    pub fn transfer_admin(&mut self, new_admin: Address) {
+        self.only_admin();
+        self.admin.set(new_admin);
+    }
+
+    // ========================================
+    // Internal Functions
+    // ========================================
+
+    fn only_admin(&self) {
+        if self.env().caller() != self.get_admin() {
+            self.env().revert(LstError::Unauthorized);
+        }
+    }
+
+    /// Sequence number of the last event emitted; indexers page from here
+    pub fn event_seq(&self) -> u64 {
+        self.event_seq.get_or_default()
+    }
+
+    /// Narrow a CSPR amount to U256, reverting if it does not fit
+    fn to_u256(&self, value: U512) -> U256 {
+        SafeMath::from_u512(value).unwrap_or_else(|_| self.env().revert(LstError::InvalidAmount))
+    }
+
+    /// Advance the event sequence and return the number for the next event
+    fn next_event_seq(&mut self) -> u64 {
+        let seq = self.event_seq.get_or_default() + 1;
+        self.event_seq.set(seq);
+        seq
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use odra::host::{Deployer, HostRef};
+    use crate::testing::{cspr, LstFixture};
+
+    #[test]
+    fn test_fund_covers_slash_before_exchange_rate_drops() {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let validator = env.get_account(1);
+        let user = env.get_account(2);
+        let mut lst = LstFixture::deploy(&env);
+        let manager = lst.staking_manager.address().clone();
+        let mut fund = InsuranceFund::deploy(&env, InsuranceFundInitArgs {
+            staking_manager: manager,
+            target_ratio_bps: 500,
+        });
+        lst.staking_manager.set_insurance_fund(Some(fund.address().clone()));
+        lst.staking_manager.schedule_set_insurance_fee(1_000);
+        lst.staking_manager.apply_insurance_fee();
+        fund.with_tokens(cspr(30).to_u512()).deposit();
+        assert_eq!(fund.get_balance(), cspr(30));
+
+        env.set_caller(user);
+        lst.staking_manager.stake(cspr(1_000));
+        let rate = lst.staking_manager.get_exchange_rate();
+
+        // Fully covered: the exchange rate does not move
+        env.set_caller(admin);
+        assert_eq!(lst.staking_manager.report_slash(validator, cspr(20)), cspr(20));
+        assert_eq!(lst.staking_manager.get_exchange_rate(), rate);
+        assert_eq!(lst.staking_manager.get_total_cspr_staked(), cspr(1_000));
+        assert_eq!(fund.get_balance(), cspr(10));
+
+        // Partly covered: only the remainder is socialized
+        assert_eq!(lst.staking_manager.report_slash(validator, cspr(30)), cspr(10));
+        assert_eq!(lst.staking_manager.get_total_cspr_staked(), cspr(980));
+        assert_eq!(fund.get_balance(), U256::zero());
+    }
+
+    #[test]
+    fn test_withdrawals_keep_target_balance() {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let mut lst = LstFixture::deploy(&env);
+        let mut fund = InsuranceFund::deploy(&env, InsuranceFundInitArgs {
+            staking_manager: lst.staking_manager.address().clone(),
+            target_ratio_bps: 500,
+        });
+        env.set_caller(env.get_account(2));
+        lst.staking_manager.stake(cspr(1_000));
+        fund.with_tokens(cspr(80).to_u512()).deposit();
+
+        // Target is 5% of 1000 CSPR
+        env.set_caller(admin);
+        assert_eq!(fund.get_target_balance(), cspr(50));
+        assert_eq!(fund.get_excess(), cspr(30));
+        assert_eq!(
+            fund.try_withdraw_excess(cspr(31), admin),
+            Err(LstError::InsuranceBelowTarget.into())
+        );
+        fund.withdraw_excess(cspr(30), admin);
+        assert_eq!(fund.get_balance(), cspr(50));
+
+        // Only the staking manager draws cover
+        assert_eq!(fund.try_cover(cspr(1)), Err(LstError::Unauthorized.into()));
+    }
+
+    #[test]
+    fn test_reward_fee_is_owed_then_collected() {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let validator = env.get_account(1);
+        let mut lst = LstFixture::deploy(&env);
+        let mut fund = InsuranceFund::deploy(&env, InsuranceFundInitArgs {
+            staking_manager: lst.staking_manager.address().clone(),
+            target_ratio_bps: 500,
+        });
+        lst.staking_manager.set_insurance_fund(Some(fund.address().clone()));
+        lst.staking_manager.schedule_set_insurance_fee(1_000);
+        lst.staking_manager.apply_insurance_fee();
+        fund.with_tokens(cspr(30).to_u512()).deposit();
+        env.set_caller(env.get_account(2));
+        lst.staking_manager.stake(cspr(1_000));
+
+        // Distributing with a fund set only records the 10% slice
+        env.set_caller(admin);
+        lst.staking_manager.distribute_rewards(cspr(100));
+        assert_eq!(lst.staking_manager.get_insurance_fees_owed(), cspr(10));
+        assert_eq!(lst.staking_manager.get_total_cspr_staked(), cspr(1_090));
+
+        // Nothing to pay while the manager holds no CSPR
+        assert_eq!(fund.collect_fees(), U256::zero());
+        assert_eq!(fund.get_balance(), cspr(30));
+
+        // Once it holds CSPR (here: slash cover) the fund collects what it is owed
+        lst.staking_manager.report_slash(validator, cspr(20));
+        assert_eq!(fund.get_balance(), cspr(10));
+        assert_eq!(fund.collect_fees(), cspr(10));
+        assert_eq!(fund.get_balance(), cspr(20));
+        assert_eq!(lst.staking_manager.get_insurance_fees_owed(), U256::zero());
+
+        // Only the fund is paid
+        assert_eq!(
+            lst.staking_manager.try_pay_insurance_fees(),
+            Err(LstError::Unauthorized.into())
+        );
+    }
+}
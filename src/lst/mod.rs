@@ -6,6 +6,9 @@
 
 pub mod scspr_token;
 pub mod staking_manager;
+pub mod compounder_vault;
+pub mod fee_distributor;
+pub mod insurance_fund;
 pub mod errors;
 pub mod events;
 
@@ -15,5 +18,8 @@ mod working_tests;
 
 pub use scspr_token::ScsprToken;
 pub use staking_manager::StakingManager;
+pub use compounder_vault::CompounderVault;
+pub use fee_distributor::FeeDistributor;
+pub use insurance_fund::InsuranceFund;
 pub use errors::LstError;
 pub use events::*;
@@ -7,6 +7,9 @@ use odra::prelude::*;
 use odra::casper_types::U256;
 use crate::events::{Transfer, Approval};
 use crate::errors::TokenError;
+use odra::ContractRef;
+use crate::governance::denylist::DenylistContractRef;
+use crate::governance::events::DenylistEnforcementUpdated;
 
 /// sCSPR Token - Staked CSPR liquid token
 /// This token is minted when users stake CSPR and burned when they unstake.
@@ -29,6 +32,12 @@ pub struct ScsprToken {
     staking_manager: Var<Address>,
     /// Contract admin
     admin: Var<Address>,
+    /// Denylist consulted on transfers
+    denylist: Var<Address>,
+    /// Whether listed addresses are rejected
+    denylist_enforced: Var<bool>,
+    /// Sequence number of the last event emitted
+    event_seq: Var<u64>,
 }
 
 #[odra::module]
@@ -105,6 +114,7 @@ impl ScsprToken {
     /// Mint new sCSPR tokens (only callable by staking manager)
     pub fn mint(&mut self, to: Address, amount: U256) {
         self.only_staking_manager();
+        self.ensure_not_denied(to);
         
         let current_supply = self.total_supply();
         let new_supply = current_supply + amount;
@@ -117,12 +127,14 @@ impl ScsprToken {
             from: Address::from(self.env().self_address()),
             to,
             value: amount,
+            seq: self.next_event_seq(),
         });
     }
 
     /// Burn sCSPR tokens (only callable by staking manager)
     pub fn burn(&mut self, from: Address, amount: U256) {
         self.only_staking_manager();
+        self.ensure_not_denied(from);
         
         let current_balance = self.balance_of(from);
         if current_balance < amount {
@@ -138,6 +150,7 @@ impl ScsprToken {
             from,
             to: Address::from(self.env().self_address()),
             value: amount,
+            seq: self.next_event_seq(),
         });
     }
 
@@ -152,6 +165,28 @@ impl ScsprToken {
         self.staking_manager.set(new_manager);
     }
 
+    /// Get the denylist address, if any
+    pub fn denylist(&self) -> Option<Address> {
+        self.denylist.get()
+    }
+
+    /// Check if denylist enforcement is on
+    pub fn is_denylist_enforced(&self) -> bool {
+        self.denylist_enforced.get_or_default()
+    }
+
+    /// Point the token at a denylist and switch enforcement on or off (admin only)
+    pub fn set_denylist(&mut self, denylist: Address, enforced: bool) {
+        self.only_admin();
+        self.denylist.set(denylist);
+        self.denylist_enforced.set(enforced);
+
+        self.env().emit_event(DenylistEnforcementUpdated {
+            denylist,
+            enforced,
+        });
+    }
+
     /// Get the admin address
     pub fn get_admin(&self) -> Address {
         self.admin.get_or_revert_with(TokenError::InsufficientAllowance)
@@ -166,6 +201,8 @@ impl ScsprToken {
     // Internal functions
 
     fn transfer_internal(&mut self, from: Address, to: Address, amount: U256) {
+        self.ensure_not_denied(from);
+        self.ensure_not_denied(to);
         let from_balance = self.balance_of(from);
         if from_balance < amount {
             self.env().revert(TokenError::InsufficientBalance);
@@ -179,6 +216,7 @@ impl ScsprToken {
             from,
             to,
             value: amount,
+            seq: self.next_event_seq(),
         });
     }
 
@@ -188,9 +226,21 @@ impl ScsprToken {
             owner,
             spender,
             value: amount,
+            seq: self.next_event_seq(),
         });
     }
 
+    fn ensure_not_denied(&self, account: Address) {
+        if !self.is_denylist_enforced() {
+            return;
+        }
+        if let Some(denylist) = self.denylist.get() {
+            if DenylistContractRef::new(self.env(), denylist).is_listed(account) {
+                self.env().revert(TokenError::AddressDenied);
+            }
+        }
+    }
+
     fn only_staking_manager(&self) {
         let caller = self.env().caller();
         let manager = self.staking_manager.get_or_revert_with(TokenError::InsufficientAllowance);
@@ -206,4 +256,16 @@ impl ScsprToken {
             self.env().revert(TokenError::InsufficientAllowance); // Using existing error for unauthorized
         }
     }
+
+    /// Sequence number of the last event emitted; indexers page from here
+    pub fn event_seq(&self) -> u64 {
+        self.event_seq.get_or_default()
+    }
+
+    /// Advance the event sequence and return the number for the next event
+    fn next_event_seq(&mut self) -> u64 {
+        let seq = self.event_seq.get_or_default() + 1;
+        self.event_seq.set(seq);
+        seq
+    }
 }
@@ -12,8 +12,20 @@ use odra::uints::ToU512;
 use odra::ContractRef;
 use super::errors::LstError;
 use super::events::*;
+use super::insurance_fund::InsuranceFundContractRef;
 use super::scspr_token::ScsprTokenContractRef;
+use crate::dex::pair::PairContractRef;
+use crate::dex::router::{FactoryContractRefContractRef, RouterContractRef};
+use crate::math::{SafeMath, BPS_DENOMINATOR};
+use crate::token::Cep18TokenContractRef;
 use crate::cep4626::{Cep4626Vault, Deposit as Cep4626Deposit, Withdraw as Cep4626Withdraw};
+use crate::governance::parameter_registry::{
+    ParameterRegistryContractRef, KEY_LST_MINIMUM_STAKE, KEY_LST_PAUSED, KEY_LST_UNSTAKING_PERIOD,
+};
+use crate::governance::ParametersSynced;
+use crate::governance::schedule::{ParameterSchedule, ScheduledParameter};
+use crate::governance::metrics::{EntrypointMetrics, ProtocolMetrics};
+use crate::governance::guardian::GuardianContractContractRef;
 
 /// Represents an unstaking request
 #[odra::odra_type]
@@ -28,6 +40,109 @@ pub struct UnstakeRequest {
     pub processed: bool,
 }
 
+/// Operating mode of the staking manager, from least to most restrictive
+#[odra::odra_type]
+pub enum PauseMode {
+    /// All operations allowed
+    Normal,
+    /// New stakes blocked; unstaking, withdrawals and operations continue
+    DepositsPaused,
+    /// Only unstaking and withdrawals allowed
+    WithdrawalsOnly,
+    /// Everything blocked
+    FullyPaused,
+}
+
+/// Performance score reported for a validator
+#[odra::odra_type]
+pub struct ValidatorScore {
+    /// Uptime over the reporting window in basis points
+    pub uptime_bps: u16,
+    /// Validator commission in basis points
+    pub commission_bps: u16,
+    /// Combined score: uptime weighted by the share of rewards kept (0-10000)
+    pub score: u32,
+    /// Timestamp of the report
+    pub updated_at: u64,
+}
+
+/// Settings for protocol-side sCSPR/WCSPR peg arbitrage
+#[odra::odra_type]
+pub struct PegArbitrageConfig {
+    /// DEX router used for the trades
+    pub router: Address,
+    /// WCSPR token address
+    pub wcspr: Address,
+    /// Receiver of arbitrage profit
+    pub treasury: Address,
+    /// Smallest deviation from the exchange rate that triggers a trade, in basis points
+    pub threshold_bps: u32,
+}
+
+/// Cumulative DEX price the peg TWAP is measured from
+#[odra::odra_type]
+pub struct PegObservation {
+    /// Cumulative price of sCSPR in WCSPR (scaled by 1e18)
+    pub cumulative: U256,
+    /// Block time of the observation
+    pub timestamp: u64,
+}
+
+/// Exchange rate snapshot for rate consumers such as a stable pair
+#[odra::odra_type]
+pub struct RateInfo {
+    /// CSPR per sCSPR (scaled by 1e18)
+    pub rate: U256,
+    /// Block time rewards or a slash last moved the rate
+    pub updated_at: u64,
+    /// Whether `updated_at` is older than the maximum rate age
+    pub stale: bool,
+}
+
+impl RateInfo {
+    /// Rate a consumer should peg to: the exchange rate while fresh,
+    /// `fallback` (e.g. 1:1) once it is stale
+    pub fn peg_rate(&self, fallback: U256) -> U256 {
+        if self.stale {
+            fallback
+        } else {
+            self.rate
+        }
+    }
+}
+
+/// Default longest time the rate may go without an update before it is stale (1 day)
+pub const DEFAULT_MAX_RATE_AGE: u64 = 24 * 60 * 60;
+
+/// Basis points denominator for peg deviation
+const PEG_BPS_DENOMINATOR: u32 = 10_000;
+
+/// Shortest window the peg TWAP may be measured over
+pub const PEG_TWAP_WINDOW: u64 = 10 * 60;
+
+/// Longest window the peg TWAP may be measured over before it is too stale to trade on
+pub const PEG_TWAP_MAX_AGE: u64 = 60 * 60;
+
+/// Largest shortfall of a peg trade's output against the TWAP quote, in basis points
+pub const PEG_MAX_SLIPPAGE_BPS: u32 = 100;
+
+/// Largest share of rewards that can go to the insurance fund (20%)
+pub const MAX_INSURANCE_FEE_BPS: u16 = 2_000;
+
+/// Schedule key of the insurance fee
+pub const PARAM_INSURANCE_FEE_BPS: &str = "insurance_fee_bps";
+
+/// Decimals of `get_precise_fair_price`; income checkpoints use this scale
+/// so distributions too small to move the 1e18 rate still accrue
+pub const PRECISE_RATE_DECIMALS: usize = 36;
+
+/// Metrics entry for stakes (no fee)
+pub const METRIC_STAKE: &str = "stake";
+/// Metrics entry for unstake requests (no fee)
+pub const METRIC_UNSTAKE: &str = "unstake";
+/// Metrics entry for reward distributions; fees are the insurance slice in motes
+pub const METRIC_DISTRIBUTE_REWARDS: &str = "distribute_rewards";
+
 /// Staking Manager contract
 #[odra::module]
 pub struct StakingManager {
@@ -67,14 +182,97 @@ pub struct StakingManager {
     /// Next unstake request ID
     next_unstake_request_id: Var<u64>,
     
+    /// Operator approved to transfer an unstake request: request_id -> operator
+    unstake_request_approvals: Mapping<u64, Option<Address>>,
+    
     /// Contract admin
     admin: Var<Address>,
     
-    /// Whether the contract is paused
-    paused: Var<bool>,
+    /// Current operating mode
+    pause_mode: Var<PauseMode>,
     
     /// Exchange rate scaling factor (1e18)
     exchange_rate_scale: Var<U256>,
+    
+    /// Optional parameter registry the local settings are synced from
+    parameter_registry: Var<Option<Address>>,
+    
+    /// Registry version the local settings were last synced at
+    parameter_registry_version: Var<u64>,
+    
+    /// Oracle allowed to report validator scores (admin can always report)
+    score_oracle: Var<Option<Address>>,
+    
+    /// Latest reported score per validator
+    validator_scores: Mapping<Address, ValidatorScore>,
+    
+    /// Validators scoring below this are queued for undelegation
+    min_validator_score: Var<u32>,
+    
+    /// Validators charging more commission than this (bps) get no new delegations
+    /// and are queued for undelegation (unset = no cap)
+    max_validator_commission_bps: Var<u16>,
+    
+    /// Stake queued for undelegation per validator
+    pending_undelegations: Mapping<Address, U256>,
+    
+    /// Optional guardian that can pause this contract
+    guardian: Var<Option<Address>>,
+    
+    /// Peg arbitrage settings
+    peg_config: Var<Option<PegArbitrageConfig>>,
+    
+    /// Keeper allowed to run peg arbitrage besides the admin
+    peg_keeper: Var<Option<Address>>,
+    
+    /// WCSPR held for peg arbitrage
+    peg_buffer: Var<U256>,
+    
+    /// Start of the DEX price window peg arbitrage trades on
+    peg_observation: Var<Option<PegObservation>>,
+    
+    /// Insurance fund that receives a reward fee and covers slashes
+    insurance_fund: Var<Option<Address>>,
+    
+    /// Share of rewards paid to the insurance fund, in basis points
+    insurance_fee_bps: Var<u16>,
+    
+    /// Reward fees owed to the insurance fund and not yet collected
+    insurance_fees_owed: Var<U256>,
+    
+    /// Delayed fee changes announced ahead of time
+    schedule: SubModule<ParameterSchedule>,
+
+    /// Opt-in per-entrypoint call and fee counters
+    metrics: SubModule<ProtocolMetrics>,
+    
+    /// Users who take rewards as claimable CSPR instead of sCSPR appreciation
+    auto_restake_disabled: Mapping<Address, bool>,
+    
+    /// Precise fair price (CSPR per sCSPR, 10^PRECISE_RATE_DECIMALS) up to which income users have been settled
+    income_checkpoints: Mapping<Address, U256>,
+    
+    /// sCSPR balance income accrues on, per income user: sCSPR received
+    /// since the last settlement only starts earning at the next one
+    income_balances: Mapping<Address, U256>,
+    
+    /// Settled CSPR rewards awaiting a claim, per income user
+    claimable_rewards: Mapping<Address, U256>,
+    
+    /// Sum of all settled, unclaimed income rewards
+    total_claimable_rewards: Var<U256>,
+    
+    /// Periphery allowed to withdraw on behalf of the user who called it
+    claim_router: Var<Option<Address>>,
+    
+    /// Block time the exchange rate was last moved by rewards or a slash
+    rate_updated_at: Var<u64>,
+    
+    /// Longest time the rate may go without an update before it is stale (unset = default)
+    max_rate_age: Var<u64>,
+
+    /// Sequence number of the last event emitted
+    event_seq: Var<u64>,
 }
 
 #[odra::module]
@@ -94,8 +292,9 @@ impl StakingManager {
         self.unstaking_period.set(57_600); // ~16 hours (7 eras)
         self.next_unstake_request_id.set(0);
         self.admin.set(caller);
-        self.paused.set(false);
+        self.pause_mode.set(PauseMode::Normal);
         self.exchange_rate_scale.set(U256::from(1_000_000_000_000_000_000u128)); // 1e18
+        self.rate_updated_at.set(self.env().get_block_time());
     }
 
     /// Stake CSPR and receive sCSPR
@@ -107,7 +306,7 @@ impl StakingManager {
     /// # Returns
     /// The amount of sCSPR minted
     pub fn stake(&mut self, cspr_amount: U256) -> U256 {
-        self.ensure_not_paused();
+        self.ensure_deposits_allowed();
         
         let caller = self.env().caller();
         
@@ -121,6 +320,9 @@ impl StakingManager {
             self.env().revert(LstError::BelowMinimumStake);
         }
         
+        // Settle income rewards so the new sCSPR only earns from here on
+        self.settle_income(caller);
+        
         // Calculate sCSPR amount based on current exchange rate
         let scspr_amount = self.calculate_scspr_amount(cspr_amount);
         
@@ -136,9 +338,16 @@ impl StakingManager {
         let token_address = self.scspr_token_address.get_or_revert_with(LstError::StakingFailed);
         let mut token = ScsprTokenContractRef::new(self.env(), token_address);
         token.mint(caller, scspr_amount);
+        if !self.is_auto_restake(caller) {
+            self.track_income_balance(caller);
+        }
         
+        // Route new delegations to the best scoring validator
         // TODO: Actual delegation to Casper validator would happen here
         // This would use Casper's native staking system calls
+        if let Some(validator) = self.get_best_validator() {
+            self.delegate_to(validator, cspr_amount);
+        }
         
         // Emit event
         let exchange_rate = self.get_exchange_rate();
@@ -149,7 +358,9 @@ impl StakingManager {
             scspr_amount,
             exchange_rate,
             timestamp,
+            seq: self.next_event_seq(),
         });
+        self.metrics.record(METRIC_STAKE, U256::zero());
         
         scspr_amount
     }
@@ -162,7 +373,7 @@ impl StakingManager {
     /// # Returns
     /// The unstake request ID
     pub fn unstake(&mut self, scspr_amount: U256) -> u64 {
-        self.ensure_not_paused();
+        self.ensure_withdrawals_allowed();
         
         let caller = self.env().caller();
         
@@ -192,29 +403,7 @@ impl StakingManager {
         self.total_scspr_supply.set(current_supply - scspr_amount);
         
         // Create unstake request
-        let request_id = self.next_unstake_request_id.get_or_default();
-        let timestamp = self.env().get_block_time();
-        let unstaking_period = self.unstaking_period.get_or_default();
-        let withdrawable_at = timestamp + unstaking_period;
-        
-        let request = UnstakeRequest {
-            user: caller,
-            cspr_amount,
-            withdrawable_at,
-            processed: false,
-        };
-        
-        self.unstake_requests.set(&request_id, request);
-        
-        // Add to user's request list
-        let mut user_requests = self.user_unstake_requests.get(&caller).unwrap_or_default();
-        user_requests.push(request_id);
-        self.user_unstake_requests.set(&caller, user_requests);
-        
-        // Increment request ID
-        self.next_unstake_request_id.set(request_id + 1);
-        
-        // TODO: Actual undelegation from Casper validator would happen here
+        let (request_id, withdrawable_at) = self.queue_withdrawal(caller, cspr_amount);
         
         // Emit event
         let exchange_rate = self.get_exchange_rate();
@@ -225,7 +414,9 @@ impl StakingManager {
             request_id,
             exchange_rate,
             withdrawable_at,
+            seq: self.next_event_seq(),
         });
+        self.metrics.record(METRIC_UNSTAKE, U256::zero());
         
         request_id
     }
@@ -235,52 +426,78 @@ impl StakingManager {
     /// # Arguments
     /// * `request_id` - The unstake request ID
     pub fn withdraw_unstaked(&mut self, request_id: u64) {
-        self.ensure_not_paused();
-        
         let caller = self.env().caller();
-        
-        // Get unstake request
-        let mut request = self.unstake_requests.get(&request_id)
-            .unwrap_or_else(|| self.env().revert(LstError::InvalidUnstakeRequestId));
-        
-        // Verify request belongs to caller
-        if request.user != caller {
+        self.withdraw_unstaked_of(caller, request_id);
+    }
+
+    /// Withdraw a matured unstake request of `user`, paid to `user`
+    /// Only callable by the claim router, which acts for its own caller
+    pub fn withdraw_unstaked_for(&mut self, user: Address, request_id: u64) -> U256 {
+        if self.get_claim_router() != Some(self.env().caller()) {
             self.env().revert(LstError::Unauthorized);
         }
+        self.withdraw_unstaked_of(user, request_id)
+    }
+
+    /// Transfer a pending unstake request; the new holder withdraws the CSPR at maturity
+    /// Callable by the holder or the operator it approved
+    pub fn transfer_unstake_request(&mut self, request_id: u64, to: Address) {
+        self.ensure_withdrawals_allowed();
         
-        // Check if already processed
+        let caller = self.env().caller();
+        let mut request = self.unstake_requests.get(&request_id)
+            .unwrap_or_else(|| self.env().revert(LstError::InvalidUnstakeRequestId));
         if request.processed {
             self.env().revert(LstError::UnstakeRequestAlreadyProcessed);
         }
-        
-        // Check if unstaking period has passed
-        let current_time = self.env().get_block_time();
-        if current_time < request.withdrawable_at {
-            self.env().revert(LstError::UnstakingPeriodNotComplete);
+        let from = request.user;
+        let approved = self.get_unstake_request_approval(request_id);
+        if caller != from && approved != Some(caller) {
+            self.env().revert(LstError::Unauthorized);
         }
         
-        // Mark as processed
-        request.processed = true;
-        self.unstake_requests.set(&request_id, request.clone());
+        request.user = to;
+        self.unstake_requests.set(&request_id, request);
+        self.unstake_request_approvals.set(&request_id, None);
         
-        // Update total staked
-        let current_total = self.total_cspr_staked.get_or_default();
-        self.total_cspr_staked.set(current_total - request.cspr_amount);
+        let mut from_requests = self.user_unstake_requests.get(&from).unwrap_or_default();
+        from_requests.retain(|id| *id != request_id);
+        self.user_unstake_requests.set(&from, from_requests);
+        let mut to_requests = self.user_unstake_requests.get(&to).unwrap_or_default();
+        to_requests.push(request_id);
+        self.user_unstake_requests.set(&to, to_requests);
         
-        // Transfer CSPR to user
-        let cspr_amount_u512 = request.cspr_amount.to_u512();
-        self.env().transfer_tokens(&caller, &cspr_amount_u512);
+        self.env().emit_event(UnstakeRequestTransferred {
+            request_id,
+            from,
+            to,
+            seq: self.next_event_seq(),
+        });
+    }
+
+    /// Approve (or clear) an operator, such as a marketplace, to transfer an unstake request
+    pub fn approve_unstake_request(&mut self, request_id: u64, operator: Option<Address>) {
+        let caller = self.env().caller();
+        let request = self.unstake_requests.get(&request_id)
+            .unwrap_or_else(|| self.env().revert(LstError::InvalidUnstakeRequestId));
+        if request.user != caller {
+            self.env().revert(LstError::Unauthorized);
+        }
+        self.unstake_request_approvals.set(&request_id, operator);
         
-        // Emit event
-        let timestamp = self.env().get_block_time();
-        self.env().emit_event(Withdrawn {
-            withdrawer: caller,
-            cspr_amount: request.cspr_amount,
+        self.env().emit_event(UnstakeRequestApproval {
             request_id,
-            timestamp,
+            owner: caller,
+            operator,
+            seq: self.next_event_seq(),
         });
     }
 
+    /// Get the operator approved to transfer an unstake request
+    pub fn get_unstake_request_approval(&self, request_id: u64) -> Option<Address> {
+        self.unstake_request_approvals.get(&request_id).flatten()
+    }
+
     /// Distribute staking rewards (called periodically by admin or keeper)
     /// This updates the exchange rate based on accumulated rewards
     /// 
@@ -288,15 +505,35 @@ impl StakingManager {
     /// * `rewards_amount` - Amount of CSPR rewards earned
     pub fn distribute_rewards(&mut self, rewards_amount: U256) {
         self.only_admin();
+        self.ensure_operations_allowed();
         
         if rewards_amount == U256::zero() {
             return;
         }
         
+        // The insurance slice leaves the pool before the rest accrues to holders
+        // It is only recorded here; the fund collects it once the reward CSPR
+        // has reached the contract
+        let mut rewards_amount = rewards_amount;
+        let mut insurance_fee = U256::zero();
+        if self.get_insurance_fund().is_some() {
+            let fee = self.mul_div(
+                rewards_amount,
+                U256::from(self.get_insurance_fee_bps()),
+                U256::from(BPS_DENOMINATOR),
+            );
+            if !fee.is_zero() {
+                self.insurance_fees_owed.set(self.get_insurance_fees_owed() + fee);
+                rewards_amount -= fee;
+                insurance_fee = fee;
+            }
+        }
+        
         // Update total CSPR staked (includes rewards)
         let current_total = self.total_cspr_staked.get_or_default();
         let new_total = current_total + rewards_amount;
         self.total_cspr_staked.set(new_total);
+        self.rate_updated_at.set(self.env().get_block_time());
         
         // Calculate new exchange rate
         let new_rate = self.get_exchange_rate();
@@ -310,7 +547,9 @@ impl StakingManager {
             total_scspr_supply: total_scspr,
             new_exchange_rate: new_rate,
             timestamp,
+            seq: self.next_event_seq(),
         });
+        self.metrics.record(METRIC_DISTRIBUTE_REWARDS, insurance_fee);
     }
 
     // View functions
@@ -329,7 +568,36 @@ impl StakingManager {
         // Rate = (total_scspr * 1e18) / total_cspr
         // This gives us how much sCSPR per CSPR
         let scale = self.exchange_rate_scale.get_or_default();
-        (total_scspr * scale) / total_cspr
+        self.mul_div(total_scspr, scale, total_cspr)
+    }
+
+    /// Get the exact exchange rate as (CSPR, sCSPR): CSPR per sCSPR with no rounding
+    /// (1, 1) while nothing is staked
+    pub fn get_exchange_rate_fraction(&self) -> (U256, U256) {
+        let total_scspr = self.total_scspr_supply.get_or_default();
+        let total_cspr = self.total_cspr_staked.get_or_default();
+        if total_scspr == U256::zero() || total_cspr == U256::zero() {
+            return (U256::one(), U256::one());
+        }
+        (total_cspr, total_scspr)
+    }
+
+    /// Get the sCSPR price in CSPR with its freshness, for rate consumers
+    /// Consumers should fall back to their own peg (e.g. 1:1) while `stale`
+    /// is set; `RateInfo::peg_rate` applies that rule
+    pub fn get_rate(&self) -> RateInfo {
+        let updated_at = self.rate_updated_at.get_or_default();
+        let age = self.env().get_block_time().saturating_sub(updated_at);
+        RateInfo {
+            rate: self.get_fair_price(),
+            updated_at,
+            stale: age > self.get_max_rate_age(),
+        }
+    }
+
+    /// Get the longest time the rate may go without an update before it is stale
+    pub fn get_max_rate_age(&self) -> u64 {
+        self.max_rate_age.get().unwrap_or(DEFAULT_MAX_RATE_AGE)
     }
 
     /// Get the amount of CSPR for a given amount of sCSPR
@@ -394,8 +662,161 @@ impl StakingManager {
         self.validator_stakes.get(&validator).unwrap_or_default()
     }
 
+    /// Get the latest reported score for a validator
+    pub fn get_validator_score(&self, validator: Address) -> Option<ValidatorScore> {
+        self.validator_scores.get(&validator)
+    }
+
+    /// Get the minimum score a validator needs to keep its delegation
+    pub fn get_min_validator_score(&self) -> u32 {
+        self.min_validator_score.get_or_default()
+    }
+
+    /// Get the highest commission (bps) a validator may charge to keep its delegation
+    pub fn get_max_validator_commission(&self) -> u16 {
+        self.max_validator_commission_bps.get().unwrap_or(10_000)
+    }
+
+    /// Get the score oracle address
+    pub fn get_score_oracle(&self) -> Option<Address> {
+        self.score_oracle.get_or_default()
+    }
+
+    /// Get stake queued for undelegation from a validator
+    pub fn get_pending_undelegation(&self, validator: Address) -> U256 {
+        self.pending_undelegations.get(&validator).unwrap_or_default()
+    }
+
+    /// Get the approved, scored validator with the highest score at or above the minimum
+    /// Unscored validators are never selected for new delegations
+    pub fn get_best_validator(&self) -> Option<Address> {
+        let min_score = self.min_validator_score.get_or_default();
+        let max_commission = self.get_max_validator_commission();
+        let mut best: Option<(Address, u32)> = None;
+        for validator in self.get_validators() {
+            if !self.is_validator_approved(validator) {
+                continue;
+            }
+            if let Some(score) = self.validator_scores.get(&validator) {
+                let better = match best {
+                    Some((_, best_score)) => score.score > best_score,
+                    None => true,
+                };
+                if score.score >= min_score && score.commission_bps <= max_commission && better {
+                    best = Some((validator, score.score));
+                }
+            }
+        }
+        best.map(|(validator, _)| validator)
+    }
+
+    // Scoring and rebalancing
+
+    /// Report uptime and commission for an approved validator
+    /// Callable by the score oracle or the admin. A commission above the cap
+    /// queues the validator's stake for undelegation right away
+    pub fn report_validator_score(&mut self, validator: Address, uptime_bps: u16, commission_bps: u16) {
+        let caller = self.env().caller();
+        let is_oracle = self.score_oracle.get_or_default() == Some(caller);
+        if !is_oracle && caller != self.get_admin() {
+            self.env().revert(LstError::Unauthorized);
+        }
+        if !self.is_validator_approved(validator) {
+            self.env().revert(LstError::InvalidValidator);
+        }
+        if uptime_bps > 10_000 || commission_bps > 10_000 {
+            self.env().revert(LstError::InvalidScore);
+        }
+        
+        let score = uptime_bps as u32 * (10_000 - commission_bps as u32) / 10_000;
+        let timestamp = self.env().get_block_time();
+        self.validator_scores.set(&validator, ValidatorScore {
+            uptime_bps,
+            commission_bps,
+            score,
+            updated_at: timestamp,
+        });
+        
+        self.env().emit_event(ValidatorScoreReported {
+            validator,
+            uptime_bps,
+            commission_bps,
+            score,
+            reported_by: caller,
+            seq: self.next_event_seq(),
+        });
+
+        if commission_bps > self.get_max_validator_commission() {
+            self.queue_undelegation(validator);
+        }
+    }
+
+    /// Queue undelegation of the full stake of removed, underperforming or
+    /// over-commission validators
+    /// Callable by anyone; validators without a score are left untouched
+    pub fn rebalance(&mut self) {
+        self.ensure_operations_allowed();
+        let min_score = self.min_validator_score.get_or_default();
+        let max_commission = self.get_max_validator_commission();
+        for validator in self.get_validators() {
+            let underperforming = match self.validator_scores.get(&validator) {
+                Some(score) => score.score < min_score || score.commission_bps > max_commission,
+                None => false,
+            };
+            if !underperforming && self.is_validator_approved(validator) {
+                continue;
+            }
+            self.queue_undelegation(validator);
+        }
+    }
+
+    /// Redelegate stake queued from a validator to the current best validator
+    /// Called by the admin once the undelegation has completed on chain
+    pub fn process_undelegation(&mut self, validator: Address) {
+        self.only_admin();
+        self.ensure_operations_allowed();
+        
+        let amount = self.pending_undelegations.get(&validator).unwrap_or_default();
+        if amount.is_zero() {
+            self.env().revert(LstError::InvalidAmount);
+        }
+        let target = self.get_best_validator()
+            .unwrap_or_else(|| self.env().revert(LstError::InvalidValidator));
+        
+        self.pending_undelegations.set(&validator, U256::zero());
+        self.delegate_to(target, amount);
+    }
+
     // Admin functions
 
+    /// Set (or clear) the oracle allowed to report validator scores
+    pub fn set_score_oracle(&mut self, oracle: Option<Address>) {
+        self.only_admin();
+        self.score_oracle.set(oracle);
+        self.emit_setting_updated("score_oracle");
+    }
+
+    /// Set the highest commission (0-10000 bps) validators may charge to keep their delegation
+    /// Validators already above it are queued by the next `rebalance`
+    pub fn set_max_validator_commission(&mut self, max_commission_bps: u16) {
+        self.only_admin();
+        if max_commission_bps > 10_000 {
+            self.env().revert(LstError::InvalidScore);
+        }
+        self.max_validator_commission_bps.set(max_commission_bps);
+        self.emit_setting_updated("max_validator_commission");
+    }
+
+    /// Set the minimum score (0-10000) for validators to keep their delegation
+    pub fn set_min_validator_score(&mut self, min_score: u32) {
+        self.only_admin();
+        if min_score > 10_000 {
+            self.env().revert(LstError::InvalidScore);
+        }
+        self.min_validator_score.set(min_score);
+        self.emit_setting_updated("min_validator_score");
+    }
+
     /// Add a validator to the approved list
     pub fn add_validator(&mut self, validator: Address) {
         self.only_admin();
@@ -412,6 +833,7 @@ impl StakingManager {
                 validator,
                 added_by: admin,
                 timestamp,
+                seq: self.next_event_seq(),
             });
         }
     }
@@ -431,6 +853,7 @@ impl StakingManager {
                 validator,
                 removed_by: admin,
                 timestamp,
+                seq: self.next_event_seq(),
             });
         }
     }
@@ -446,6 +869,7 @@ impl StakingManager {
             old_minimum,
             new_minimum,
             updated_by: admin,
+            seq: self.next_event_seq(),
         });
     }
 
@@ -460,90 +884,1075 @@ impl StakingManager {
             old_period,
             new_period,
             updated_by: admin,
+            seq: self.next_event_seq(),
         });
     }
 
-    /// Pause the contract
+    /// Pause the contract (equivalent to `FullyPaused`)
     pub fn pause(&mut self) {
         self.only_admin();
-        self.paused.set(true);
+        self.update_pause_mode(PauseMode::FullyPaused);
         
         let admin = self.admin.get_or_revert_with(LstError::Unauthorized);
         let timestamp = self.env().get_block_time();
         self.env().emit_event(ContractPaused {
             paused_by: admin,
             timestamp,
+            seq: self.next_event_seq(),
         });
     }
 
-    /// Unpause the contract
+    /// Unpause the contract (back to `Normal`)
     pub fn unpause(&mut self) {
         self.only_admin();
-        self.paused.set(false);
+        self.update_pause_mode(PauseMode::Normal);
         
         let admin = self.admin.get_or_revert_with(LstError::Unauthorized);
         let timestamp = self.env().get_block_time();
         self.env().emit_event(ContractUnpaused {
             unpaused_by: admin,
             timestamp,
+            seq: self.next_event_seq(),
         });
     }
 
+    /// Switch to a graded pause mode
+    pub fn set_pause_mode(&mut self, mode: PauseMode) {
+        self.only_admin();
+        self.update_pause_mode(mode);
+    }
+
     /// Transfer admin rights
     pub fn transfer_admin(&mut self, new_admin: Address) {
         self.only_admin();
+        let previous_admin = self.get_admin();
         self.admin.set(new_admin);
+        
+        self.env().emit_event(AdminTransferred {
+            previous_admin,
+            new_admin,
+            seq: self.next_event_seq(),
+        });
     }
 
-    /// Get admin address
-    pub fn get_admin(&self) -> Address {
-        self.admin.get_or_revert_with(LstError::Unauthorized)
+    /// Set (or clear) the guardian whose pauses apply to this contract
+    pub fn set_guardian(&mut self, guardian: Option<Address>) {
+        self.only_admin();
+        self.guardian.set(guardian);
+        self.emit_setting_updated("guardian");
     }
 
-    /// Check if contract is paused
-    pub fn is_paused(&self) -> bool {
-        self.paused.get_or_default()
+    /// Get the guardian address
+    pub fn get_guardian(&self) -> Option<Address> {
+        self.guardian.get_or_default()
     }
 
-    // Internal helper functions
+    /// Set (or clear) the periphery allowed to withdraw on behalf of users
+    pub fn set_claim_router(&mut self, router: Option<Address>) {
+        self.only_admin();
+        self.claim_router.set(router);
+        self.emit_setting_updated("claim_router");
+    }
 
-    fn calculate_scspr_amount(&self, cspr_amount: U256) -> U256 {
-        let total_scspr = self.total_scspr_supply.get_or_default();
-        let total_cspr = self.total_cspr_staked.get_or_default();
-        
-        if total_scspr == U256::zero() || total_cspr == U256::zero() {
-            // Initial stake: 1:1 ratio
-            return cspr_amount;
-        }
-        
-        // sCSPR = (cspr_amount * total_scspr) / total_cspr
-        (cspr_amount * total_scspr) / total_cspr
+    /// Get the periphery allowed to withdraw on behalf of users
+    pub fn get_claim_router(&self) -> Option<Address> {
+        self.claim_router.get_or_default()
     }
 
-    fn calculate_cspr_amount(&self, scspr_amount: U256) -> U256 {
-        let total_scspr = self.total_scspr_supply.get_or_default();
-        let total_cspr = self.total_cspr_staked.get_or_default();
+    /// Set the longest time the rate may go without an update before `get_rate` reports it stale
+    pub fn set_max_rate_age(&mut self, max_age: u64) {
+        self.only_admin();
+        self.max_rate_age.set(max_age);
+        self.emit_setting_updated("max_rate_age");
+    }
+
+    /// Start or stop recording stake, unstake and reward metrics
+    pub fn set_metrics_enabled(&mut self, enabled: bool) {
+        self.only_admin();
+        self.metrics.set_enabled(enabled);
+        self.emit_setting_updated("metrics_enabled");
+    }
+
+    /// Close the current metrics epoch and start a new one; returns the new epoch
+    pub fn rotate_metrics(&mut self) -> u64 {
+        self.only_admin();
+        self.metrics.rotate()
+    }
+
+    /// Check if metrics are being recorded
+    pub fn is_metrics_enabled(&self) -> bool {
+        self.metrics.is_enabled()
+    }
+
+    /// Get the current metrics epoch
+    pub fn get_metrics_epoch(&self) -> u64 {
+        self.metrics.epoch()
+    }
+
+    /// Get the counters of a metrics entry (`METRIC_*`) in an epoch
+    pub fn get_metrics(&self, epoch: u64, entrypoint: String) -> EntrypointMetrics {
+        self.metrics.get(epoch, entrypoint)
+    }
+
+    /// Set (or clear) the parameter registry used by `sync_parameters`
+    pub fn set_parameter_registry(&mut self, registry: Option<Address>) {
+        self.only_admin();
+        self.parameter_registry.set(registry);
+        self.parameter_registry_version.set(0);
+        self.emit_setting_updated("parameter_registry");
+    }
+
+    /// Get the parameter registry address
+    pub fn get_parameter_registry(&self) -> Option<Address> {
+        self.parameter_registry.get_or_default()
+    }
+
+    /// Pull minimum stake, unstaking period and pause flag from the registry
+    /// Callable by anyone; does nothing if the registry has not changed
+    pub fn sync_parameters(&mut self) {
+        let registry_address = self.parameter_registry.get_or_default()
+            .unwrap_or_else(|| self.env().revert(LstError::ParameterRegistryNotSet));
+        let registry = ParameterRegistryContractRef::new(self.env(), registry_address);
+        
+        let version = registry.version();
+        if version == self.parameter_registry_version.get_or_default() {
+            return;
+        }
         
-        if total_scspr == U256::zero() {
-            return U256::zero();
+        if let Some(minimum) = registry.get_u256(String::from(KEY_LST_MINIMUM_STAKE)) {
+            self.minimum_stake.set(minimum);
+        }
+        if let Some(period) = registry.get_u64(String::from(KEY_LST_UNSTAKING_PERIOD)) {
+            self.unstaking_period.set(period);
+        }
+        if let Some(paused) = registry.get_bool(String::from(KEY_LST_PAUSED)) {
+            // The registry flag toggles a full pause; graded modes set locally are kept
+            if paused {
+                self.update_pause_mode(PauseMode::FullyPaused);
+            } else if self.get_pause_mode() == PauseMode::FullyPaused {
+                self.update_pause_mode(PauseMode::Normal);
+            }
         }
+        self.parameter_registry_version.set(version);
         
-        // CSPR = (scspr_amount * total_cspr) / total_scspr
-        (scspr_amount * total_cspr) / total_scspr
+        self.env().emit_event(ParametersSynced {
+            registry: registry_address,
+            version,
+        });
     }
 
-    fn only_admin(&self) {
-        let caller = self.env().caller();
-        let admin = self.admin.get_or_revert_with(LstError::Unauthorized);
-        if caller != admin {
-            self.env().revert(LstError::Unauthorized);
-        }
+    /// Get admin address
+    pub fn get_admin(&self) -> Address {
+        self.admin.get_or_revert_with(LstError::Unauthorized)
     }
 
-    fn ensure_not_paused(&self) {
-        if self.paused.get_or_default() {
-            self.env().revert(LstError::ContractPaused);
+    /// Check if contract is fully paused
+    pub fn is_paused(&self) -> bool {
+        self.get_pause_mode() == PauseMode::FullyPaused
+    }
+
+    /// Get the pause mode set on this contract
+    pub fn get_pause_mode(&self) -> PauseMode {
+        self.pause_mode.get().unwrap_or(PauseMode::Normal)
+    }
+
+    /// Get the mode in force, treating an active guardian pause as `FullyPaused`
+    pub fn get_effective_pause_mode(&self) -> PauseMode {
+        if let Some(guardian) = self.guardian.get_or_default() {
+            let guardian = GuardianContractContractRef::new(self.env(), guardian);
+            if guardian.is_paused(self.env().self_address()) {
+                return PauseMode::FullyPaused;
+            }
+        }
+        self.get_pause_mode()
+    }
+
+    // ========================================
+    // Peg Arbitrage
+    // ========================================
+
+    /// Trade the DEX sCSPR/WCSPR price back toward the exchange rate
+    ///
+    /// At a premium the manager stakes WCSPR from the peg buffer, mints sCSPR
+    /// and sells it; the buffer is made whole and the surplus goes to the
+    /// treasury. At a discount it buys sCSPR with buffer WCSPR and redeems it
+    /// through an unstake request owned by the treasury. Trade size is capped
+    /// by `max_amount` (WCSPR) and the buffer.
+    ///
+    /// The decision and the size use the pair's TWAP since the last
+    /// observation (see `observe_peg`), which must be between
+    /// PEG_TWAP_WINDOW and PEG_TWAP_MAX_AGE old, so a same-block sandwich
+    /// cannot steer the buffer. The swap must return at least the TWAP quote
+    /// less PEG_MAX_SLIPPAGE_BPS. A successful trade opens the next window.
+    ///
+    /// # Returns
+    /// The profit (WCSPR paid, or CSPR queued, beyond the WCSPR spent)
+    pub fn arbitrage_peg(&mut self, max_amount: U256) -> U256 {
+        self.ensure_operations_allowed();
+        self.only_peg_keeper();
+        
+        let config = self.get_peg_config()
+            .unwrap_or_else(|| self.env().revert(LstError::PegArbitrageNotConfigured));
+        let scspr = self.scspr_token_address.get_or_revert_with(LstError::StakingFailed);
+        let router = RouterContractRef::new(self.env(), config.router);
+        
+        let (reserve_scspr, reserve_wcspr) = router.get_reserves(scspr, config.wcspr);
+        if reserve_scspr.is_zero() || reserve_wcspr.is_zero() {
+            self.env().revert(LstError::PegWithinThreshold);
+        }
+        
+        // TWAP since the open observation; the next window starts now
+        let observation = self.peg_observation.get_or_default()
+            .unwrap_or_else(|| self.env().revert(LstError::PegTwapNotReady));
+        let now = self.env().get_block_time();
+        let elapsed = now.saturating_sub(observation.timestamp);
+        if !(PEG_TWAP_WINDOW..=PEG_TWAP_MAX_AGE).contains(&elapsed) {
+            self.env().revert(LstError::PegTwapNotReady);
+        }
+        let cumulative = self.peg_cumulative(&config, scspr);
+        let dex_price = cumulative.overflowing_sub(observation.cumulative).0 / U256::from(elapsed);
+        if dex_price.is_zero() {
+            self.env().revert(LstError::PegTwapNotReady);
+        }
+        self.peg_observation.set(Some(PegObservation { cumulative, timestamp: now }));
+        
+        let scale = self.exchange_rate_scale.get_or_default();
+        let fair_price = self.get_fair_price();
+        let deviation = if dex_price > fair_price { dex_price - fair_price } else { fair_price - dex_price };
+        let threshold = self.mul_div(fair_price, U256::from(config.threshold_bps), U256::from(PEG_BPS_DENOMINATOR));
+        if deviation.is_zero() || deviation <= threshold {
+            self.env().revert(LstError::PegWithinThreshold);
+        }
+        
+        let budget = max_amount.min(self.peg_buffer.get_or_default());
+        if budget.is_zero() {
+            self.env().revert(LstError::InsufficientPegBuffer);
+        }
+        
+        // Depth comes from the spot reserves and price from the TWAP: a
+        // sandwich moves the price, not the product of the reserves
+        let depth = self.mul_div(reserve_scspr, reserve_wcspr, U256::one());
+        let scspr_at = |price: U256| SafeMath::sqrt(self.mul_div(depth, scale, price));
+        let wcspr_at = |price: U256| SafeMath::sqrt(self.mul_div(depth, price, scale));
+        let (twap_scspr, twap_wcspr) = (scspr_at(dex_price), wcspr_at(dex_price));
+        let fee_bps = PairContractRef::new(self.env(), self.peg_pair(&config, scspr)).fee_bps();
+        
+        // Sizing ignores the swap fee, so trades stop just short of the peg
+        if dex_price > fair_price {
+            self.ensure_deposits_allowed();
+            let scspr_to_sell = scspr_at(fair_price).saturating_sub(twap_scspr);
+            let cspr_cost = self.calculate_cspr_amount(scspr_to_sell).min(budget);
+            let scspr_amount = self.calculate_scspr_amount(cspr_cost);
+            let min_out = self.peg_min_out(scspr_amount, twap_scspr, twap_wcspr, fee_bps).max(cspr_cost);
+            self.arbitrage_premium(config, scspr, dex_price, fair_price, cspr_cost, min_out)
+        } else {
+            let wcspr_in = wcspr_at(fair_price).saturating_sub(twap_wcspr).min(budget);
+            let min_out = self.peg_min_out(wcspr_in, twap_wcspr, twap_scspr, fee_bps)
+                .max(self.calculate_scspr_amount(wcspr_in));
+            self.arbitrage_discount(config, scspr, dex_price, fair_price, wcspr_in, min_out)
+        }
+    }
+
+    /// Open the DEX price window the next peg arbitrage measures its TWAP over
+    /// Only the keeper or admin can observe, so nobody else can keep resetting the window
+    pub fn observe_peg(&mut self) {
+        self.only_peg_keeper();
+        let config = self.get_peg_config()
+            .unwrap_or_else(|| self.env().revert(LstError::PegArbitrageNotConfigured));
+        let scspr = self.scspr_token_address.get_or_revert_with(LstError::StakingFailed);
+        let cumulative = self.peg_cumulative(&config, scspr);
+        let timestamp = self.env().get_block_time();
+        self.peg_observation.set(Some(PegObservation { cumulative, timestamp }));
+        self.env().emit_event(PegObserved {
+            cumulative,
+            timestamp,
+            seq: self.next_event_seq(),
+        });
+    }
+
+    /// Get the observation the next peg TWAP is measured from
+    pub fn get_peg_observation(&self) -> Option<PegObservation> {
+        self.peg_observation.get_or_default()
+    }
+
+    /// Add WCSPR to the peg buffer (pulled from the caller)
+    pub fn fund_peg_buffer(&mut self, amount: U256) {
+        if amount.is_zero() {
+            self.env().revert(LstError::InvalidAmount);
+        }
+        let config = self.get_peg_config()
+            .unwrap_or_else(|| self.env().revert(LstError::PegArbitrageNotConfigured));
+        let caller = self.env().caller();
+        let mut wcspr = Cep18TokenContractRef::new(self.env(), config.wcspr);
+        wcspr.transfer_from(caller, self.env().self_address(), amount);
+        
+        let balance = self.peg_buffer.get_or_default() + amount;
+        self.peg_buffer.set(balance);
+        self.env().emit_event(PegBufferUpdated {
+            amount,
+            funded: true,
+            balance,
+            account: caller,
+            seq: self.next_event_seq(),
+        });
+    }
+
+    /// Take WCSPR out of the peg buffer (admin only)
+    pub fn withdraw_peg_buffer(&mut self, amount: U256, to: Address) {
+        self.only_admin();
+        let config = self.get_peg_config()
+            .unwrap_or_else(|| self.env().revert(LstError::PegArbitrageNotConfigured));
+        let buffer = self.peg_buffer.get_or_default();
+        if amount > buffer {
+            self.env().revert(LstError::InsufficientPegBuffer);
+        }
+        
+        let balance = buffer - amount;
+        self.peg_buffer.set(balance);
+        let mut wcspr = Cep18TokenContractRef::new(self.env(), config.wcspr);
+        wcspr.transfer(to, amount);
+        
+        self.env().emit_event(PegBufferUpdated {
+            amount,
+            funded: false,
+            balance,
+            account: to,
+            seq: self.next_event_seq(),
+        });
+    }
+
+    /// Configure (or disable) peg arbitrage (admin only)
+    pub fn set_peg_arbitrage(&mut self, config: Option<PegArbitrageConfig>) {
+        self.only_admin();
+        if let Some(config) = &config {
+            if config.threshold_bps >= PEG_BPS_DENOMINATOR {
+                self.env().revert(LstError::InvalidAmount);
+            }
+        }
+        self.peg_config.set(config);
+        self.emit_setting_updated("peg_arbitrage");
+    }
+
+    /// Set (or clear) the peg arbitrage keeper (admin only)
+    pub fn set_peg_keeper(&mut self, keeper: Option<Address>) {
+        self.only_admin();
+        self.peg_keeper.set(keeper);
+        self.emit_setting_updated("peg_keeper");
+    }
+
+    /// Get the peg arbitrage settings
+    pub fn get_peg_config(&self) -> Option<PegArbitrageConfig> {
+        self.peg_config.get_or_default()
+    }
+
+    /// Get the peg arbitrage keeper
+    pub fn get_peg_keeper(&self) -> Option<Address> {
+        self.peg_keeper.get_or_default()
+    }
+
+    /// Get the WCSPR held for peg arbitrage
+    pub fn get_peg_buffer(&self) -> U256 {
+        self.peg_buffer.get_or_default()
+    }
+
+    /// Get the exchange-rate price of sCSPR (CSPR per sCSPR, scaled by 1e18)
+    pub fn get_fair_price(&self) -> U256 {
+        let total_scspr = self.total_scspr_supply.get_or_default();
+        let total_cspr = self.total_cspr_staked.get_or_default();
+        let scale = self.exchange_rate_scale.get_or_default();
+        if total_scspr == U256::zero() || total_cspr == U256::zero() {
+            return scale;
+        }
+        self.mul_div(total_cspr, scale, total_scspr)
+    }
+
+    /// Get the exchange-rate price of sCSPR scaled by 10^`PRECISE_RATE_DECIMALS`
+    pub fn get_precise_fair_price(&self) -> U256 {
+        let (cspr, scspr) = self.get_exchange_rate_fraction();
+        self.mul_div(cspr, U256::exp10(PRECISE_RATE_DECIMALS), scspr)
+    }
+
+    // ========================================
+    // Insurance
+    // ========================================
+
+    /// Set (or clear) the insurance fund
+    /// Its share of rewards only changes through `schedule_set_insurance_fee`
+    pub fn set_insurance_fund(&mut self, fund: Option<Address>) {
+        self.only_admin();
+        self.insurance_fund.set(fund);
+        self.emit_setting_updated("insurance_fund");
+    }
+
+    /// Set the delay (in seconds) applied to scheduled fee changes
+    /// A longer delay applies at once; a shorter one is scheduled behind
+    /// the current delay and applied with `apply_schedule_delay`
+    pub fn set_schedule_delay(&mut self, delay: u64) {
+        self.only_admin();
+        if self.schedule.set_delay(delay) {
+            self.emit_setting_updated("schedule_delay");
+        }
+    }
+
+    /// Apply a scheduled delay reduction once it is due; callable by anyone
+    pub fn apply_schedule_delay(&mut self) {
+        self.schedule.apply_delay();
+        self.emit_setting_updated("schedule_delay");
+    }
+
+    /// Announce a new insurance fee that can be applied after the schedule delay
+    /// Replaces any pending change
+    pub fn schedule_set_insurance_fee(&mut self, fee_bps: u16) {
+        self.only_admin();
+        if fee_bps > MAX_INSURANCE_FEE_BPS {
+            self.env().revert(LstError::InvalidAmount);
+        }
+        self.schedule.schedule(PARAM_INSURANCE_FEE_BPS, U256::from(fee_bps));
+    }
+
+    /// Apply the scheduled insurance fee once it is due; callable by anyone
+    pub fn apply_insurance_fee(&mut self) {
+        let fee_bps = self.schedule.take_due(PARAM_INSURANCE_FEE_BPS).as_u32() as u16;
+        self.insurance_fee_bps.set(fee_bps);
+        self.emit_setting_updated("insurance_fund");
+    }
+
+    /// Drop a scheduled fee change
+    pub fn cancel_scheduled(&mut self, parameter: String) {
+        self.only_admin();
+        self.schedule.cancel(&parameter);
+    }
+
+    /// Get the delay (in seconds) applied to scheduled fee changes
+    pub fn get_schedule_delay(&self) -> u64 {
+        self.schedule.delay()
+    }
+
+    /// Get the pending change of a scheduled parameter
+    pub fn get_scheduled(&self, parameter: String) -> Option<ScheduledParameter> {
+        self.schedule.get(parameter)
+    }
+
+    /// Get the insurance fund
+    pub fn get_insurance_fund(&self) -> Option<Address> {
+        self.insurance_fund.get_or_default()
+    }
+
+    /// Get the share of rewards paid to the insurance fund, in basis points
+    pub fn get_insurance_fee_bps(&self) -> u16 {
+        self.insurance_fee_bps.get_or_default()
+    }
+
+    /// Record a slash of `amount` CSPR on `validator`
+    /// The insurance fund covers what it can; only the rest lowers the
+    /// exchange rate. Returns the covered amount
+    pub fn report_slash(&mut self, validator: Address, amount: U256) -> U256 {
+        self.only_admin();
+        let total = self.total_cspr_staked.get_or_default();
+        if amount.is_zero() || amount > total {
+            self.env().revert(LstError::InvalidAmount);
+        }
+
+        let covered = match self.get_insurance_fund() {
+            Some(fund) => InsuranceFundContractRef::new(self.env(), fund).cover(amount),
+            None => U256::zero(),
+        };
+        let loss = amount - covered;
+        self.total_cspr_staked.set(total - loss);
+        self.rate_updated_at.set(self.env().get_block_time());
+        let stake = self.validator_stakes.get(&validator).unwrap_or_default();
+        self.validator_stakes.set(&validator, stake.saturating_sub(loss));
+
+        self.env().emit_event(SlashReported {
+            validator,
+            amount,
+            covered,
+            new_exchange_rate: self.get_exchange_rate(),
+            timestamp: self.env().get_block_time(),
+            seq: self.next_event_seq(),
+        });
+
+        covered
+    }
+
+    /// Pay the insurance fund the reward fees it is owed, up to the CSPR
+    /// the contract holds. Only callable by the insurance fund (see
+    /// `InsuranceFund::collect_fees`); returns the amount paid
+    pub fn pay_insurance_fees(&mut self) -> U256 {
+        let fund = self.get_insurance_fund()
+            .unwrap_or_else(|| self.env().revert(LstError::Unauthorized));
+        if self.env().caller() != fund {
+            self.env().revert(LstError::Unauthorized);
         }
+
+        let balance = SafeMath::from_u512(self.env().self_balance())
+            .unwrap_or_else(|_| self.env().revert(LstError::InvalidAmount));
+        let owed = self.get_insurance_fees_owed();
+        let amount = owed.min(balance);
+        if amount.is_zero() {
+            return amount;
+        }
+        self.insurance_fees_owed.set(owed - amount);
+        InsuranceFundContractRef::new(self.env(), fund)
+            .with_tokens(amount.to_u512())
+            .deposit();
+
+        self.env().emit_event(InsuranceFeesPaid {
+            fund,
+            amount,
+            owed: owed - amount,
+            seq: self.next_event_seq(),
+        });
+        amount
+    }
+
+    /// Get the reward fees owed to the insurance fund and not yet collected
+    pub fn get_insurance_fees_owed(&self) -> U256 {
+        self.insurance_fees_owed.get_or_default()
+    }
+
+    /// Receive slash cover paid by the insurance fund
+    #[odra(payable)]
+    pub fn receive_insurance_cover(&mut self) {
+        let fund = self.get_insurance_fund()
+            .unwrap_or_else(|| self.env().revert(LstError::Unauthorized));
+        if self.env().caller() != fund {
+            self.env().revert(LstError::Unauthorized);
+        }
+
+        let amount = SafeMath::from_u512(self.env().attached_value())
+            .unwrap_or_else(|_| self.env().revert(LstError::InvalidAmount));
+        self.env().emit_event(InsuranceCoverReceived {
+            fund,
+            amount,
+            seq: self.next_event_seq(),
+        });
+    }
+
+    // ========================================
+    // Auto-restake
+    // ========================================
+
+    /// Choose how the caller receives rewards
+    /// With auto-restake on (the default) rewards raise the sCSPR exchange
+    /// rate; with it off they accrue as claimable CSPR
+    pub fn set_auto_restake(&mut self, enabled: bool) {
+        let caller = self.env().caller();
+        if self.is_auto_restake(caller) == enabled {
+            return;
+        }
+        if enabled {
+            self.settle_income(caller);
+            self.income_checkpoints.set(&caller, U256::zero());
+            self.income_balances.set(&caller, U256::zero());
+            self.auto_restake_disabled.set(&caller, false);
+        } else {
+            let price = self.get_precise_fair_price();
+            self.income_checkpoints.set(&caller, price);
+            self.auto_restake_disabled.set(&caller, true);
+            self.track_income_balance(caller);
+        }
+
+        self.env().emit_event(AutoRestakeUpdated {
+            user: caller,
+            enabled,
+            timestamp: self.env().get_block_time(),
+            seq: self.next_event_seq(),
+        });
+    }
+
+    /// Convert the rewards an income user earned since the last settlement
+    /// into claimable CSPR. Anyone may settle on behalf of a user
+    pub fn settle_rewards(&mut self, user: Address) -> U256 {
+        self.ensure_operations_allowed();
+        self.settle_income(user)
+    }
+
+    /// Claim the caller's settled and pending income rewards
+    pub fn claim_rewards(&mut self) -> U256 {
+        self.ensure_withdrawals_allowed();
+        let caller = self.env().caller();
+        self.settle_income(caller);
+
+        let amount = self.claimable_rewards.get(&caller).unwrap_or_default();
+        if amount.is_zero() {
+            self.env().revert(LstError::NoWithdrawableFunds);
+        }
+        self.claimable_rewards.set(&caller, U256::zero());
+        let total_claimable = self.get_total_claimable_rewards();
+        self.total_claimable_rewards.set(total_claimable - amount);
+        self.env().transfer_tokens(&caller, &amount.to_u512());
+
+        self.env().emit_event(IncomeRewardsClaimed {
+            user: caller,
+            cspr_amount: amount,
+            timestamp: self.env().get_block_time(),
+            seq: self.next_event_seq(),
+        });
+        amount
+    }
+
+    /// Whether a user's rewards are restaked as sCSPR appreciation
+    pub fn is_auto_restake(&self, user: Address) -> bool {
+        !self.auto_restake_disabled.get(&user).unwrap_or(false)
+    }
+
+    /// CSPR an income user can claim, including rewards not yet settled
+    pub fn get_claimable_rewards(&self, user: Address) -> U256 {
+        let settled = self.claimable_rewards.get(&user).unwrap_or_default();
+        settled + self.pending_income(user)
+    }
+
+    /// Sum of all settled, unclaimed income rewards
+    pub fn get_total_claimable_rewards(&self) -> U256 {
+        self.total_claimable_rewards.get_or_default()
+    }
+
+    // Internal helper functions
+
+    /// CSPR value an income user gained since their checkpoint
+    /// Only the sCSPR held since the last settlement earns (the tracked
+    /// balance, or less if some was sent away), so sCSPR bought or received
+    /// later cannot claim rewards from before it arrived. Nothing accrues
+    /// while the fair price is below the checkpoint, so a slash has to be
+    /// recovered before income is paid again
+    fn pending_income(&self, user: Address) -> U256 {
+        if self.is_auto_restake(user) {
+            return U256::zero();
+        }
+        let checkpoint = self.income_checkpoints.get(&user).unwrap_or_default();
+        let price = self.get_precise_fair_price();
+        if price <= checkpoint {
+            return U256::zero();
+        }
+        let balance = self.scspr_balance_of(user).min(self.income_balances.get(&user).unwrap_or_default());
+        self.mul_div(balance, price - checkpoint, U256::exp10(PRECISE_RATE_DECIMALS))
+    }
+
+    /// Start income accrual on the user's current sCSPR balance
+    /// If the balance grew, the checkpoint moves up to the current price so
+    /// the new sCSPR only earns from here on; a pending dust gain of the
+    /// old balance is given up in that case
+    fn track_income_balance(&mut self, user: Address) {
+        let balance = self.scspr_balance_of(user);
+        if balance > self.income_balances.get(&user).unwrap_or_default() {
+            let price = self.get_precise_fair_price();
+            if price > self.income_checkpoints.get(&user).unwrap_or_default() {
+                self.income_checkpoints.set(&user, price);
+            }
+        }
+        self.income_balances.set(&user, balance);
+    }
+
+    /// sCSPR balance of an account
+    fn scspr_balance_of(&self, user: Address) -> U256 {
+        let token_address = self.scspr_token_address.get_or_revert_with(LstError::StakingFailed);
+        ScsprTokenContractRef::new(self.env(), token_address).balance_of(user)
+    }
+
+    /// Burn the sCSPR backing an income user's gain and credit it as claimable CSPR
+    /// Burning at the fair price leaves the exchange rate unchanged for everyone else
+    fn settle_income(&mut self, user: Address) -> U256 {
+        if self.is_auto_restake(user) {
+            return U256::zero();
+        }
+        let gain = self.pending_income(user);
+        // Dust below one sCSPR unit stays pending until it grows
+        let scspr_burned = self.calculate_scspr_amount(gain);
+        if scspr_burned.is_zero() {
+            self.track_income_balance(user);
+            return U256::zero();
+        }
+        let price = self.get_precise_fair_price();
+        if price > self.income_checkpoints.get(&user).unwrap_or_default() {
+            self.income_checkpoints.set(&user, price);
+        }
+        // Pay the CSPR value of the burned sCSPR so rounding favours the pool
+        let cspr_amount = self.calculate_cspr_amount(scspr_burned);
+
+        let token_address = self.scspr_token_address.get_or_revert_with(LstError::StakingFailed);
+        ScsprTokenContractRef::new(self.env(), token_address).burn(user, scspr_burned);
+        let supply = self.total_scspr_supply.get_or_default();
+        self.total_scspr_supply.set(supply - scspr_burned);
+        let total = self.total_cspr_staked.get_or_default();
+        self.total_cspr_staked.set(total - cspr_amount);
+        let claimable = self.claimable_rewards.get(&user).unwrap_or_default();
+        self.claimable_rewards.set(&user, claimable + cspr_amount);
+        let total_claimable = self.get_total_claimable_rewards();
+        self.total_claimable_rewards.set(total_claimable + cspr_amount);
+        self.track_income_balance(user);
+
+        self.env().emit_event(IncomeRewardsSettled {
+            user,
+            cspr_amount,
+            scspr_burned,
+            seq: self.next_event_seq(),
+        });
+        cspr_amount
+    }
+
+    /// Pay out a matured unstake request held by `user`
+    fn withdraw_unstaked_of(&mut self, user: Address, request_id: u64) -> U256 {
+        self.ensure_withdrawals_allowed();
+        
+        // Get unstake request
+        let mut request = self.unstake_requests.get(&request_id)
+            .unwrap_or_else(|| self.env().revert(LstError::InvalidUnstakeRequestId));
+        
+        // Verify request belongs to user
+        if request.user != user {
+            self.env().revert(LstError::Unauthorized);
+        }
+        
+        // Check if already processed
+        if request.processed {
+            self.env().revert(LstError::UnstakeRequestAlreadyProcessed);
+        }
+        
+        // Check if unstaking period has passed
+        let current_time = self.env().get_block_time();
+        if current_time < request.withdrawable_at {
+            self.env().revert(LstError::UnstakingPeriodNotComplete);
+        }
+        
+        // Mark as processed
+        request.processed = true;
+        self.unstake_requests.set(&request_id, request.clone());
+        
+        // Update total staked
+        let current_total = self.total_cspr_staked.get_or_default();
+        self.total_cspr_staked.set(current_total - request.cspr_amount);
+        
+        // Transfer CSPR to user
+        let cspr_amount_u512 = request.cspr_amount.to_u512();
+        self.env().transfer_tokens(&user, &cspr_amount_u512);
+        
+        // Emit event
+        let timestamp = self.env().get_block_time();
+        self.env().emit_event(Withdrawn {
+            withdrawer: user,
+            cspr_amount: request.cspr_amount,
+            request_id,
+            timestamp,
+            seq: self.next_event_seq(),
+        });
+        
+        request.cspr_amount
+    }
+
+    /// Move a validator's whole stake to the undelegation queue
+    fn queue_undelegation(&mut self, validator: Address) {
+        let stake = self.validator_stakes.get(&validator).unwrap_or_default();
+        if stake.is_zero() {
+            return;
+        }
+        
+        // TODO: Actual undelegation from Casper validator would happen here
+        let pending = self.pending_undelegations.get(&validator).unwrap_or_default();
+        self.pending_undelegations.set(&validator, pending + stake);
+        self.validator_stakes.set(&validator, U256::zero());
+        
+        self.env().emit_event(UndelegationQueued {
+            validator,
+            amount: stake,
+            timestamp: self.env().get_block_time(),
+            seq: self.next_event_seq(),
+        });
+    }
+
+    /// Stake buffer WCSPR, mint sCSPR and sell it above the fair price
+    fn arbitrage_premium(
+        &mut self,
+        config: PegArbitrageConfig,
+        scspr: Address,
+        dex_price: U256,
+        fair_price: U256,
+        cspr_cost: U256,
+        min_out: U256,
+    ) -> U256 {
+        let scspr_amount = self.calculate_scspr_amount(cspr_cost);
+        if scspr_amount.is_zero() {
+            self.env().revert(LstError::PegWithinThreshold);
+        }
+        let self_address = self.env().self_address();
+        
+        // Protocol-side stake: the WCSPR leaves the buffer and backs the new sCSPR
+        // TODO: unwrap the WCSPR and delegate it, as for user stakes
+        self.peg_buffer.set(self.peg_buffer.get_or_default() - cspr_cost);
+        self.total_cspr_staked.set(self.total_cspr_staked.get_or_default() + cspr_cost);
+        self.total_scspr_supply.set(self.total_scspr_supply.get_or_default() + scspr_amount);
+        let mut token = ScsprTokenContractRef::new(self.env(), scspr);
+        token.mint(self_address, scspr_amount);
+        if let Some(validator) = self.get_best_validator() {
+            self.delegate_to(validator, cspr_cost);
+        }
+        
+        // Sell for at least the cost and the TWAP quote, so the trade cannot lose
+        token.approve(config.router, scspr_amount);
+        let mut router = RouterContractRef::new(self.env(), config.router);
+        let amounts = router.swap_exact_tokens_for_tokens(
+            scspr_amount,
+            min_out,
+            vec![scspr, config.wcspr],
+            self_address,
+            self.env().get_block_time(),
+        );
+        let wcspr_out = amounts[amounts.len() - 1];
+        
+        // Make the buffer whole; the surplus is profit
+        self.peg_buffer.set(self.peg_buffer.get_or_default() + cspr_cost);
+        let profit = wcspr_out - cspr_cost;
+        if !profit.is_zero() {
+            Cep18TokenContractRef::new(self.env(), config.wcspr).transfer(config.treasury, profit);
+        }
+        
+        self.env().emit_event(PegArbitraged {
+            premium: true,
+            dex_price,
+            fair_price,
+            scspr_amount,
+            wcspr_spent: cspr_cost,
+            profit,
+            request_id: None,
+            seq: self.next_event_seq(),
+        });
+        profit
+    }
+
+    /// Buy sCSPR below the fair price with buffer WCSPR and redeem it for the treasury
+    fn arbitrage_discount(
+        &mut self,
+        config: PegArbitrageConfig,
+        scspr: Address,
+        dex_price: U256,
+        fair_price: U256,
+        wcspr_in: U256,
+        min_scspr: U256,
+    ) -> U256 {
+        if wcspr_in.is_zero() {
+            self.env().revert(LstError::PegWithinThreshold);
+        }
+        let self_address = self.env().self_address();
+        self.peg_buffer.set(self.peg_buffer.get_or_default() - wcspr_in);
+        
+        // Buy at least the exchange-rate equivalent and the TWAP quote, so the redemption cannot lose
+        Cep18TokenContractRef::new(self.env(), config.wcspr).approve(config.router, wcspr_in);
+        let mut router = RouterContractRef::new(self.env(), config.router);
+        let amounts = router.swap_exact_tokens_for_tokens(
+            wcspr_in,
+            min_scspr,
+            vec![config.wcspr, scspr],
+            self_address,
+            self.env().get_block_time(),
+        );
+        let scspr_amount = amounts[amounts.len() - 1];
+        
+        // Redeem through the unstake queue; the CSPR goes to the treasury
+        let cspr_amount = self.calculate_cspr_amount(scspr_amount);
+        let mut token = ScsprTokenContractRef::new(self.env(), scspr);
+        token.burn(self_address, scspr_amount);
+        self.total_scspr_supply.set(self.total_scspr_supply.get_or_default() - scspr_amount);
+        let (request_id, _) = self.queue_withdrawal(config.treasury, cspr_amount);
+        
+        let profit = cspr_amount.saturating_sub(wcspr_in);
+        self.env().emit_event(PegArbitraged {
+            premium: false,
+            dex_price,
+            fair_price,
+            scspr_amount,
+            wcspr_spent: wcspr_in,
+            profit,
+            request_id: Some(request_id),
+            seq: self.next_event_seq(),
+        });
+        profit
+    }
+
+    /// Record an unstake request for `user`
+    /// Returns (request id, withdrawable at)
+    fn queue_withdrawal(&mut self, user: Address, cspr_amount: U256) -> (u64, u64) {
+        let request_id = self.next_unstake_request_id.get_or_default();
+        let timestamp = self.env().get_block_time();
+        let unstaking_period = self.unstaking_period.get_or_default();
+        let withdrawable_at = timestamp + unstaking_period;
+        
+        let request = UnstakeRequest {
+            user,
+            cspr_amount,
+            withdrawable_at,
+            processed: false,
+        };
+        
+        self.unstake_requests.set(&request_id, request);
+        
+        // Add to user's request list
+        let mut user_requests = self.user_unstake_requests.get(&user).unwrap_or_default();
+        user_requests.push(request_id);
+        self.user_unstake_requests.set(&user, user_requests);
+        
+        // Increment request ID
+        self.next_unstake_request_id.set(request_id + 1);
+        
+        // TODO: Actual undelegation from Casper validator would happen here
+        
+        (request_id, withdrawable_at)
+    }
+
+    /// sCSPR/WCSPR pair the router trades through
+    fn peg_pair(&self, config: &PegArbitrageConfig, scspr: Address) -> Address {
+        let factory = RouterContractRef::new(self.env(), config.router).factory();
+        FactoryContractRefContractRef::new(self.env(), factory)
+            .get_pair(scspr, config.wcspr)
+            .unwrap_or_else(|| self.env().revert(LstError::PegArbitrageNotConfigured))
+    }
+
+    /// Cumulative DEX price of sCSPR in WCSPR (scaled by 1e18) as of this block
+    /// The current reserves are only counted from their last update, so
+    /// trades in this block do not move it
+    fn peg_cumulative(&self, config: &PegArbitrageConfig, scspr: Address) -> U256 {
+        let pair = PairContractRef::new(self.env(), self.peg_pair(config, scspr));
+        let (cumulative0, cumulative1) = pair.get_price_cumulatives();
+        let (reserve0, reserve1, updated_at) = pair.get_reserves();
+        let (cumulative, reserve_scspr, reserve_wcspr) = if pair.token0() == scspr {
+            (cumulative0, reserve0, reserve1)
+        } else {
+            (cumulative1, reserve1, reserve0)
+        };
+        if reserve_scspr.is_zero() {
+            return cumulative;
+        }
+        let price = self.mul_div(reserve_wcspr, U256::from(1_000_000_000_000_000_000u128), reserve_scspr);
+        let elapsed = U256::from(self.env().get_block_time().saturating_sub(updated_at));
+        cumulative.overflowing_add(price.overflowing_mul(elapsed).0).0
+    }
+
+    /// Least output accepted for a peg trade: the constant product quote at
+    /// the TWAP reserves, less PEG_MAX_SLIPPAGE_BPS
+    fn peg_min_out(&self, amount_in: U256, reserve_in: U256, reserve_out: U256, fee_bps: u16) -> U256 {
+        let denominator = U256::from(PEG_BPS_DENOMINATOR);
+        let amount_in_with_fee = self.mul_div(amount_in, denominator - U256::from(fee_bps), U256::one());
+        let reserve_in_scaled = self.mul_div(reserve_in, denominator, U256::one());
+        let quote = self.mul_div(amount_in_with_fee, reserve_out, reserve_in_scaled + amount_in_with_fee);
+        self.mul_div(quote, U256::from(PEG_BPS_DENOMINATOR - PEG_MAX_SLIPPAGE_BPS), denominator)
+    }
+
+    fn only_peg_keeper(&self) {
+        let caller = self.env().caller();
+        if Some(caller) != self.get_peg_keeper() && caller != self.get_admin() {
+            self.env().revert(LstError::Unauthorized);
+        }
+    }
+
+    /// Record a delegation to a validator
+    fn delegate_to(&mut self, validator: Address, amount: U256) {
+        let stake = self.validator_stakes.get(&validator).unwrap_or_default();
+        self.validator_stakes.set(&validator, stake + amount);
+        
+        self.env().emit_event(DelegationAssigned {
+            validator,
+            amount,
+            timestamp: self.env().get_block_time(),
+            seq: self.next_event_seq(),
+        });
+    }
+
+    fn calculate_scspr_amount(&self, cspr_amount: U256) -> U256 {
+        let total_scspr = self.total_scspr_supply.get_or_default();
+        let total_cspr = self.total_cspr_staked.get_or_default();
+        
+        if total_scspr == U256::zero() || total_cspr == U256::zero() {
+            // Initial stake: 1:1 ratio
+            return cspr_amount;
+        }
+        
+        // sCSPR = (cspr_amount * total_scspr) / total_cspr
+        self.mul_div(cspr_amount, total_scspr, total_cspr)
+    }
+
+    fn calculate_cspr_amount(&self, scspr_amount: U256) -> U256 {
+        let total_scspr = self.total_scspr_supply.get_or_default();
+        let total_cspr = self.total_cspr_staked.get_or_default();
+        
+        if total_scspr == U256::zero() {
+            return U256::zero();
+        }
+        
+        // CSPR = (scspr_amount * total_cspr) / total_scspr
+        self.mul_div(scspr_amount, total_cspr, total_scspr)
+    }
+
+    /// (a * b) / c through a U512 product; only the result is narrowed to U256
+    fn mul_div(&self, a: U256, b: U256, c: U256) -> U256 {
+        SafeMath::mul_div(a, b, c)
+            .unwrap_or_else(|_| self.env().revert(LstError::ExchangeRateError))
+    }
+
+    fn only_admin(&self) {
+        let caller = self.env().caller();
+        let admin = self.admin.get_or_revert_with(LstError::Unauthorized);
+        if caller != admin {
+            self.env().revert(LstError::Unauthorized);
+        }
+    }
+
+    fn emit_setting_updated(&mut self, setting: &str) {
+        self.env().emit_event(SettingUpdated {
+            setting: String::from(setting),
+            updated_by: self.env().caller(),
+            timestamp: self.env().get_block_time(),
+            seq: self.next_event_seq(),
+        });
+    }
+
+    fn update_pause_mode(&mut self, mode: PauseMode) {
+        let old_mode = self.get_pause_mode();
+        self.pause_mode.set(mode.clone());
+        
+        self.env().emit_event(PauseModeChanged {
+            old_mode,
+            new_mode: mode,
+            updated_by: self.env().caller(),
+            timestamp: self.env().get_block_time(),
+            seq: self.next_event_seq(),
+        });
+    }
+
+    /// Stakes are only accepted in `Normal` mode
+    fn ensure_deposits_allowed(&self) {
+        match self.get_effective_pause_mode() {
+            PauseMode::Normal => {}
+            PauseMode::DepositsPaused => self.env().revert(LstError::DepositsPaused),
+            PauseMode::WithdrawalsOnly => self.env().revert(LstError::WithdrawalsOnly),
+            PauseMode::FullyPaused => self.env().revert(LstError::ContractPaused),
+        }
+    }
+
+    /// Unstaking and withdrawals stay open in every mode but `FullyPaused`
+    fn ensure_withdrawals_allowed(&self) {
+        if self.get_effective_pause_mode() == PauseMode::FullyPaused {
+            self.env().revert(LstError::ContractPaused);
+        }
+    }
+
+    /// Rewards and validator operations run in `Normal` and `DepositsPaused`
+    fn ensure_operations_allowed(&self) {
+        match self.get_effective_pause_mode() {
+            PauseMode::Normal | PauseMode::DepositsPaused => {}
+            PauseMode::WithdrawalsOnly => self.env().revert(LstError::WithdrawalsOnly),
+            PauseMode::FullyPaused => self.env().revert(LstError::ContractPaused),
+        }
+    }
+
+    /// Sequence number of the last event emitted; indexers page from here
+    pub fn event_seq(&self) -> u64 {
+        self.event_seq.get_or_default()
+    }
+
+    /// Advance the event sequence and return the number for the next event
+    fn next_event_seq(&mut self) -> u64 {
+        let seq = self.event_seq.get_or_default() + 1;
+        self.event_seq.set(seq);
+        seq
     }
 }
 
@@ -589,7 +1998,7 @@ impl Cep4626Vault for StakingManager {
     // ========================================
     
     fn max_deposit(&self, _receiver: Address) -> U256 {
-        if self.paused.get_or_default() {
+        if self.get_effective_pause_mode() != PauseMode::Normal {
             return U256::zero();
         }
         // No maximum deposit limit for liquid staking
@@ -597,7 +2006,7 @@ impl Cep4626Vault for StakingManager {
     }
     
     fn max_mint(&self, _receiver: Address) -> U256 {
-        if self.paused.get_or_default() {
+        if self.get_effective_pause_mode() != PauseMode::Normal {
             return U256::zero();
         }
         // No maximum mint limit
@@ -605,7 +2014,7 @@ impl Cep4626Vault for StakingManager {
     }
     
     fn max_withdraw(&self, owner: Address) -> U256 {
-        if self.paused.get_or_default() {
+        if self.get_effective_pause_mode() == PauseMode::FullyPaused {
             return U256::zero();
         }
         // Maximum withdrawal is the user's sCSPR balance converted to CSPR
@@ -616,7 +2025,7 @@ impl Cep4626Vault for StakingManager {
     }
     
     fn max_redeem(&self, owner: Address) -> U256 {
-        if self.paused.get_or_default() {
+        if self.get_effective_pause_mode() == PauseMode::FullyPaused {
             return U256::zero();
         }
         // Maximum redeem is the user's sCSPR balance
@@ -121,6 +121,37 @@ mod tests {
         assert!(exchange_rate < scale); // Rate is sCSPR per CSPR, so it should be less
     }
 
+    #[test]
+    fn test_rate_provider_reports_staleness() {
+        let env = odra_test::env();
+        let mut scspr_token = ScsprToken::deploy(&env, NoArgs);
+        scspr_token.init(env.get_account(1));
+
+        let mut staking_manager = StakingManager::deploy(&env, NoArgs);
+        staking_manager.init(scspr_token.address());
+        staking_manager.set_max_rate_age(3_600);
+
+        let stake_amount = U256::from(1000_000_000_000u64); // 1000 CSPR
+        env.set_caller(env.get_account(3));
+        staking_manager.stake(stake_amount);
+
+        // Fresh at deployment, stale after an hour without rewards
+        let one = U256::from(1_000_000_000_000_000_000u128);
+        assert!(!staking_manager.get_rate().stale);
+        env.advance_block_time(3_601);
+        assert!(staking_manager.get_rate().stale);
+        assert_eq!(staking_manager.get_rate().peg_rate(U256::from(7u8)), U256::from(7u8));
+
+        // Rewards refresh the rate and move it to 1.1 CSPR per sCSPR
+        env.set_caller(staking_manager.get_admin());
+        staking_manager.distribute_rewards(U256::from(100_000_000_000u64));
+        let rate = staking_manager.get_rate();
+        assert!(!rate.stale);
+        assert_eq!(rate.updated_at, env.block_time());
+        assert_eq!(rate.rate, U256::from(1_100_000_000_000_000_000u128));
+        assert_eq!(rate.peg_rate(one), rate.rate);
+    }
+
     #[test]
     fn test_unstake_and_withdraw() {
         let env = odra_test::env();
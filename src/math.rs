@@ -1,6 +1,7 @@
 //! Mathematical utilities for the DEX smart contract
 //! Implements safe math operations and AMM formulas
-use odra::casper_types::U256;
+use odra::casper_types::{U256, U512};
+use odra::uints::ToU512;
 use crate::errors::DexError;
 
 /// Minimum liquidity that is locked forever to prevent division by zero
@@ -12,6 +13,21 @@ pub const FEE_DENOMINATOR: u128 = 1000;
 /// Fee numerator (0.3% fee)
 pub const FEE_NUMERATOR: u128 = 3;
 
+/// Basis points denominator for per-pair swap fees
+pub const BPS_DENOMINATOR: u128 = 10_000;
+
+/// Default pair swap fee in basis points (0.3%)
+pub const DEFAULT_FEE_BPS: u16 = 30;
+
+/// Highest swap fee a pair type may charge, in basis points (10%)
+pub const MAX_FEE_BPS: u16 = 1_000;
+
+/// Fee charged on pair flash loans in basis points (0.09%)
+pub const FLASH_LOAN_FEE_BPS: u16 = 9;
+
+/// Decimals used for UI-facing normalized amounts
+pub const NORMALIZED_DECIMALS: u8 = 18;
+
 /// Safe math operations for U256
 pub struct SafeMath;
 
@@ -39,6 +55,25 @@ impl SafeMath {
         Ok(a / b)
     }
 
+    /// Compute (a * b) / c with a 512-bit intermediate product
+    /// Only the final quotient has to fit in U256
+    pub fn mul_div(a: U256, b: U256, c: U256) -> Result<U256, DexError> {
+        if c.is_zero() {
+            return Err(DexError::DivisionByZero);
+        }
+        Self::from_u512(a.to_u512() * b.to_u512() / c.to_u512())
+    }
+
+    /// Narrow a U512 (e.g. a native CSPR amount) to U256, failing if it does not fit
+    pub fn from_u512(value: U512) -> Result<U256, DexError> {
+        if value > U256::MAX.to_u512() {
+            return Err(DexError::Overflow);
+        }
+        let mut bytes = [0u8; 64];
+        value.to_little_endian(&mut bytes);
+        Ok(U256::from_little_endian(&bytes[..32]))
+    }
+
     /// Calculate square root using Newton's method (Babylonian method)
     pub fn sqrt(y: U256) -> U256 {
         if y > U256::from(3) {
@@ -67,6 +102,45 @@ impl SafeMath {
     }
 }
 
+/// Conversions between raw token amounts and `NORMALIZED_DECIMALS` amounts
+pub struct DecimalMath;
+
+impl DecimalMath {
+    /// Convert a raw amount of a token with `decimals` to normalized units
+    /// `round_up` rounds away from zero when decimals exceed the normalized scale
+    pub fn to_normalized(amount: U256, decimals: u8, round_up: bool) -> Result<U256, DexError> {
+        if decimals <= NORMALIZED_DECIMALS {
+            SafeMath::mul(amount, Self::scale(NORMALIZED_DECIMALS - decimals)?)
+        } else {
+            Self::div_round(amount, Self::scale(decimals - NORMALIZED_DECIMALS)?, round_up)
+        }
+    }
+
+    /// Convert a normalized amount to raw units of a token with `decimals`
+    /// `round_up` rounds away from zero when precision is lost
+    pub fn from_normalized(amount: U256, decimals: u8, round_up: bool) -> Result<U256, DexError> {
+        if decimals >= NORMALIZED_DECIMALS {
+            SafeMath::mul(amount, Self::scale(decimals - NORMALIZED_DECIMALS)?)
+        } else {
+            Self::div_round(amount, Self::scale(NORMALIZED_DECIMALS - decimals)?, round_up)
+        }
+    }
+
+    /// 10^exponent
+    fn scale(exponent: u8) -> Result<U256, DexError> {
+        U256::from(10).checked_pow(U256::from(exponent)).ok_or(DexError::Overflow)
+    }
+
+    fn div_round(amount: U256, divisor: U256, round_up: bool) -> Result<U256, DexError> {
+        let quotient = SafeMath::div(amount, divisor)?;
+        if round_up && !(amount % divisor).is_zero() {
+            SafeMath::add(quotient, U256::one())
+        } else {
+            Ok(quotient)
+        }
+    }
+}
+
 /// AMM (Automated Market Maker) calculations
 pub struct AmmMath;
 
@@ -233,6 +307,43 @@ mod tests {
         assert_eq!(SafeMath::sqrt(U256::from(100)), U256::from(10));
     }
 
+    #[test]
+    fn test_mul_div_beyond_u256_product() {
+        // 2^255 * 4 overflows U256, the quotient does not
+        let a = U256::one() << 255;
+        assert_eq!(SafeMath::mul_div(a, U256::from(4), U256::from(8)).unwrap(), a / 2);
+        assert!(SafeMath::mul(a, U256::from(4)).is_err());
+
+        assert!(matches!(SafeMath::mul_div(a, U256::from(4), U256::one()), Err(DexError::Overflow)));
+        assert!(matches!(SafeMath::mul_div(a, a, U256::zero()), Err(DexError::DivisionByZero)));
+    }
+
+    #[test]
+    fn test_from_u512() {
+        let max = U256::MAX.to_u512();
+        assert_eq!(SafeMath::from_u512(max).unwrap(), U256::MAX);
+        assert_eq!(SafeMath::from_u512(U512::from(u128::MAX) + 1).unwrap(), U256::from(u128::MAX) + 1);
+        assert!(matches!(SafeMath::from_u512(max + 1), Err(DexError::Overflow)));
+    }
+
+    #[test]
+    fn test_decimal_normalization() {
+        // 1.5 USDC (6 decimals) is 1.5e18 normalized
+        let raw = U256::from(1_500_000u64);
+        let normalized = DecimalMath::to_normalized(raw, 6, false).unwrap();
+        assert_eq!(normalized, U256::from(1_500_000_000_000_000_000u128));
+        assert_eq!(DecimalMath::from_normalized(normalized, 6, false).unwrap(), raw);
+
+        // Sub-unit precision is truncated or rounded up on request
+        let dust = U256::from(1_000_000_000_001u64);
+        assert_eq!(DecimalMath::from_normalized(dust, 6, false).unwrap(), U256::from(1));
+        assert_eq!(DecimalMath::from_normalized(dust, 6, true).unwrap(), U256::from(2));
+
+        // Tokens above the normalized scale divide instead
+        assert_eq!(DecimalMath::to_normalized(U256::from(15), 19, true).unwrap(), U256::from(2));
+        assert_eq!(DecimalMath::from_normalized(U256::from(2), 19, false).unwrap(), U256::from(20));
+    }
+
     #[test]
     fn test_get_amount_out() {
         let amount_in = U256::from(1000);
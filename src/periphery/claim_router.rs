@@ -0,0 +1,226 @@
+//! Claim Router - Batch claims across the protocol in one deploy
+//!
+//! Executes a list of claim operations for the caller: farm harvests on the
+//! StakingPool, WCSPR claims on the FeeDistributor and matured unstake
+//! withdrawals on the StakingManager. Each target pays the caller directly
+//! and only accepts the `*_for` calls from the router it has been configured
+//! with, so the router never holds funds. Any failing operation reverts the
+//! whole batch.
+
+use odra::prelude::*;
+use odra::casper_types::U256;
+use odra::ContractRef;
+use crate::errors::DexError;
+use crate::farming::staking_pool::StakingPoolContractRef;
+use crate::lst::fee_distributor::FeeDistributorContractRef;
+use crate::lst::staking_manager::StakingManagerContractRef;
+
+/// Most operations a single batch may contain
+pub const MAX_CLAIM_OPS: usize = 16;
+
+/// Kind of claim operation
+#[odra::odra_type]
+pub enum ClaimKind {
+    /// Harvest farm rewards; `id` is the pool ID
+    FarmHarvest,
+    /// Claim FeeDistributor WCSPR; `id` is ignored
+    FeeRewards,
+    /// Withdraw a matured unstake request; `id` is the request ID
+    Unstake,
+}
+
+/// One claim operation of a batch
+#[odra::odra_type]
+pub struct ClaimOp {
+    /// What to claim
+    pub kind: ClaimKind,
+    /// Pool or request ID, depending on `kind`
+    pub id: u64,
+}
+
+/// Claim Router contract
+#[odra::module]
+pub struct ClaimRouter {
+    /// Contract admin
+    admin: Var<Address>,
+    /// Farm staking pool
+    staking_pool: Var<Option<Address>>,
+    /// LST fee distributor
+    fee_distributor: Var<Option<Address>>,
+    /// LST staking manager
+    staking_manager: Var<Option<Address>>,
+}
+
+#[odra::module]
+impl ClaimRouter {
+    /// Initialize the router with the contracts it claims from
+    /// A target left as `None` rejects operations of its kind
+    pub fn init(
+        &mut self,
+        staking_pool: Option<Address>,
+        fee_distributor: Option<Address>,
+        staking_manager: Option<Address>,
+    ) {
+        self.admin.set(self.env().caller());
+        self.staking_pool.set(staking_pool);
+        self.fee_distributor.set(fee_distributor);
+        self.staking_manager.set(staking_manager);
+    }
+
+    /// Execute `ops` in order for the caller
+    /// Returns the amount paid out by each operation
+    pub fn claim_all(&mut self, ops: Vec<ClaimOp>) -> Vec<U256> {
+        if ops.is_empty() || ops.len() > MAX_CLAIM_OPS {
+            self.env().revert(DexError::InvalidConfiguration);
+        }
+        let user = self.env().caller();
+        ops.into_iter().map(|op| self.claim(user, op)).collect()
+    }
+
+    /// Get the farm staking pool, if any
+    pub fn staking_pool(&self) -> Option<Address> {
+        self.staking_pool.get_or_default()
+    }
+
+    /// Get the fee distributor, if any
+    pub fn fee_distributor(&self) -> Option<Address> {
+        self.fee_distributor.get_or_default()
+    }
+
+    /// Get the staking manager, if any
+    pub fn staking_manager(&self) -> Option<Address> {
+        self.staking_manager.get_or_default()
+    }
+
+    // ============ Admin Functions ============
+
+    /// Replace the contracts the router claims from
+    pub fn set_targets(
+        &mut self,
+        staking_pool: Option<Address>,
+        fee_distributor: Option<Address>,
+        staking_manager: Option<Address>,
+    ) {
+        self.only_admin();
+        self.staking_pool.set(staking_pool);
+        self.fee_distributor.set(fee_distributor);
+        self.staking_manager.set(staking_manager);
+    }
+
+    /// Transfer admin rights
+    pub fn transfer_admin(&mut self, new_admin: Address) {
+        self.only_admin();
+        self.admin.set(new_admin);
+    }
+
+    // ============ Internal Functions ============
+
+    fn claim(&self, user: Address, op: ClaimOp) -> U256 {
+        match op.kind {
+            ClaimKind::FarmHarvest => {
+                let pool_id = u32::try_from(op.id)
+                    .unwrap_or_else(|_| self.env().revert(DexError::InvalidConfiguration));
+                StakingPoolContractRef::new(self.env(), self.target(self.staking_pool()))
+                    .claim_rewards_for(user, pool_id)
+            }
+            ClaimKind::FeeRewards => {
+                FeeDistributorContractRef::new(self.env(), self.target(self.fee_distributor()))
+                    .claim_for(user)
+            }
+            ClaimKind::Unstake => {
+                StakingManagerContractRef::new(self.env(), self.target(self.staking_manager()))
+                    .withdraw_unstaked_for(user, op.id)
+            }
+        }
+    }
+
+    fn target(&self, target: Option<Address>) -> Address {
+        target.unwrap_or_else(|| self.env().revert(DexError::InvalidConfiguration))
+    }
+
+    fn only_admin(&self) {
+        if Some(self.env().caller()) != self.admin.get() {
+            self.env().revert(DexError::Unauthorized);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use odra::host::Deployer;
+    use crate::lst::errors::LstError;
+    use crate::lst::fee_distributor::{FeeDistributor, FeeDistributorInitArgs, EPOCH_DURATION};
+    use crate::testing::{cspr, Fixture};
+
+    #[test]
+    fn test_claim_all_pays_fee_rewards_to_caller() {
+        let mut fixture = Fixture::new();
+        let env = fixture.env.clone();
+        let user = fixture.account(1);
+
+        let mut distributor = FeeDistributor::deploy(&env, FeeDistributorInitArgs {
+            scspr_token: fixture.lst.scspr.address(),
+            wcspr: fixture.dex.wcspr.address(),
+            router: fixture.account(5),
+        });
+        let mut router = ClaimRouter::deploy(&env, ClaimRouterInitArgs {
+            staking_pool: None,
+            fee_distributor: Some(distributor.address()),
+            staking_manager: Some(fixture.lst.staking_manager.address()),
+        });
+        distributor.set_claim_router(Some(router.address()));
+
+        env.set_caller(user);
+        fixture.lst.staking_manager.stake(cspr(100));
+        fixture.lst.scspr.approve(distributor.address(), cspr(100));
+        distributor.deposit(cspr(100));
+
+        env.set_caller(fixture.admin);
+        fixture.dex.wcspr.mint(distributor.address(), cspr(7));
+        distributor.start_epoch();
+        env.advance_block_time(EPOCH_DURATION);
+
+        env.set_caller(user);
+        let paid = router.claim_all(vec![ClaimOp { kind: ClaimKind::FeeRewards, id: 0 }]);
+        assert!(cspr(7) - paid[0] < U256::from(1_000));
+        assert_eq!(fixture.dex.wcspr.balance_of(user), paid[0]);
+        assert_eq!(fixture.dex.wcspr.balance_of(router.address()), U256::zero());
+    }
+
+    #[test]
+    fn test_claim_all_rejects_unconfigured_and_oversized_batches() {
+        let fixture = Fixture::new();
+        let mut router = ClaimRouter::deploy(&fixture.env, ClaimRouterInitArgs {
+            staking_pool: None,
+            fee_distributor: None,
+            staking_manager: None,
+        });
+
+        let op = ClaimOp { kind: ClaimKind::FarmHarvest, id: 0 };
+        assert_eq!(router.try_claim_all(vec![op.clone()]), Err(DexError::InvalidConfiguration.into()));
+        assert_eq!(router.try_claim_all(vec![]), Err(DexError::InvalidConfiguration.into()));
+        assert_eq!(
+            router.try_claim_all(vec![op; MAX_CLAIM_OPS + 1]),
+            Err(DexError::InvalidConfiguration.into())
+        );
+    }
+
+    #[test]
+    fn test_only_claim_router_claims_for_users() {
+        let mut fixture = Fixture::new();
+        let user = fixture.account(1);
+        let router = ClaimRouter::deploy(&fixture.env, ClaimRouterInitArgs {
+            staking_pool: None,
+            fee_distributor: None,
+            staking_manager: Some(fixture.lst.staking_manager.address()),
+        });
+        fixture.lst.staking_manager.set_claim_router(Some(router.address()));
+
+        fixture.env.set_caller(fixture.account(2));
+        assert_eq!(
+            fixture.lst.staking_manager.try_withdraw_unstaked_for(user, 0),
+            Err(LstError::Unauthorized.into())
+        );
+    }
+}
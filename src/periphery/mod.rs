@@ -0,0 +1,14 @@
+//! Periphery contracts
+//!
+//! Helpers that aggregate state and actions across the DEX, farm and LST contracts:
+//! - PositionViewer: portfolio view of a user's positions in one call
+//! - StatusBoard: protocol health snapshot for monitoring
+//! - ClaimRouter: farm, fee and unstake claims batched into one deploy
+
+pub mod claim_router;
+pub mod position_viewer;
+pub mod status_board;
+
+pub use claim_router::ClaimRouter;
+pub use position_viewer::PositionViewer;
+pub use status_board::StatusBoard;
@@ -0,0 +1,375 @@
+//! Position Viewer - Aggregated, read-only view of a user's positions
+//!
+//! Returns a user's DEX LP positions (share of the pool and underlying
+//! amounts), sCSPR holdings with their CSPR value and pending unstake
+//! requests in a single call, as the backend for a portfolio page.
+//!
+//! Users can also snapshot a pair with `record_entry` when they provide
+//! liquidity; `get_impermanent_loss` then compares the position with simply
+//! holding the tokens deposited at that snapshot.
+
+use odra::prelude::*;
+use odra::casper_types::U256;
+use odra::ContractRef;
+use crate::dex::factory::FactoryContractContractRef;
+use crate::dex::router::PairContractContractRef;
+use crate::errors::DexError;
+use crate::lst::staking_manager::StakingManagerContractRef;
+use crate::token::Cep18TokenContractRef;
+
+/// Share scaling factor (1e18 = 100%)
+const SHARE_SCALE: u128 = 1_000_000_000_000_000_000;
+
+/// Basis points denominator
+const BPS: u64 = 10_000;
+
+/// Underlying value of an amount of LP tokens
+#[odra::odra_type]
+pub struct PositionValue {
+    /// Pair address
+    pub pair: Address,
+    /// LP tokens valued
+    pub lp_amount: U256,
+    /// Underlying token0 amount
+    pub amount0: U256,
+    /// Underlying token1 amount
+    pub amount1: U256,
+}
+
+/// Pair state recorded when a user entered a position
+#[odra::odra_type]
+pub struct EntrySnapshot {
+    /// Reserve of token0 at entry
+    pub reserve0: U256,
+    /// Reserve of token1 at entry
+    pub reserve1: U256,
+    /// LP total supply at entry
+    pub total_supply: U256,
+    /// Block time of the snapshot
+    pub timestamp: u64,
+}
+
+/// A position compared with holding its entry amounts, valued in token1
+#[odra::odra_type]
+pub struct ImpermanentLossView {
+    /// Entry snapshot the estimate is based on
+    pub entry: EntrySnapshot,
+    /// Current underlying amounts
+    pub current: PositionValue,
+    /// Value of the entry amounts at the current price
+    pub hold_value: U256,
+    /// Value of the current amounts (swap fees included)
+    pub lp_value: U256,
+    /// Shortfall of the position versus holding in basis points (0 if ahead)
+    pub loss_bps: u32,
+}
+
+/// A user's LP position in one pair
+#[odra::odra_type]
+pub struct LpPositionView {
+    /// Pair address
+    pub pair: Address,
+    /// Address of token0
+    pub token0: Address,
+    /// Address of token1
+    pub token1: Address,
+    /// LP tokens held by the user
+    pub lp_balance: U256,
+    /// Share of the pool (scaled by 1e18)
+    pub share: U256,
+    /// Underlying token0 amount
+    pub amount0: U256,
+    /// Underlying token1 amount
+    pub amount1: U256,
+}
+
+/// A pending unstake request
+#[odra::odra_type]
+pub struct UnstakeRequestView {
+    /// Unstake request ID
+    pub request_id: u64,
+    /// Amount of CSPR to be withdrawn
+    pub cspr_amount: U256,
+    /// Timestamp when the funds become withdrawable
+    pub withdrawable_at: u64,
+}
+
+/// Aggregated positions of a user
+#[odra::odra_type]
+pub struct Portfolio {
+    /// User address
+    pub user: Address,
+    /// LP positions with a non-zero balance
+    pub lp_positions: Vec<LpPositionView>,
+    /// sCSPR balance
+    pub scspr_balance: U256,
+    /// CSPR value of the sCSPR balance at the current exchange rate
+    pub scspr_cspr_value: U256,
+    /// Unstake requests not yet withdrawn
+    pub pending_unstakes: Vec<UnstakeRequestView>,
+}
+
+/// Position Viewer contract
+#[odra::module]
+pub struct PositionViewer {
+    /// DEX factory address
+    factory: Var<Address>,
+    /// Staking manager address
+    staking_manager: Var<Address>,
+    /// sCSPR token address
+    scspr_token: Var<Address>,
+    /// Entry snapshots: (user, pair) -> snapshot
+    entries: Mapping<(Address, Address), EntrySnapshot>,
+}
+
+#[odra::module]
+impl PositionViewer {
+    /// Initialize the viewer with the protocol contract addresses
+    pub fn init(&mut self, factory: Address, staking_manager: Address, scspr_token: Address) {
+        self.factory.set(factory);
+        self.staking_manager.set(staking_manager);
+        self.scspr_token.set(scspr_token);
+    }
+
+    /// Get a user's positions
+    /// Pairs are scanned from `pair_offset`, at most `pair_limit` of them
+    pub fn get_portfolio(&self, user: Address, pair_offset: u32, pair_limit: u32) -> Portfolio {
+        let scspr = Cep18TokenContractRef::new(self.env(), self.scspr_token());
+        let staking_manager = StakingManagerContractRef::new(self.env(), self.staking_manager());
+
+        let scspr_balance = scspr.balance_of(user);
+        let scspr_cspr_value = staking_manager.get_cspr_by_scspr(scspr_balance);
+
+        Portfolio {
+            user,
+            lp_positions: self.get_lp_positions(user, pair_offset, pair_limit),
+            scspr_balance,
+            scspr_cspr_value,
+            pending_unstakes: self.get_pending_unstakes(user),
+        }
+    }
+
+    /// Get a user's LP positions in pairs `pair_offset..pair_offset + pair_limit`
+    pub fn get_lp_positions(&self, user: Address, pair_offset: u32, pair_limit: u32) -> Vec<LpPositionView> {
+        let factory = FactoryContractContractRef::new(self.env(), self.factory());
+        let end = pair_offset.saturating_add(pair_limit).min(factory.all_pairs_length());
+
+        let mut positions = Vec::new();
+        for index in pair_offset..end {
+            let pair = match factory.all_pairs_at(index) {
+                Some(pair) => pair,
+                None => continue,
+            };
+            if let Some(position) = self.lp_position(user, pair) {
+                positions.push(position);
+            }
+        }
+        positions
+    }
+
+    /// Get a user's unstake requests that have not been withdrawn yet
+    pub fn get_pending_unstakes(&self, user: Address) -> Vec<UnstakeRequestView> {
+        let staking_manager = StakingManagerContractRef::new(self.env(), self.staking_manager());
+
+        let mut pending = Vec::new();
+        for request_id in staking_manager.get_user_unstake_requests(user) {
+            if let Some(request) = staking_manager.get_unstake_request(request_id) {
+                if !request.processed {
+                    pending.push(UnstakeRequestView {
+                        request_id,
+                        cspr_amount: request.cspr_amount,
+                        withdrawable_at: request.withdrawable_at,
+                    });
+                }
+            }
+        }
+        pending
+    }
+
+    /// Get the underlying token amounts of `lp_amount` LP tokens of `pair`
+    pub fn get_position_value(&self, pair: Address, lp_amount: U256) -> PositionValue {
+        let total_supply = Cep18TokenContractRef::new(self.env(), pair).total_supply();
+        let (reserve0, reserve1, _) = PairContractContractRef::new(self.env(), pair).get_reserves();
+        let (amount0, amount1) = if total_supply.is_zero() {
+            (U256::zero(), U256::zero())
+        } else {
+            (lp_amount * reserve0 / total_supply, lp_amount * reserve1 / total_supply)
+        };
+        PositionValue { pair, lp_amount, amount0, amount1 }
+    }
+
+    /// Snapshot `pair` for the caller, as the baseline of `get_impermanent_loss`
+    /// Call it right after adding liquidity; a later call replaces the snapshot
+    pub fn record_entry(&mut self, pair: Address) {
+        let total_supply = Cep18TokenContractRef::new(self.env(), pair).total_supply();
+        if total_supply.is_zero() {
+            self.env().revert(DexError::InsufficientLiquidity);
+        }
+        let (reserve0, reserve1, _) = PairContractContractRef::new(self.env(), pair).get_reserves();
+        let user = self.env().caller();
+        self.entries.set(&(user, pair), EntrySnapshot {
+            reserve0,
+            reserve1,
+            total_supply,
+            timestamp: self.env().get_block_time(),
+        });
+    }
+
+    /// Get a user's entry snapshot for a pair
+    pub fn get_entry(&self, user: Address, pair: Address) -> Option<EntrySnapshot> {
+        self.entries.get(&(user, pair))
+    }
+
+    /// Estimate impermanent loss of `lp_amount` LP tokens of `pair` against
+    /// holding what they were worth at the user's entry snapshot
+    /// Returns None without a snapshot or while the pair is empty
+    pub fn get_impermanent_loss(&self, user: Address, pair: Address, lp_amount: U256) -> Option<ImpermanentLossView> {
+        let entry = self.get_entry(user, pair)?;
+        let current = self.get_position_value(pair, lp_amount);
+        let (reserve0, reserve1, _) = PairContractContractRef::new(self.env(), pair).get_reserves();
+        if reserve0.is_zero() {
+            return None;
+        }
+
+        // Value everything in token1 at the current pool price
+        let entry0 = lp_amount * entry.reserve0 / entry.total_supply;
+        let entry1 = lp_amount * entry.reserve1 / entry.total_supply;
+        let hold_value = entry0 * reserve1 / reserve0 + entry1;
+        let lp_value = current.amount0 * reserve1 / reserve0 + current.amount1;
+        let loss_bps = if hold_value > lp_value {
+            ((hold_value - lp_value) * U256::from(BPS) / hold_value).as_u32()
+        } else {
+            0
+        };
+
+        Some(ImpermanentLossView { entry, current, hold_value, lp_value, loss_bps })
+    }
+
+    /// Get the factory address
+    pub fn factory(&self) -> Address {
+        self.factory.get_or_revert_with(DexError::InvalidConfiguration)
+    }
+
+    /// Get the staking manager address
+    pub fn staking_manager(&self) -> Address {
+        self.staking_manager.get_or_revert_with(DexError::InvalidConfiguration)
+    }
+
+    /// Get the sCSPR token address
+    pub fn scspr_token(&self) -> Address {
+        self.scspr_token.get_or_revert_with(DexError::InvalidConfiguration)
+    }
+
+    // ============ Internal Functions ============
+
+    /// Build the LP position of `user` in `pair`, if any
+    fn lp_position(&self, user: Address, pair: Address) -> Option<LpPositionView> {
+        let lp_token = Cep18TokenContractRef::new(self.env(), pair);
+        let lp_balance = lp_token.balance_of(user);
+        if lp_balance.is_zero() {
+            return None;
+        }
+        let total_supply = lp_token.total_supply();
+
+        let pair_ref = PairContractContractRef::new(self.env(), pair);
+        let (reserve0, reserve1, _) = pair_ref.get_reserves();
+
+        Some(LpPositionView {
+            pair,
+            token0: pair_ref.token0(),
+            token1: pair_ref.token1(),
+            lp_balance,
+            share: lp_balance * U256::from(SHARE_SCALE) / total_supply,
+            amount0: lp_balance * reserve0 / total_supply,
+            amount1: lp_balance * reserve1 / total_supply,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use odra::host::{Deployer, NoArgs};
+    use crate::dex::factory::{Factory, FactoryInitArgs};
+    use crate::dex::pair::PairFactory;
+    use crate::lst::scspr_token::{ScsprToken, ScsprTokenInitArgs};
+    use crate::lst::staking_manager::{StakingManager, StakingManagerInitArgs};
+    use crate::testing::Fixture;
+
+    #[test]
+    fn test_portfolio_with_lst_positions() {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let user = env.get_account(2);
+
+        let pair_factory = PairFactory::deploy(&env, NoArgs);
+        let factory = Factory::deploy(&env, FactoryInitArgs {
+            fee_to_setter: admin,
+            pair_factory: pair_factory.address().clone(),
+        });
+
+        let mut scspr_token = ScsprToken::deploy(&env, ScsprTokenInitArgs {
+            staking_manager: env.get_account(8),
+        });
+        let mut staking_manager = StakingManager::deploy(&env, StakingManagerInitArgs {
+            scspr_token_address: scspr_token.address().clone(),
+        });
+        scspr_token.set_staking_manager(staking_manager.address().clone());
+
+        let viewer = PositionViewer::deploy(&env, PositionViewerInitArgs {
+            factory: factory.address().clone(),
+            staking_manager: staking_manager.address().clone(),
+            scspr_token: scspr_token.address().clone(),
+        });
+
+        let stake = U256::from(1_000_000_000_000u64);
+        env.set_caller(user);
+        let minted = staking_manager.stake(stake);
+        let request_id = staking_manager.unstake(minted / 4);
+
+        let portfolio = viewer.get_portfolio(user, 0, 10);
+        assert!(portfolio.lp_positions.is_empty());
+        assert_eq!(portfolio.scspr_balance, minted - minted / 4);
+        assert_eq!(portfolio.scspr_cspr_value, staking_manager.get_cspr_by_scspr(portfolio.scspr_balance));
+        assert_eq!(portfolio.pending_unstakes.len(), 1);
+        assert_eq!(portfolio.pending_unstakes[0].request_id, request_id);
+    }
+
+    #[test]
+    fn test_impermanent_loss_against_entry_snapshot() {
+        let mut fixture = Fixture::new();
+        let env = fixture.env.clone();
+        let provider = fixture.account(1);
+        let trader = fixture.account(2);
+        let mut pair = fixture.dex.deploy_pair(&env);
+        let pair_addr = pair.address().clone();
+
+        let mut viewer = PositionViewer::deploy(&env, PositionViewerInitArgs {
+            factory: fixture.dex.factory.address().clone(),
+            staking_manager: fixture.lst.staking_manager.address().clone(),
+            scspr_token: fixture.lst.scspr.address().clone(),
+        });
+
+        env.set_caller(provider);
+        fixture.dex.token_a.transfer(pair_addr, U256::from(1_000_000u64));
+        fixture.dex.token_b.transfer(pair_addr, U256::from(1_000_000u64));
+        let lp_amount = pair.mint(provider);
+        assert!(viewer.get_impermanent_loss(provider, pair_addr, lp_amount).is_none());
+        viewer.record_entry(pair_addr);
+
+        let value = viewer.get_position_value(pair_addr, lp_amount);
+        assert_eq!(value.amount0, U256::from(999_000u64));
+        assert_eq!(value.amount1, U256::from(999_000u64));
+        let il = viewer.get_impermanent_loss(provider, pair_addr, lp_amount).unwrap();
+        assert_eq!(il.loss_bps, 0);
+
+        // Doubling one reserve moves the price ~4x: about 20% loss versus holding
+        env.set_caller(trader);
+        fixture.dex.token_a.transfer(pair_addr, U256::from(1_000_000u64));
+        pair.swap_exact_in(U256::zero(), trader);
+
+        let il = viewer.get_impermanent_loss(provider, pair_addr, lp_amount).unwrap();
+        assert!(il.lp_value < il.hold_value);
+        assert!(il.loss_bps > 1_800 && il.loss_bps < 2_100, "loss {} bps", il.loss_bps);
+    }
+}
@@ -0,0 +1,221 @@
+//! Status Board - Aggregated, read-only protocol health view
+//!
+//! Collects the metrics a monitoring dashboard polls in a single call: the
+//! number of DEX pairs, the LST exchange rate, backing and peg buffer, the
+//! staking manager's pause mode and the guardian pause flag of every
+//! monitored contract.
+
+use odra::prelude::*;
+use odra::casper_types::U256;
+use odra::ContractRef;
+use crate::dex::factory::FactoryContractContractRef;
+use crate::errors::DexError;
+use crate::governance::guardian::GuardianContractContractRef;
+use crate::lst::staking_manager::{PauseMode, StakingManagerContractRef};
+
+/// Most contracts the board reports pause flags for
+pub const MAX_MONITORED: usize = 32;
+
+/// Guardian pause flag of one monitored contract
+#[odra::odra_type]
+pub struct ModuleStatus {
+    /// Monitored contract
+    pub target: Address,
+    /// Whether the guardian has it paused
+    pub paused: bool,
+}
+
+/// Snapshot of protocol health
+#[odra::odra_type]
+pub struct ProtocolStatus {
+    /// Number of DEX pairs created by the factory
+    pub pair_count: u32,
+    /// CSPR per sCSPR (scaled by 1e9)
+    pub lst_exchange_rate: U256,
+    /// Total CSPR backing sCSPR
+    pub lst_total_staked: U256,
+    /// Total sCSPR supply
+    pub lst_total_supply: U256,
+    /// WCSPR held for peg arbitrage
+    pub lst_peg_buffer: U256,
+    /// Effective staking manager pause mode (own mode or guardian pause)
+    pub lst_pause_mode: PauseMode,
+    /// Guardian pause flags; empty when no guardian is configured
+    pub modules: Vec<ModuleStatus>,
+    /// Whether any monitored contract or the LST is paused
+    pub any_paused: bool,
+    /// Block time of the snapshot
+    pub timestamp: u64,
+}
+
+/// Status Board contract
+#[odra::module]
+pub struct StatusBoard {
+    /// Contract admin
+    admin: Var<Address>,
+    /// DEX factory address
+    factory: Var<Address>,
+    /// Staking manager address
+    staking_manager: Var<Address>,
+    /// Guardian queried for pause flags
+    guardian: Var<Option<Address>>,
+    /// Contracts whose pause flags are reported
+    monitored: Var<Vec<Address>>,
+}
+
+#[odra::module]
+impl StatusBoard {
+    /// Initialize the board with the protocol contract addresses
+    pub fn init(&mut self, factory: Address, staking_manager: Address) {
+        self.admin.set(self.env().caller());
+        self.factory.set(factory);
+        self.staking_manager.set(staking_manager);
+    }
+
+    /// Get the current protocol status
+    pub fn get_status(&self) -> ProtocolStatus {
+        let factory = FactoryContractContractRef::new(self.env(), self.factory());
+        let staking_manager = StakingManagerContractRef::new(self.env(), self.staking_manager());
+
+        let modules = self.get_module_statuses();
+        let lst_pause_mode = staking_manager.get_effective_pause_mode();
+        let any_paused = lst_pause_mode != PauseMode::Normal
+            || modules.iter().any(|module| module.paused);
+
+        ProtocolStatus {
+            pair_count: factory.all_pairs_length(),
+            lst_exchange_rate: staking_manager.get_exchange_rate(),
+            lst_total_staked: staking_manager.get_total_cspr_staked(),
+            lst_total_supply: staking_manager.get_total_scspr_supply(),
+            lst_peg_buffer: staking_manager.get_peg_buffer(),
+            lst_pause_mode,
+            modules,
+            any_paused,
+            timestamp: self.env().get_block_time(),
+        }
+    }
+
+    /// Get the guardian pause flag of every monitored contract
+    pub fn get_module_statuses(&self) -> Vec<ModuleStatus> {
+        let Some(guardian) = self.guardian() else {
+            return Vec::new();
+        };
+        let guardian = GuardianContractContractRef::new(self.env(), guardian);
+        self.monitored()
+            .into_iter()
+            .map(|target| ModuleStatus { target, paused: guardian.is_paused(target) })
+            .collect()
+    }
+
+    /// Get the factory address
+    pub fn factory(&self) -> Address {
+        self.factory.get_or_revert_with(DexError::InvalidConfiguration)
+    }
+
+    /// Get the staking manager address
+    pub fn staking_manager(&self) -> Address {
+        self.staking_manager.get_or_revert_with(DexError::InvalidConfiguration)
+    }
+
+    /// Get the guardian address, if any
+    pub fn guardian(&self) -> Option<Address> {
+        self.guardian.get_or_default()
+    }
+
+    /// Get the monitored contracts
+    pub fn monitored(&self) -> Vec<Address> {
+        self.monitored.get_or_default()
+    }
+
+    // ============ Admin Functions ============
+
+    /// Set or clear the guardian queried for pause flags
+    pub fn set_guardian(&mut self, guardian: Option<Address>) {
+        self.only_admin();
+        self.guardian.set(guardian);
+    }
+
+    /// Replace the list of monitored contracts
+    pub fn set_monitored(&mut self, targets: Vec<Address>) {
+        self.only_admin();
+        if targets.len() > MAX_MONITORED {
+            self.env().revert(DexError::InvalidConfiguration);
+        }
+        self.monitored.set(targets);
+    }
+
+    /// Transfer admin rights
+    pub fn transfer_admin(&mut self, new_admin: Address) {
+        self.only_admin();
+        self.admin.set(new_admin);
+    }
+
+    // ============ Internal Functions ============
+
+    fn only_admin(&self) {
+        if Some(self.env().caller()) != self.admin.get() {
+            self.env().revert(DexError::Unauthorized);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use odra::host::{Deployer, NoArgs};
+    use crate::governance::guardian::Guardian;
+    use crate::testing::Fixture;
+
+    #[test]
+    fn test_status_reports_lst_and_pause_flags() {
+        let mut fixture = Fixture::new();
+        let env = fixture.env.clone();
+        let router = fixture.dex.router.address().clone();
+        let factory = fixture.dex.factory.address().clone();
+
+        let mut guardian = Guardian::deploy(&env, NoArgs);
+        let mut board = StatusBoard::deploy(&env, StatusBoardInitArgs {
+            factory,
+            staking_manager: fixture.lst.staking_manager.address().clone(),
+        });
+        board.set_guardian(Some(guardian.address().clone()));
+        board.set_monitored(vec![router, factory]);
+
+        env.set_caller(fixture.account(1));
+        fixture.lst.staking_manager.stake(U256::from(1_000_000_000_000u64));
+
+        let status = board.get_status();
+        assert_eq!(status.pair_count, 0);
+        assert_eq!(status.lst_total_staked, U256::from(1_000_000_000_000u64));
+        assert_eq!(status.lst_exchange_rate, fixture.lst.staking_manager.get_exchange_rate());
+        assert_eq!(status.lst_pause_mode, PauseMode::Normal);
+        assert!(!status.any_paused);
+
+        env.set_caller(fixture.admin);
+        guardian.set_registered(router, true);
+        guardian.pause(router);
+        let status = board.get_status();
+        assert_eq!(status.modules, vec![
+            ModuleStatus { target: router, paused: true },
+            ModuleStatus { target: factory, paused: false },
+        ]);
+        assert!(status.any_paused);
+    }
+
+    #[test]
+    fn test_only_admin_configures_board() {
+        let fixture = Fixture::new();
+        let mut board = StatusBoard::deploy(&fixture.env, StatusBoardInitArgs {
+            factory: fixture.dex.factory.address().clone(),
+            staking_manager: fixture.lst.staking_manager.address().clone(),
+        });
+
+        fixture.env.set_caller(fixture.account(1));
+        assert_eq!(board.try_set_monitored(vec![]), Err(DexError::Unauthorized.into()));
+        fixture.env.set_caller(fixture.admin);
+        assert_eq!(
+            board.try_set_monitored(vec![fixture.admin; MAX_MONITORED + 1]),
+            Err(DexError::InvalidConfiguration.into())
+        );
+    }
+}
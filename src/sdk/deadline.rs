@@ -0,0 +1,38 @@
+//! Deadline helpers for swap and liquidity calls
+//!
+//! Casper block timestamps can lag or lead wall-clock time. Clients should
+//! derive deadlines from the chain's own block time (the Router exposes
+//! `current_block_time`) rather than the local clock.
+
+/// Default time-to-live for a transaction deadline (20 minutes)
+pub const DEFAULT_DEADLINE_TTL: u64 = 20 * 60;
+
+/// Deadline `ttl_secs` after `block_time`, saturating at `u64::MAX`
+pub fn suggest_deadline(block_time: u64, ttl_secs: u64) -> u64 {
+    block_time.saturating_add(ttl_secs)
+}
+
+/// Whether a deadline has passed at `block_time`, allowing `tolerance` seconds of drift
+pub fn deadline_expired(block_time: u64, deadline: u64, tolerance: u64) -> bool {
+    block_time > deadline.saturating_add(tolerance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suggest_deadline_saturates() {
+        assert_eq!(suggest_deadline(1_000, DEFAULT_DEADLINE_TTL), 2_200);
+        assert_eq!(suggest_deadline(u64::MAX - 1, 60), u64::MAX);
+    }
+
+    #[test]
+    fn test_deadline_tolerance() {
+        assert!(!deadline_expired(1_000, 1_000, 0));
+        assert!(deadline_expired(1_001, 1_000, 0));
+        assert!(!deadline_expired(1_030, 1_000, 30));
+        assert!(deadline_expired(1_031, 1_000, 30));
+        assert!(!deadline_expired(u64::MAX, u64::MAX - 5, 10));
+    }
+}
@@ -0,0 +1,15 @@
+//! Off-chain integration helpers
+//!
+//! Plain functions shared by the contracts and by third-party integrators:
+//! - route: encoding of swap routes passed to the Router's aggregator interface
+//!   and of swap payloads sent with `transfer_and_call`
+//! - deadline: drift-safe transaction deadlines
+
+pub mod route;
+pub mod deadline;
+
+pub use route::{
+    decode_route, decode_swap_call, decode_typed_route, encode_route, encode_swap_call,
+    encode_typed_route, SwapCall, ROUTE_VERSION, TYPED_ROUTE_VERSION,
+};
+pub use deadline::{deadline_expired, suggest_deadline, DEFAULT_DEADLINE_TTL};
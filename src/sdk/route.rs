@@ -0,0 +1,178 @@
+//! Swap route encoding for aggregator integrations
+//!
+//! A route is a version byte followed by the token path serialized with
+//! Casper's `bytesrepr` (a u32 length and each `Address`). Version 2 routes
+//! append the pair type of each hop, so a route can cross pools of different
+//! types. Aggregators build routes with `encode_route`/`encode_typed_route`
+//! and never need to know the Router's argument layout; the Router decodes
+//! both versions with `decode_typed_route`.
+//!
+//! Tokens with a `transfer_and_call` hook can swap in one transaction by
+//! sending the input straight to the Router with a payload from
+//! `encode_swap_call`: a route plus the swap's limit, recipient and deadline.
+use odra::prelude::*;
+use odra::casper_types::U256;
+use odra::casper_types::bytesrepr::{Bytes, FromBytes, ToBytes};
+use crate::dex::factory::DEFAULT_PAIR_TYPE;
+
+/// Route encoding version for paths over default pairs
+pub const ROUTE_VERSION: u8 = 1;
+
+/// Route encoding version carrying a pair type per hop
+pub const TYPED_ROUTE_VERSION: u8 = 2;
+
+/// Encode a token path into route bytes
+/// Returns None if the path has fewer than two tokens
+pub fn encode_route(path: &[Address]) -> Option<Bytes> {
+    if path.len() < 2 {
+        return None;
+    }
+    let mut bytes = vec![ROUTE_VERSION];
+    bytes.extend(path.to_vec().to_bytes().ok()?);
+    Some(Bytes::from(bytes))
+}
+
+/// Decode route bytes back into a token path
+/// Returns None for an unknown version, trailing bytes or a path shorter than two tokens
+pub fn decode_route(route: &[u8]) -> Option<Vec<Address>> {
+    let (version, rest) = route.split_first()?;
+    if *version != ROUTE_VERSION {
+        return None;
+    }
+    let (path, remainder) = Vec::<Address>::from_bytes(rest).ok()?;
+    if !remainder.is_empty() || path.len() < 2 {
+        return None;
+    }
+    Some(path)
+}
+
+/// Encode a token path with the pair type of each hop
+/// Returns None unless there is exactly one pair type per hop
+pub fn encode_typed_route(path: &[Address], pair_types: &[u8]) -> Option<Bytes> {
+    if path.len() < 2 || pair_types.len() != path.len() - 1 {
+        return None;
+    }
+    let mut bytes = vec![TYPED_ROUTE_VERSION];
+    bytes.extend(path.to_vec().to_bytes().ok()?);
+    bytes.extend(pair_types.to_vec().to_bytes().ok()?);
+    Some(Bytes::from(bytes))
+}
+
+/// Decode route bytes of either version into a token path and per-hop pair types
+/// Version 1 routes use the default pair type for every hop
+pub fn decode_typed_route(route: &[u8]) -> Option<(Vec<Address>, Vec<u8>)> {
+    match route.first()? {
+        &ROUTE_VERSION => {
+            let path = decode_route(route)?;
+            let pair_types = vec![DEFAULT_PAIR_TYPE; path.len() - 1];
+            Some((path, pair_types))
+        }
+        &TYPED_ROUTE_VERSION => {
+            let (path, rest) = Vec::<Address>::from_bytes(&route[1..]).ok()?;
+            let (pair_types, remainder) = Vec::<u8>::from_bytes(rest).ok()?;
+            if !remainder.is_empty() || path.len() < 2 || pair_types.len() != path.len() - 1 {
+                return None;
+            }
+            Some((path, pair_types))
+        }
+        _ => None,
+    }
+}
+
+/// Swap instructions carried by a `transfer_and_call` to the Router
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SwapCall {
+    /// Encoded route starting at the transferred token
+    pub route: Bytes,
+    /// Minimum output amount
+    pub amount_out_min: U256,
+    /// Recipient of the output
+    pub to: Address,
+    /// Swap deadline
+    pub deadline: u64,
+}
+
+/// Encode swap instructions for a `transfer_and_call` to the Router
+/// Returns None if the route does not decode
+pub fn encode_swap_call(route: &[u8], amount_out_min: U256, to: Address, deadline: u64) -> Option<Bytes> {
+    decode_typed_route(route)?;
+    let call = (Bytes::from(route.to_vec()), amount_out_min, to, deadline);
+    Some(Bytes::from(call.to_bytes().ok()?))
+}
+
+/// Decode swap instructions from a `transfer_and_call` payload
+/// Returns None for trailing bytes or a route that does not decode
+pub fn decode_swap_call(data: &[u8]) -> Option<SwapCall> {
+    let ((route, amount_out_min, to, deadline), remainder) =
+        <(Bytes, U256, Address, u64)>::from_bytes(data).ok()?;
+    if !remainder.is_empty() {
+        return None;
+    }
+    decode_typed_route(&route)?;
+    Some(SwapCall { route, amount_out_min, to, deadline })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use odra::casper_types::account::AccountHash;
+
+    fn account(byte: u8) -> Address {
+        Address::Account(AccountHash::new([byte; 32]))
+    }
+
+    #[test]
+    fn test_route_roundtrip() {
+        let path = vec![account(1), account(2), account(3)];
+        let route = encode_route(&path).unwrap();
+        assert_eq!(route[0], ROUTE_VERSION);
+        assert_eq!(decode_route(&route), Some(path));
+    }
+
+    #[test]
+    fn test_typed_route_roundtrip() {
+        // Volatile hop into a type 1 hop
+        let path = vec![account(1), account(2), account(3)];
+        let route = encode_typed_route(&path, &[0, 1]).unwrap();
+        assert_eq!(route[0], TYPED_ROUTE_VERSION);
+        assert_eq!(decode_typed_route(&route), Some((path.clone(), vec![0, 1])));
+
+        // Untyped routes decode with default types
+        let route = encode_route(&path).unwrap();
+        assert_eq!(decode_typed_route(&route), Some((path.clone(), vec![0, 0])));
+
+        // One pair type per hop
+        assert_eq!(encode_typed_route(&path, &[0]), None);
+    }
+
+    #[test]
+    fn test_invalid_routes_rejected() {
+        assert_eq!(encode_route(&[account(1)]), None);
+
+        let mut route: Vec<u8> = encode_route(&[account(1), account(2)]).unwrap().into();
+        route[0] = ROUTE_VERSION + 1;
+        assert_eq!(decode_route(&route), None);
+
+        route[0] = ROUTE_VERSION;
+        route.push(0);
+        assert_eq!(decode_route(&route), None);
+        assert_eq!(decode_route(&[]), None);
+    }
+
+    #[test]
+    fn test_swap_call_roundtrip() {
+        let route = encode_route(&[account(1), account(2)]).unwrap();
+        let data = encode_swap_call(&route, U256::from(5u64), account(9), 1_000).unwrap();
+        let call = decode_swap_call(&data).unwrap();
+        assert_eq!(call.route, route);
+        assert_eq!(call.amount_out_min, U256::from(5u64));
+        assert_eq!(call.to, account(9));
+        assert_eq!(call.deadline, 1_000);
+
+        // Routes are validated on both ends
+        assert_eq!(encode_swap_call(&[ROUTE_VERSION], U256::zero(), account(9), 0), None);
+        let mut data: Vec<u8> = data.into();
+        data.push(0);
+        assert_eq!(decode_swap_call(&data), None);
+    }
+}
@@ -0,0 +1,304 @@
+//! Deterministic test fixtures
+//!
+//! Deploys a fully wired protocol (test tokens, factory, router, LST) in a
+//! fixed order so every test sees the same addresses, plus helpers to fund
+//! and approve accounts. Only compiled for tests.
+//!
+//! The mock VM cannot create pairs through the PairFactory, so router tests
+//! that need pools use `RoutedDex`: a Router backed by `MockFactory`, which
+//! serves pairs deployed directly.
+
+use odra::prelude::*;
+use odra::casper_types::U256;
+use odra::host::{Deployer, HostEnv, NoArgs};
+
+use crate::dex::factory::{Factory, FactoryHostRef, FactoryInitArgs, PairKind, PairTypeInfo, DEFAULT_PAIR_TYPE};
+use crate::dex::pair::{Pair, PairFactory, PairHostRef, PairInitArgs};
+use crate::dex::pair_key::sort_tokens;
+use crate::dex::router::{Router, RouterHostRef, RouterInitArgs};
+use crate::errors::DexError;
+use crate::math::DEFAULT_FEE_BPS;
+use crate::lst::scspr_token::{ScsprTokenHostRef, ScsprTokenInitArgs};
+use crate::lst::staking_manager::{StakingManagerHostRef, StakingManagerInitArgs};
+use crate::lst::{ScsprToken, StakingManager};
+use crate::token::{LpToken, LpTokenHostRef, LpTokenInitArgs};
+
+/// Amount every fixture account receives of each test token
+pub const FIXTURE_BALANCE: u64 = 1_000_000_000_000;
+
+/// Whole CSPR in motes
+pub fn cspr(amount: u64) -> U256 {
+    U256::from(amount) * U256::from(1_000_000_000u64)
+}
+
+/// Deploy a plain CEP-18 test token
+pub fn deploy_token(env: &HostEnv, name: &str, symbol: &str) -> LpTokenHostRef {
+    LpToken::deploy(env, LpTokenInitArgs {
+        name: String::from(name),
+        symbol: String::from(symbol),
+    })
+}
+
+/// Mint `amount` to `owner` and approve `spender` for it, as `owner`
+/// The caller is restored afterwards
+pub fn fund_and_approve(
+    env: &HostEnv,
+    token: &mut LpTokenHostRef,
+    owner: Address,
+    spender: Address,
+    amount: U256,
+) {
+    let caller = env.caller();
+    token.mint(owner, amount);
+    env.set_caller(owner);
+    token.approve(spender, amount);
+    env.set_caller(caller);
+}
+
+/// DEX contracts with two test tokens and WCSPR
+pub struct DexFixture {
+    pub factory: FactoryHostRef,
+    pub router: RouterHostRef,
+    pub token_a: LpTokenHostRef,
+    pub token_b: LpTokenHostRef,
+    pub wcspr: LpTokenHostRef,
+}
+
+impl DexFixture {
+    /// Deploy the tokens, PairFactory, Factory and Router with account 0 as admin
+    pub fn deploy(env: &HostEnv) -> Self {
+        let admin = env.get_account(0);
+        let token_a = deploy_token(env, "Token A", "TKA");
+        let token_b = deploy_token(env, "Token B", "TKB");
+        let wcspr = deploy_token(env, "Wrapped CSPR", "WCSPR");
+
+        let pair_factory = PairFactory::deploy(env, NoArgs);
+        let factory = Factory::deploy(env, FactoryInitArgs {
+            fee_to_setter: admin,
+            pair_factory: pair_factory.address().clone(),
+        });
+        let router = Router::deploy(env, RouterInitArgs {
+            factory: factory.address().clone(),
+            wcspr: wcspr.address().clone(),
+        });
+
+        DexFixture { factory, router, token_a, token_b, wcspr }
+    }
+
+    /// Deploy a standalone pair of token A and token B
+    /// Factory-created pairs are not available in the mock VM
+    pub fn deploy_pair(&self, env: &HostEnv) -> PairHostRef {
+        Pair::deploy(env, PairInitArgs {
+            token0: self.token_a.address().clone(),
+            token1: self.token_b.address().clone(),
+            factory: env.get_account(0),
+        })
+    }
+
+    /// Give `user` both test tokens and approve the router for them
+    pub fn fund(&mut self, env: &HostEnv, user: Address, amount: U256) {
+        let router = self.router.address().clone();
+        fund_and_approve(env, &mut self.token_a, user, router, amount);
+        fund_and_approve(env, &mut self.token_b, user, router, amount);
+    }
+}
+
+/// Factory stand-in serving pairs deployed with `Pair::deploy`
+/// Pair types that were not configured are constant product pools
+#[odra::module]
+pub struct MockFactory {
+    fee_to_setter: Var<Address>,
+    pairs: Mapping<(Address, Address, u8), Address>,
+    pair_types: Mapping<Address, u8>,
+    type_infos: Mapping<u8, PairTypeInfo>,
+}
+
+#[odra::module]
+impl MockFactory {
+    pub fn init(&mut self, fee_to_setter: Address) {
+        self.fee_to_setter.set(fee_to_setter);
+    }
+
+    /// Serve `pair` for its tokens under `pair_type`
+    pub fn register_pair(&mut self, token_a: Address, token_b: Address, pair_type: u8, pair: Address) {
+        let (token0, token1) = sort_tokens(token_a, token_b);
+        self.pairs.set(&(token0, token1, pair_type), pair);
+        self.pair_types.set(&pair, pair_type);
+    }
+
+    /// Describe a pair type; pairs keep the fee they were deployed with
+    pub fn set_pair_type(&mut self, pair_type: u8, kind: PairKind, fee_bps: u16, enabled: bool) {
+        self.type_infos.set(&pair_type, PairTypeInfo { kind, fee_bps, enabled });
+    }
+
+    pub fn fee_to(&self) -> Option<Address> {
+        None
+    }
+
+    pub fn fee_to_setter(&self) -> Address {
+        self.fee_to_setter.get_or_revert_with(DexError::Unauthorized)
+    }
+
+    pub fn get_pair(&self, token_a: Address, token_b: Address) -> Option<Address> {
+        self.get_pair_with_type(token_a, token_b, DEFAULT_PAIR_TYPE)
+    }
+
+    pub fn get_pair_with_type(&self, token_a: Address, token_b: Address, pair_type: u8) -> Option<Address> {
+        let (token0, token1) = sort_tokens(token_a, token_b);
+        self.pairs.get(&(token0, token1, pair_type))
+    }
+
+    /// Pairs must be registered up front
+    pub fn create_pair(&mut self, _token_a: Address, _token_b: Address) -> Address {
+        self.env().revert(DexError::PairNotFound)
+    }
+
+    pub fn get_pair_type(&self, pair_type: u8) -> Option<PairTypeInfo> {
+        self.type_infos.get(&pair_type).or(Some(PairTypeInfo {
+            kind: PairKind::ConstantProduct,
+            fee_bps: DEFAULT_FEE_BPS,
+            enabled: true,
+        }))
+    }
+
+    pub fn pair_type_of(&self, pair: Address) -> Option<u8> {
+        self.pair_types.get(&pair)
+    }
+}
+
+/// Router backed by a `MockFactory`, so swaps reach directly deployed pairs
+/// Pairs are deployed with account 0 as their factory, so their admin
+/// setters are called from account 0
+pub struct RoutedDex {
+    pub factory: MockFactoryHostRef,
+    pub router: RouterHostRef,
+}
+
+impl RoutedDex {
+    /// Deploy the mock factory and a Router using `wcspr`, with account 0 as admin
+    pub fn deploy(env: &HostEnv, wcspr: Address) -> Self {
+        let factory = MockFactory::deploy(env, MockFactoryInitArgs {
+            fee_to_setter: env.get_account(0),
+        });
+        let router = Router::deploy(env, RouterInitArgs {
+            factory: factory.address().clone(),
+            wcspr,
+        });
+        RoutedDex { factory, router }
+    }
+
+    /// Deploy a pair of two tokens and serve it under `pair_type`
+    pub fn add_pair(&mut self, env: &HostEnv, token_a: Address, token_b: Address, pair_type: u8) -> PairHostRef {
+        let pair = Pair::deploy(env, PairInitArgs {
+            token0: token_a,
+            token1: token_b,
+            factory: env.get_account(0),
+        });
+        self.factory.register_pair(token_a, token_b, pair_type, pair.address().clone());
+        pair
+    }
+}
+
+/// Mint `amount_a` and `amount_b` straight into `pair` and mint its LP to `to`
+pub fn seed_pair(
+    pair: &mut PairHostRef,
+    token_a: &mut LpTokenHostRef,
+    token_b: &mut LpTokenHostRef,
+    amount_a: U256,
+    amount_b: U256,
+    to: Address,
+) -> U256 {
+    let pair_addr = pair.address().clone();
+    token_a.mint(pair_addr, amount_a);
+    token_b.mint(pair_addr, amount_b);
+    pair.mint(to)
+}
+
+/// sCSPR token wired to its StakingManager
+pub struct LstFixture {
+    pub scspr: ScsprTokenHostRef,
+    pub staking_manager: StakingManagerHostRef,
+}
+
+impl LstFixture {
+    /// Deploy sCSPR and the StakingManager with account 0 as admin
+    pub fn deploy(env: &HostEnv) -> Self {
+        let mut scspr = ScsprToken::deploy(env, ScsprTokenInitArgs {
+            staking_manager: env.get_account(0),
+        });
+        let staking_manager = StakingManager::deploy(env, StakingManagerInitArgs {
+            scspr_token_address: scspr.address().clone(),
+        });
+        scspr.set_staking_manager(staking_manager.address().clone());
+
+        LstFixture { scspr, staking_manager }
+    }
+}
+
+/// Whole protocol deployed from account 0
+pub struct Fixture {
+    pub env: HostEnv,
+    pub admin: Address,
+    pub dex: DexFixture,
+    pub lst: LstFixture,
+}
+
+impl Fixture {
+    /// Deploy everything in a fixed order and fund accounts 1..=3 with
+    /// both test tokens, approved for the router
+    pub fn new() -> Self {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        env.set_caller(admin);
+
+        let mut dex = DexFixture::deploy(&env);
+        let lst = LstFixture::deploy(&env);
+        for i in 1..=3 {
+            dex.fund(&env, env.get_account(i), U256::from(FIXTURE_BALANCE));
+        }
+
+        Fixture { env, admin, dex, lst }
+    }
+
+    /// Test account by index
+    pub fn account(&self, index: usize) -> Address {
+        self.env.get_account(index)
+    }
+}
+
+impl Default for Fixture {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixture_is_wired_and_funded() {
+        let fixture = Fixture::new();
+        let user = fixture.account(1);
+        let router = fixture.dex.router.address().clone();
+
+        assert_eq!(fixture.dex.factory.fee_to_setter(), fixture.admin);
+        assert_eq!(fixture.dex.token_a.balance_of(user), U256::from(FIXTURE_BALANCE));
+        assert_eq!(fixture.dex.token_b.allowance(user, router), U256::from(FIXTURE_BALANCE));
+        assert_eq!(
+            fixture.lst.scspr.get_staking_manager(),
+            fixture.lst.staking_manager.address().clone()
+        );
+    }
+
+    #[test]
+    fn test_fixture_is_deterministic() {
+        let first = Fixture::new();
+        let second = Fixture::new();
+        assert_eq!(first.dex.router.address(), second.dex.router.address());
+        assert_eq!(
+            first.lst.staking_manager.address(),
+            second.lst.staking_manager.address()
+        );
+    }
+}
@@ -2,8 +2,15 @@
 //! This module provides the LP (Liquidity Provider) token functionality
 use odra::prelude::*;
 use odra::casper_types::U256;
-use crate::events::{Transfer, Approval};
+use odra::casper_types::bytesrepr::Bytes;
+use crate::events::{Transfer, Approval, TransferListenersUpdated};
 use crate::errors::TokenError;
+use odra::ContractRef;
+use crate::governance::denylist::DenylistContractRef;
+use crate::governance::events::DenylistEnforcementUpdated;
+
+/// Most contracts an LP token will notify on transfers
+pub const MAX_TRANSFER_LISTENERS: usize = 4;
 
 /// LP Token module implementing CEP-18 standard
 #[odra::module]
@@ -20,6 +27,14 @@ pub struct LpToken {
     balances: Mapping<Address, U256>,
     /// Allowance mapping: owner -> spender -> amount
     allowances: Mapping<(Address, Address), U256>,
+    /// Denylist consulted on transfers
+    denylist: Var<Address>,
+    /// Whether listed addresses are rejected
+    denylist_enforced: Var<bool>,
+    /// Contracts notified after every balance change
+    transfer_listeners: Var<Vec<Address>>,
+    /// Sequence number of the last event emitted
+    event_seq: Var<u64>,
 }
 
 #[odra::module]
@@ -62,6 +77,16 @@ impl LpToken {
         self.allowances.get(&(owner, spender)).unwrap_or_default()
     }
 
+    /// Get the denylist address, if any
+    pub fn denylist(&self) -> Option<Address> {
+        self.denylist.get()
+    }
+
+    /// Check if denylist enforcement is on
+    pub fn is_denylist_enforced(&self) -> bool {
+        self.denylist_enforced.get_or_default()
+    }
+
     /// Transfer tokens to another address
     pub fn transfer(&mut self, to: Address, amount: U256) -> bool {
         let caller = self.env().caller();
@@ -69,6 +94,16 @@ impl LpToken {
         true
     }
 
+    /// Transfer tokens to a contract and notify it in the same call
+    /// The receiver's `on_token_received` runs after the balance moves and
+    /// gets `data` untouched; a revert there undoes the transfer
+    pub fn transfer_and_call(&mut self, to: Address, amount: U256, data: Bytes) -> bool {
+        let caller = self.env().caller();
+        self.transfer_internal(caller, to, amount);
+        TokenReceiverContractRef::new(self.env(), to).on_token_received(caller, amount, data);
+        true
+    }
+
     /// Approve a spender to spend tokens
     pub fn approve(&mut self, spender: Address, amount: U256) -> bool {
         let caller = self.env().caller();
@@ -76,7 +111,27 @@ impl LpToken {
         true
     }
 
+    /// Increase the caller's allowance for a spender
+    pub fn increase_allowance(&mut self, spender: Address, amount: U256) -> bool {
+        let caller = self.env().caller();
+        let current_allowance = self.allowance(caller, spender);
+        self.approve_internal(caller, spender, current_allowance.saturating_add(amount));
+        true
+    }
+
+    /// Decrease the caller's allowance for a spender
+    pub fn decrease_allowance(&mut self, spender: Address, amount: U256) -> bool {
+        let caller = self.env().caller();
+        let current_allowance = self.allowance(caller, spender);
+        if current_allowance < amount {
+            self.env().revert(TokenError::InsufficientAllowance);
+        }
+        self.approve_internal(caller, spender, current_allowance - amount);
+        true
+    }
+
     /// Transfer tokens from one address to another (requires approval)
+    /// An allowance of `U256::MAX` is infinite and is never decremented
     pub fn transfer_from(&mut self, from: Address, to: Address, amount: U256) -> bool {
         let caller = self.env().caller();
         let current_allowance = self.allowance(from, caller);
@@ -85,48 +140,29 @@ impl LpToken {
             self.env().revert(TokenError::InsufficientAllowance);
         }
         
-        self.approve_internal(from, caller, current_allowance - amount);
+        if current_allowance != U256::MAX {
+            self.approve_internal(from, caller, current_allowance - amount);
+        }
         self.transfer_internal(from, to, amount);
         true
     }
 
     /// Mint new tokens (internal function)
     pub fn mint(&mut self, to: Address, amount: U256) {
-        let current_supply = self.total_supply();
-        let new_supply = current_supply + amount;
-        self.total_supply.set(new_supply);
-
-        let current_balance = self.balance_of(to);
-        self.balances.set(&to, current_balance + amount);
-
-        self.env().emit_event(Transfer {
-            from: Address::from(self.env().self_address()),
-            to,
-            value: amount,
-        });
+        let from = self.mint_deferred(to, amount);
+        self.notify_listeners(from, to, amount);
     }
 
     /// Burn tokens (internal function)
     pub fn burn(&mut self, from: Address, amount: U256) {
-        let current_balance = self.balance_of(from);
-        if current_balance < amount {
-            self.env().revert(TokenError::InsufficientBalance);
-        }
-
-        self.balances.set(&from, current_balance - amount);
-
-        let current_supply = self.total_supply();
-        self.total_supply.set(current_supply - amount);
-
-        self.env().emit_event(Transfer {
-            from,
-            to: Address::from(self.env().self_address()),
-            value: amount,
-        });
+        let to = self.burn_deferred(from, amount);
+        self.notify_listeners(from, to, amount);
     }
 
     /// Internal transfer function
     fn transfer_internal(&mut self, from: Address, to: Address, amount: U256) {
+        self.ensure_not_denied(from);
+        self.ensure_not_denied(to);
         let from_balance = self.balance_of(from);
         if from_balance < amount {
             self.env().revert(TokenError::InsufficientBalance);
@@ -140,7 +176,9 @@ impl LpToken {
             from,
             to,
             value: amount,
+            seq: self.next_event_seq(),
         });
+        self.notify_listeners(from, to, amount);
     }
 
     /// Internal approve function
@@ -151,8 +189,124 @@ impl LpToken {
             owner,
             spender,
             value: amount,
+            seq: self.next_event_seq(),
         });
     }
+
+    /// Revert if enforcement is on and the account is listed
+    fn ensure_not_denied(&self, account: Address) {
+        if !self.is_denylist_enforced() {
+            return;
+        }
+        if let Some(denylist) = self.denylist.get() {
+            if DenylistContractRef::new(self.env(), denylist).is_listed(account) {
+                self.env().revert(TokenError::AddressDenied);
+            }
+        }
+    }
+
+    /// Sequence number of the last event emitted; indexers page from here
+    pub fn event_seq(&self) -> u64 {
+        self.event_seq.get_or_default()
+    }
+}
+
+impl LpToken {
+    /// Point the token at a denylist and switch enforcement on or off
+    /// Not an entrypoint: the embedding contract applies its own access control
+    pub(crate) fn configure_denylist(&mut self, denylist: Address, enforced: bool) {
+        self.denylist.set(denylist);
+        self.denylist_enforced.set(enforced);
+
+        self.env().emit_event(DenylistEnforcementUpdated {
+            denylist,
+            enforced,
+        });
+    }
+
+    /// Replace the contracts notified on transfers
+    /// Not an entrypoint: the embedding contract applies its own access control
+    pub(crate) fn configure_transfer_listeners(&mut self, listeners: Vec<Address>) {
+        if listeners.len() > MAX_TRANSFER_LISTENERS {
+            self.env().revert(TokenError::TooManyListeners);
+        }
+        self.transfer_listeners.set(listeners.clone());
+
+        self.env().emit_event(TransferListenersUpdated { listeners, seq: self.next_event_seq() });
+    }
+
+    /// Mint without notifying listeners; returns the `from` to notify with
+    /// A caller holding a lock calls `notify_listeners` once it has released it
+    pub(crate) fn mint_deferred(&mut self, to: Address, amount: U256) -> Address {
+        self.ensure_not_denied(to);
+        let current_supply = self.total_supply();
+        let new_supply = current_supply + amount;
+        self.total_supply.set(new_supply);
+
+        let current_balance = self.balance_of(to);
+        self.balances.set(&to, current_balance + amount);
+
+        let from = Address::from(self.env().self_address());
+        self.env().emit_event(Transfer {
+            from,
+            to,
+            value: amount,
+            seq: self.next_event_seq(),
+        });
+        from
+    }
+
+    /// Burn without notifying listeners; returns the `to` to notify with
+    /// A caller holding a lock calls `notify_listeners` once it has released it
+    pub(crate) fn burn_deferred(&mut self, from: Address, amount: U256) -> Address {
+        self.ensure_not_denied(from);
+        let current_balance = self.balance_of(from);
+        if current_balance < amount {
+            self.env().revert(TokenError::InsufficientBalance);
+        }
+
+        self.balances.set(&from, current_balance - amount);
+
+        let current_supply = self.total_supply();
+        self.total_supply.set(current_supply - amount);
+
+        let to = Address::from(self.env().self_address());
+        self.env().emit_event(Transfer {
+            from,
+            to,
+            value: amount,
+            seq: self.next_event_seq(),
+        });
+        to
+    }
+
+    /// Tell every registered listener about a balance change
+    pub(crate) fn notify_listeners(&self, from: Address, to: Address, amount: U256) {
+        for listener in self.transfer_listeners() {
+            LpTransferListenerContractRef::new(self.env(), listener).on_lp_transfer(from, to, amount);
+        }
+    }
+
+    /// Advance the event sequence and return the number for the next event
+    pub(crate) fn next_event_seq(&mut self) -> u64 {
+        let seq = self.event_seq.get_or_default() + 1;
+        self.event_seq.set(seq);
+        seq
+    }
+}
+
+/// Callback interface for contracts tracking LP balances
+/// The caller is the LP token; mints come from and burns go to its own address
+#[odra::external_contract]
+pub trait LpTransferListener {
+    fn on_lp_transfer(&mut self, from: Address, to: Address, amount: U256);
+}
+
+/// Callback interface for contracts accepting `transfer_and_call`
+/// The caller is the token; `from` is the sender of `amount`
+#[odra::external_contract]
+pub trait TokenReceiver {
+    fn on_token_received(&mut self, from: Address, amount: U256, data: Bytes);
 }
 
 /// External token interface for interacting with CEP-18 tokens
@@ -225,6 +379,59 @@ mod tests {
         assert_eq!(token.total_supply(), U256::zero());
     }
 
+    #[test]
+    fn test_infinite_allowance_is_not_decremented() {
+        let (env, mut token) = setup();
+        let owner = env.get_account(0);
+        let router = env.get_account(1);
+        let pair = env.get_account(2);
+
+        token.mint(owner, U256::from(1000));
+        token.approve(router, U256::MAX);
+
+        // Each pull emits only the Transfer event: no allowance write, no Approval
+        env.set_caller(router);
+        for _ in 0..3 {
+            let before = env.events_count(&token.address());
+            token.transfer_from(owner, pair, U256::from(100));
+            assert_eq!(env.events_count(&token.address()), before + 1);
+        }
+        assert_eq!(token.allowance(owner, router), U256::MAX);
+        assert_eq!(token.balance_of(pair), U256::from(300));
+
+        // A finite allowance is decremented and re-emitted on every pull
+        env.set_caller(owner);
+        token.approve(router, U256::from(200));
+        env.set_caller(router);
+        let before = env.events_count(&token.address());
+        token.transfer_from(owner, pair, U256::from(100));
+        assert_eq!(env.events_count(&token.address()), before + 2);
+        assert_eq!(token.allowance(owner, router), U256::from(100));
+    }
+
+    #[test]
+    fn test_increase_and_decrease_allowance() {
+        let (env, mut token) = setup();
+        let spender = env.get_account(1);
+
+        token.increase_allowance(spender, U256::from(100));
+        token.increase_allowance(spender, U256::from(50));
+        assert_eq!(token.allowance(env.get_account(0), spender), U256::from(150));
+
+        token.decrease_allowance(spender, U256::from(120));
+        assert_eq!(token.allowance(env.get_account(0), spender), U256::from(30));
+
+        assert_eq!(
+            token.try_decrease_allowance(spender, U256::from(31)),
+            Err(TokenError::InsufficientAllowance.into())
+        );
+
+        // Increasing an infinite allowance keeps it infinite
+        token.approve(spender, U256::MAX);
+        token.increase_allowance(spender, U256::from(1));
+        assert_eq!(token.allowance(env.get_account(0), spender), U256::MAX);
+    }
+
     #[test]
     fn test_transfer() {
         let (env, mut token) = setup();
@@ -240,4 +447,23 @@ mod tests {
         assert_eq!(token.balance_of(user1), U256::from(500));
         assert_eq!(token.balance_of(user2), U256::from(500));
     }
+
+    #[test]
+    fn test_event_seq_increments_per_event() {
+        let (env, mut token) = setup();
+        let user1 = env.get_account(0);
+        let user2 = env.get_account(1);
+        assert_eq!(token.event_seq(), 0);
+
+        token.mint(user1, U256::from(1000));
+        token.approve(user2, U256::from(100));
+        token.transfer(user2, U256::from(300));
+        assert_eq!(token.event_seq(), 3);
+        assert!(env.emitted_event(&token.address(), Transfer {
+            from: user1,
+            to: user2,
+            value: U256::from(300),
+            seq: 3,
+        }));
+    }
 }
\ No newline at end of file
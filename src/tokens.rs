@@ -2,8 +2,10 @@
 //! Each token is a separate type so Odra can deploy them independently
 use odra::prelude::*;
 use odra::casper_types::U256;
+use odra::uints::ToU512;
 use crate::events::{Transfer, Approval};
 use crate::errors::TokenError;
+use crate::math::SafeMath;
 
 /// ECTO Token - Ectoplasm native token
 #[odra::module]
@@ -14,6 +16,8 @@ pub struct EctoToken {
     total_supply: Var<U256>,
     balances: Mapping<Address, U256>,
     allowances: Mapping<(Address, Address), U256>,
+    /// Sequence number of the last event emitted
+    event_seq: Var<u64>,
 }
 
 #[odra::module]
@@ -58,7 +62,7 @@ impl EctoToken {
         self.total_supply.set(current_supply + amount);
         let current_balance = self.balance_of(to);
         self.balances.set(&to, current_balance + amount);
-        self.env().emit_event(Transfer { from: Address::from(self.env().self_address()), to, value: amount });
+        self.env().emit_event(Transfer { from: Address::from(self.env().self_address()), to, value: amount, seq: self.next_event_seq() });
     }
 
     pub fn burn(&mut self, from: Address, amount: U256) {
@@ -67,7 +71,7 @@ impl EctoToken {
         self.balances.set(&from, current_balance - amount);
         let current_supply = self.total_supply();
         self.total_supply.set(current_supply - amount);
-        self.env().emit_event(Transfer { from, to: Address::from(self.env().self_address()), value: amount });
+        self.env().emit_event(Transfer { from, to: Address::from(self.env().self_address()), value: amount, seq: self.next_event_seq() });
     }
 
     fn transfer_internal(&mut self, from: Address, to: Address, amount: U256) {
@@ -76,12 +80,24 @@ impl EctoToken {
         self.balances.set(&from, from_balance - amount);
         let to_balance = self.balance_of(to);
         self.balances.set(&to, to_balance + amount);
-        self.env().emit_event(Transfer { from, to, value: amount });
+        self.env().emit_event(Transfer { from, to, value: amount, seq: self.next_event_seq() });
     }
 
     fn approve_internal(&mut self, owner: Address, spender: Address, amount: U256) {
         self.allowances.set(&(owner, spender), amount);
-        self.env().emit_event(Approval { owner, spender, value: amount });
+        self.env().emit_event(Approval { owner, spender, value: amount, seq: self.next_event_seq() });
+    }
+
+    /// Sequence number of the last event emitted; indexers page from here
+    pub fn event_seq(&self) -> u64 {
+        self.event_seq.get_or_default()
+    }
+
+    /// Advance the event sequence and return the number for the next event
+    fn next_event_seq(&mut self) -> u64 {
+        let seq = self.event_seq.get_or_default() + 1;
+        self.event_seq.set(seq);
+        seq
     }
 }
 
@@ -94,6 +110,8 @@ pub struct UsdcToken {
     total_supply: Var<U256>,
     balances: Mapping<Address, U256>,
     allowances: Mapping<(Address, Address), U256>,
+    /// Sequence number of the last event emitted
+    event_seq: Var<u64>,
 }
 
 #[odra::module]
@@ -138,7 +156,7 @@ impl UsdcToken {
         self.total_supply.set(current_supply + amount);
         let current_balance = self.balance_of(to);
         self.balances.set(&to, current_balance + amount);
-        self.env().emit_event(Transfer { from: Address::from(self.env().self_address()), to, value: amount });
+        self.env().emit_event(Transfer { from: Address::from(self.env().self_address()), to, value: amount, seq: self.next_event_seq() });
     }
 
     pub fn burn(&mut self, from: Address, amount: U256) {
@@ -147,7 +165,7 @@ impl UsdcToken {
         self.balances.set(&from, current_balance - amount);
         let current_supply = self.total_supply();
         self.total_supply.set(current_supply - amount);
-        self.env().emit_event(Transfer { from, to: Address::from(self.env().self_address()), value: amount });
+        self.env().emit_event(Transfer { from, to: Address::from(self.env().self_address()), value: amount, seq: self.next_event_seq() });
     }
 
     fn transfer_internal(&mut self, from: Address, to: Address, amount: U256) {
@@ -156,12 +174,24 @@ impl UsdcToken {
         self.balances.set(&from, from_balance - amount);
         let to_balance = self.balance_of(to);
         self.balances.set(&to, to_balance + amount);
-        self.env().emit_event(Transfer { from, to, value: amount });
+        self.env().emit_event(Transfer { from, to, value: amount, seq: self.next_event_seq() });
     }
 
     fn approve_internal(&mut self, owner: Address, spender: Address, amount: U256) {
         self.allowances.set(&(owner, spender), amount);
-        self.env().emit_event(Approval { owner, spender, value: amount });
+        self.env().emit_event(Approval { owner, spender, value: amount, seq: self.next_event_seq() });
+    }
+
+    /// Sequence number of the last event emitted; indexers page from here
+    pub fn event_seq(&self) -> u64 {
+        self.event_seq.get_or_default()
+    }
+
+    /// Advance the event sequence and return the number for the next event
+    fn next_event_seq(&mut self) -> u64 {
+        let seq = self.event_seq.get_or_default() + 1;
+        self.event_seq.set(seq);
+        seq
     }
 }
 
@@ -174,6 +204,8 @@ pub struct WethToken {
     total_supply: Var<U256>,
     balances: Mapping<Address, U256>,
     allowances: Mapping<(Address, Address), U256>,
+    /// Sequence number of the last event emitted
+    event_seq: Var<u64>,
 }
 
 #[odra::module]
@@ -218,7 +250,7 @@ impl WethToken {
         self.total_supply.set(current_supply + amount);
         let current_balance = self.balance_of(to);
         self.balances.set(&to, current_balance + amount);
-        self.env().emit_event(Transfer { from: Address::from(self.env().self_address()), to, value: amount });
+        self.env().emit_event(Transfer { from: Address::from(self.env().self_address()), to, value: amount, seq: self.next_event_seq() });
     }
 
     pub fn burn(&mut self, from: Address, amount: U256) {
@@ -227,7 +259,7 @@ impl WethToken {
         self.balances.set(&from, current_balance - amount);
         let current_supply = self.total_supply();
         self.total_supply.set(current_supply - amount);
-        self.env().emit_event(Transfer { from, to: Address::from(self.env().self_address()), value: amount });
+        self.env().emit_event(Transfer { from, to: Address::from(self.env().self_address()), value: amount, seq: self.next_event_seq() });
     }
 
     fn transfer_internal(&mut self, from: Address, to: Address, amount: U256) {
@@ -236,12 +268,24 @@ impl WethToken {
         self.balances.set(&from, from_balance - amount);
         let to_balance = self.balance_of(to);
         self.balances.set(&to, to_balance + amount);
-        self.env().emit_event(Transfer { from, to, value: amount });
+        self.env().emit_event(Transfer { from, to, value: amount, seq: self.next_event_seq() });
     }
 
     fn approve_internal(&mut self, owner: Address, spender: Address, amount: U256) {
         self.allowances.set(&(owner, spender), amount);
-        self.env().emit_event(Approval { owner, spender, value: amount });
+        self.env().emit_event(Approval { owner, spender, value: amount, seq: self.next_event_seq() });
+    }
+
+    /// Sequence number of the last event emitted; indexers page from here
+    pub fn event_seq(&self) -> u64 {
+        self.event_seq.get_or_default()
+    }
+
+    /// Advance the event sequence and return the number for the next event
+    fn next_event_seq(&mut self) -> u64 {
+        let seq = self.event_seq.get_or_default() + 1;
+        self.event_seq.set(seq);
+        seq
     }
 }
 
@@ -254,6 +298,8 @@ pub struct WbtcToken {
     total_supply: Var<U256>,
     balances: Mapping<Address, U256>,
     allowances: Mapping<(Address, Address), U256>,
+    /// Sequence number of the last event emitted
+    event_seq: Var<u64>,
 }
 
 #[odra::module]
@@ -298,7 +344,7 @@ impl WbtcToken {
         self.total_supply.set(current_supply + amount);
         let current_balance = self.balance_of(to);
         self.balances.set(&to, current_balance + amount);
-        self.env().emit_event(Transfer { from: Address::from(self.env().self_address()), to, value: amount });
+        self.env().emit_event(Transfer { from: Address::from(self.env().self_address()), to, value: amount, seq: self.next_event_seq() });
     }
 
     pub fn burn(&mut self, from: Address, amount: U256) {
@@ -307,7 +353,7 @@ impl WbtcToken {
         self.balances.set(&from, current_balance - amount);
         let current_supply = self.total_supply();
         self.total_supply.set(current_supply - amount);
-        self.env().emit_event(Transfer { from, to: Address::from(self.env().self_address()), value: amount });
+        self.env().emit_event(Transfer { from, to: Address::from(self.env().self_address()), value: amount, seq: self.next_event_seq() });
     }
 
     fn transfer_internal(&mut self, from: Address, to: Address, amount: U256) {
@@ -316,11 +362,124 @@ impl WbtcToken {
         self.balances.set(&from, from_balance - amount);
         let to_balance = self.balance_of(to);
         self.balances.set(&to, to_balance + amount);
-        self.env().emit_event(Transfer { from, to, value: amount });
+        self.env().emit_event(Transfer { from, to, value: amount, seq: self.next_event_seq() });
     }
 
     fn approve_internal(&mut self, owner: Address, spender: Address, amount: U256) {
         self.allowances.set(&(owner, spender), amount);
-        self.env().emit_event(Approval { owner, spender, value: amount });
+        self.env().emit_event(Approval { owner, spender, value: amount, seq: self.next_event_seq() });
+    }
+
+    /// Sequence number of the last event emitted; indexers page from here
+    pub fn event_seq(&self) -> u64 {
+        self.event_seq.get_or_default()
+    }
+
+    /// Advance the event sequence and return the number for the next event
+    fn next_event_seq(&mut self) -> u64 {
+        let seq = self.event_seq.get_or_default() + 1;
+        self.event_seq.set(seq);
+        seq
+    }
+}
+
+/// WCSPR Token - Wrapped CSPR (9 decimals, 1:1 with motes)
+/// CSPR attached to `deposit` is minted as WCSPR; `withdraw` burns it and pays the CSPR back
+#[odra::module]
+pub struct WcsprToken {
+    name: Var<String>,
+    symbol: Var<String>,
+    decimals: Var<u8>,
+    total_supply: Var<U256>,
+    balances: Mapping<Address, U256>,
+    allowances: Mapping<(Address, Address), U256>,
+    /// Sequence number of the last event emitted
+    event_seq: Var<u64>,
+}
+
+#[odra::module]
+impl WcsprToken {
+    pub fn init(&mut self) {
+        self.name.set(String::from("Wrapped CSPR"));
+        self.symbol.set(String::from("WCSPR"));
+        self.decimals.set(9);
+        self.total_supply.set(U256::zero());
     }
-}
\ No newline at end of file
+
+    pub fn name(&self) -> String { self.name.get_or_default() }
+    pub fn symbol(&self) -> String { self.symbol.get_or_default() }
+    pub fn decimals(&self) -> u8 { self.decimals.get_or_default() }
+    pub fn total_supply(&self) -> U256 { self.total_supply.get_or_default() }
+    pub fn balance_of(&self, owner: Address) -> U256 { self.balances.get(&owner).unwrap_or_default() }
+    pub fn allowance(&self, owner: Address, spender: Address) -> U256 { self.allowances.get(&(owner, spender)).unwrap_or_default() }
+
+    pub fn transfer(&mut self, to: Address, amount: U256) -> bool {
+        let caller = self.env().caller();
+        self.transfer_internal(caller, to, amount);
+        true
+    }
+
+    pub fn approve(&mut self, spender: Address, amount: U256) -> bool {
+        let caller = self.env().caller();
+        self.approve_internal(caller, spender, amount);
+        true
+    }
+
+    pub fn transfer_from(&mut self, from: Address, to: Address, amount: U256) -> bool {
+        let caller = self.env().caller();
+        let current_allowance = self.allowance(from, caller);
+        if current_allowance < amount { self.env().revert(TokenError::InsufficientAllowance); }
+        self.approve_internal(from, caller, current_allowance - amount);
+        self.transfer_internal(from, to, amount);
+        true
+    }
+
+    /// Wrap the attached CSPR into WCSPR for the caller
+    #[odra(payable)]
+    pub fn deposit(&mut self) -> U256 {
+        let caller = self.env().caller();
+        let amount = SafeMath::from_u512(self.env().attached_value()).unwrap_or_else(|e| self.env().revert(e));
+        self.total_supply.set(self.total_supply() + amount);
+        let current_balance = self.balance_of(caller);
+        self.balances.set(&caller, current_balance + amount);
+        self.env().emit_event(Transfer { from: Address::from(self.env().self_address()), to: caller, value: amount, seq: self.next_event_seq() });
+        amount
+    }
+
+    /// Burn WCSPR and pay the caller the same amount of CSPR
+    pub fn withdraw(&mut self, amount: U256) {
+        let caller = self.env().caller();
+        let current_balance = self.balance_of(caller);
+        if current_balance < amount { self.env().revert(TokenError::InsufficientBalance); }
+        self.balances.set(&caller, current_balance - amount);
+        self.total_supply.set(self.total_supply() - amount);
+        self.env().emit_event(Transfer { from: caller, to: Address::from(self.env().self_address()), value: amount, seq: self.next_event_seq() });
+        self.env().transfer_tokens(&caller, &amount.to_u512());
+    }
+
+    fn transfer_internal(&mut self, from: Address, to: Address, amount: U256) {
+        let from_balance = self.balance_of(from);
+        if from_balance < amount { self.env().revert(TokenError::InsufficientBalance); }
+        self.balances.set(&from, from_balance - amount);
+        let to_balance = self.balance_of(to);
+        self.balances.set(&to, to_balance + amount);
+        self.env().emit_event(Transfer { from, to, value: amount, seq: self.next_event_seq() });
+    }
+
+    fn approve_internal(&mut self, owner: Address, spender: Address, amount: U256) {
+        self.allowances.set(&(owner, spender), amount);
+        self.env().emit_event(Approval { owner, spender, value: amount, seq: self.next_event_seq() });
+    }
+
+    /// Sequence number of the last event emitted; indexers page from here
+    pub fn event_seq(&self) -> u64 {
+        self.event_seq.get_or_default()
+    }
+
+    /// Advance the event sequence and return the number for the next event
+    fn next_event_seq(&mut self) -> u64 {
+        let seq = self.event_seq.get_or_default() + 1;
+        self.event_seq.set(seq);
+        seq
+    }
+}